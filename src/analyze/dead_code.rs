@@ -0,0 +1,144 @@
+//! Heuristic detection of possibly unused public symbols.
+//!
+//! A public top-level symbol is flagged when no other file's imports mention
+//! either the symbol name or the module it lives in, and its file is not an
+//! entry point. Imports are matched textually, so dynamic dispatch, re-exports
+//! through globs and reflection are invisible here: results are candidates
+//! for review, never proof.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::analyze::FileGraph;
+use crate::types::{Symbol, SymbolKind, Visibility};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct UnusedSymbol {
+    pub file: String,
+    pub name: String,
+    pub kind: SymbolKind,
+    pub line: usize,
+}
+
+pub fn find_unused_symbols(
+    graph: &FileGraph,
+    all_symbols: &HashMap<String, Vec<Symbol>>,
+    entry_points: &[String],
+) -> Vec<UnusedSymbol> {
+    let mut unused = Vec::new();
+
+    for (file, symbols) in all_symbols {
+        if entry_points.contains(file) {
+            continue;
+        }
+
+        let referenced = imported_identifiers(graph, file);
+        if file_stem(file).is_some_and(|stem| referenced.contains(stem)) {
+            continue;
+        }
+
+        for symbol in symbols {
+            if symbol.visibility != Visibility::Public
+                || symbol.kind == SymbolKind::Method
+                || symbol.kind == SymbolKind::Test
+                || referenced.contains(symbol.name.as_str())
+            {
+                continue;
+            }
+
+            unused.push(UnusedSymbol {
+                file: file.clone(),
+                name: symbol.name.clone(),
+                kind: symbol.kind,
+                line: symbol.line_range.start,
+            });
+        }
+    }
+
+    unused.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+    unused
+}
+
+/// Identifiers appearing in the imports of every file except `exclude`
+fn imported_identifiers<'a>(graph: &'a FileGraph, exclude: &str) -> HashSet<&'a str> {
+    graph
+        .imports
+        .iter()
+        .filter(|(file, _)| file.as_str() != exclude)
+        .flat_map(|(_, imports)| imports.iter())
+        .flat_map(|import| import.split(|c: char| !c.is_alphanumeric() && c != '_'))
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+fn file_stem(file: &str) -> Option<&str> {
+    Path::new(file).file_stem().and_then(|s| s.to_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn public_fn(name: &str, line: usize) -> Symbol {
+        Symbol::new(
+            SymbolKind::Function,
+            name.to_string(),
+            line,
+            Visibility::Public,
+        )
+    }
+
+    #[test]
+    fn test_unreferenced_public_function_is_flagged() {
+        let mut graph = FileGraph::new();
+        graph.add_file("src/main.rs", vec!["helpers::format_name".to_string()]);
+        graph.add_file("src/helpers.rs", vec![]);
+        graph.add_file("src/legacy.rs", vec![]);
+
+        let mut symbols = HashMap::new();
+        symbols.insert("src/main.rs".to_string(), vec![public_fn("main", 1)]);
+        symbols.insert(
+            "src/helpers.rs".to_string(),
+            vec![public_fn("format_name", 3)],
+        );
+        symbols.insert(
+            "src/legacy.rs".to_string(),
+            vec![
+                public_fn("old_formatter", 5),
+                Symbol::new(
+                    SymbolKind::Function,
+                    "private_helper".to_string(),
+                    9,
+                    Visibility::Private,
+                ),
+            ],
+        );
+
+        let unused = find_unused_symbols(&graph, &symbols, &["src/main.rs".to_string()]);
+
+        assert_eq!(unused.len(), 1);
+        assert_eq!(unused[0].file, "src/legacy.rs");
+        assert_eq!(unused[0].name, "old_formatter");
+        assert_eq!(unused[0].line, 5);
+    }
+
+    #[test]
+    fn test_symbol_named_in_import_is_not_flagged() {
+        let mut graph = FileGraph::new();
+        graph.add_file("app.py", vec!["parse_config".to_string()]);
+        graph.add_file("config_utils.py", vec![]);
+
+        let mut symbols = HashMap::new();
+        symbols.insert(
+            "config_utils.py".to_string(),
+            vec![public_fn("parse_config", 1), public_fn("dump_config", 8)],
+        );
+
+        let unused = find_unused_symbols(&graph, &symbols, &[]);
+
+        assert_eq!(unused.len(), 1);
+        assert_eq!(unused[0].name, "dump_config");
+    }
+}
@@ -0,0 +1,237 @@
+//! Environment variables read by the code.
+//!
+//! Access sites are found with per-language regexes, so dynamically built
+//! names (`env::var(format!(..))`, `process.env[key]`) are not detected. A
+//! variable is inferred optional when every access handles its absence (a
+//! fallback, `.ok()`, `os.getenv`, `LookupEnv`, ...) and required otherwise.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::scan::read_source;
+use crate::types::{FileEntry, Language};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct EnvVarReference {
+    pub file: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct EnvVar {
+    pub name: String,
+    /// Whether some access has no fallback when the variable is unset
+    pub required: bool,
+    pub references: Vec<EnvVarReference>,
+}
+
+/// One access site: variable name, line and whether absence is handled there
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvAccess {
+    pub name: String,
+    pub line: usize,
+    pub optional: bool,
+}
+
+const NAME: &str = r"([A-Za-z_][A-Za-z0-9_]*)";
+
+static RUST_VAR: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!(r#"\benv::var(?:_os)?\(\s*"{}"\s*\)"#, NAME)).unwrap());
+static RUST_ENV_MACRO: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!(r#"\b(option_env|env)!\(\s*"{}""#, NAME)).unwrap());
+static JS_DOT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!(r"\bprocess\.env\.{}", NAME)).unwrap());
+static JS_INDEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!(r#"\bprocess\.env\[\s*["'`]{}["'`]\s*\]"#, NAME)).unwrap());
+static PY_INDEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!(r#"\bos\.environ\[\s*["']{}["']\s*\]"#, NAME)).unwrap());
+static PY_GET: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r#"\bos\.(?:environ\.get|getenv)\(\s*["']{}["']"#,
+        NAME
+    ))
+    .unwrap()
+});
+static GO_GETENV: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!(r#"\bos\.Getenv\(\s*"{}"\s*\)"#, NAME)).unwrap());
+static GO_LOOKUP: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!(r#"\bos\.LookupEnv\(\s*"{}"\s*\)"#, NAME)).unwrap());
+
+/// Adapters on `env::var`/`var_os` results that handle an unset variable
+const RUST_FALLBACKS: &[&str] = &[
+    ".ok()",
+    ".unwrap_or",
+    ".is_ok()",
+    ".is_err()",
+    ".map_or",
+    ".or(",
+    ".or_else(",
+    ".is_some()",
+    ".is_none()",
+];
+
+/// Find environment variable accesses in one file
+pub fn extract_env_accesses(file: &FileEntry, content: &str) -> Vec<EnvAccess> {
+    let mut accesses = Vec::new();
+
+    for (idx, line) in content.lines().enumerate() {
+        let line_number = idx + 1;
+        let mut push = |name: &str, optional: bool| {
+            accesses.push(EnvAccess {
+                name: name.to_string(),
+                line: line_number,
+                optional,
+            })
+        };
+
+        match file.language {
+            Language::Rust => {
+                for caps in RUST_VAR.captures_iter(line) {
+                    let whole = caps.get(0).unwrap();
+                    let rest = line[whole.end()..].trim_start();
+                    let optional = RUST_FALLBACKS.iter().any(|f| rest.starts_with(f))
+                        || line[..whole.start()].contains("if let Ok");
+                    push(&caps[1], optional);
+                }
+                for caps in RUST_ENV_MACRO.captures_iter(line) {
+                    push(&caps[2], &caps[1] == "option_env");
+                }
+            }
+            Language::JavaScript | Language::TypeScript | Language::Vue | Language::Svelte => {
+                for re in [&*JS_DOT, &*JS_INDEX] {
+                    for caps in re.captures_iter(line) {
+                        let rest = line[caps.get(0).unwrap().end()..].trim_start();
+                        push(&caps[1], rest.starts_with("||") || rest.starts_with("??"));
+                    }
+                }
+            }
+            Language::Python => {
+                for caps in PY_INDEX.captures_iter(line) {
+                    push(&caps[1], false);
+                }
+                for caps in PY_GET.captures_iter(line) {
+                    push(&caps[1], true);
+                }
+            }
+            Language::Go => {
+                for caps in GO_GETENV.captures_iter(line) {
+                    push(&caps[1], false);
+                }
+                for caps in GO_LOOKUP.captures_iter(line) {
+                    push(&caps[1], true);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    accesses
+}
+
+/// Read `files` and group their environment variable accesses by name
+pub fn scan_env_vars(files: &[FileEntry]) -> Vec<EnvVar> {
+    let mut vars: BTreeMap<String, EnvVar> = BTreeMap::new();
+
+    for file in files {
+        let Ok(content) = read_source(&file.path) else {
+            continue;
+        };
+
+        for access in extract_env_accesses(file, &content) {
+            let var = vars.entry(access.name.clone()).or_insert_with(|| EnvVar {
+                name: access.name,
+                required: false,
+                references: Vec::new(),
+            });
+            var.required |= !access.optional;
+            var.references.push(EnvVarReference {
+                file: file.relative_path.clone(),
+                line: access.line,
+            });
+        }
+    }
+
+    vars.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn make_file(path: &str) -> FileEntry {
+        FileEntry::new(PathBuf::from(path), path.to_string(), 100, 10, 500)
+    }
+
+    fn names(accesses: &[EnvAccess]) -> Vec<(&str, bool)> {
+        accesses
+            .iter()
+            .map(|a| (a.name.as_str(), a.optional))
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_env_access() {
+        let content = r#"
+let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+let port = env::var("PORT").unwrap_or_else(|_| "8080".into());
+let debug = env::var_os("DEBUG").is_some();
+if let Ok(token) = env::var("API_TOKEN") {}
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+const SHA: Option<&str> = option_env!("GIT_SHA");
+"#;
+        let accesses = extract_env_accesses(&make_file("src/main.rs"), content);
+
+        assert_eq!(
+            names(&accesses),
+            vec![
+                ("DATABASE_URL", false),
+                ("PORT", true),
+                ("DEBUG", true),
+                ("API_TOKEN", true),
+                ("CARGO_PKG_VERSION", false),
+                ("GIT_SHA", true),
+            ]
+        );
+        assert_eq!(accesses[0].line, 2);
+    }
+
+    #[test]
+    fn test_node_env_access() {
+        let content = r#"
+const secret = process.env.JWT_SECRET;
+const port = process.env.PORT || 3000;
+const region = process.env["AWS_REGION"] ?? "us-east-1";
+if (process.env.NODE_ENV === "production") {}
+"#;
+        let accesses = extract_env_accesses(&make_file("server.ts"), content);
+
+        assert_eq!(
+            names(&accesses),
+            vec![
+                ("JWT_SECRET", false),
+                ("PORT", true),
+                ("AWS_REGION", true),
+                ("NODE_ENV", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_python_and_go_env_access() {
+        let py = "key = os.environ[\"SECRET_KEY\"]\nlevel = os.getenv(\"LOG_LEVEL\", \"info\")\n";
+        assert_eq!(
+            names(&extract_env_accesses(&make_file("app.py"), py)),
+            vec![("SECRET_KEY", false), ("LOG_LEVEL", true)]
+        );
+
+        let go = "addr := os.Getenv(\"ADDR\")\nif v, ok := os.LookupEnv(\"TRACE\"); ok {}\n";
+        assert_eq!(
+            names(&extract_env_accesses(&make_file("main.go"), go)),
+            vec![("ADDR", false), ("TRACE", true)]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::path::Path;
 
 #[derive(Debug, Default)]
@@ -47,7 +47,7 @@ impl FileGraph {
             .map(|(file, importers)| (file.clone(), importers.len()))
             .collect();
 
-        hubs.sort_by(|a, b| b.1.cmp(&a.1));
+        hubs.sort_by_key(|h| std::cmp::Reverse(h.1));
         hubs
     }
 
@@ -57,6 +57,98 @@ impl FileGraph {
             .map(|i| i.len() >= 3)
             .unwrap_or(false)
     }
+
+    /// Import cycles: strongly connected components with more than one file,
+    /// plus files importing themselves. Each cycle and the list are sorted.
+    pub fn find_cycles(&self) -> Vec<Vec<String>> {
+        let nodes: BTreeSet<&str> = self
+            .imports
+            .iter()
+            .flat_map(|(file, imports)| {
+                std::iter::once(file.as_str()).chain(imports.iter().map(String::as_str))
+            })
+            .collect();
+
+        let mut tarjan = Tarjan {
+            graph: self,
+            next_index: 0,
+            index: HashMap::new(),
+            low_link: HashMap::new(),
+            stack: Vec::new(),
+            on_stack: BTreeSet::new(),
+            components: Vec::new(),
+        };
+        for node in nodes {
+            if !tarjan.index.contains_key(node) {
+                tarjan.visit(node);
+            }
+        }
+
+        let mut cycles: Vec<Vec<String>> = tarjan
+            .components
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1
+                    || self
+                        .imports
+                        .get(component[0])
+                        .is_some_and(|imports| imports.iter().any(|i| i == component[0]))
+            })
+            .map(|component| {
+                let mut cycle: Vec<String> = component.into_iter().map(String::from).collect();
+                cycle.sort();
+                cycle
+            })
+            .collect();
+        cycles.sort();
+        cycles
+    }
+}
+
+/// Tarjan's strongly connected components over import edges
+struct Tarjan<'a> {
+    graph: &'a FileGraph,
+    next_index: usize,
+    index: HashMap<&'a str, usize>,
+    low_link: HashMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: BTreeSet<&'a str>,
+    components: Vec<Vec<&'a str>>,
+}
+
+impl<'a> Tarjan<'a> {
+    fn visit(&mut self, node: &'a str) {
+        self.index.insert(node, self.next_index);
+        self.low_link.insert(node, self.next_index);
+        self.next_index += 1;
+        self.stack.push(node);
+        self.on_stack.insert(node);
+
+        let graph = self.graph;
+        for target in graph.imports.get(node).into_iter().flatten() {
+            let target = target.as_str();
+            if !self.index.contains_key(target) {
+                self.visit(target);
+                let low = self.low_link[node].min(self.low_link[target]);
+                self.low_link.insert(node, low);
+            } else if self.on_stack.contains(target) {
+                let low = self.low_link[node].min(self.index[target]);
+                self.low_link.insert(node, low);
+            }
+        }
+
+        if self.low_link[node] == self.index[node] {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack.remove(member);
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
 }
 
 fn normalize_path(path: &str) -> String {
@@ -95,4 +187,62 @@ mod tests {
         let hubs = graph.hub_files();
         assert!(hubs.is_empty());
     }
+
+    #[test]
+    fn test_find_cycles() {
+        let mut graph = FileGraph::new();
+
+        graph.add_file("a.rs", vec!["b.rs".to_string()]);
+        graph.add_file("b.rs", vec!["c.rs".to_string(), "utils".to_string()]);
+        graph.add_file("c.rs", vec!["a.rs".to_string()]);
+        graph.add_file("d.rs", vec!["a.rs".to_string(), "d.rs".to_string()]);
+        graph.add_file("e.rs", vec!["utils".to_string()]);
+
+        assert_eq!(
+            graph.find_cycles(),
+            vec![
+                vec!["a.rs".to_string(), "b.rs".to_string(), "c.rs".to_string()],
+                vec!["d.rs".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_cycles_in_tree() {
+        let mut graph = FileGraph::new();
+
+        graph.add_file("a.rs", vec!["b.rs".to_string(), "c.rs".to_string()]);
+        graph.add_file("b.rs", vec!["c.rs".to_string()]);
+
+        assert!(graph.find_cycles().is_empty());
+    }
+
+    #[test]
+    fn test_public_imports_graph_skips_private_use() {
+        let file = crate::types::FileEntry::new(
+            std::path::PathBuf::from("src/lib.rs"),
+            "src/lib.rs".to_string(),
+            0,
+            4,
+            500,
+        );
+        let content =
+            "pub use api::Client;\nuse internal::helpers;\npub(crate) use cache::Store;\n";
+
+        let mut full = FileGraph::new();
+        full.add_file(
+            "src/lib.rs",
+            crate::analyze::extract_imports(&file, content),
+        );
+        let mut public = FileGraph::new();
+        public.add_file(
+            "src/lib.rs",
+            crate::analyze::extract_public_imports(&file, content),
+        );
+
+        assert!(full.importers.contains_key("internal"));
+        assert_eq!(public.imports["src/lib.rs"], vec!["api".to_string()]);
+        assert!(!public.importers.contains_key("internal"));
+        assert!(!public.importers.contains_key("cache"));
+    }
 }
@@ -0,0 +1,242 @@
+//! Doc comments attached to declarations, collected after symbols are parsed.
+
+use crate::types::Symbol;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum DocStyle {
+    /// `///` runs or a `/** ... */` block above the declaration
+    Doc,
+    /// Plain line comments with this prefix above the declaration, e.g. Go `//`
+    Line(&'static str),
+    /// Python `"""docstring"""` as the first statement of the body
+    Docstring,
+}
+
+/// Fill in `doc_comment` for every symbol that has documentation
+pub(crate) fn attach_doc_comments(content: &str, symbols: &mut [Symbol], style: DocStyle) {
+    let lines: Vec<&str> = content.lines().collect();
+    for sym in symbols.iter_mut() {
+        let doc = match style {
+            DocStyle::Doc | DocStyle::Line(_) => {
+                doc_comment_above(&lines, sym.line_range.start, style)
+            }
+            DocStyle::Docstring => docstring_below(&lines, sym.line_range.start),
+        };
+        if let Some(doc) = doc {
+            sym.doc_comment = Some(doc);
+        }
+    }
+}
+
+/// The contiguous comment block directly above the 1-based `line`, skipping
+/// attribute and annotation lines. Stops at the first blank or code line.
+fn doc_comment_above(lines: &[&str], line: usize, style: DocStyle) -> Option<String> {
+    // Some patterns match from leading blank lines; start at the declaration
+    let mut decl = line.saturating_sub(1);
+    while decl < lines.len() && lines[decl].trim().is_empty() {
+        decl += 1;
+    }
+
+    let mut above = lines[..decl.min(lines.len())]
+        .iter()
+        .rev()
+        .map(|l| l.trim())
+        .skip_while(|l| is_attribute(l, style))
+        .peekable();
+
+    let mut doc: Vec<&str> = Vec::new();
+    match style {
+        DocStyle::Doc if above.peek().is_some_and(|l| l.ends_with("*/")) => {
+            for l in above {
+                let opens = l.starts_with("/*");
+                doc.push(l);
+                if opens {
+                    if !l.starts_with("/**") {
+                        return None;
+                    }
+                    break;
+                }
+            }
+        }
+        DocStyle::Doc => {
+            doc.extend(above.take_while(|l| l.starts_with("///") && !l.starts_with("////")));
+        }
+        DocStyle::Line(prefix) => {
+            doc.extend(above.take_while(|l| l.starts_with(prefix)));
+        }
+        DocStyle::Docstring => return None,
+    }
+
+    doc.reverse();
+    let text = doc
+        .iter()
+        .map(|l| strip_comment_markers(l, style))
+        .collect::<Vec<_>>()
+        .join("\n");
+    non_empty(text.trim())
+}
+
+fn is_attribute(line: &str, style: DocStyle) -> bool {
+    match style {
+        DocStyle::Doc => {
+            line.starts_with("#[")
+                || line.starts_with('@')
+                || (line.starts_with('[') && line.ends_with(']'))
+        }
+        _ => false,
+    }
+}
+
+fn strip_comment_markers(line: &str, style: DocStyle) -> String {
+    let line = match style {
+        DocStyle::Line(prefix) => line.trim_start_matches(prefix),
+        _ => line
+            .trim_start_matches("///")
+            .trim_start_matches("/**")
+            .trim_end_matches("*/")
+            .trim_start_matches('*'),
+    };
+    let line = line.trim();
+    // C# XML docs wrap the text in <summary> tags
+    line.replace("<summary>", "")
+        .replace("</summary>", "")
+        .trim()
+        .to_string()
+}
+
+/// Python docstring: the first statement after the `def`/`class` header at
+/// the 1-based `line`, if it is a string literal
+fn docstring_below(lines: &[&str], line: usize) -> Option<String> {
+    let header_end = (line.saturating_sub(1)..lines.len())
+        .take(20)
+        .find(|&i| lines[i].trim_end().ends_with(':'))?;
+    let first = (header_end + 1..lines.len()).find(|&i| !lines[i].trim().is_empty())?;
+
+    let opening = lines[first].trim().trim_start_matches(['r', 'R', 'u', 'U']);
+    let quote = ["\"\"\"", "'''"]
+        .into_iter()
+        .find(|q| opening.starts_with(q))?;
+    let rest = &opening[quote.len()..];
+
+    if let Some(end) = rest.find(quote) {
+        return non_empty(rest[..end].trim());
+    }
+
+    let mut doc = vec![rest.trim()];
+    for l in &lines[first + 1..] {
+        match l.find(quote) {
+            Some(end) => {
+                doc.push(l[..end].trim());
+                break;
+            }
+            None => doc.push(l.trim()),
+        }
+    }
+    non_empty(doc.join("\n").trim())
+}
+
+fn non_empty(text: &str) -> Option<String> {
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze::lang::{GoParser, JavaParser, LanguageParser, PythonParser, RustParser};
+
+    fn doc_of(symbols: &[Symbol], name: &str) -> Option<String> {
+        symbols
+            .iter()
+            .find(|s| s.name == name)
+            .unwrap()
+            .doc_comment
+            .clone()
+    }
+
+    #[test]
+    fn test_rust_doc_comments_skip_attributes_and_stop_at_blank_lines() {
+        let content = r#"// Not a doc comment
+
+/// Parsed settings.
+///
+/// Loaded once at startup.
+#[derive(Debug)]
+pub struct Settings {}
+
+/// Orphaned doc
+
+pub fn undocumented() {}
+"#;
+        let symbols = RustParser.parse_symbols(content);
+
+        assert_eq!(
+            doc_of(&symbols, "Settings").as_deref(),
+            Some("Parsed settings.\n\nLoaded once at startup.")
+        );
+        assert_eq!(doc_of(&symbols, "undocumented"), None);
+    }
+
+    #[test]
+    fn test_javadoc_block_but_not_plain_block_comment() {
+        let content = r#"public class Api {
+    /**
+     * Fetches a user.
+     * @param id user id
+     */
+    @Override
+    public User fetch(long id) {
+        return null;
+    }
+
+    /* implementation detail */
+    private void helper() {
+    }
+}
+"#;
+        let symbols = JavaParser.parse_symbols(content);
+
+        assert_eq!(
+            doc_of(&symbols, "fetch").as_deref(),
+            Some("Fetches a user.\n@param id user id")
+        );
+        assert_eq!(doc_of(&symbols, "helper"), None);
+    }
+
+    #[test]
+    fn test_go_line_comments() {
+        let content = "package auth\n\n// Login checks credentials.\n// It never panics.\nfunc Login() bool {\n\treturn true\n}\n";
+        let symbols = GoParser.parse_symbols(content);
+
+        assert_eq!(
+            doc_of(&symbols, "Login").as_deref(),
+            Some("Login checks credentials.\nIt never panics.")
+        );
+    }
+
+    #[test]
+    fn test_python_docstrings() {
+        let content = r#"class Cache:
+    """LRU cache."""
+
+    def get(self, key):
+        """
+        Look up a key.
+
+        Returns None when missing.
+        """
+        return None
+
+    def put(self, key, value):
+        # not a docstring
+        pass
+"#;
+        let symbols = PythonParser.parse_symbols(content);
+
+        assert_eq!(doc_of(&symbols, "Cache").as_deref(), Some("LRU cache."));
+        assert_eq!(
+            doc_of(&symbols, "get").as_deref(),
+            Some("Look up a key.\n\nReturns None when missing.")
+        );
+        assert_eq!(doc_of(&symbols, "put"), None);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::analyze::lang::doc::{attach_doc_comments, DocStyle};
+use crate::analyze::lang::signature::{attach_signatures, ReturnStyle};
 use crate::analyze::lang::LanguageParser;
 use crate::types::{Symbol, SymbolKind, Visibility};
 use once_cell::sync::Lazy;
@@ -8,6 +10,12 @@ pub struct GoParser;
 static FUNC_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?m)^func\s+(?:\([^)]+\)\s+)?(\w+)\s*\(").unwrap());
 
+/// `func TestX(t *testing.T)`, and likewise benchmarks and fuzz targets
+static TEST_FUNC_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^func\s+(?:Test|Benchmark|Fuzz)(?:[A-Z0-9_]\w*)?\s*\(\s*\w+\s+\*testing\.[TBF]\b")
+        .unwrap()
+});
+
 static STRUCT_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?m)^type\s+(\w+)\s+struct\b").unwrap());
 
@@ -40,9 +48,16 @@ impl LanguageParser for GoParser {
                 .next()
                 .map(|c| c.is_uppercase())
                 .unwrap_or(false);
+            let is_test = signature
+                .as_deref()
+                .is_some_and(|sig| TEST_FUNC_PATTERN.is_match(sig));
 
             let mut sym = Symbol::new(
-                SymbolKind::Function,
+                if is_test {
+                    SymbolKind::Test
+                } else {
+                    SymbolKind::Function
+                },
                 name.to_string(),
                 line,
                 if is_exported {
@@ -137,6 +152,8 @@ impl LanguageParser for GoParser {
         }
 
         symbols.sort_by_key(|s| s.line_range.start);
+        attach_doc_comments(content, &mut symbols, DocStyle::Line("//"));
+        attach_signatures(content, &mut symbols, ReturnStyle::Trailing);
         symbols
     }
 
@@ -206,3 +223,42 @@ fn find_brace_end(content: &str, start_offset: usize) -> Option<usize> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_test_functions_get_test_kind() {
+        let content = r#"package auth
+
+import "testing"
+
+func Login(user string) bool {
+	return user != ""
+}
+
+func TestLogin(t *testing.T) {
+	if !Login("a") {
+		t.Fatal("expected login")
+	}
+}
+
+func BenchmarkLogin(b *testing.B) {
+	for i := 0; i < b.N; i++ {
+		Login("a")
+	}
+}
+
+func Testify(s string) {}
+"#;
+
+        let symbols = GoParser.parse_symbols(content);
+        let kind_of = |name: &str| symbols.iter().find(|s| s.name == name).unwrap().kind;
+
+        assert_eq!(kind_of("Login"), SymbolKind::Function);
+        assert_eq!(kind_of("TestLogin"), SymbolKind::Test);
+        assert_eq!(kind_of("BenchmarkLogin"), SymbolKind::Test);
+        assert_eq!(kind_of("Testify"), SymbolKind::Function);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::analyze::lang::doc::{attach_doc_comments, DocStyle};
+use crate::analyze::lang::signature::{attach_signatures, ReturnStyle};
 use crate::analyze::lang::LanguageParser;
 use crate::types::{Symbol, SymbolKind, Visibility};
 use once_cell::sync::Lazy;
@@ -111,6 +113,8 @@ impl LanguageParser for JavaParser {
         }
 
         symbols.sort_by_key(|s| s.line_range.start);
+        attach_doc_comments(content, &mut symbols, DocStyle::Doc);
+        attach_signatures(content, &mut symbols, ReturnStyle::Prefix);
         symbols
     }
 }
@@ -1,3 +1,5 @@
+use crate::analyze::lang::doc::{attach_doc_comments, DocStyle};
+use crate::analyze::lang::signature::{attach_signatures, ReturnStyle};
 use crate::analyze::lang::LanguageParser;
 use crate::types::{Symbol, SymbolKind, Visibility};
 use once_cell::sync::Lazy;
@@ -26,6 +28,14 @@ static INTERFACE_PATTERN: Lazy<Regex> =
 static TYPE_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?m)^[ \t]*(export\s+)?type\s+(\w+)\s*=").unwrap());
 
+/// Test cases such as `it("works", ...)` or `test.only('case', ...)`, named by their description
+static TEST_CALL_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"(?m)^[ \t]*(?:it|test)(?:\.(?:only|skip|concurrent))?\s*\(\s*['"`](.+?)['"`]\s*,"#,
+    )
+    .unwrap()
+});
+
 static IMPORT_PATTERN: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r#"(?m)^[ \t]*import\s+(?:(?:\{[^}]*\}|[\w*]+)\s+from\s+)?['"]([^'"]+)['"]"#)
         .unwrap()
@@ -169,7 +179,26 @@ impl LanguageParser for JavaScriptParser {
             ));
         }
 
+        for cap in TEST_CALL_PATTERN.captures_iter(content) {
+            let name = cap.get(1).map(|m| m.as_str()).unwrap_or("");
+            let line = line_number_at_offset(content, cap.get(0).unwrap().start());
+            let end_line = find_paren_end(content, cap.get(0).unwrap().start());
+
+            let mut sym = Symbol::new(
+                SymbolKind::Test,
+                name.to_string(),
+                line,
+                Visibility::Private,
+            );
+            if let Some(end) = end_line {
+                sym = sym.with_line_range(line, end);
+            }
+            symbols.push(sym);
+        }
+
         symbols.sort_by_key(|s| s.line_range.start);
+        attach_doc_comments(content, &mut symbols, DocStyle::Doc);
+        attach_signatures(content, &mut symbols, ReturnStyle::Colon);
         symbols
     }
 
@@ -202,6 +231,24 @@ fn line_number_at_offset(content: &str, offset: usize) -> usize {
     content[..offset].matches('\n').count() + 1
 }
 
+/// Line of the `)` closing the first `(` at or after `start_offset`
+fn find_paren_end(content: &str, start_offset: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, b) in content.bytes().enumerate().skip(start_offset) {
+        match b {
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(line_number_at_offset(content, i));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
 fn find_brace_end(content: &str, start_offset: usize) -> Option<usize> {
     let bytes = content.as_bytes();
     let mut depth = 0;
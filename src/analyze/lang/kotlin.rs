@@ -0,0 +1,384 @@
+use crate::analyze::lang::doc::{attach_doc_comments, DocStyle};
+use crate::analyze::lang::signature::{attach_signatures, ReturnStyle};
+use crate::analyze::lang::LanguageParser;
+use crate::types::{Symbol, SymbolKind, Visibility};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+pub struct KotlinParser;
+
+// (public|private|...)? (data|sealed|enum|...)? class|interface|object Name
+// `companion object` is deliberately not matched: its members are picked up
+// by the function and property patterns like any other class member.
+static TYPE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^[ \t]*(?:@[\w.:]+(?:\([^)\n]*\))?\s+)*((?:(?:public|private|protected|internal|open|abstract|final|sealed|data|enum|annotation|inner|value|inline|fun|expect|actual)\s+)*)(class|interface|object)\s+(\w+)")
+        .unwrap()
+});
+
+// fun <T> Receiver.name(params): Type {
+static FUN_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^[ \t]*(?:@[\w.:]+(?:\([^)\n]*\))?\s+)*((?:(?:public|private|protected|internal|open|abstract|final|override|suspend|inline|operator|infix|tailrec|external|expect|actual)\s+)*)fun\s+(?:<[^>]*>\s*)?((?:\w+(?:<[^>]*>)?\??\.)+)?(\w+)\s*\(")
+        .unwrap()
+});
+
+// val|var Receiver.name: Type
+static PROPERTY_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^[ \t]*(?:@[\w.:]+(?:\([^)\n]*\))?\s+)*((?:(?:public|private|protected|internal|open|abstract|final|override|const|lateinit|expect|actual)\s+)*)(?:val|var)\s+(?:<[^>]*>\s*)?((?:\w+(?:<[^>]*>)?\??\.)+)?(\w+)")
+        .unwrap()
+});
+
+// companion object Name? {
+static COMPANION_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^[ \t]*(?:(?:public|private|protected|internal)\s+)?companion\s+object\b")
+        .unwrap()
+});
+
+impl LanguageParser for KotlinParser {
+    fn parse_symbols(&self, content: &str) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+        let blocks = brace_blocks(content);
+
+        // Opening braces of class, interface and object bodies; declarations
+        // directly inside these (or at the top level) are members, anything
+        // nested deeper is a local.
+        let mut containers = Vec::new();
+
+        for cap in TYPE_PATTERN.captures_iter(content) {
+            let modifiers = cap.get(1).map(|m| m.as_str()).unwrap_or("");
+            let keyword = cap.get(2).map(|m| m.as_str()).unwrap_or("class");
+            let name = cap.get(3).map(|m| m.as_str()).unwrap_or("");
+            let line = line_number_at_offset(content, cap.get(0).unwrap().start());
+
+            let kind = match keyword {
+                "interface" => SymbolKind::Interface,
+                _ if has_modifier(modifiers, "enum") => SymbolKind::Enum,
+                _ => SymbolKind::Class,
+            };
+
+            let (_, body) = find_header_end(content, cap.get(3).unwrap().end());
+            let end_line = body
+                .and_then(|open| {
+                    containers.push(open);
+                    find_brace_end(content, open)
+                })
+                .map(|pos| line_number_at_offset(content, pos))
+                .unwrap_or(line);
+
+            let signature = collapse_whitespace(&format!("{}{} {}", modifiers, keyword, name));
+            let mut sym = Symbol::new(kind, name.to_string(), line, parse_visibility(modifiers));
+            sym = sym.with_line_range(line, end_line);
+            sym = sym.with_signature(signature);
+            symbols.push(sym);
+        }
+
+        for m in COMPANION_PATTERN.find_iter(content) {
+            if let (_, Some(open)) = find_header_end(content, m.end()) {
+                containers.push(open);
+            }
+        }
+
+        let is_member = |offset: usize| match innermost_block(&blocks, offset) {
+            Some(open) => containers.contains(&open),
+            None => true,
+        };
+
+        for cap in FUN_PATTERN.captures_iter(content) {
+            let start = cap.get(0).unwrap().start();
+            if !is_member(start) {
+                continue;
+            }
+
+            let modifiers = cap.get(1).unwrap();
+            let name = cap.get(3).map(|m| m.as_str()).unwrap_or("");
+            let line = line_number_at_offset(content, start);
+
+            let (header_end, body) = find_header_end(content, cap.get(0).unwrap().end() - 1);
+            let end_line = body
+                .and_then(|open| find_brace_end(content, open))
+                .map(|pos| line_number_at_offset(content, pos))
+                .unwrap_or(line);
+
+            let signature = collapse_whitespace(&content[modifiers.start()..header_end]);
+
+            let mut sym = Symbol::new(
+                SymbolKind::Function,
+                name.to_string(),
+                line,
+                parse_visibility(modifiers.as_str()),
+            );
+            sym = sym.with_line_range(line, end_line);
+            sym = sym.with_signature(signature);
+            symbols.push(sym);
+        }
+
+        for cap in PROPERTY_PATTERN.captures_iter(content) {
+            let start = cap.get(0).unwrap().start();
+            if !is_member(start) {
+                continue;
+            }
+
+            let modifiers = cap.get(1).unwrap();
+            let name = cap.get(3).map(|m| m.as_str()).unwrap_or("");
+            let line = line_number_at_offset(content, start);
+
+            let (header_end, _) = find_header_end(content, cap.get(0).unwrap().end());
+            let signature = collapse_whitespace(&content[modifiers.start()..header_end]);
+
+            let mut sym = Symbol::new(
+                SymbolKind::Const,
+                name.to_string(),
+                line,
+                parse_visibility(modifiers.as_str()),
+            );
+            sym = sym.with_signature(signature);
+            symbols.push(sym);
+        }
+
+        symbols.sort_by_key(|s| s.line_range.start);
+        attach_doc_comments(content, &mut symbols, DocStyle::Doc);
+        attach_signatures(content, &mut symbols, ReturnStyle::Colon);
+        symbols
+    }
+}
+
+/// Kotlin declarations are public unless stated otherwise
+fn parse_visibility(modifiers: &str) -> Visibility {
+    if has_modifier(modifiers, "private") {
+        Visibility::Private
+    } else if has_modifier(modifiers, "protected") {
+        Visibility::Protected
+    } else if has_modifier(modifiers, "internal") {
+        Visibility::Internal
+    } else {
+        Visibility::Public
+    }
+}
+
+fn has_modifier(modifiers: &str, modifier: &str) -> bool {
+    modifiers.split_whitespace().any(|m| m == modifier)
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn line_number_at_offset(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+/// Scan a declaration header starting at `start` (a name or the opening
+/// parenthesis of a parameter list) and return where the header ends, plus
+/// the offset of the body's `{` when there is one. Headers may continue
+/// across lines inside parentheses or after a trailing `,`/`:`; an `=`
+/// (expression body or initializer) or a plain line break ends them.
+fn find_header_end(content: &str, start: usize) -> (usize, Option<usize>) {
+    let bytes = content.as_bytes();
+    let mut depth = 0i32;
+    let mut i = start;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' => depth += 1,
+            b')' => depth -= 1,
+            b'{' if depth == 0 => return (i, Some(i)),
+            b'=' if depth == 0 && bytes.get(i + 1) != Some(&b'>') => return (i, None),
+            b'\n' if depth == 0 => {
+                let before = content[start..i].trim_end();
+                let after = content[i..].trim_start();
+                let continues = before.ends_with(',')
+                    || before.ends_with(':')
+                    || after.starts_with('{')
+                    || after.starts_with(':')
+                    || after.starts_with(',')
+                    || after.starts_with("where ");
+                if !continues {
+                    return (i, None);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    (bytes.len(), None)
+}
+
+fn find_brace_end(content: &str, start: usize) -> Option<usize> {
+    let bytes = content.as_bytes();
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut string_char = b'"';
+    let mut i = start;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+
+        if in_string {
+            if b == string_char && (i == 0 || bytes[i - 1] != b'\\') {
+                in_string = false;
+            }
+            i += 1;
+            continue;
+        }
+
+        match b {
+            b'"' | b'\'' => {
+                in_string = true;
+                string_char = b;
+            }
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Every `{ ... }` pair in the file as (open, close) offsets, skipping braces
+/// inside string and char literals
+fn brace_blocks(content: &str) -> Vec<(usize, usize)> {
+    let bytes = content.as_bytes();
+    let mut blocks = Vec::new();
+    let mut open = Vec::new();
+    let mut in_string = false;
+    let mut string_char = b'"';
+
+    for (i, &b) in bytes.iter().enumerate() {
+        if in_string {
+            if b == string_char && (i == 0 || bytes[i - 1] != b'\\') {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match b {
+            b'"' | b'\'' => {
+                in_string = true;
+                string_char = b;
+            }
+            b'{' => open.push(i),
+            b'}' => {
+                if let Some(start) = open.pop() {
+                    blocks.push((start, i));
+                }
+            }
+            _ => {}
+        }
+    }
+    blocks
+}
+
+/// Opening brace of the innermost block containing `offset`, if any
+fn innermost_block(blocks: &[(usize, usize)], offset: usize) -> Option<usize> {
+    blocks
+        .iter()
+        .filter(|(open, close)| *open < offset && offset < *close)
+        .map(|(open, _)| *open)
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_declarations_with_visibility() {
+        let content = r#"package com.example
+
+data class User(val id: Long, val name: String)
+
+sealed class Result {
+    object Loading : Result()
+}
+
+enum class Color { RED, GREEN }
+
+internal interface Repo {
+    fun find(id: Long): User?
+}
+
+private const val MAX_USERS = 10
+
+class UserService(private val repo: Repo) {
+    protected var cache: Map<Long, User> = emptyMap()
+
+    fun load(id: Long): User? {
+        val local = repo.find(id)
+        return local
+    }
+
+    companion object {
+        const val TAG = "UserService"
+
+        fun create(repo: Repo): UserService = UserService(repo)
+    }
+}
+
+fun String.toSlug(): String = lowercase().replace(" ", "-")
+"#;
+
+        let symbols = KotlinParser.parse_symbols(content);
+        let find = |name: &str| {
+            let matches: Vec<_> = symbols.iter().filter(|s| s.name == name).collect();
+            assert_eq!(matches.len(), 1, "expected one symbol named {}", name);
+            matches[0]
+        };
+
+        assert_eq!(find("User").kind, SymbolKind::Class);
+        assert_eq!(find("Result").kind, SymbolKind::Class);
+        assert_eq!(find("Result").line_range.end, 7);
+        assert_eq!(find("Loading").kind, SymbolKind::Class);
+        assert_eq!(find("Color").kind, SymbolKind::Enum);
+        assert_eq!(find("Repo").kind, SymbolKind::Interface);
+        assert_eq!(find("Repo").visibility, Visibility::Internal);
+        assert_eq!(find("find").kind, SymbolKind::Function);
+
+        assert_eq!(find("MAX_USERS").kind, SymbolKind::Const);
+        assert_eq!(find("MAX_USERS").visibility, Visibility::Private);
+        assert_eq!(find("cache").visibility, Visibility::Protected);
+
+        let load = find("load");
+        assert_eq!(load.visibility, Visibility::Public);
+        assert_eq!(load.line_range.start, 20);
+        assert_eq!(load.line_range.end, 23);
+
+        // companion members are counted once, the companion itself not at all
+        find("TAG");
+        assert_eq!(
+            find("create").signature.as_deref(),
+            Some("fun create(repo: Repo): UserService")
+        );
+        assert!(!symbols
+            .iter()
+            .any(|s| s.name == "object" || s.name == "Companion"));
+
+        // locals are not members
+        assert!(!symbols.iter().any(|s| s.name == "local"));
+    }
+
+    #[test]
+    fn test_extension_function_keeps_receiver() {
+        let content = "fun String.toSlug(): String = lowercase()\n\nsuspend fun <T> List<T>.second(): T {\n    return this[1]\n}\n";
+
+        let symbols = KotlinParser.parse_symbols(content);
+
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].name, "toSlug");
+        assert_eq!(symbols[0].kind, SymbolKind::Function);
+        assert_eq!(
+            symbols[0].signature.as_deref(),
+            Some("fun String.toSlug(): String")
+        );
+        assert_eq!(symbols[1].name, "second");
+        assert_eq!(
+            symbols[1].signature.as_deref(),
+            Some("suspend fun <T> List<T>.second(): T")
+        );
+        assert_eq!(symbols[1].line_range.end, 5);
+    }
+}
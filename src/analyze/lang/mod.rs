@@ -2,13 +2,18 @@ mod c;
 mod cpp;
 mod csharp;
 mod dart;
+mod doc;
 mod go;
 mod java;
 mod javascript;
+mod kotlin;
 mod php;
 mod python;
 mod ruby;
 mod rust;
+mod sfc;
+mod signature;
+mod sql;
 mod swift;
 
 pub use c::CParser;
@@ -18,10 +23,13 @@ pub use dart::DartParser;
 pub use go::GoParser;
 pub use java::JavaParser;
 pub use javascript::JavaScriptParser;
+pub use kotlin::KotlinParser;
 pub use php::PhpParser;
 pub use python::PythonParser;
 pub use ruby::RubyParser;
 pub use rust::RustParser;
+pub use sfc::SfcParser;
+pub use sql::SqlParser;
 pub use swift::SwiftParser;
 
 use crate::types::{Language, Symbol};
@@ -33,6 +41,12 @@ pub trait LanguageParser {
         let _ = content;
         Vec::new()
     }
+
+    /// Imports that are part of the file's public surface (e.g. Rust `pub use`).
+    /// Languages without import visibility treat every import as public.
+    fn parse_public_imports(&self, content: &str) -> Vec<String> {
+        self.parse_imports(content)
+    }
 }
 
 pub fn get_parser(language: Language) -> Option<Box<dyn LanguageParser>> {
@@ -49,6 +63,9 @@ pub fn get_parser(language: Language) -> Option<Box<dyn LanguageParser>> {
         Language::Ruby => Some(Box::new(RubyParser)),
         Language::Dart => Some(Box::new(DartParser)),
         Language::Swift => Some(Box::new(SwiftParser)),
+        Language::Kotlin => Some(Box::new(KotlinParser)),
+        Language::Vue | Language::Svelte => Some(Box::new(SfcParser)),
+        Language::Sql => Some(Box::new(SqlParser)),
         Language::Unknown => None,
     }
 }
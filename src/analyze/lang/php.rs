@@ -1,3 +1,5 @@
+use crate::analyze::lang::doc::{attach_doc_comments, DocStyle};
+use crate::analyze::lang::signature::{attach_signatures, ReturnStyle};
 use crate::analyze::lang::LanguageParser;
 use crate::types::{Symbol, SymbolKind, Visibility};
 use once_cell::sync::Lazy;
@@ -5,91 +7,127 @@ use regex::Regex;
 
 pub struct PhpParser;
 
-static CLASS_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?m)^\s*(abstract\s+)?(final\s+)?class\s+(\w+)").unwrap());
+static NAMESPACE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*namespace\s+([\w\\]+)\s*([;{])").unwrap());
+
+static CLASS_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^[ \t]*((?:(?:abstract|final|readonly)\s+)*)class\s+(\w+)").unwrap()
+});
 
 static INTERFACE_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?m)^\s*interface\s+(\w+)").unwrap());
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*interface\s+(\w+)").unwrap());
+
+static TRAIT_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?m)^[ \t]*trait\s+(\w+)").unwrap());
 
-static TRAIT_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?m)^\s*trait\s+(\w+)").unwrap());
+static ENUM_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?m)^[ \t]*enum\s+(\w+)").unwrap());
 
+// Named functions only: closures (`function (`) and arrow functions (`fn (`) have no name
 static FUNCTION_PATTERN: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?m)^\s*(public|private|protected|static|\s)*\s*function\s+(\w+)\s*\(").unwrap()
+    Regex::new(
+        r"(?m)^[ \t]*((?:(?:public|private|protected|static|abstract|final)\s+)*)function\s+&?(\w+)\s*\(",
+    )
+    .unwrap()
 });
 
-static CONST_PATTERN: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"(?m)^\s*(public|private|protected)?\s*const\s+(\w+)\s*=").unwrap());
+static CONST_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?m)^[ \t]*(?:final\s+)?(public|private|protected)?\s*(?:final\s+)?const\s+(\w+)\s*=",
+    )
+    .unwrap()
+});
 
 impl LanguageParser for PhpParser {
     fn parse_symbols(&self, content: &str) -> Vec<Symbol> {
         let mut symbols = Vec::new();
+        let total_lines = content.lines().count().max(1);
 
-        for cap in CLASS_PATTERN.captures_iter(content) {
-            let name = cap.get(3).map(|m| m.as_str()).unwrap_or("");
+        let namespaces: Vec<_> = NAMESPACE_PATTERN.captures_iter(content).collect();
+        for (i, cap) in namespaces.iter().enumerate() {
+            let name = cap.get(1).map(|m| m.as_str()).unwrap_or("");
             let line = line_number_at_offset(content, cap.get(0).unwrap().start());
-            let is_abstract = cap.get(1).is_some();
-
-            let full_match = cap.get(0).unwrap().as_str().trim();
-            let end_line = find_brace_end(content, cap.get(0).unwrap().end())
-                .map(|pos| line_number_at_offset(content, pos))
-                .unwrap_or(line);
+            let end_line = if &cap[2] == "{" {
+                find_brace_end(content, cap.get(2).unwrap().start())
+                    .map(|pos| line_number_at_offset(content, pos))
+                    .unwrap_or(line)
+            } else {
+                // `namespace X;` runs until the next namespace or the end of the file
+                namespaces
+                    .get(i + 1)
+                    .map(|next| line_number_at_offset(content, next.get(0).unwrap().start()) - 1)
+                    .unwrap_or(total_lines)
+            };
 
-            let mut sym = Symbol::new(
-                SymbolKind::Class,
+            let sym = Symbol::new(
+                SymbolKind::Module,
                 name.to_string(),
                 line,
                 Visibility::Public,
-            );
-            sym = sym.with_line_range(line, end_line);
-            if is_abstract {
-                sym = sym.with_signature(format!("abstract class {}", name));
-            } else {
-                sym = sym.with_signature(full_match.to_string());
-            }
+            )
+            .with_line_range(line, end_line)
+            .with_signature(format!("namespace {}", name));
             symbols.push(sym);
         }
 
-        for cap in INTERFACE_PATTERN.captures_iter(content) {
-            let name = cap.get(1).map(|m| m.as_str()).unwrap_or("");
+        for cap in CLASS_PATTERN.captures_iter(content) {
+            let modifiers = cap.get(1).map(|m| m.as_str()).unwrap_or("");
+            let name = cap.get(2).map(|m| m.as_str()).unwrap_or("");
             let line = line_number_at_offset(content, cap.get(0).unwrap().start());
+
             let end_line = find_brace_end(content, cap.get(0).unwrap().end())
                 .map(|pos| line_number_at_offset(content, pos))
                 .unwrap_or(line);
 
-            let mut sym = Symbol::new(
-                SymbolKind::Interface,
+            let modifiers = modifiers.split_whitespace().collect::<Vec<_>>().join(" ");
+            let signature = if modifiers.is_empty() {
+                format!("class {}", name)
+            } else {
+                format!("{} class {}", modifiers, name)
+            };
+
+            let sym = Symbol::new(
+                SymbolKind::Class,
                 name.to_string(),
                 line,
                 Visibility::Public,
-            );
-            sym = sym.with_line_range(line, end_line);
-            sym = sym.with_signature(format!("interface {}", name));
+            )
+            .with_line_range(line, end_line)
+            .with_signature(signature);
             symbols.push(sym);
         }
 
-        for cap in TRAIT_PATTERN.captures_iter(content) {
-            let name = cap.get(1).map(|m| m.as_str()).unwrap_or("");
-            let line = line_number_at_offset(content, cap.get(0).unwrap().start());
-            let end_line = find_brace_end(content, cap.get(0).unwrap().end())
-                .map(|pos| line_number_at_offset(content, pos))
-                .unwrap_or(line);
+        let containers = [
+            (&*INTERFACE_PATTERN, SymbolKind::Interface, "interface"),
+            (&*TRAIT_PATTERN, SymbolKind::Trait, "trait"),
+            (&*ENUM_PATTERN, SymbolKind::Enum, "enum"),
+        ];
+        for (pattern, kind, keyword) in containers {
+            for cap in pattern.captures_iter(content) {
+                let name = cap.get(1).map(|m| m.as_str()).unwrap_or("");
+                let line = line_number_at_offset(content, cap.get(0).unwrap().start());
+                let end_line = find_brace_end(content, cap.get(0).unwrap().end())
+                    .map(|pos| line_number_at_offset(content, pos))
+                    .unwrap_or(line);
 
-            let mut sym = Symbol::new(
-                SymbolKind::Trait,
-                name.to_string(),
-                line,
-                Visibility::Public,
-            );
-            sym = sym.with_line_range(line, end_line);
-            sym = sym.with_signature(format!("trait {}", name));
-            symbols.push(sym);
+                let sym = Symbol::new(kind, name.to_string(), line, Visibility::Public)
+                    .with_line_range(line, end_line)
+                    .with_signature(format!("{} {}", keyword, name));
+                symbols.push(sym);
+            }
         }
 
+        // Functions declared inside a class-like body are methods
+        let type_bodies: Vec<(usize, usize)> = symbols
+            .iter()
+            .filter(|s| s.kind != SymbolKind::Module)
+            .map(|s| (s.line_range.start, s.line_range.end))
+            .collect();
+
         for cap in FUNCTION_PATTERN.captures_iter(content) {
             let modifiers = cap.get(1).map(|m| m.as_str()).unwrap_or("");
             let name = cap.get(2).map(|m| m.as_str()).unwrap_or("");
             let line = line_number_at_offset(content, cap.get(0).unwrap().start());
 
+            // PHP members without a modifier are public
             let visibility = if modifiers.contains("private") {
                 Visibility::Private
             } else if modifiers.contains("protected") {
@@ -98,16 +136,25 @@ impl LanguageParser for PhpParser {
                 Visibility::Public
             };
 
-            let end_line = find_brace_end(content, cap.get(0).unwrap().end())
+            let end_line = find_body_end(content, cap.get(0).unwrap().end() - 1)
                 .map(|pos| line_number_at_offset(content, pos))
                 .unwrap_or(line);
 
+            let kind = if type_bodies
+                .iter()
+                .any(|&(start, end)| start < line && line <= end)
+            {
+                SymbolKind::Method
+            } else {
+                SymbolKind::Function
+            };
+
             let full_match = cap.get(0).unwrap().as_str().trim();
             let signature = full_match.trim_end_matches('(').to_string() + "(...)";
 
-            let mut sym = Symbol::new(SymbolKind::Function, name.to_string(), line, visibility);
-            sym = sym.with_line_range(line, end_line);
-            sym = sym.with_signature(signature);
+            let sym = Symbol::new(kind, name.to_string(), line, visibility)
+                .with_line_range(line, end_line)
+                .with_signature(signature);
             symbols.push(sym);
         }
 
@@ -127,10 +174,39 @@ impl LanguageParser for PhpParser {
         }
 
         symbols.sort_by_key(|s| s.line_range.start);
+        attach_doc_comments(content, &mut symbols, DocStyle::Doc);
+        attach_signatures(content, &mut symbols, ReturnStyle::Colon);
         symbols
     }
 }
 
+/// End of a function body given the offset of its parameter list's `(`.
+/// Abstract and interface methods end at their `;`.
+fn find_body_end(content: &str, paren_open: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut params_end = None;
+    for (i, c) in content[paren_open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    params_end = Some(paren_open + i);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let after = params_end? + 1;
+    let body = after + content[after..].find(['{', ';'])?;
+    if content[body..].starts_with(';') {
+        return Some(body);
+    }
+    find_brace_end(content, body)
+}
+
 fn line_number_at_offset(content: &str, offset: usize) -> usize {
     content[..offset].matches('\n').count() + 1
 }
@@ -171,3 +247,102 @@ fn find_brace_end(content: &str, start: usize) -> Option<usize> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(symbols: &'a [Symbol], name: &str) -> &'a Symbol {
+        symbols.iter().find(|s| s.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_namespaces_types_and_methods() {
+        let content = r#"<?php
+namespace App\Http;
+
+abstract class Controller
+{
+    abstract protected function authorize(Request $r): bool;
+
+    function handle(Request $r)
+    {
+        $double = fn ($x) => $x * 2;
+        $cb = function ($y) { return $y; };
+        return $double(1);
+    }
+
+    final public static function make(): static
+    {
+        return new static();
+    }
+
+    private function log() {}
+}
+
+enum Status: string
+{
+    case Active = 'active';
+
+    public function label(): string
+    {
+        return ucfirst($this->value);
+    }
+}
+
+function helper() {}
+"#;
+        let symbols = PhpParser.parse_symbols(content);
+
+        let namespace = find(&symbols, "App\\Http");
+        assert_eq!(namespace.kind, SymbolKind::Module);
+        assert_eq!(namespace.line_range.end, content.lines().count());
+
+        let controller = find(&symbols, "Controller");
+        assert_eq!(
+            controller.signature.as_deref(),
+            Some("abstract class Controller")
+        );
+        assert_eq!(controller.line_range, crate::types::LineRange::new(4, 21));
+
+        let authorize = find(&symbols, "authorize");
+        assert_eq!(authorize.kind, SymbolKind::Method);
+        assert_eq!(authorize.visibility, Visibility::Protected);
+        assert_eq!(authorize.line_range.end, 6);
+
+        let handle = find(&symbols, "handle");
+        assert_eq!(handle.kind, SymbolKind::Method);
+        assert_eq!(handle.visibility, Visibility::Public);
+        assert_eq!(handle.line_range.end, 13);
+
+        assert_eq!(find(&symbols, "make").visibility, Visibility::Public);
+        assert_eq!(find(&symbols, "log").visibility, Visibility::Private);
+
+        assert_eq!(find(&symbols, "Status").kind, SymbolKind::Enum);
+        assert_eq!(find(&symbols, "label").kind, SymbolKind::Method);
+        assert_eq!(find(&symbols, "helper").kind, SymbolKind::Function);
+
+        // Closures and arrow functions are not named symbols
+        let functions = symbols
+            .iter()
+            .filter(|s| matches!(s.kind, SymbolKind::Function | SymbolKind::Method))
+            .count();
+        assert_eq!(functions, 6);
+    }
+
+    #[test]
+    fn test_braced_namespaces() {
+        let content =
+            "<?php\nnamespace Lib {\n    class A {}\n}\nnamespace Tests {\n    class ATest {}\n}\n";
+        let symbols = PhpParser.parse_symbols(content);
+
+        assert_eq!(
+            find(&symbols, "Lib").line_range,
+            crate::types::LineRange::new(2, 4)
+        );
+        assert_eq!(
+            find(&symbols, "Tests").line_range,
+            crate::types::LineRange::new(5, 7)
+        );
+    }
+}
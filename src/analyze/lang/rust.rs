@@ -1,3 +1,5 @@
+use crate::analyze::lang::doc::{attach_doc_comments, DocStyle};
+use crate::analyze::lang::signature::{attach_signatures, ReturnStyle};
 use crate::analyze::lang::LanguageParser;
 use crate::types::{Symbol, SymbolKind, Visibility};
 use once_cell::sync::Lazy;
@@ -8,6 +10,9 @@ pub struct RustParser;
 static FN_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?m)^[ \t]*(pub(?:\([^)]+\))?\s+)?(?:async\s+)?fn\s+(\w+)").unwrap());
 
+/// `#[test]`, `#[tokio::test]`, `#[rstest::test(...)]` and similar attributes
+static TEST_ATTR_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^#\[(?:\w+::)*test\b").unwrap());
+
 static STRUCT_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?m)^[ \t]*(pub(?:\([^)]+\))?\s+)?struct\s+(\w+)").unwrap());
 
@@ -29,6 +34,12 @@ static USE_PATTERN: Lazy<Regex> =
 static MOD_PATTERN: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?m)^[ \t]*(?:pub\s+)?mod\s+(\w+)\s*;").unwrap());
 
+static PUB_USE_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*pub\s+use\s+(?:crate::)?(\w+)").unwrap());
+
+static PUB_MOD_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*pub\s+mod\s+(\w+)\s*;").unwrap());
+
 impl LanguageParser for RustParser {
     fn parse_symbols(&self, content: &str) -> Vec<Symbol> {
         let mut symbols = Vec::new();
@@ -40,9 +51,14 @@ impl LanguageParser for RustParser {
             let line = line_number_at_offset(content, cap.get(0).unwrap().start());
             let signature = lines.get(line - 1).map(|s| s.trim().to_string());
             let end_line = find_brace_end(content, cap.get(0).unwrap().end());
+            let kind = if has_test_attribute(&lines, line) {
+                SymbolKind::Test
+            } else {
+                SymbolKind::Function
+            };
 
             let mut sym = Symbol::new(
-                SymbolKind::Function,
+                kind,
                 name.to_string(),
                 line,
                 if is_pub {
@@ -161,6 +177,8 @@ impl LanguageParser for RustParser {
         }
 
         symbols.sort_by_key(|s| s.line_range.start);
+        attach_doc_comments(content, &mut symbols, DocStyle::Doc);
+        attach_signatures(content, &mut symbols, ReturnStyle::Arrow);
         symbols
     }
 
@@ -187,6 +205,35 @@ impl LanguageParser for RustParser {
 
         imports
     }
+
+    /// Only `pub use` re-exports and `pub mod` declarations; restricted
+    /// visibility such as `pub(crate) use` counts as private plumbing.
+    fn parse_public_imports(&self, content: &str) -> Vec<String> {
+        let mut imports = Vec::new();
+
+        for pattern in [&PUB_USE_PATTERN, &PUB_MOD_PATTERN] {
+            for cap in pattern.captures_iter(content) {
+                if let Some(m) = cap.get(1) {
+                    let module = m.as_str().to_string();
+                    if !imports.contains(&module) {
+                        imports.push(module);
+                    }
+                }
+            }
+        }
+
+        imports
+    }
+}
+
+/// Whether the attributes stacked directly above the 1-based `line` mark a test
+fn has_test_attribute(lines: &[&str], line: usize) -> bool {
+    lines[..line - 1]
+        .iter()
+        .rev()
+        .map(|l| l.trim())
+        .take_while(|l| l.starts_with("#[") || l.starts_with("//"))
+        .any(|l| TEST_ATTR_PATTERN.is_match(l))
 }
 
 fn line_number_at_offset(content: &str, offset: usize) -> usize {
@@ -218,3 +265,38 @@ fn find_brace_end(content: &str, start_offset: usize) -> Option<usize> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_test_functions_get_test_kind() {
+        let content = r#"pub fn parse(input: &str) -> u32 {
+    input.len() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn parses_empty() {
+        assert_eq!(parse(""), 0);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn parses_async() {}
+
+    fn helper() {}
+}
+"#;
+
+        let symbols = RustParser.parse_symbols(content);
+        let kind_of = |name: &str| symbols.iter().find(|s| s.name == name).unwrap().kind;
+
+        assert_eq!(kind_of("parse"), SymbolKind::Function);
+        assert_eq!(kind_of("parses_empty"), SymbolKind::Test);
+        assert_eq!(kind_of("parses_async"), SymbolKind::Test);
+        assert_eq!(kind_of("helper"), SymbolKind::Function);
+    }
+}
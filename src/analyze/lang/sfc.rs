@@ -0,0 +1,89 @@
+use crate::analyze::lang::{JavaScriptParser, LanguageParser};
+use crate::types::Symbol;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Parser for single-file components (Vue SFC, Svelte).
+///
+/// Splits the file into its `<script>`, `<template>` and `<style>` sections and
+/// runs the JavaScript/TypeScript parser over each script block, shifting line
+/// numbers back to their position in the original file. Template and style
+/// sections carry no symbols and are skipped.
+pub struct SfcParser;
+
+static SCRIPT_BLOCK: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)<script\b[^>]*>(.*?)</script\s*>").unwrap());
+
+impl LanguageParser for SfcParser {
+    fn parse_symbols(&self, content: &str) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+
+        for (line_offset, script) in script_blocks(content) {
+            for mut sym in JavaScriptParser.parse_symbols(script) {
+                sym.line_range.start += line_offset;
+                sym.line_range.end += line_offset;
+                symbols.push(sym);
+            }
+        }
+
+        symbols.sort_by_key(|s| s.line_range.start);
+        symbols
+    }
+
+    fn parse_imports(&self, content: &str) -> Vec<String> {
+        let mut imports = Vec::new();
+
+        for (_, script) in script_blocks(content) {
+            for import in JavaScriptParser.parse_imports(script) {
+                if !imports.contains(&import) {
+                    imports.push(import);
+                }
+            }
+        }
+
+        imports
+    }
+}
+
+/// Extract `<script>` bodies with the number of lines preceding each one
+fn script_blocks(content: &str) -> Vec<(usize, &str)> {
+    SCRIPT_BLOCK
+        .captures_iter(content)
+        .filter_map(|cap| cap.get(1))
+        .map(|body| (content[..body.start()].matches('\n').count(), body.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SymbolKind;
+
+    #[test]
+    fn test_extracts_function_from_script_block_with_line_offset() {
+        let content = r#"<template>
+  <button @click="increment">{{ count }}</button>
+</template>
+
+<script setup lang="ts">
+import { ref } from 'vue'
+
+function increment() {
+  count.value++
+}
+</script>
+
+<style scoped>
+button { color: red; }
+</style>
+"#;
+
+        let symbols = SfcParser.parse_symbols(content);
+        let increment = symbols.iter().find(|s| s.name == "increment").unwrap();
+
+        assert_eq!(increment.kind, SymbolKind::Function);
+        assert_eq!(increment.line_range.start, 8);
+        assert_eq!(increment.line_range.end, 10);
+        assert_eq!(SfcParser.parse_imports(content), vec!["vue".to_string()]);
+    }
+}
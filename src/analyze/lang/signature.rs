@@ -0,0 +1,333 @@
+//! Parameter lists and return types of functions, read from the source at
+//! each symbol's declaration after symbols are parsed.
+
+use crate::types::{Symbol, SymbolKind};
+
+/// Where a language writes a function's return type
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ReturnStyle {
+    /// `fn f(a) -> T` (Rust, Swift, Python)
+    Arrow,
+    /// `function f(a): T` (TypeScript, Kotlin, PHP)
+    Colon,
+    /// `func f(a) T` (Go)
+    Trailing,
+    /// `T f(a)` (Java, C#, C, C++, Dart)
+    Prefix,
+    /// `f(a) RETURNS T` (SQL)
+    Returns,
+    /// No return type syntax (Ruby)
+    Untyped,
+}
+
+/// Modifiers that can precede a prefix return type and are not part of it
+const PREFIX_MODIFIERS: &[&str] = &[
+    "public",
+    "private",
+    "protected",
+    "internal",
+    "static",
+    "final",
+    "abstract",
+    "virtual",
+    "override",
+    "sealed",
+    "async",
+    "inline",
+    "extern",
+    "unsafe",
+    "synchronized",
+    "native",
+    "new",
+    "partial",
+    "readonly",
+    "external",
+    "factory",
+    "constexpr",
+    "explicit",
+    "default",
+];
+
+/// Fill in `params` and `return_type` for functions, methods and tests, and
+/// derive `signature` from them when the parser left it empty
+pub(crate) fn attach_signatures(content: &str, symbols: &mut [Symbol], style: ReturnStyle) {
+    let lines: Vec<&str> = content.lines().collect();
+    let line_starts = line_offsets(content);
+
+    for sym in symbols.iter_mut() {
+        if !matches!(
+            sym.kind,
+            SymbolKind::Function | SymbolKind::Method | SymbolKind::Test
+        ) {
+            continue;
+        }
+
+        // Some patterns match from leading blank lines; start at the declaration
+        let mut decl = sym.line_range.start.saturating_sub(1);
+        while decl < lines.len() && lines[decl].trim().is_empty() {
+            decl += 1;
+        }
+        let Some(&decl_start) = line_starts.get(decl) else {
+            continue;
+        };
+
+        let Some((name_start, open)) = find_param_list(content, decl_start, &sym.name) else {
+            continue;
+        };
+        let Some(close) = matching_paren(content, open) else {
+            continue;
+        };
+
+        sym.params = split_top_level(&content[open + 1..close]);
+        sym.return_type = match style {
+            ReturnStyle::Prefix => prefix_return_type(&content[decl_start..name_start]),
+            _ => suffix_return_type(&content[close + 1..], style),
+        };
+
+        if sym.signature.is_none() {
+            let mut signature = format!("{}({})", sym.name, sym.params.join(", "));
+            if let Some(return_type) = &sym.return_type {
+                signature.push_str(&format!(" -> {}", return_type));
+            }
+            sym.signature = Some(signature);
+        }
+    }
+}
+
+fn line_offsets(content: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+/// Offsets of `name` and of the `(` opening its parameter list, searching a
+/// few lines from `from`. Allows generics (`f<T>(`) and JS assignments
+/// (`f = async (`, `f = function (`) between the two.
+fn find_param_list(content: &str, from: usize, name: &str) -> Option<(usize, usize)> {
+    let window_end = content[from..]
+        .match_indices('\n')
+        .nth(4)
+        .map(|(i, _)| from + i)
+        .unwrap_or(content.len());
+    let window = &content[from..window_end];
+
+    for (i, _) in window.match_indices(name) {
+        let before = window[..i].chars().next_back();
+        if before.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            continue;
+        }
+
+        let mut rest = &window[i + name.len()..];
+        if rest.starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+            continue;
+        }
+        rest = rest.trim_start();
+        if rest.starts_with('<') {
+            let Some(end) = rest.find('>') else { continue };
+            rest = rest[end + 1..].trim_start();
+        }
+        if let Some(assigned) = rest.strip_prefix('=') {
+            rest = assigned.trim_start();
+            rest = rest.strip_prefix("async").unwrap_or(rest).trim_start();
+            rest = rest.strip_prefix("function").unwrap_or(rest).trim_start();
+        }
+        if rest.starts_with('(') {
+            return Some((from + i, from + window.len() - rest.len()));
+        }
+    }
+    None
+}
+
+fn matching_paren(content: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in content[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Comma-separated items at bracket depth zero, trimmed and whitespace-collapsed
+fn split_top_level(list: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+
+    for (i, c) in list.char_indices() {
+        match c {
+            '(' | '[' | '{' | '<' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            // `->` and `=>` are not closing brackets
+            '>' if !list[..i].ends_with(['-', '=']) => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(&list[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&list[start..]);
+
+    items
+        .into_iter()
+        .map(collapse_whitespace)
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// Return type written after the parameter list, up to where the body begins
+fn suffix_return_type(after: &str, style: ReturnStyle) -> Option<String> {
+    let header_end = after
+        .find(['{', ';', '\n'])
+        .unwrap_or(after.len())
+        .min(after.find("=>").unwrap_or(after.len()));
+    let header = after[..header_end].trim();
+
+    let return_type = match style {
+        ReturnStyle::Arrow => header.split_once("->")?.1,
+        ReturnStyle::Colon => header.strip_prefix(':')?,
+        ReturnStyle::Trailing => header,
+        ReturnStyle::Returns => {
+            // RETURNS usually sits on its own line, so look past the first line break
+            let clause = &after[..after.find([';', '$']).unwrap_or(after.len())];
+            let at = clause.to_ascii_lowercase().find("returns")?;
+            let words: Vec<&str> = clause[at + "returns".len()..]
+                .split_whitespace()
+                .take_while(|w| {
+                    !w.eq_ignore_ascii_case("as") && !w.eq_ignore_ascii_case("language")
+                })
+                .collect();
+            return (!words.is_empty()).then(|| words.join(" "));
+        }
+        ReturnStyle::Prefix | ReturnStyle::Untyped => return None,
+    };
+
+    // Rust `where` clauses, Python's trailing `:`, Kotlin expression bodies
+    let return_type = return_type
+        .split(" where ")
+        .next()
+        .unwrap_or("")
+        .split('=')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .trim_end_matches(':')
+        .trim();
+    (!return_type.is_empty()).then(|| collapse_whitespace(return_type))
+}
+
+/// Return type written before the name, minus modifiers and annotations
+fn prefix_return_type(before_name: &str) -> Option<String> {
+    let words: Vec<&str> = before_name
+        .split_whitespace()
+        // Annotations, attributes, generic parameters and `Class::` qualifiers
+        .filter(|w| !w.starts_with(['@', '[', '<']) && !w.ends_with("::"))
+        .filter(|w| !PREFIX_MODIFIERS.contains(w))
+        .collect();
+    // Constructors and plain calls have nothing left
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze::lang::{
+        GoParser, JavaParser, JavaScriptParser, LanguageParser, PythonParser, RustParser,
+        SqlParser, SwiftParser,
+    };
+
+    fn find<'a>(symbols: &'a [Symbol], name: &str) -> &'a Symbol {
+        symbols.iter().find(|s| s.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_arrow_return_types() {
+        let swift = SwiftParser.parse_symbols(
+            "func fetch(id: Int, force: Bool = false) async throws -> [User] {\n    return []\n}\n",
+        );
+        let fetch = find(&swift, "fetch");
+        assert_eq!(fetch.params, vec!["id: Int", "force: Bool = false"]);
+        assert_eq!(fetch.return_type.as_deref(), Some("[User]"));
+
+        let rust = RustParser.parse_symbols(
+            "pub fn merge<T: Ord>(a: Vec<T>, b: HashMap<String, T>) -> Result<Vec<T>>\nwhere\n    T: Clone,\n{\n}\n\nfn run(&self) {}\n",
+        );
+        let merge = find(&rust, "merge");
+        assert_eq!(merge.params, vec!["a: Vec<T>", "b: HashMap<String, T>"]);
+        assert_eq!(merge.return_type.as_deref(), Some("Result<Vec<T>>"));
+        assert_eq!(find(&rust, "run").params, vec!["&self"]);
+        assert_eq!(find(&rust, "run").return_type, None);
+
+        let python = PythonParser
+            .parse_symbols("def load(path, *, strict=False) -> dict[str, int]:\n    pass\n");
+        let load = find(&python, "load");
+        assert_eq!(load.params, vec!["path", "*", "strict=False"]);
+        assert_eq!(load.return_type.as_deref(), Some("dict[str, int]"));
+    }
+
+    #[test]
+    fn test_colon_trailing_and_prefix_return_types() {
+        let ts = JavaScriptParser.parse_symbols(
+            "export function parse(input: string, opts: Options): Promise<Ast> {\n  return null;\n}\n",
+        );
+        let parse = find(&ts, "parse");
+        assert_eq!(parse.params.len(), 2);
+        assert_eq!(parse.return_type.as_deref(), Some("Promise<Ast>"));
+
+        let go = GoParser.parse_symbols(
+            "package db\n\nfunc (s *Store) Get(ctx context.Context, key string) (string, error) {\n\treturn \"\", nil\n}\n",
+        );
+        let get = find(&go, "Get");
+        assert_eq!(get.params, vec!["ctx context.Context", "key string"]);
+        assert_eq!(get.return_type.as_deref(), Some("(string, error)"));
+
+        let java = JavaParser.parse_symbols(
+            "public class Repo {\n    public static List<User> findAll(int limit, String order) {\n        return null;\n    }\n}\n",
+        );
+        let find_all = find(&java, "findAll");
+        assert_eq!(find_all.params, vec!["int limit", "String order"]);
+        assert_eq!(find_all.return_type.as_deref(), Some("List<User>"));
+        // The parser's own signature is kept
+        assert!(find_all.signature.as_deref().unwrap().ends_with("(...)"));
+    }
+
+    #[test]
+    fn test_sql_returns_clause() {
+        let sql = SqlParser.parse_symbols(
+            "CREATE FUNCTION add_one(x integer)\nRETURNS integer AS $$\n  SELECT x + 1;\n$$ LANGUAGE sql;\n",
+        );
+        let add_one = find(&sql, "add_one");
+        assert_eq!(add_one.params, vec!["x integer"]);
+        assert_eq!(add_one.return_type.as_deref(), Some("integer"));
+    }
+
+    #[test]
+    fn test_signature_derived_when_absent() {
+        let content = "fn area(w: f32, h: f32) -> f32 {\n    w * h\n}\n";
+        let mut symbols = vec![Symbol::new(
+            SymbolKind::Function,
+            "area".to_string(),
+            1,
+            crate::types::Visibility::Private,
+        )];
+
+        attach_signatures(content, &mut symbols, ReturnStyle::Arrow);
+
+        assert_eq!(
+            symbols[0].signature.as_deref(),
+            Some("area(w: f32, h: f32) -> f32")
+        );
+    }
+}
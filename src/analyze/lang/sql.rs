@@ -0,0 +1,182 @@
+use crate::analyze::lang::doc::{attach_doc_comments, DocStyle};
+use crate::analyze::lang::signature::{attach_signatures, ReturnStyle};
+use crate::analyze::lang::LanguageParser;
+use crate::types::{Symbol, SymbolKind, Visibility};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Parser for SQL schema and migration files.
+///
+/// Extracts `CREATE TABLE`, `CREATE VIEW`, `CREATE FUNCTION` and `ALTER TABLE`
+/// statements. SQL has no notion of visibility, so every symbol is public.
+pub struct SqlParser;
+
+/// Optionally schema-qualified and quoted identifier, e.g. `public."users"`
+const IDENT: &str = r#"((?:[`"\[]?\w+[`"\]]?\.)?[`"\[]?\w+[`"\]]?)"#;
+
+static TABLE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?im)^[ \t]*CREATE\s+(?:OR\s+REPLACE\s+)?(?:(?:GLOBAL\s+|LOCAL\s+)?(?:TEMP|TEMPORARY)\s+|UNLOGGED\s+)?TABLE\s+(?:IF\s+NOT\s+EXISTS\s+)?{IDENT}"
+    ))
+    .unwrap()
+});
+
+static VIEW_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?im)^[ \t]*CREATE\s+(?:OR\s+REPLACE\s+)?(?:(?:TEMP|TEMPORARY)\s+)?(?:MATERIALIZED\s+)?VIEW\s+(?:IF\s+NOT\s+EXISTS\s+)?{IDENT}"
+    ))
+    .unwrap()
+});
+
+static FUNCTION_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?im)^[ \t]*CREATE\s+(?:OR\s+REPLACE\s+)?(?:FUNCTION|PROCEDURE)\s+(?:IF\s+NOT\s+EXISTS\s+)?{IDENT}"
+    ))
+    .unwrap()
+});
+
+static ALTER_TABLE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?im)^[ \t]*ALTER\s+TABLE\s+(?:IF\s+EXISTS\s+)?(?:ONLY\s+)?{IDENT}"
+    ))
+    .unwrap()
+});
+
+impl LanguageParser for SqlParser {
+    fn parse_symbols(&self, content: &str) -> Vec<Symbol> {
+        let lines: Vec<&str> = content.lines().collect();
+        let mut symbols = Vec::new();
+
+        let patterns: [(&Regex, SymbolKind); 4] = [
+            (&TABLE_PATTERN, SymbolKind::Struct),
+            (&VIEW_PATTERN, SymbolKind::Type),
+            (&FUNCTION_PATTERN, SymbolKind::Function),
+            (&ALTER_TABLE_PATTERN, SymbolKind::Struct),
+        ];
+
+        for (pattern, kind) in patterns {
+            for cap in pattern.captures_iter(content) {
+                let name = cap.get(1).map(|m| unquote(m.as_str())).unwrap_or_default();
+                let statement = cap.get(0).unwrap();
+                let line = line_number_at_offset(content, statement.start());
+                let end_line = find_statement_end(content, statement.end());
+
+                let mut sym = Symbol::new(kind, name, line, Visibility::Public);
+                if let Some(sig) = lines.get(line - 1) {
+                    sym = sym.with_signature(sig.trim().to_string());
+                }
+                sym = sym.with_line_range(line, end_line);
+                symbols.push(sym);
+            }
+        }
+
+        symbols.sort_by_key(|s| s.line_range.start);
+        attach_doc_comments(content, &mut symbols, DocStyle::Line("--"));
+        attach_signatures(content, &mut symbols, ReturnStyle::Returns);
+        symbols
+    }
+}
+
+/// Strip identifier quoting from each dotted part
+fn unquote(ident: &str) -> String {
+    ident
+        .split('.')
+        .map(|part| part.trim_matches(|c| matches!(c, '"' | '`' | '[' | ']')))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn line_number_at_offset(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+/// Line of the `;` terminating the statement, skipping quoted strings and
+/// `$$`-delimited function bodies. Falls back to the last line of the file.
+fn find_statement_end(content: &str, start: usize) -> usize {
+    let bytes = content.as_bytes();
+    let mut in_string = false;
+    let mut in_dollar = false;
+    let mut i = start;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        if in_dollar {
+            if bytes[i..].starts_with(b"$$") {
+                in_dollar = false;
+                i += 1;
+            }
+        } else if in_string {
+            if b == b'\'' {
+                in_string = false;
+            }
+        } else if bytes[i..].starts_with(b"$$") {
+            in_dollar = true;
+            i += 1;
+        } else if b == b'\'' {
+            in_string = true;
+        } else if b == b';' {
+            return line_number_at_offset(content, i);
+        }
+        i += 1;
+    }
+
+    content.lines().count().max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extracts_create_table_statements() {
+        let content = r#"-- 001_init.sql
+CREATE TABLE users (
+    id SERIAL PRIMARY KEY,
+    email TEXT NOT NULL
+);
+
+create table if not exists public."orders" (
+    id SERIAL PRIMARY KEY,
+    user_id INT REFERENCES users(id)
+);
+
+ALTER TABLE orders ADD COLUMN note TEXT DEFAULT 'a;b';
+"#;
+
+        let symbols = SqlParser.parse_symbols(content);
+        let tables: Vec<&Symbol> = symbols
+            .iter()
+            .filter(|s| {
+                s.signature
+                    .as_deref()
+                    .is_some_and(|sig| sig.to_uppercase().starts_with("CREATE"))
+            })
+            .collect();
+
+        assert_eq!(tables.len(), 2);
+        assert_eq!(tables[0].name, "users");
+        assert_eq!(tables[0].kind, SymbolKind::Struct);
+        assert_eq!(tables[0].visibility, Visibility::Public);
+        assert_eq!(tables[0].line_range.start, 2);
+        assert_eq!(tables[0].line_range.end, 5);
+        assert_eq!(tables[1].name, "public.orders");
+        assert_eq!(tables[1].line_range.start, 7);
+
+        let alter = symbols.last().unwrap();
+        assert_eq!(alter.name, "orders");
+        assert_eq!(alter.line_range.start, 12);
+        assert_eq!(alter.line_range.end, 12);
+    }
+
+    #[test]
+    fn test_non_ascii_text_does_not_split_characters() {
+        let content = "CREATE TABLE users ( -- ユーザー\n id INT\n);\n";
+        let symbols = SqlParser.parse_symbols(content);
+        assert_eq!(symbols[0].name, "users");
+        assert_eq!(symbols[0].line_range.end, 3);
+
+        let content =
+            "CREATE FUNCTION greet() RETURNS text AS $$ SELECT 'é; ü' $$\nLANGUAGE sql;\n";
+        assert_eq!(find_statement_end(content, 0), 2);
+    }
+}
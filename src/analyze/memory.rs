@@ -1,6 +1,28 @@
-use crate::types::{MemoryEntry, MemoryKind};
+use crate::types::{MemoryEntry, MemoryKind, Priority, Severity};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::collections::HashMap;
+
+/// Keywords the built-in patterns recognize; configuring one of these only
+/// changes its severity
+const BUILTIN_KEYWORDS: &[&str] = &[
+    "TODO",
+    "FIXME",
+    "XXX",
+    "BUG",
+    "HACK",
+    "WARNING",
+    "WARN",
+    "NOTE",
+    "SAFETY",
+    "INVARIANT",
+    "GUARANTEE",
+    "GUARANTEES",
+    "RULE",
+    "POLICY",
+    "DEPRECATED",
+    "@DEPRECATED",
+];
 
 static STANDARD_ANNOTATION: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"(?im)(?://|#|/\*+|\*)\s*\b(TODO|FIXME|XXX|BUG|HACK|WARNING|NOTE|WARN)\b[:\s]*(.*)")
@@ -19,7 +41,83 @@ static DEPRECATED_MARKER: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"(?im)(?://|#|/\*+|\*)\s*\b(DEPRECATED|@deprecated)\b[:\s]*(.*)").unwrap()
 });
 
-pub fn extract_memory_markers(content: &str, source_file: &str) -> Vec<MemoryEntry> {
+/// Marker keywords to extract: the built-in set plus configured ones, each
+/// optionally with its own severity
+#[derive(Debug, Default)]
+pub struct MemoryMarkers {
+    /// Matches configured keywords that aren't built in
+    custom: Option<Regex>,
+    /// Severity overrides, keyed by uppercase keyword
+    severities: HashMap<String, Severity>,
+}
+
+impl MemoryMarkers {
+    /// Built-in keywords plus `keywords`, matched case-insensitively like the
+    /// built-ins. A keyword ending in `-` is a ticket prefix: `JIRA-` matches
+    /// `JIRA-1234:`. Custom keywords default to `Severity::Info`.
+    pub fn new(keywords: &[(String, Option<Severity>)]) -> Self {
+        let mut severities = HashMap::new();
+        let mut patterns = Vec::new();
+        for (keyword, severity) in keywords {
+            let upper = keyword.trim().to_uppercase();
+            if upper.is_empty() {
+                continue;
+            }
+            let builtin = BUILTIN_KEYWORDS.contains(&upper.as_str());
+            if let Some(severity) = severity {
+                severities.insert(upper.clone(), *severity);
+            }
+            if builtin {
+                continue;
+            }
+            if let Some(prefix) = upper.strip_suffix('-') {
+                patterns.push(format!(r"{}-\d+", regex::escape(prefix)));
+            } else {
+                patterns.push(regex::escape(&upper));
+            }
+        }
+
+        let custom = (!patterns.is_empty()).then(|| {
+            Regex::new(&format!(
+                r"(?im)(?://|#|/\*+|\*)\s*\b({})\b[:\s]*(.*)",
+                patterns.join("|")
+            ))
+            .expect("escaped keywords form a valid pattern")
+        });
+        Self { custom, severities }
+    }
+
+    /// Configured severity for a matched keyword; a ticket id like
+    /// `JIRA-1234` looks up its `JIRA-` prefix
+    fn severity_of(&self, keyword: &str) -> Option<Severity> {
+        let keyword = keyword.to_uppercase();
+        self.severities.get(&keyword).copied().or_else(|| {
+            let prefix = keyword.trim_end_matches(|c: char| c.is_ascii_digit());
+            self.severities.get(prefix).copied()
+        })
+    }
+
+    fn entry(
+        &self,
+        kind: MemoryKind,
+        keyword: &str,
+        message: String,
+        source_file: &str,
+        line: usize,
+    ) -> MemoryEntry {
+        let entry = MemoryEntry::new(kind, message, source_file.to_string(), line);
+        match self.severity_of(keyword) {
+            Some(severity) => entry.with_severity(severity),
+            None => entry,
+        }
+    }
+}
+
+pub fn extract_memory_markers(
+    content: &str,
+    source_file: &str,
+    markers: &MemoryMarkers,
+) -> Vec<MemoryEntry> {
     let mut entries = Vec::new();
 
     for cap in STANDARD_ANNOTATION.captures_iter(content) {
@@ -48,12 +146,7 @@ pub fn extract_memory_markers(content: &str, source_file: &str) -> Vec<MemoryEnt
             _ => MemoryKind::Note,
         };
 
-        entries.push(MemoryEntry::new(
-            kind,
-            message,
-            source_file.to_string(),
-            line,
-        ));
+        entries.push(markers.entry(kind, &keyword, message, source_file, line));
     }
 
     for cap in SAFETY_MARKER.captures_iter(content) {
@@ -78,15 +171,11 @@ pub fn extract_memory_markers(content: &str, source_file: &str) -> Vec<MemoryEnt
             _ => MemoryKind::Safety,
         };
 
-        entries.push(MemoryEntry::new(
-            kind,
-            message,
-            source_file.to_string(),
-            line,
-        ));
+        entries.push(markers.entry(kind, &keyword, message, source_file, line));
     }
 
     for cap in BUSINESS_RULE.captures_iter(content) {
+        let keyword = cap.get(1).map_or("", |m| m.as_str());
         let message = cap
             .get(2)
             .map(|m| m.as_str().trim())
@@ -98,15 +187,17 @@ pub fn extract_memory_markers(content: &str, source_file: &str) -> Vec<MemoryEnt
             continue;
         }
 
-        entries.push(MemoryEntry::new(
+        entries.push(markers.entry(
             MemoryKind::BusinessRule,
+            keyword,
             message,
-            source_file.to_string(),
+            source_file,
             line,
         ));
     }
 
     for cap in DEPRECATED_MARKER.captures_iter(content) {
+        let keyword = cap.get(1).map_or("", |m| m.as_str());
         let message = cap
             .get(2)
             .map(|m| m.as_str().trim())
@@ -120,12 +211,30 @@ pub fn extract_memory_markers(content: &str, source_file: &str) -> Vec<MemoryEnt
             message
         };
 
-        entries.push(MemoryEntry::new(
-            MemoryKind::Deprecated,
-            msg,
-            source_file.to_string(),
-            line,
-        ));
+        entries.push(markers.entry(MemoryKind::Deprecated, keyword, msg, source_file, line));
+    }
+
+    if let Some(custom) = &markers.custom {
+        for cap in custom.captures_iter(content) {
+            let keyword = cap[1].to_uppercase();
+            let message = cap.get(2).map_or("", |m| m.as_str().trim()).to_string();
+            if message.is_empty() {
+                continue;
+            }
+            let line = line_number_at_offset(content, cap.get(0).unwrap().start());
+            let severity = markers.severity_of(&keyword).unwrap_or(Severity::Info);
+            let priority = match severity {
+                Severity::Critical => Priority::High,
+                Severity::Warning => Priority::Medium,
+                Severity::Info => Priority::Low,
+            };
+            entries.push(
+                MemoryEntry::new(MemoryKind::Custom, message, source_file.to_string(), line)
+                    .with_keyword(keyword)
+                    .with_severity(severity)
+                    .with_priority(priority),
+            );
+        }
     }
 
     entries.sort_by_key(|e| e.line_number);
@@ -135,3 +244,47 @@ pub fn extract_memory_markers(content: &str, source_file: &str) -> Vec<MemoryEnt
 fn line_number_at_offset(content: &str, offset: usize) -> usize {
     content[..offset].matches('\n').count() + 1
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "fn main() {\n    // HACK: retry until the socket is ready\n    // PERF: allocates per call\n    // JIRA-1234: drop after migration\n    // TODO: add logging\n}\n";
+
+    #[test]
+    fn test_default_markers_ignore_unknown_keywords() {
+        let entries = extract_memory_markers(SOURCE, "src/main.rs", &MemoryMarkers::default());
+        let kinds: Vec<MemoryKind> = entries.iter().map(|e| e.kind).collect();
+        assert_eq!(kinds, vec![MemoryKind::Hack, MemoryKind::Todo]);
+    }
+
+    #[test]
+    fn test_configured_keywords_are_captured() {
+        let markers = MemoryMarkers::new(&[
+            ("HACK".to_string(), Some(Severity::Critical)),
+            ("perf".to_string(), Some(Severity::Warning)),
+            ("JIRA-".to_string(), None),
+        ]);
+
+        let entries = extract_memory_markers(SOURCE, "src/main.rs", &markers);
+
+        let hack = &entries[0];
+        assert_eq!(hack.kind, MemoryKind::Hack);
+        assert_eq!(hack.line_number, 2);
+        assert_eq!(hack.source_file, "src/main.rs");
+        assert_eq!(hack.severity, Severity::Critical);
+        assert_eq!(entries.iter().filter(|e| e.line_number == 2).count(), 1);
+
+        let perf = &entries[1];
+        assert_eq!(perf.kind, MemoryKind::Custom);
+        assert_eq!(perf.marker(), "PERF");
+        assert_eq!(perf.content, "allocates per call");
+        assert_eq!(perf.line_number, 3);
+        assert_eq!(perf.severity, Severity::Warning);
+
+        let ticket = &entries[2];
+        assert_eq!(ticket.marker(), "JIRA-1234");
+        assert_eq!(ticket.content, "drop after migration");
+        assert_eq!(ticket.severity, Severity::Info);
+    }
+}
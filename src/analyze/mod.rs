@@ -1,10 +1,21 @@
+pub mod dead_code;
+pub mod env_vars;
 pub mod graph;
 pub mod lang;
 mod memory;
 pub mod module;
 mod parser;
+pub mod project;
+pub mod symbol_filter;
 
+pub use dead_code::{find_unused_symbols, UnusedSymbol};
+pub use env_vars::{scan_env_vars, EnvVar, EnvVarReference};
 pub use graph::FileGraph;
-pub use memory::extract_memory_markers;
-pub use module::{detect_modules, path_to_slug, BoundaryType, ModuleInfo};
-pub use parser::{extract_imports, extract_symbols};
+pub use memory::{extract_memory_markers, MemoryMarkers};
+pub use module::{
+    detect_modules, detect_modules_in_workspace, detect_project_modules, detect_workspace,
+    path_to_slug, BoundaryType, ModuleInfo, Workspace,
+};
+pub use parser::{extract_imports, extract_public_imports, extract_symbols};
+pub use project::{detect_project, ProjectProfile};
+pub use symbol_filter::SymbolFilter;
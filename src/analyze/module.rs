@@ -2,11 +2,15 @@
 //!
 //! Detects semantic module boundaries based on:
 //! - Explicit markers: mod.rs, __init__.py, index.{js,ts,tsx,jsx}
+//! - Go packages: any directory containing .go files
+//! - Workspace members: crates/packages listed by a root Cargo.toml
+//!   `[workspace]` or package.json `workspaces`, grouped under the root module
 //! - Implicit boundaries: directories with 5+ source files
 
 use crate::types::FileEntry;
 use serde::Serialize;
 use std::collections::{HashMap, HashSet};
+use std::fs;
 use std::path::Path;
 
 /// Minimum number of files for implicit module detection
@@ -44,6 +48,10 @@ pub enum BoundaryType {
     GoPackage,
     /// Implicit: directory with 5+ source files
     Implicit,
+    /// Crate or package listed as a workspace member
+    WorkspaceMember,
+    /// Root module of a Cargo or npm workspace; parent of its members
+    Workspace,
     /// Root module (project root)
     Root,
 }
@@ -56,6 +64,8 @@ impl BoundaryType {
             BoundaryType::JsModule => "js/ts",
             BoundaryType::GoPackage => "go",
             BoundaryType::Implicit => "implicit",
+            BoundaryType::WorkspaceMember => "member",
+            BoundaryType::Workspace => "workspace",
             BoundaryType::Root => "root",
         }
     }
@@ -98,8 +108,140 @@ pub fn path_to_slug(path: &str) -> String {
     path.replace(['/', '\\'], "-")
 }
 
+/// Member directories of a workspace declared at the project root
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Workspace {
+    /// Member paths relative to the root, sorted
+    pub members: Vec<String>,
+}
+
+/// Workspace declared by `root`'s Cargo.toml (`[workspace] members`, minus
+/// `exclude`) or package.json (`workspaces`); `*` in member globs matches one
+/// directory level. `None` when neither declares members.
+pub fn detect_workspace(root: &Path) -> Option<Workspace> {
+    let mut members = Vec::new();
+
+    if let Ok(content) = fs::read_to_string(root.join("Cargo.toml")) {
+        let manifest: toml::Table = content.parse().unwrap_or_default();
+        if let Some(workspace) = manifest.get("workspace").and_then(|w| w.as_table()) {
+            let patterns = |key: &str| -> Vec<String> {
+                workspace
+                    .get(key)
+                    .and_then(|v| v.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|v| v.as_str().map(str::to_string))
+                    .collect()
+            };
+            let excluded: Vec<String> = patterns("exclude")
+                .iter()
+                .flat_map(|p| expand_member_pattern(root, p))
+                .collect();
+            members.extend(
+                patterns("members")
+                    .iter()
+                    .flat_map(|p| expand_member_pattern(root, p))
+                    .filter(|m| !excluded.contains(m)),
+            );
+        }
+    }
+
+    if let Ok(content) = fs::read_to_string(root.join("package.json")) {
+        let manifest: serde_json::Value = serde_json::from_str(&content).unwrap_or_default();
+        // Either ["packages/*"] or { "packages": ["packages/*"] } (yarn)
+        let workspaces = &manifest["workspaces"];
+        let patterns = workspaces
+            .as_array()
+            .or_else(|| workspaces["packages"].as_array());
+        members.extend(
+            patterns
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v.as_str())
+                .flat_map(|p| expand_member_pattern(root, p)),
+        );
+    }
+
+    members.sort();
+    members.dedup();
+    (!members.is_empty()).then_some(Workspace { members })
+}
+
+/// Directories under `root` matching a member pattern such as "crates/*"
+fn expand_member_pattern(root: &Path, pattern: &str) -> Vec<String> {
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    let mut matches = vec![String::new()];
+
+    for component in pattern.split('/').filter(|c| !c.is_empty() && *c != ".") {
+        let mut next = Vec::new();
+        for base in &matches {
+            let dir = root.join(base);
+            if component.contains('*') {
+                let Ok(entries) = fs::read_dir(&dir) else {
+                    continue;
+                };
+                let mut names: Vec<String> = entries
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.path().is_dir())
+                    .filter_map(|e| e.file_name().to_str().map(str::to_string))
+                    .filter(|name| !name.starts_with('.') && wildcard_match(component, name))
+                    .collect();
+                names.sort();
+                next.extend(names.into_iter().map(|name| join_path(base, &name)));
+            } else if dir.join(component).is_dir() {
+                next.push(join_path(base, component));
+            }
+        }
+        matches = next;
+    }
+
+    matches.retain(|m| !m.is_empty());
+    matches
+}
+
+/// Whether `name` matches `pattern`, which contains at least one `*` standing
+/// for any run of characters
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !name.starts_with(first) || name.len() < first.len() + last.len() {
+        return false;
+    }
+    let mut rest = &name[first.len()..];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+fn join_path(base: &str, name: &str) -> String {
+    if base.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", base, name)
+    }
+}
+
 /// Detect all modules in a codebase
 pub fn detect_modules(files: &[FileEntry]) -> Vec<ModuleInfo> {
+    detect_modules_in_workspace(files, None)
+}
+
+/// Detect modules of the project at `root`, grouping the members of a
+/// workspace it declares under the root module
+pub fn detect_project_modules(root: &Path, files: &[FileEntry]) -> Vec<ModuleInfo> {
+    detect_modules_in_workspace(files, detect_workspace(root).as_ref())
+}
+
+/// Detect modules, making each `workspace` member that holds files a module
+/// whose parent is the (workspace) root module
+pub fn detect_modules_in_workspace(
+    files: &[FileEntry],
+    workspace: Option<&Workspace>,
+) -> Vec<ModuleInfo> {
     let mut modules: HashMap<String, ModuleInfo> = HashMap::new();
     let mut file_to_module: HashMap<String, String> = HashMap::new();
 
@@ -112,6 +254,28 @@ pub fn detect_modules(files: &[FileEntry]) -> Vec<ModuleInfo> {
         }
     }
 
+    // Go packages have no marker file, so they are keyed on extension instead
+    for (dir, entry_point) in detect_go_packages(files) {
+        modules
+            .entry(dir.clone())
+            .or_insert_with(|| ModuleInfo::new(&dir, BoundaryType::GoPackage, entry_point));
+    }
+
+    // Workspace members are modules even when no marker file sits at their root
+    let members: Vec<&String> = workspace
+        .map(|w| w.members.iter().collect())
+        .unwrap_or_default();
+    for member in &members {
+        let prefix = format!("{}/", member);
+        if !files.iter().any(|f| f.relative_path.starts_with(&prefix)) {
+            continue;
+        }
+        modules
+            .entry(member.to_string())
+            .or_insert_with(|| ModuleInfo::new(member, BoundaryType::WorkspaceMember, None))
+            .boundary_type = BoundaryType::WorkspaceMember;
+    }
+
     // Second pass: assign files to their nearest module
     for file in files {
         let dir = get_parent_dir(&file.relative_path);
@@ -168,8 +332,14 @@ pub fn detect_modules(files: &[FileEntry]) -> Vec<ModuleInfo> {
         .map(|f| f.relative_path.clone())
         .collect();
 
-    if !orphan_files.is_empty() {
-        let mut root_module = ModuleInfo::new("", BoundaryType::Root, None);
+    // A workspace always has a root module for its members to hang from
+    if !orphan_files.is_empty() || workspace.is_some() {
+        let boundary_type = if workspace.is_some() {
+            BoundaryType::Workspace
+        } else {
+            BoundaryType::Root
+        };
+        let mut root_module = ModuleInfo::new("", boundary_type, None);
         for file_path in orphan_files {
             root_module.add_file(file_path);
         }
@@ -183,8 +353,9 @@ pub fn detect_modules(files: &[FileEntry]) -> Vec<ModuleInfo> {
             continue;
         }
 
-        // Find parent
-        let parent_path = find_parent_module(path, &module_paths);
+        // Find parent; in a workspace, top-level modules hang from the root
+        let parent_path =
+            find_parent_module(path, &module_paths).or_else(|| workspace.map(|_| String::new()));
         if let Some(parent) = parent_path {
             if let Some(module) = modules.get_mut(path) {
                 module.parent = Some(path_to_slug(&parent));
@@ -221,8 +392,8 @@ fn detect_explicit_boundary(file: &FileEntry) -> Option<(String, BoundaryType)>
         // Rust: mod.rs marks a module, lib.rs marks crate root
         "mod.rs" => Some((dir, BoundaryType::RustModule)),
         "lib.rs" => {
-            // lib.rs at src/ level is crate root
-            if dir == "src" || dir.is_empty() {
+            // lib.rs at src/ level is crate root, including workspace crates' src/
+            if dir == "src" || dir.is_empty() || dir.ends_with("/src") {
                 Some((dir, BoundaryType::RustModule))
             } else {
                 None
@@ -241,6 +412,29 @@ fn detect_explicit_boundary(file: &FileEntry) -> Option<(String, BoundaryType)>
     }
 }
 
+/// Find directories holding .go files, with main.go as entry point when present
+fn detect_go_packages(files: &[FileEntry]) -> HashMap<String, Option<String>> {
+    let mut packages: HashMap<String, Option<String>> = HashMap::new();
+
+    for file in files {
+        if file.extension.as_deref() != Some("go") {
+            continue;
+        }
+
+        let entry_point = packages
+            .entry(get_parent_dir(&file.relative_path))
+            .or_default();
+        let is_main = Path::new(&file.relative_path)
+            .file_name()
+            .is_some_and(|n| n == "main.go");
+        if is_main {
+            *entry_point = Some(file.relative_path.clone());
+        }
+    }
+
+    packages
+}
+
 /// Get the parent directory of a file path
 fn get_parent_dir(path: &str) -> String {
     Path::new(path)
@@ -330,12 +524,13 @@ mod tests {
     use super::*;
     use crate::types::Language;
     use std::path::PathBuf;
+    use tempfile::TempDir;
 
     fn make_file(relative_path: &str) -> FileEntry {
         FileEntry {
             path: PathBuf::from(relative_path),
             relative_path: relative_path.to_string(),
-            extension: relative_path.split('.').last().map(|s| s.to_string()),
+            extension: relative_path.split('.').next_back().map(|s| s.to_string()),
             language: Language::Rust,
             size_bytes: 100,
             line_count: 50,
@@ -425,6 +620,39 @@ mod tests {
         assert_eq!(components.boundary_type, BoundaryType::JsModule);
     }
 
+    #[test]
+    fn test_detect_go_packages() {
+        let files: Vec<FileEntry> = [
+            "cmd/server/main.go",
+            "cmd/server/config.go",
+            "cmd/server/routes.go",
+            "cmd/server/handlers.go",
+            "cmd/server/middleware.go",
+            "internal/store/store.go",
+        ]
+        .iter()
+        .map(|path| {
+            let mut f = make_file(path);
+            f.language = Language::Go;
+            f
+        })
+        .collect();
+
+        let modules = detect_modules(&files);
+
+        // Enough files for an implicit module too, but only one module per directory
+        let server: Vec<&ModuleInfo> = modules.iter().filter(|m| m.path == "cmd/server").collect();
+        assert_eq!(server.len(), 1);
+        assert_eq!(server[0].slug, "cmd-server");
+        assert_eq!(server[0].boundary_type, BoundaryType::GoPackage);
+        assert_eq!(server[0].entry_point.as_deref(), Some("cmd/server/main.go"));
+        assert_eq!(server[0].file_count(), 5);
+
+        let store = modules.iter().find(|m| m.path == "internal/store").unwrap();
+        assert_eq!(store.boundary_type, BoundaryType::GoPackage);
+        assert_eq!(store.entry_point, None);
+    }
+
     #[test]
     fn test_detect_implicit_modules() {
         // Create 6 files in a directory without explicit boundary
@@ -493,4 +721,74 @@ mod tests {
         assert!(src.files.contains(&"src/lib.rs".to_string()));
         assert!(src.files.contains(&"src/main.rs".to_string()));
     }
+
+    #[test]
+    fn test_detect_workspace_members() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        for dir in [
+            "crates/core",
+            "crates/cli",
+            "crates/scratch",
+            "tools/xtask",
+            "web/app",
+        ] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\", \"tools/xtask\"]\nexclude = [\"crates/scratch\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("package.json"),
+            r#"{"private": true, "workspaces": {"packages": ["web/*"]}}"#,
+        )
+        .unwrap();
+
+        let workspace = detect_workspace(root).unwrap();
+
+        assert_eq!(
+            workspace.members,
+            vec!["crates/cli", "crates/core", "tools/xtask", "web/app"]
+        );
+        assert_eq!(detect_workspace(&root.join("crates/core")), None);
+    }
+
+    #[test]
+    fn test_workspace_members_hang_from_workspace_root() {
+        let files = vec![
+            make_file("crates/core/src/lib.rs"),
+            make_file("crates/core/src/parse/mod.rs"),
+            make_file("crates/core/build.rs"),
+            make_file("crates/cli/src/main.rs"),
+            make_file("xtask.rs"),
+        ];
+        let workspace = Workspace {
+            members: vec!["crates/cli".to_string(), "crates/core".to_string()],
+        };
+
+        let modules = detect_modules_in_workspace(&files, Some(&workspace));
+        let find = |path: &str| modules.iter().find(|m| m.path == path).unwrap();
+
+        let root = find("");
+        assert_eq!(root.boundary_type, BoundaryType::Workspace);
+        assert_eq!(root.children, vec!["crates-cli", "crates-core"]);
+        assert_eq!(root.files, vec!["xtask.rs"]);
+
+        let core = find("crates/core");
+        assert_eq!(core.boundary_type, BoundaryType::WorkspaceMember);
+        assert_eq!(core.parent.as_deref(), Some("root"));
+        assert_eq!(core.files, vec!["crates/core/build.rs"]);
+        assert_eq!(core.children, vec!["crates-core-src"]);
+
+        // Member crate roots are crate modules below the member
+        let core_src = find("crates/core/src");
+        assert_eq!(core_src.boundary_type, BoundaryType::RustModule);
+        assert_eq!(core_src.parent.as_deref(), Some("crates-core"));
+        assert_eq!(
+            find("crates/cli").files,
+            vec!["crates/cli/src/main.rs".to_string()]
+        );
+    }
 }
@@ -3,14 +3,89 @@ use crate::types::{FileEntry, Symbol};
 
 pub fn extract_symbols(file: &FileEntry, content: &str) -> Vec<Symbol> {
     match get_parser(file.language) {
-        Some(parser) => parser.parse_symbols(content),
+        Some(parser) => {
+            let mut symbols = parser.parse_symbols(content);
+            assign_parents(&mut symbols);
+            symbols
+        }
         None => Vec::new(),
     }
 }
 
+/// Set each symbol's `parent` to the innermost other symbol containing it
+fn assign_parents(symbols: &mut [Symbol]) {
+    let parents: Vec<Option<String>> = symbols
+        .iter()
+        .map(|sym| {
+            symbols
+                .iter()
+                .filter(|other| other.contains(sym))
+                .min_by_key(|other| other.line_range.end - other.line_range.start)
+                .map(|other| other.name.clone())
+        })
+        .collect();
+
+    for (sym, parent) in symbols.iter_mut().zip(parents) {
+        sym.parent = parent;
+    }
+}
+
 pub fn extract_imports(file: &FileEntry, content: &str) -> Vec<String> {
     match get_parser(file.language) {
         Some(parser) => parser.parse_imports(content),
         None => Vec::new(),
     }
 }
+
+/// Like `extract_imports`, keeping only imports that are part of the public API
+pub fn extract_public_imports(file: &FileEntry, content: &str) -> Vec<String> {
+    match get_parser(file.language) {
+        Some(parser) => parser.parse_public_imports(content),
+        None => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_methods_get_their_class_as_parent() {
+        let content = r#"class Outer {
+    void first() {
+    }
+
+    class Inner {
+        void second() {
+        }
+    }
+}
+
+void standalone() {
+}
+"#;
+        let file = FileEntry::new(
+            PathBuf::from("Outer.java"),
+            "Outer.java".to_string(),
+            content.len() as u64,
+            content.lines().count(),
+            500,
+        );
+        let symbols = extract_symbols(&file, content);
+        let parent_of = |name: &str| {
+            symbols
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap()
+                .parent
+                .clone()
+        };
+
+        assert_eq!(parent_of("Outer"), None);
+        assert_eq!(parent_of("first").as_deref(), Some("Outer"));
+        assert_eq!(parent_of("Inner").as_deref(), Some("Outer"));
+        assert_eq!(parent_of("second").as_deref(), Some("Inner"));
+        assert_eq!(parent_of("standalone"), None);
+    }
+}
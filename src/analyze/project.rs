@@ -0,0 +1,247 @@
+//! Primary language and project type, judged from the mix of source files and
+//! the manifests at the project root.
+
+use crate::types::{FileEntry, Language};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Languages holding less than this share of source files are not primary
+const PRIMARY_SHARE: f64 = 0.2;
+const MAX_PRIMARY_LANGUAGES: usize = 3;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct ProjectProfile {
+    /// Languages making up a large share of source files, most common first
+    pub primary_languages: Vec<String>,
+    /// What the project is, e.g. "Rust CLI application" or "Next.js application"
+    pub project_type: Option<String>,
+}
+
+impl ProjectProfile {
+    /// One-line description for the top of INDEX.md, if anything was detected
+    pub fn summary(&self) -> Option<String> {
+        let languages = self.primary_languages.join(", ");
+        match (&self.project_type, languages.is_empty()) {
+            (Some(kind), false) => Some(format!("**{}** · {}", kind, languages)),
+            (Some(kind), true) => Some(format!("**{}**", kind)),
+            (None, false) => Some(languages),
+            (None, true) => None,
+        }
+    }
+}
+
+/// Detect the primary languages and project type of the project at `root`
+pub fn detect_project(root: &Path, files: &[FileEntry]) -> ProjectProfile {
+    let languages = rank_languages(files);
+    let primary_languages: Vec<String> = languages
+        .iter()
+        .map(|lang| display_name(*lang).to_string())
+        .collect();
+
+    let candidates = manifest_types(root);
+    let project_type = candidates
+        .iter()
+        .find(|(langs, _)| {
+            languages
+                .first()
+                .is_some_and(|primary| langs.contains(primary))
+        })
+        .or_else(|| candidates.first())
+        .map(|(_, kind)| kind.clone());
+
+    ProjectProfile {
+        primary_languages,
+        project_type,
+    }
+}
+
+/// Languages with at least `PRIMARY_SHARE` of the source files, most common
+/// first; the most common language always counts
+fn rank_languages(files: &[FileEntry]) -> Vec<Language> {
+    let mut counts: HashMap<Language, usize> = HashMap::new();
+    for file in files {
+        if file.language != Language::Unknown {
+            *counts.entry(file.language).or_default() += 1;
+        }
+    }
+    let total: usize = counts.values().sum();
+
+    let mut ranked: Vec<(Language, usize)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.name().cmp(b.0.name())));
+
+    ranked
+        .into_iter()
+        .enumerate()
+        .filter(|(i, (_, count))| *i == 0 || *count as f64 / total as f64 >= PRIMARY_SHARE)
+        .map(|(_, (lang, _))| lang)
+        .take(MAX_PRIMARY_LANGUAGES)
+        .collect()
+}
+
+/// Project types suggested by root manifests, each with the languages it implies
+fn manifest_types(root: &Path) -> Vec<(Vec<Language>, String)> {
+    let mut types = Vec::new();
+
+    if let Ok(content) = fs::read_to_string(root.join("Cargo.toml")) {
+        types.push((vec![Language::Rust], cargo_type(root, &content)));
+    }
+    if let Ok(content) = fs::read_to_string(root.join("package.json")) {
+        types.push((
+            vec![Language::JavaScript, Language::TypeScript],
+            node_type(&content),
+        ));
+    }
+    if let Ok(content) = fs::read_to_string(root.join("pyproject.toml")) {
+        types.push((vec![Language::Python], python_type(&content)));
+    }
+    if root.join("go.mod").is_file() {
+        let kind = if root.join("main.go").is_file() || root.join("cmd").is_dir() {
+            "Go application"
+        } else {
+            "Go module"
+        };
+        types.push((vec![Language::Go], kind.to_string()));
+    }
+
+    types
+}
+
+fn cargo_type(root: &Path, content: &str) -> String {
+    let manifest: toml::Table = content.parse().unwrap_or_default();
+    let has_package = manifest.contains_key("package");
+    if manifest.contains_key("workspace") && !has_package {
+        return "Rust workspace".to_string();
+    }
+
+    let has_bin = root.join("src/main.rs").is_file() || manifest.contains_key("bin");
+    let has_lib = root.join("src/lib.rs").is_file() || manifest.contains_key("lib");
+    match (has_bin, has_lib) {
+        (true, true) => "Rust library and CLI application",
+        (true, false) => "Rust CLI application",
+        _ => "Rust library",
+    }
+    .to_string()
+}
+
+fn node_type(content: &str) -> String {
+    let manifest: serde_json::Value = serde_json::from_str(content).unwrap_or_default();
+    let has_dependency = |name: &str| {
+        ["dependencies", "devDependencies"]
+            .iter()
+            .any(|section| manifest[section].get(name).is_some())
+    };
+
+    let kind = if has_dependency("next") {
+        "Next.js application"
+    } else if has_dependency("react") {
+        "React application"
+    } else if has_dependency("vue") {
+        "Vue application"
+    } else if has_dependency("svelte") {
+        "Svelte application"
+    } else if has_dependency("express") || has_dependency("fastify") {
+        "Node.js server"
+    } else {
+        "Node.js package"
+    };
+    kind.to_string()
+}
+
+fn python_type(content: &str) -> String {
+    let lower = content.to_lowercase();
+    let kind = if lower.contains("django") {
+        "Django application"
+    } else if lower.contains("fastapi") {
+        "FastAPI service"
+    } else if lower.contains("flask") {
+        "Flask application"
+    } else {
+        "Python package"
+    };
+    kind.to_string()
+}
+
+fn display_name(lang: Language) -> &'static str {
+    match lang {
+        Language::Rust => "Rust",
+        Language::Python => "Python",
+        Language::JavaScript => "JavaScript",
+        Language::TypeScript => "TypeScript",
+        Language::Go => "Go",
+        Language::Php => "PHP",
+        Language::Java => "Java",
+        Language::CSharp => "C#",
+        Language::C => "C",
+        Language::Cpp => "C++",
+        Language::Ruby => "Ruby",
+        Language::Dart => "Dart",
+        Language::Swift => "Swift",
+        Language::Kotlin => "Kotlin",
+        Language::Vue => "Vue",
+        Language::Svelte => "Svelte",
+        Language::Sql => "SQL",
+        Language::Unknown => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(path: &str) -> FileEntry {
+        FileEntry::new(path.into(), path.to_string(), 1000, 40, 500)
+    }
+
+    #[test]
+    fn test_rust_dominated_repo_is_rust_project() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("src")).unwrap();
+        fs::write(
+            temp.path().join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        fs::write(temp.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+
+        let files = vec![
+            entry("src/main.rs"),
+            entry("src/cli.rs"),
+            entry("src/scan.rs"),
+            entry("src/output.rs"),
+            entry("src/config.rs"),
+            entry("scripts/release.py"),
+        ];
+        let profile = detect_project(temp.path(), &files);
+
+        assert_eq!(profile.primary_languages, vec!["Rust"]);
+        assert_eq!(
+            profile.project_type.as_deref(),
+            Some("Rust CLI application")
+        );
+        assert_eq!(
+            profile.summary().as_deref(),
+            Some("**Rust CLI application** · Rust")
+        );
+    }
+
+    #[test]
+    fn test_manifest_matching_primary_language_wins() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("Cargo.toml"), "[package]\nname = \"x\"\n").unwrap();
+        fs::write(
+            temp.path().join("package.json"),
+            r#"{"dependencies": {"react": "^18"}}"#,
+        )
+        .unwrap();
+
+        let files = vec![entry("web/app.tsx"), entry("web/page.tsx"), entry("lib.rs")];
+        let profile = detect_project(temp.path(), &files);
+
+        assert_eq!(profile.primary_languages, vec!["TypeScript", "Rust"]);
+        assert_eq!(profile.project_type.as_deref(), Some("React application"));
+    }
+}
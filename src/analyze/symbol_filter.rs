@@ -0,0 +1,116 @@
+//! Symbol-name filter loaded from `.agentlens/ignore-symbols`.
+//!
+//! One pattern per line; `#` starts a comment. Patterns are globs (`*`, `?`)
+//! matched against the whole symbol name, or regexes when wrapped in slashes
+//! (e.g. `/^__\w+__$/`). The filter is applied by consumers of extracted
+//! symbols (outlines, file docs, chunking), never by the parsers themselves.
+
+use crate::types::Symbol;
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+
+pub const IGNORE_SYMBOLS_FILE: &str = "ignore-symbols";
+
+#[derive(Debug, Clone, Default)]
+pub struct SymbolFilter {
+    patterns: Vec<Regex>,
+}
+
+impl SymbolFilter {
+    /// Load patterns from `<output_dir>/ignore-symbols`, or an empty filter if absent
+    pub fn load(output_dir: &Path) -> Self {
+        match fs::read_to_string(output_dir.join(IGNORE_SYMBOLS_FILE)) {
+            Ok(content) => Self::from_patterns(content.lines()),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Build a filter from raw pattern lines; invalid regexes are skipped
+    pub fn from_patterns<'a>(lines: impl IntoIterator<Item = &'a str>) -> Self {
+        let patterns = lines
+            .into_iter()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let regex = if line.len() > 1 && line.starts_with('/') && line.ends_with('/') {
+                    line[1..line.len() - 1].to_string()
+                } else {
+                    glob_to_regex(line)
+                };
+                Regex::new(&regex).ok()
+            })
+            .collect();
+
+        Self { patterns }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    pub fn is_ignored(&self, symbol: &Symbol) -> bool {
+        self.patterns.iter().any(|p| p.is_match(&symbol.name))
+    }
+
+    /// Drop symbols whose names match any pattern
+    pub fn apply(&self, symbols: Vec<Symbol>) -> Vec<Symbol> {
+        if self.is_empty() {
+            return symbols;
+        }
+        symbols
+            .into_iter()
+            .filter(|s| !self.is_ignored(s))
+            .collect()
+    }
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            _ => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{SymbolKind, Visibility};
+
+    fn make_symbol(name: &str) -> Symbol {
+        Symbol::new(
+            SymbolKind::Function,
+            name.to_string(),
+            1,
+            Visibility::Public,
+        )
+    }
+
+    #[test]
+    fn test_glob_suppresses_test_functions() {
+        let filter = SymbolFilter::from_patterns(["# tests are noise", "test_*"]);
+        let symbols = vec![
+            make_symbol("test_parse"),
+            make_symbol("parse"),
+            make_symbol("test_"),
+            make_symbol("contest_entry"),
+        ];
+
+        let names: Vec<String> = filter.apply(symbols).into_iter().map(|s| s.name).collect();
+
+        assert_eq!(names, vec!["parse", "contest_entry"]);
+    }
+
+    #[test]
+    fn test_regex_pattern() {
+        let filter = SymbolFilter::from_patterns(["/^__\\w+__$/"]);
+        assert!(filter.is_ignored(&make_symbol("__proto__")));
+        assert!(!filter.is_ignored(&make_symbol("proto")));
+    }
+}
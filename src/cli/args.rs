@@ -1,15 +1,34 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
+use super::search::{DEFAULT_SNIPPET_CHARS, DEFAULT_SNIPPET_LINES};
+use crate::analyze::MemoryMarkers;
+use crate::config::{ComplexityConfig, MarkerConfig};
+use crate::emit::LineEnding;
+use crate::generate::{ComplexityThresholds, MermaidConfig, DEFAULT_COMPLEX_SYMBOLS_THRESHOLD};
+use crate::scan::DEFAULT_LARGE_FILE_LINES;
+use crate::search::ChunkType;
+use crate::types::{Severity, Visibility};
+
 #[derive(Subcommand, Debug, Clone)]
 pub enum Command {
     /// Update agentlens to the latest version
     Update,
     /// Watch for file changes and regenerate docs automatically
     Watch {
-        /// Debounce delay in milliseconds
+        /// Debounce delay in milliseconds; saves within this window are handled as one batch
         #[arg(long, default_value = "300")]
         debounce: u64,
+        /// Also keep the search index fresh, re-embedding only changed files
+        #[arg(long)]
+        index: bool,
+        /// Also keep the search index fresh and serve it over HTTP on localhost
+        #[arg(long)]
+        serve: bool,
+        /// Port for --serve
+        #[arg(long, default_value = "7878", requires = "serve")]
+        port: u16,
     },
     /// Manage git hooks for automatic regeneration
     Hooks {
@@ -36,9 +55,20 @@ pub enum Command {
         /// Run in MCP mode (stdio transport)
         #[arg(long)]
         mcp: bool,
-        /// HTTP port for SSE transport (enables HTTP mode)
+        /// HTTP port for SSE transport (enables HTTP mode), or for --store (default 7879)
         #[arg(long, value_name = "PORT")]
         port: Option<u16>,
+        /// Serve the search index over HTTP for `agentlens search --remote`
+        #[arg(long, conflicts_with = "mcp")]
+        store: bool,
+        /// Address to listen on with --store; use 0.0.0.0 to share across machines (needs AGENTLENS_STORE_TOKEN)
+        #[arg(
+            long,
+            value_name = "ADDR",
+            default_value = "127.0.0.1",
+            requires = "store"
+        )]
+        bind: String,
     },
     /// Analyze token usage and efficiency of generated docs
     Telemetry {
@@ -54,29 +84,172 @@ pub enum Command {
     Index {
         #[command(subcommand)]
         action: Option<IndexAction>,
+        /// Index a .tar.gz/.tgz/.zip archive (local path or URL) instead of the target directory
+        #[arg(value_name = "ARCHIVE")]
+        archive: Option<String>,
         /// Force re-index all files (ignore cache)
         #[arg(long)]
         force: bool,
+        /// Only re-index files changed between REF and HEAD (e.g. main in CI); deleted files are removed
+        #[arg(long, value_name = "REF", conflicts_with_all = ["archive", "force"])]
+        since: Option<String>,
         /// Prune deleted files from index
         #[arg(long)]
         prune: bool,
+        /// Skip --prune when it would remove more than this percentage of indexed files, e.g. when run against a partial checkout
+        #[arg(long, default_value = "50", value_name = "PERCENT", value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(0..=100))]
+        prune_threshold: usize,
+        /// Prune even when more than --prune-threshold of the index would be removed
+        #[arg(long, requires = "prune")]
+        force_prune: bool,
+        /// Store a git commit-frequency "hotness" score on chunks as a ranking boost
+        #[arg(long)]
+        hotness: bool,
+        /// Number of recent commits considered for --hotness
+        #[arg(long, default_value = "100", value_name = "N")]
+        hotness_commits: usize,
+        /// Cap how many chunks a single file contributes; the rest of the file is skipped
+        #[arg(long, value_name = "N", value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        max_chunks_per_file: Option<usize>,
+        /// Save the index after every N re-embedded files so an interrupted run keeps its progress (0 disables)
+        #[arg(long, default_value = "100", value_name = "N")]
+        checkpoint_interval: usize,
+        /// Only embed symbols at least this visible (public, internal, protected, private), e.g. for a shareable public-API index; use with --force on an existing index
+        #[arg(long, value_name = "LEVEL")]
+        visibility: Option<Visibility>,
+        /// Also embed one summary chunk per detected module (its doc, files and symbol names) so module-level queries match the module
+        #[arg(long)]
+        module_summaries: bool,
+        /// Index files with this extension even though it is denied by default (e.g. min.js, lock); repeatable
+        #[arg(long = "include-ext", value_name = "EXT", value_delimiter = ',')]
+        include_ext: Vec<String>,
+        /// Index into this named collection instead of the global one (searchable through MCP `collection`)
+        #[arg(long, value_name = "NAME")]
+        collection: Option<String>,
+        /// Embedding model to use (overrides the default)
+        #[arg(long, value_name = "NAME")]
+        model: Option<String>,
+        /// Embedding server endpoint (e.g. http://localhost:11434)
+        #[arg(long, value_name = "URL")]
+        embed_endpoint: Option<String>,
+        /// Use the named `[[search.embedder.profiles]]` entry from the config
+        #[arg(long, value_name = "NAME")]
+        embed_profile: Option<String>,
     },
+    /// Print the JSON Schema of the --json output
+    Schema,
     /// Semantic search across the codebase
     Search {
         /// Natural language query
-        query: String,
+        #[arg(required_unless_present_any = ["review", "batch"])]
+        query: Option<String>,
+        /// Read one query per line from FILE and search each with the same loaded index
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["query", "review"])]
+        batch: Option<PathBuf>,
         /// Number of results to return
         #[arg(short = 'n', long, default_value = "10")]
         limit: usize,
         /// Enable hybrid search (vector + text)
         #[arg(long)]
         hybrid: bool,
+        /// Output as JSON (shorthand for --format json)
+        #[arg(long, conflicts_with = "format")]
+        json: bool,
+        /// Output format for results
+        #[arg(long, value_enum, default_value = "human")]
+        format: SearchFormat,
+        /// Lines of each result's preview (human format)
+        #[arg(long, value_name = "N", default_value_t = DEFAULT_SNIPPET_LINES)]
+        snippet_lines: usize,
+        /// Characters of each preview before it is cut off (human format)
+        #[arg(long, value_name = "M", default_value_t = DEFAULT_SNIPPET_CHARS)]
+        snippet_chars: usize,
+        /// Stream vectors from disk with bounded memory (vector-only, disables hybrid)
+        #[arg(long, conflicts_with = "hybrid")]
+        streaming: bool,
+        /// Lexical search over the working tree without an index (automatic if none exists)
+        #[arg(long, conflicts_with_all = ["hybrid", "streaming"])]
+        grep: bool,
+        /// Also print how many results came from each language and module (human and JSON)
+        #[arg(long)]
+        facets: bool,
+        /// Show each result's vector rank, text rank and fused RRF score (always hybrid)
+        #[arg(long, conflicts_with_all = ["grep", "streaming", "review"])]
+        explain: bool,
+        /// Rebuild the index first if it was built with a different embedding model
+        #[arg(long, conflicts_with = "grep")]
+        reindex_on_mismatch: bool,
+        /// Boost results near the current directory (or --focus) in a monorepo
+        #[arg(long)]
+        rerank_by_path_affinity: bool,
+        /// Directory to favor, relative to the project root (implies --rerank-by-path-affinity)
+        #[arg(long, value_name = "PATH")]
+        focus: Option<String>,
+        /// Only search files under this path, relative to the project root
+        #[arg(long = "path", value_name = "PREFIX")]
+        path_prefix: Option<String>,
+        /// Only search chunks of these types (function, class, method, module, file-header, block)
+        #[arg(long = "type", value_name = "TYPE", value_delimiter = ',')]
+        chunk_types: Vec<ChunkType>,
+        /// Find indexed code related to each hunk changed since this git ref
+        #[arg(long, value_name = "BASE_REF", conflicts_with_all = ["query", "hybrid", "streaming"])]
+        review: Option<String>,
+        /// Search a shared index served by `agentlens serve --store` instead of the local one
+        #[arg(long, value_name = "URL", conflicts_with_all = ["grep", "streaming", "reindex_on_mismatch", "review"])]
+        remote: Option<String>,
+        /// Embedding model to use (must match the one the index was built with)
+        #[arg(long, value_name = "NAME")]
+        model: Option<String>,
+        /// Embedding server endpoint (e.g. http://localhost:11434)
+        #[arg(long, value_name = "URL")]
+        embed_endpoint: Option<String>,
+        /// Use the named `[[search.embedder.profiles]]` entry from the config
+        #[arg(long, value_name = "NAME")]
+        embed_profile: Option<String>,
+    },
+    /// Rank indexed files by how similar they are to FILE (averaged chunk embeddings)
+    SimilarFiles {
+        /// Indexed file to compare against
+        file: PathBuf,
+        /// Number of files to return
+        #[arg(short = 'n', long, default_value = "10")]
+        limit: usize,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show how FILE is split into chunks for indexing (no embedding)
+    ExplainChunking {
+        /// File to chunk
+        file: PathBuf,
         /// Output as JSON
         #[arg(long)]
         json: bool,
     },
 }
 
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Json,
+    Yaml,
+    /// One JSON object per module, file and memory entry, tagged with `kind`
+    Jsonl,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchFormat {
+    /// Colored, human-readable listing
+    Human,
+    /// Pretty-printed JSON array
+    Json,
+    /// CSV with one row per result
+    Csv,
+    /// One JSON object per line holding a query and its results
+    Jsonl,
+    /// YAML document with the same fields as JSON
+    Yaml,
+}
+
 #[derive(Subcommand, Debug, Clone)]
 pub enum HooksAction {
     /// Install git hooks (pre-commit, post-checkout, post-merge)
@@ -135,7 +308,11 @@ pub enum SkillsAction {
 #[derive(Subcommand, Debug, Clone)]
 pub enum IndexAction {
     /// Show index status and statistics
-    Status,
+    Status {
+        /// Output the statistics as JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// Clear the search index
     Clear,
 }
@@ -168,12 +345,16 @@ pub struct Args {
     #[arg(default_value = ".")]
     pub path: PathBuf,
 
+    /// Branch or tag to clone when the target is a remote URL (default branch otherwise)
+    #[arg(long = "ref", value_name = "REF")]
+    pub ref_name: Option<String>,
+
     /// Output directory for generated documentation
     #[arg(short, long, default_value = ".agentlens")]
     pub output: PathBuf,
 
     /// Line threshold for "large" files (generates outline)
-    #[arg(short, long, default_value = "500")]
+    #[arg(short, long, visible_alias = "large-threshold", default_value_t = DEFAULT_LARGE_FILE_LINES)]
     pub threshold: usize,
 
     /// Line threshold for L2 file-level docs (very complex files)
@@ -212,10 +393,14 @@ pub struct Args {
     #[arg(long, value_name = "REF")]
     pub diff: Option<String>,
 
-    /// Output JSON to stdout instead of markdown files
+    /// Output JSON to stdout instead of markdown files (shorthand for --format json)
     #[arg(long, default_value = "false")]
     pub json: bool,
 
+    /// Print the analysis to stdout in this format instead of writing markdown files
+    #[arg(long, value_enum, value_name = "FORMAT", conflicts_with = "json")]
+    pub format: Option<OutputFormat>,
+
     /// Max directory depth (0 = unlimited)
     #[arg(short = 'd', long, default_value = "0")]
     pub depth: usize,
@@ -231,6 +416,39 @@ pub struct Args {
     /// Check if docs are stale (exit 1 if regeneration needed)
     #[arg(long, default_value = "false")]
     pub check: bool,
+
+    /// Report public symbols that nothing imports (heuristic, written to dead-code.md)
+    #[arg(long, default_value = "false")]
+    pub dead_code: bool,
+
+    /// List environment variables the code reads (written to config.md)
+    #[arg(long, default_value = "false")]
+    pub env_scan: bool,
+
+    /// Only report memory markers at least this severe (info, warning, critical)
+    #[arg(long, value_name = "LEVEL", default_value = "info")]
+    pub min_severity: Severity,
+
+    /// Build the import graph from public imports only (e.g. Rust `pub use`)
+    #[arg(long, default_value = "false")]
+    pub public_imports: bool,
+
+    /// Append a Mermaid diagram of imports between a module's files to its
+    /// imports.md (skipped for modules over 40 files)
+    #[arg(long, default_value = "false")]
+    pub file_graph: bool,
+
+    /// Line endings for generated docs
+    #[arg(long, value_enum, default_value = "lf")]
+    pub line_ending: LineEnding,
+
+    /// Per-language L2 doc thresholds from the config file
+    #[arg(skip)]
+    pub complexity: HashMap<String, ComplexityConfig>,
+
+    /// Extra memory marker keywords from the config file
+    #[arg(skip)]
+    pub markers: HashMap<String, MarkerConfig>,
 }
 
 impl Args {
@@ -252,8 +470,8 @@ impl Args {
                     self.output = PathBuf::from(output);
                 }
             }
-            if let Some(threshold) = cfg.threshold {
-                if self.threshold == 500 {
+            if let Some(threshold) = cfg.large_file_lines {
+                if self.threshold == DEFAULT_LARGE_FILE_LINES {
                     self.threshold = threshold;
                 }
             }
@@ -283,11 +501,55 @@ impl Args {
                     self.no_gitignore = no_gitignore;
                 }
             }
+            self.complexity = cfg.complexity;
+            self.markers = cfg.markers;
+            if let Some(line_ending) = cfg.line_ending {
+                if self.line_ending == LineEnding::Lf {
+                    self.line_ending = line_ending;
+                }
+            }
         }
 
         self
     }
 
+    /// L2 doc thresholds: `--complex-threshold` plus per-language config overrides
+    pub fn complexity_thresholds(&self) -> ComplexityThresholds {
+        self.complexity.iter().fold(
+            ComplexityThresholds::new(self.complex_threshold, DEFAULT_COMPLEX_SYMBOLS_THRESHOLD),
+            |thresholds, (language, cfg)| {
+                thresholds.with_language(language, cfg.lines, cfg.symbols)
+            },
+        )
+    }
+
+    /// Memory marker keywords: the built-ins plus any from `[markers]`
+    pub fn memory_markers(&self) -> MemoryMarkers {
+        let keywords: Vec<(String, Option<Severity>)> = self
+            .markers
+            .iter()
+            .map(|(keyword, cfg)| (keyword.clone(), cfg.severity))
+            .collect();
+        MemoryMarkers::new(&keywords)
+    }
+
+    /// Format for stdout output, if not writing markdown files
+    pub fn output_format(&self) -> Option<OutputFormat> {
+        if self.json {
+            Some(OutputFormat::Json)
+        } else {
+            self.format
+        }
+    }
+
+    /// File-level import diagrams in module imports.md, if `--file-graph`
+    pub fn mermaid_config(&self) -> MermaidConfig {
+        MermaidConfig {
+            enabled: self.file_graph,
+            ..MermaidConfig::default()
+        }
+    }
+
     pub fn verbosity(&self) -> u8 {
         if self.quiet {
             0
@@ -309,6 +571,10 @@ impl Args {
             return Ok(());
         }
 
+        if self.ref_name.is_some() {
+            return Err("--ref only applies to a remote repository URL".to_string());
+        }
+
         if !self.path.exists() {
             return Err(format!("Path does not exist: {}", self.path.display()));
         }
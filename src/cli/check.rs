@@ -2,7 +2,7 @@ use anyhow::Result;
 use std::collections::HashMap;
 use std::path::Path;
 
-use crate::analyze::detect_modules;
+use crate::analyze::detect_project_modules;
 use crate::cli::Args;
 use crate::emit::{calculate_module_state, Manifest};
 use crate::scan::scan_directory;
@@ -33,7 +33,7 @@ pub fn check_staleness(args: &Args, work_path: &Path) -> Result<CheckResult> {
     let files: Vec<FileEntry> =
         scan_directory(work_path, args.threshold, !args.no_gitignore, max_depth)?;
 
-    let modules = detect_modules(&files);
+    let modules = detect_project_modules(work_path, &files);
 
     let module_states: HashMap<String, _> = modules
         .iter()
@@ -1,29 +1,52 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use console::{style, Emoji};
 use indicatif::{ProgressBar, ProgressStyle};
 use std::path::Path;
 use std::sync::Arc;
 
-use crate::config::ChunkingConfig;
-use crate::search::{create_embedder, Embedder, EmbedderConfig, GobStore, Indexer, VectorStore};
+use crate::analyze::SymbolFilter;
+use crate::config::Config;
+use crate::scan::get_commit_counts;
+use crate::search::{
+    create_embedder, create_store, create_store_for_writing, index_path, Embedder, EmbedderConfig,
+    IndexMeta, Indexer, StaleDocuments, VectorStore,
+};
+use crate::types::Visibility;
 
 static INDEXING: Emoji<'_, '_> = Emoji("📊 ", "");
 static SUCCESS: Emoji<'_, '_> = Emoji("✅ ", "");
 static ERROR: Emoji<'_, '_> = Emoji("❌ ", "");
 static INFO: Emoji<'_, '_> = Emoji("ℹ️  ", "");
 
+/// Index the files under `source_root` into the store under `path`/`output_dir`.
+/// The two differ when indexing an extracted archive.
+#[allow(clippy::too_many_arguments)]
 pub async fn run_index(
+    source_root: &Path,
     path: &Path,
+    embedder_config: &EmbedderConfig,
     force: bool,
+    since: Option<&str>,
     prune: bool,
+    prune_threshold: usize,
+    force_prune: bool,
+    hotness_commits: Option<usize>,
+    max_chunks_per_file: Option<usize>,
+    checkpoint_interval: usize,
+    min_visibility: Option<Visibility>,
+    module_summaries: bool,
+    include_ext: &[String],
+    collection: Option<&str>,
     output_dir: &str,
     verbose: bool,
 ) -> Result<()> {
-    let store_path = path.join(output_dir).join("index.json");
+    let search_config = Config::load(path)
+        .and_then(|c| c.search)
+        .unwrap_or_default();
+    let symbol_filter = SymbolFilter::load(&path.join(output_dir));
 
     // Create embedder and store
-    let embedder_config = EmbedderConfig::default();
-    let embedder: Arc<dyn Embedder> = Arc::from(create_embedder(&embedder_config));
+    let embedder: Arc<dyn Embedder> = Arc::from(create_embedder(embedder_config));
 
     // Health check
     if verbose {
@@ -32,11 +55,60 @@ pub async fn run_index(
 
     embedder.health_check().await?;
 
-    let store: Arc<dyn VectorStore> = Arc::new(GobStore::new(store_path));
+    let store: Arc<dyn VectorStore> = Arc::from(create_store_for_writing(
+        &path.join(output_dir),
+        &search_config,
+    )?);
 
     // Create indexer
-    let chunking_config = ChunkingConfig::default();
-    let indexer = Indexer::new(Arc::clone(&store), Arc::clone(&embedder), &chunking_config);
+    let mut indexer = Indexer::new(
+        Arc::clone(&store),
+        Arc::clone(&embedder),
+        &search_config.chunking,
+    )
+    .with_symbol_filter(symbol_filter)
+    .with_io_concurrency(search_config.io_concurrency)
+    .with_save_batch_size(search_config.save_batch_size)
+    .with_extension_denylist(search_config.extension_denylist(include_ext));
+    if let Some(max_chunks) = max_chunks_per_file {
+        indexer = indexer.with_max_chunks_per_file(max_chunks);
+    }
+    indexer = indexer.with_checkpoint_interval(checkpoint_interval);
+    if let Some(min) = min_visibility {
+        indexer = indexer.with_min_visibility(min);
+    }
+    if module_summaries {
+        indexer = indexer.with_module_summaries();
+    }
+    if let Some(collection) = collection {
+        indexer = indexer.with_collection(collection);
+    }
+
+    if let Some(max_commits) = hotness_commits {
+        match get_commit_counts(source_root, max_commits) {
+            Some(counts) => {
+                if verbose {
+                    println!(
+                        "{}Hotness: {} files touched in last {} commits",
+                        INFO,
+                        counts.len(),
+                        max_commits
+                    );
+                }
+                indexer = indexer.with_hotness(counts);
+            }
+            None => println!("{}--hotness requires a git repository, ignoring", INFO),
+        }
+    }
+
+    // Vectors from two models can't share an index: a new model re-embeds everything
+    store.load().await?;
+    let force = reembed_for_model(
+        &store.get_meta().await?,
+        &embedder_config.model,
+        force,
+        since,
+    )?;
 
     // Show progress
     let pb = ProgressBar::new_spinner();
@@ -45,11 +117,32 @@ pub async fn run_index(
             .template("{spinner:.green} {msg}")
             .unwrap(),
     );
-    pb.set_message(format!("{}Indexing {}...", INDEXING, path.display()));
+    pb.set_message(format!("{}Indexing {}...", INDEXING, source_root.display()));
     pb.enable_steady_tick(std::time::Duration::from_millis(100));
 
     // Run indexing
-    let result = indexer.index_all(path, true, force).await?;
+    let result = match since {
+        Some(base_ref) => indexer.index_diff(source_root, base_ref).await?,
+        None => {
+            indexer
+                .index_all_with_checkpoints(source_root, true, force, &|progress| {
+                    pb.println(format!(
+                        "{}Checkpoint: saved {} files, {} chunks",
+                        INFO, progress.files_processed, progress.chunks_created
+                    ));
+                })
+                .await?
+        }
+    };
+
+    // Record which model produced the vectors so search can detect mismatches
+    store
+        .save_meta(IndexMeta {
+            model: Some(embedder_config.model.clone()),
+            dimensions: Some(indexer.vector_dimensions().await?),
+        })
+        .await?;
+    store.persist().await?;
 
     pb.finish_and_clear();
 
@@ -60,10 +153,56 @@ pub async fn run_index(
         style(result.files_processed).green()
     );
     println!("  Chunks created:  {}", style(result.chunks_created).cyan());
+    if result.chunks_reused > 0 {
+        println!(
+            "  Chunks reused:   {} (unchanged symbols)",
+            style(result.chunks_reused).dim()
+        );
+    }
+    if result.chunks_deduplicated > 0 {
+        println!(
+            "  Chunks deduplicated: {} (identical content embedded once)",
+            style(result.chunks_deduplicated).dim()
+        );
+    }
     println!(
         "  Files skipped:   {} (unchanged)",
         style(result.files_skipped).dim()
     );
+    if result.files_skipped_binary > 0 {
+        println!(
+            "  Files skipped:   {} (binary)",
+            style(result.files_skipped_binary).dim()
+        );
+    }
+
+    if !result.truncated.is_empty() {
+        println!(
+            "\n{}Truncated at --max-chunks-per-file ({}):",
+            INFO,
+            result.truncated.len()
+        );
+        for note in result.truncated.iter().take(10) {
+            println!("  - {}", style(note).yellow());
+        }
+        if result.truncated.len() > 10 {
+            println!("  ... and {} more", result.truncated.len() - 10);
+        }
+    }
+
+    if !result.permission_denied.is_empty() {
+        println!(
+            "\n{}Unreadable, not indexed (permission denied) ({}):",
+            INFO,
+            result.permission_denied.len()
+        );
+        for path in result.permission_denied.iter().take(10) {
+            println!("  - {}", style(path).yellow());
+        }
+        if result.permission_denied.len() > 10 {
+            println!("  ... and {} more", result.permission_denied.len() - 10);
+        }
+    }
 
     if !result.errors.is_empty() {
         println!("\n{}Errors ({}):", ERROR, result.errors.len());
@@ -77,12 +216,41 @@ pub async fn run_index(
 
     // Prune deleted files
     if prune {
-        let pruned = indexer.prune_deleted(path, true).await?;
-        if pruned > 0 {
-            println!(
+        let pb = ProgressBar::new(0);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("{bar:30.cyan/blue} {pos}/{len} checked, {msg}")
+                .unwrap(),
+        );
+        let outcome = prune_guarded(
+            &indexer,
+            source_root,
+            prune_threshold,
+            force_prune,
+            &|checked, total, pruned| {
+                pb.set_length(total as u64);
+                pb.set_position(checked as u64);
+                pb.set_message(format!("{} pruned", pruned));
+            },
+        )
+        .await?;
+        pb.finish_and_clear();
+        match outcome {
+            PruneOutcome::Pruned(pruned) if pruned > 0 => println!(
                 "\n  Pruned:          {} (deleted files removed from index)",
                 style(pruned).yellow()
-            );
+            ),
+            PruneOutcome::Pruned(_) => {}
+            PruneOutcome::Refused(stale) => {
+                return Err(anyhow!(
+                    "Not pruning: {} of {} indexed files ({:.0}%) are missing, above --prune-threshold {}%. \
+                     Check the index root, or pass --force-prune.",
+                    stale.paths.len(),
+                    stale.indexed.len(),
+                    stale.percent(),
+                    prune_threshold
+                ));
+            }
         }
     }
 
@@ -99,19 +267,63 @@ pub async fn run_index(
     Ok(())
 }
 
-pub async fn run_index_status(path: &Path, output_dir: &str) -> Result<()> {
-    let store_path = path.join(output_dir).join("index.json");
+/// Result of `--prune` under the mass-deletion guard
+#[derive(Debug)]
+enum PruneOutcome {
+    /// Number of documents removed
+    Pruned(usize),
+    /// Nothing removed: too much of the index would have gone
+    Refused(StaleDocuments),
+}
+
+/// Prune deleted files unless more than `threshold_percent` of the index would
+/// be removed, which usually means the wrong or a partial root; `force` skips the check
+async fn prune_guarded(
+    indexer: &Indexer,
+    root: &Path,
+    threshold_percent: usize,
+    force: bool,
+    progress: &(dyn Fn(usize, usize, usize) + Send + Sync),
+) -> Result<PruneOutcome> {
+    let stale = indexer.stale_documents(root, true).await?;
+    if !force && stale.percent() > threshold_percent as f64 {
+        return Ok(PruneOutcome::Refused(stale));
+    }
+    Ok(PruneOutcome::Pruned(
+        indexer.prune_documents(&stale, progress).await?,
+    ))
+}
+
+/// Index storage backend from the project's `[search] store_backend`
+pub(super) fn store_backend(path: &Path) -> String {
+    Config::load(path)
+        .and_then(|c| c.search)
+        .unwrap_or_default()
+        .store_backend
+}
+
+/// Print index statistics; with `json`, only the `IndexStats` as JSON
+pub async fn run_index_status(path: &Path, output_dir: &str, json: bool) -> Result<()> {
+    let backend = store_backend(path);
+    let store_path = index_path(&path.join(output_dir), &backend);
 
     if !store_path.exists() {
+        if json {
+            return Err(anyhow!("No index found at {}", store_path.display()));
+        }
         println!("{}No index found at {}", INFO, store_path.display());
         println!("Run `agentlens index` to build the search index.");
         return Ok(());
     }
 
-    let store: Arc<dyn VectorStore> = Arc::new(GobStore::new(store_path.clone()));
+    let store: Arc<dyn VectorStore> = Arc::from(create_store(&path.join(output_dir), &backend)?);
     store.load().await?;
 
     let stats = store.stats().await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
 
     println!("\n{}Index Status: {}\n", INFO, store_path.display());
     println!("  Total files:     {}", style(stats.total_files).green());
@@ -127,18 +339,43 @@ pub async fn run_index_status(path: &Path, output_dir: &str) -> Result<()> {
         );
     }
 
+    if !stats.chunk_type_counts.is_empty() {
+        let mut counts: Vec<_> = stats.chunk_type_counts.iter().collect();
+        counts.sort_by(|a, b| {
+            b.1.cmp(a.1)
+                .then_with(|| format!("{:?}", a.0).cmp(&format!("{:?}", b.0)))
+        });
+        println!("\n  Chunk types:");
+        for (chunk_type, count) in counts {
+            println!(
+                "    {:<12} {:>7}  ({:.0}%)",
+                format!("{:?}", chunk_type),
+                count,
+                *count as f64 * 100.0 / stats.total_chunks.max(1) as f64
+            );
+        }
+    }
+    if let Some(files) = stats.files_without_symbols {
+        // Usually a language without symbol extraction, or files that fail to parse
+        println!(
+            "  Files without symbols: {} of {} (window chunks only)",
+            style(files).yellow(),
+            stats.total_files
+        );
+    }
+
     Ok(())
 }
 
 pub async fn run_index_clear(path: &Path, output_dir: &str) -> Result<()> {
-    let store_path = path.join(output_dir).join("index.json");
+    let backend = store_backend(path);
 
-    if !store_path.exists() {
+    if !index_path(&path.join(output_dir), &backend).exists() {
         println!("{}No index found.", INFO);
         return Ok(());
     }
 
-    let store: Arc<dyn VectorStore> = Arc::new(GobStore::new(store_path));
+    let store: Arc<dyn VectorStore> = Arc::from(create_store(&path.join(output_dir), &backend)?);
     store.load().await?;
     store.clear().await?;
 
@@ -146,3 +383,126 @@ pub async fn run_index_clear(path: &Path, output_dir: &str) -> Result<()> {
 
     Ok(())
 }
+
+/// Whether to re-embed every file: with `force`, or when the index `meta`
+/// describes was built with a model other than `model`, since `meta.model`
+/// may only change once no vector from the old model is left. A `--since`
+/// run only re-embeds changed files, so it is refused instead.
+fn reembed_for_model(
+    meta: &IndexMeta,
+    model: &str,
+    force: bool,
+    since: Option<&str>,
+) -> Result<bool> {
+    let Some(old_model) = meta.model.as_deref().filter(|m| *m != model) else {
+        return Ok(force);
+    };
+    if since.is_some() {
+        anyhow::bail!(
+            "Index was built with '{}', now using '{}'; run `agentlens index` without --since to re-embed all files",
+            old_model,
+            model
+        );
+    }
+    if !force {
+        println!(
+            "{}Index was built with '{}', now using '{}'; re-embedding all files",
+            INFO, old_model, model
+        );
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ChunkingConfig;
+    use crate::search::GobStore;
+    use crate::test_support::FixedEmbedder;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_prune_refuses_mass_deletion_without_force() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        for i in 0..10 {
+            let body = format!("fn f{}() {{\n    let x = {};\n}}\n", i, i);
+            fs::write(root.join(format!("f{}.rs", i)), body).unwrap();
+        }
+
+        let store: Arc<dyn VectorStore> =
+            Arc::new(GobStore::new(root.join(".agentlens/index.json")));
+        let indexer = Indexer::new(
+            Arc::clone(&store),
+            Arc::new(FixedEmbedder::new(vec![0.1, 0.2])),
+            &ChunkingConfig::default(),
+        );
+        indexer.index_all(root, true, false).await.unwrap();
+        for i in 1..10 {
+            fs::remove_file(root.join(format!("f{}.rs", i))).unwrap();
+        }
+
+        let outcome = prune_guarded(&indexer, root, 50, false, &|_, _, _| {})
+            .await
+            .unwrap();
+        match outcome {
+            PruneOutcome::Refused(stale) => {
+                assert_eq!(stale.paths.len(), 9);
+                assert_eq!(stale.percent(), 90.0);
+            }
+            other => panic!("expected refusal, got {:?}", other),
+        }
+        assert_eq!(store.list_documents(None).await.unwrap().len(), 10);
+
+        let outcome = prune_guarded(&indexer, root, 50, true, &|_, _, _| {})
+            .await
+            .unwrap();
+        assert!(matches!(outcome, PruneOutcome::Pruned(9)));
+        assert_eq!(store.list_documents(None).await.unwrap(), vec!["f0.rs"]);
+    }
+
+    #[tokio::test]
+    async fn test_status_json_needs_an_index() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        assert!(run_index_status(root, ".agentlens", true).await.is_err());
+
+        fs::write(root.join("lib.rs"), "fn lib() {\n    let x = 1;\n}\n").unwrap();
+        let store: Arc<dyn VectorStore> =
+            Arc::new(GobStore::new(root.join(".agentlens/index.json")));
+        let indexer = Indexer::new(
+            Arc::clone(&store),
+            Arc::new(FixedEmbedder::new(vec![0.1, 0.2])),
+            &ChunkingConfig::default(),
+        );
+        indexer.index_all(root, true, false).await.unwrap();
+        run_index_status(root, ".agentlens", true).await.unwrap();
+
+        // Chunk types key the breakdown, so they must serialize as strings
+        let stats = serde_json::to_value(store.stats().await.unwrap()).unwrap();
+        assert_eq!(stats["total_files"], 1);
+        assert!(!stats["chunk_type_counts"].as_object().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_model_change_re_embeds_everything() {
+        let meta = IndexMeta {
+            model: Some("nomic-embed-text".to_string()),
+            dimensions: Some(768),
+        };
+        assert!(!reembed_for_model(&meta, "nomic-embed-text", false, None).unwrap());
+        assert!(reembed_for_model(&meta, "nomic-embed-text", true, None).unwrap());
+        assert!(
+            !reembed_for_model(&IndexMeta::default(), "mxbai-embed-large", false, None).unwrap()
+        );
+        assert!(reembed_for_model(&meta, "mxbai-embed-large", false, None).unwrap());
+
+        // Only changed files would get the new model, even with --force
+        for force in [false, true] {
+            let err =
+                reembed_for_model(&meta, "mxbai-embed-large", force, Some("main")).unwrap_err();
+            assert!(err.to_string().contains("without --since"), "{}", err);
+        }
+    }
+}
@@ -11,15 +11,21 @@ pub mod tui;
 mod update;
 mod watch;
 
-pub use args::{Args, Command, HooksAction, IndexAction, SkillsAction, TelemetryAction};
+pub use args::{
+    Args, Command, HooksAction, IndexAction, OutputFormat, SearchFormat, SkillsAction,
+    TelemetryAction,
+};
 pub use check::run_check;
 pub use hooks::{install_hooks, install_hooks_with_manager, remove_hooks};
 pub use index::{run_index, run_index_clear, run_index_status};
-pub use search::run_search;
-pub use serve::{run_mcp_http_server, run_mcp_server};
+pub use search::{
+    read_batch_queries, run_explain_chunking, run_review, run_search, run_similar_files,
+    SnippetSize,
+};
+pub use serve::{run_mcp_http_server, run_mcp_server, run_store_server};
 pub use skills::{install_skills, list_skills, remove_skills};
 pub use telemetry::{run_telemetry_all_modules, run_telemetry_module, run_telemetry_summary};
 pub use templates::run_templates;
 pub use tui::{execute_setup, is_interactive, run_interactive_init, InitOptions};
 pub use update::run_update;
-pub use watch::run_watch;
+pub use watch::{run_watch, run_watch_serve};
@@ -1,97 +1,1089 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use console::{style, Emoji};
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Instant;
 
-use crate::config::SearchOptionsConfig;
-use crate::search::{create_embedder, EmbedderConfig, GobStore, Searcher};
+use crate::analyze::{extract_symbols, SymbolFilter};
+use crate::cli::index::store_backend;
+use crate::cli::SearchFormat;
+use crate::config::Config;
+use crate::emit::to_yaml;
+use crate::scan::{get_diff_hunks, is_git_repo};
+use crate::search::{
+    apply_path_affinity, compute_facets, create_embedder, create_reranker, create_store,
+    create_store_for_writing, explain_chunking, grep_search, index_path, matched_terms,
+    preview_lines, probe_dimensions, review_hunks, similar_files, term_spans, ChunkExplanation,
+    Chunker, Embedder, EmbedderConfig, GobStore, Indexer, RemoteStore, RerankerConfig, ReviewMatch,
+    ScoreBreakdown, SearchAnalytics, SearchFacets, SearchFilter, SearchResult, Searcher,
+    SimilarFile, VectorStore, PATH_AFFINITY_CANDIDATES_FACTOR,
+};
+use crate::types::FileEntry;
 
 static SEARCH: Emoji<'_, '_> = Emoji("🔍 ", "");
 static FILE: Emoji<'_, '_> = Emoji("📄 ", "");
+static DIFF: Emoji<'_, '_> = Emoji("📝 ", "");
 
+/// Default lines of each result's preview in the human listing
+pub const DEFAULT_SNIPPET_LINES: usize = 5;
+/// Default characters of each preview shown before it is cut off
+pub const DEFAULT_SNIPPET_CHARS: usize = 200;
+
+/// How much of each result's content the human listing previews
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnippetSize {
+    pub lines: usize,
+    /// Characters across the whole preview (line breaks count as one), not
+    /// bytes
+    pub chars: usize,
+}
+
+impl Default for SnippetSize {
+    fn default() -> Self {
+        Self {
+            lines: DEFAULT_SNIPPET_LINES,
+            chars: DEFAULT_SNIPPET_CHARS,
+        }
+    }
+}
+
+/// Run each of `queries` against one loaded store and embedder, printing
+/// results per query (a single JSONL record each with `--format jsonl`)
+#[allow(clippy::too_many_arguments)]
 pub async fn run_search(
     path: &Path,
-    query: &str,
+    embedder_config: &EmbedderConfig,
+    queries: &[String],
     limit: usize,
     hybrid: bool,
-    json: bool,
+    streaming: bool,
+    grep: bool,
+    reindex_on_mismatch: bool,
+    facets: bool,
+    explain: bool,
+    focus: Option<&str>,
+    filter: SearchFilter,
+    format: SearchFormat,
+    snippet: SnippetSize,
+    remote: Option<&str>,
     output_dir: &str,
+    quiet: bool,
 ) -> Result<()> {
-    // Setup store path
-    let store_path = path.join(output_dir).join("index.json");
-
-    if !store_path.exists() {
-        anyhow::bail!("No search index found. Run `agentlens index` first to build the index.");
+    let backend = store_backend(path);
+    let has_index = remote.is_some() || index_path(&path.join(output_dir), &backend).exists();
+    let config = Config::load(path)
+        .and_then(|c| c.search)
+        .unwrap_or_default();
+    let rerank = config.rerank.clone();
+    let search_config = config.search.clone();
+    let analytics = SearchAnalytics::when_enabled(&path.join(output_dir), search_config.analytics);
+    if grep || !has_index {
+        if !quiet
+            && !matches!(
+                format,
+                SearchFormat::Json | SearchFormat::Jsonl | SearchFormat::Yaml
+            )
+        {
+            eprintln!(
+                "{}",
+                style("Grep mode: matching words in files directly. Run `agentlens index` for semantic results.").dim()
+            );
+        }
+        for query in queries {
+            let started = Instant::now();
+            let results = match focus {
+                Some(focus) => {
+                    let candidates = limit * PATH_AFFINITY_CANDIDATES_FACTOR;
+                    let mut results = apply_path_affinity(
+                        grep_search(path, query, candidates, true, &filter)?,
+                        focus,
+                    );
+                    results.truncate(limit);
+                    results
+                }
+                None => grep_search(path, query, limit, true, &filter)?,
+            };
+            if let Some(analytics) = &analytics {
+                analytics.record(query, "grep", &results, started.elapsed());
+            }
+            print_results(query, &results, None, format, snippet, facets)?;
+        }
+        return Ok(());
     }
 
     // Create embedder
-    let embedder_config = EmbedderConfig::default();
-    let embedder: Arc<dyn crate::search::Embedder> = Arc::from(create_embedder(&embedder_config));
+    let embedder: Arc<dyn Embedder> = Arc::from(create_embedder(embedder_config));
 
-    // Create store
-    let store: Arc<dyn crate::search::VectorStore> = Arc::new(GobStore::new(store_path));
+    if reindex_on_mismatch {
+        // Rebuild the index as `agentlens index` would with this config
+        let store: Arc<dyn VectorStore> =
+            Arc::from(create_store_for_writing(&path.join(output_dir), &config)?);
+        let indexer = Indexer::new(store, Arc::clone(&embedder), &config.chunking)
+            .with_symbol_filter(SymbolFilter::load(&path.join(output_dir)))
+            .with_io_concurrency(config.io_concurrency)
+            .with_save_batch_size(config.save_batch_size)
+            .with_extension_denylist(config.extension_denylist(&[]));
+        if indexer
+            .reindex_if_mismatched(path, &embedder_config.model, true)
+            .await?
+            && !quiet
+        {
+            eprintln!(
+                "Rebuilt index with model '{}' before searching",
+                embedder_config.model
+            );
+        }
+    }
+
+    let store = match remote {
+        Some(url) => {
+            check_model(
+                Arc::new(RemoteStore::new(url)),
+                embedder_config,
+                embedder.as_ref(),
+            )
+            .await?
+        }
+        None => open_store(path, output_dir, embedder_config, &embedder, streaming).await?,
+    };
 
-    // Create searcher
-    let search_config = SearchOptionsConfig::default();
-    let searcher = Searcher::new(
-        store,
+    // Create searcher (hybrid needs every chunk in memory, so streaming is vector-only)
+    let mut searcher = Searcher::new(
+        Arc::clone(&store),
         embedder,
-        if hybrid {
-            true
+        if streaming {
+            false
         } else {
-            search_config.hybrid_enabled
+            hybrid || search_config.hybrid_enabled
         },
         search_config.hybrid_k,
+    )
+    .with_hybrid_weights(
+        search_config.hybrid_vector_weight,
+        search_config.hybrid_text_weight,
     );
+    if let Some(focus) = focus {
+        searcher = searcher.with_focus(focus);
+    }
+    if let Some(rerank) = rerank {
+        let reranker = create_reranker(&RerankerConfig {
+            provider: rerank.provider,
+            model: rerank.model,
+            endpoint: rerank.endpoint,
+            timeout_secs: rerank.timeout_secs,
+        });
+        searcher = searcher.with_reranker(Arc::from(reranker));
+    }
 
-    // Perform search
-    let results = searcher.smart_search(query, limit).await?;
+    // Loaded once here; searches reuse it while the file is unchanged (streaming has no timestamps)
+    store.load().await?;
+    if !quiet
+        && !matches!(
+            format,
+            SearchFormat::Json | SearchFormat::Jsonl | SearchFormat::Yaml
+        )
+    {
+        let last_updated = store.stats().await?.last_updated;
+        if let Some(warning) =
+            staleness_warning(last_updated, Utc::now(), search_config.stale_after_days)
+        {
+            eprintln!("{}", style(warning).yellow());
+        }
+    }
 
-    if json {
-        let output = serde_json::to_string_pretty(&results)?;
-        println!("{}", output);
+    search_queries(
+        &searcher,
+        queries,
+        limit,
+        &filter,
+        explain,
+        analytics.as_ref(),
+        quiet,
+        |query, results, breakdowns| {
+            print_results(query, results, breakdowns, format, snippet, facets)
+        },
+    )
+    .await
+}
+
+/// Search each query in turn with the same searcher, handing results (and
+/// their score breakdowns with `explain`) to `emit` and logging each search to
+/// `analytics` when enabled. Warns once (unless `quiet`) when the embedder is
+/// unreachable and results are keyword-only.
+#[allow(clippy::too_many_arguments)]
+async fn search_queries(
+    searcher: &Searcher,
+    queries: &[String],
+    limit: usize,
+    filter: &SearchFilter,
+    explain: bool,
+    analytics: Option<&SearchAnalytics>,
+    quiet: bool,
+    mut emit: impl FnMut(&str, &[SearchResult], Option<&[ScoreBreakdown]>) -> Result<()>,
+) -> Result<()> {
+    let mut warned = quiet;
+    for query in queries {
+        let started = Instant::now();
+        let outcome = searcher
+            .search_or_keywords(query, limit, filter.clone(), explain)
+            .await?;
+        if outcome.degraded() && !warned {
+            eprintln!(
+                "{}",
+                style("Warning: cannot reach the embedding server; results are keyword-only")
+                    .yellow()
+            );
+            warned = true;
+        }
+        if let Some(analytics) = analytics {
+            analytics.record(query, outcome.mode, &outcome.results, started.elapsed());
+        }
+        emit(query, &outcome.results, outcome.breakdowns.as_deref())?;
+    }
+    Ok(())
+}
+
+/// Non-empty lines of a `--batch` file, trimmed
+pub fn read_batch_queries(path: &Path) -> Result<Vec<String>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read queries from {}", path.display()))?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// One JSONL line: the query with its results (and facets when requested)
+fn format_jsonl_record(
+    query: &str,
+    results: &[SearchResult],
+    breakdowns: Option<&[ScoreBreakdown]>,
+    facets: bool,
+) -> Result<String> {
+    let mut record = serde_json::json!({
+        "query": query,
+        "results": results_json(query, results, breakdowns)?,
+    });
+    if facets {
+        record["facets"] = serde_json::to_value(compute_facets(results))?;
+    }
+    Ok(serde_json::to_string(&record)?)
+}
+
+fn print_results(
+    query: &str,
+    results: &[SearchResult],
+    breakdowns: Option<&[ScoreBreakdown]>,
+    format: SearchFormat,
+    snippet: SnippetSize,
+    facets: bool,
+) -> Result<()> {
+    match format {
+        SearchFormat::Human => {
+            print_human(query, results, breakdowns, snippet);
+            if facets && !results.is_empty() {
+                print_facets(&compute_facets(results));
+            }
+        }
+        SearchFormat::Json | SearchFormat::Yaml => {
+            let value = if facets {
+                serde_json::json!({
+                    "results": results_json(query, results, breakdowns)?,
+                    "facets": compute_facets(results),
+                })
+            } else {
+                results_json(query, results, breakdowns)?
+            };
+            print_document(&value, format)?;
+        }
+        SearchFormat::Csv => print!("{}", format_csv(results)),
+        SearchFormat::Jsonl => println!(
+            "{}",
+            format_jsonl_record(query, results, breakdowns, facets)?
+        ),
+    }
+
+    Ok(())
+}
+
+/// Pretty JSON, or YAML for `--format yaml`
+fn print_document<T: serde::Serialize>(value: &T, format: SearchFormat) -> Result<()> {
+    if format == SearchFormat::Yaml {
+        print!("{}", to_yaml(value)?);
     } else {
-        if results.is_empty() {
-            println!("No results found for: {}", style(query).italic());
-            return Ok(());
+        println!("{}", serde_json::to_string_pretty(value)?);
+    }
+    Ok(())
+}
+
+fn print_facets(facets: &SearchFacets) {
+    for (title, counts) in [
+        ("Languages", &facets.languages),
+        ("Modules", &facets.modules),
+    ] {
+        let line: Vec<String> = counts
+            .iter()
+            .map(|f| format!("{} {}", f.value, style(f.count).cyan()))
+            .collect();
+        println!("{}: {}", style(title).bold(), line.join(", "));
+    }
+}
+
+/// Embed the hunks changed since `base_ref` and list related indexed code for each
+pub async fn run_review(
+    path: &Path,
+    embedder_config: &EmbedderConfig,
+    base_ref: &str,
+    limit: usize,
+    format: SearchFormat,
+    output_dir: &str,
+) -> Result<()> {
+    if !is_git_repo(path) {
+        anyhow::bail!("--review requires a git repository");
+    }
+
+    let embedder: Arc<dyn Embedder> = Arc::from(create_embedder(embedder_config));
+    let store = open_store(path, output_dir, embedder_config, &embedder, false).await?;
+    let searcher = Searcher::new(store, embedder, false, 0.0);
+
+    let hunks = get_diff_hunks(path, base_ref)
+        .ok_or_else(|| anyhow::anyhow!("Failed to read diff against {}", base_ref))?;
+    let matches = review_hunks(&searcher, &hunks, limit).await?;
+
+    match format {
+        SearchFormat::Human => print_review_human(base_ref, &matches),
+        SearchFormat::Json | SearchFormat::Yaml => print_document(&matches, format)?,
+        SearchFormat::Csv => print!("{}", format_review_csv(&matches)),
+        SearchFormat::Jsonl => {
+            for m in &matches {
+                println!("{}", serde_json::to_string(m)?);
+            }
         }
+    }
+
+    Ok(())
+}
+
+/// List the indexed files whose averaged embeddings are closest to `file`'s
+pub async fn run_similar_files(
+    path: &Path,
+    file: &Path,
+    limit: usize,
+    json: bool,
+    output_dir: &str,
+) -> Result<()> {
+    let store = open_index(path, output_dir, false)?;
+    store.load().await?;
+    let target = index_relative_path(path, file);
+    let similar = similar_files(store.as_ref(), &target, None, limit).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&similar)?);
+    } else {
+        print_similar_human(&target, &similar);
+    }
+
+    Ok(())
+}
+
+/// Print the chunks `agentlens index` would create for `file`, using the
+/// same symbol filter and chunk sizes as the indexer
+pub fn run_explain_chunking(path: &Path, file: &Path, json: bool, output_dir: &str) -> Result<()> {
+    let relative = index_relative_path(path, file);
+    let abs_path = path.join(&relative);
+    let content = std::fs::read_to_string(&abs_path)
+        .with_context(|| format!("Failed to read {}", abs_path.display()))?;
+    let entry = FileEntry::new(
+        abs_path,
+        relative,
+        content.len() as u64,
+        content.lines().count(),
+        0,
+    );
+
+    let symbols =
+        SymbolFilter::load(&path.join(output_dir)).apply(extract_symbols(&entry, &content));
+    let config = Config::load(path)
+        .and_then(|c| c.search)
+        .unwrap_or_default()
+        .chunking;
+    let chunker = Chunker::from_config(&config);
+    let report = explain_chunking(&chunker, &entry, &content, &symbols);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_chunking_human(&entry, symbols.len(), &report);
+    }
+
+    Ok(())
+}
 
+fn print_chunking_human(file: &FileEntry, symbol_count: usize, report: &[ChunkExplanation]) {
+    println!(
+        "\n{}{} ({:?}, {} lines, {} symbols) -> {} chunks\n",
+        FILE,
+        style(&file.relative_path).yellow().bold(),
+        file.language,
+        file.line_count,
+        symbol_count,
+        report.len()
+    );
+    for chunk in report {
         println!(
-            "\n{}Found {} results for: {}\n",
-            SEARCH,
-            style(results.len()).cyan(),
-            style(query).yellow().bold()
+            "  {} {} {} {}",
+            style(format!("L{}-{}", chunk.start_line, chunk.end_line)).cyan(),
+            style(format!("{:?}", chunk.chunk_type)).green(),
+            style(&chunk.origin).magenta(),
+            style(&chunk.id).dim()
         );
+        println!("      {} chars, ~{} tokens", chunk.chars, chunk.tokens);
+    }
+    println!();
+}
 
-        for (i, result) in results.iter().enumerate() {
-            let chunk = &result.chunk;
-            println!(
-                "{} {}. {} {}",
-                FILE,
-                style(i + 1).dim(),
-                style(&chunk.file_path).green(),
-                style(format!("(L{}-{})", chunk.start_line, chunk.end_line)).dim()
-            );
+/// `file` as the index stores it: relative to the project root with `/`
+/// separators. Paths that exist on disk may be given relative to the cwd.
+fn index_relative_path(root: &Path, file: &Path) -> String {
+    let relative = file
+        .canonicalize()
+        .ok()
+        .and_then(|abs| abs.strip_prefix(root).ok().map(Path::to_path_buf))
+        .unwrap_or_else(|| file.to_path_buf());
+    relative
+        .to_string_lossy()
+        .replace('\\', "/")
+        .trim_start_matches("./")
+        .to_string()
+}
+
+fn print_similar_human(target: &str, similar: &[SimilarFile]) {
+    if similar.is_empty() {
+        println!(
+            "No other indexed files to compare with {}",
+            style(target).italic()
+        );
+        return;
+    }
+
+    println!(
+        "\n{}Files most similar to {}\n",
+        SEARCH,
+        style(target).yellow().bold()
+    );
+    for (i, file) in similar.iter().enumerate() {
+        println!(
+            "{} {}. {} {} {}",
+            FILE,
+            style(i + 1).dim(),
+            style(format!("{:.3}", file.score)).cyan(),
+            style(&file.file_path).green(),
+            style(format!("({} chunks)", file.chunks)).dim()
+        );
+    }
+    println!();
+}
+
+/// Warning text when the index was last updated more than `max_age_days` ago
+fn staleness_warning(
+    last_updated: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    max_age_days: u64,
+) -> Option<String> {
+    if max_age_days == 0 {
+        return None;
+    }
+
+    let age_days = (now - last_updated?).num_days();
+    (age_days > max_age_days as i64).then(|| {
+        format!(
+            "Warning: search index is {} days old; run `agentlens index` to refresh it",
+            age_days
+        )
+    })
+}
+
+/// Open the on-disk index, warning if it was built with a different model
+async fn open_store(
+    path: &Path,
+    output_dir: &str,
+    embedder_config: &EmbedderConfig,
+    embedder: &Arc<dyn Embedder>,
+    streaming: bool,
+) -> Result<Arc<dyn VectorStore>> {
+    check_model(
+        open_index(path, output_dir, streaming)?,
+        embedder_config,
+        embedder.as_ref(),
+    )
+    .await
+}
+
+/// Warn when `store` was built with a different model than `embedder_config`'s,
+/// and fail when its vectors have a different length than `embedder` returns
+async fn check_model(
+    store: Arc<dyn VectorStore>,
+    embedder_config: &EmbedderConfig,
+    embedder: &dyn Embedder,
+) -> Result<Arc<dyn VectorStore>> {
+    if let Some(warning) = model_warning(store.as_ref(), embedder_config, embedder).await? {
+        eprintln!("{}", warning);
+    }
+    Ok(store)
+}
+
+/// Warning text when `store` was built with a different model. The store is
+/// loaded first: the JSON store only knows its meta once read from disk.
+async fn model_warning(
+    store: &dyn VectorStore,
+    embedder_config: &EmbedderConfig,
+    embedder: &dyn Embedder,
+) -> Result<Option<String>> {
+    store.load().await?;
+    let meta = store.get_meta().await?;
+    // The configured dimensions may not match what the model returns
+    if meta.dimensions.is_some() {
+        meta.check_dimensions(probe_dimensions(embedder).await?)?;
+    }
+    Ok(meta
+        .model
+        .filter(|index_model| *index_model != embedder_config.model)
+        .map(|index_model| {
+            format!(
+                "Warning: index was built with model '{}' but searching with '{}'; results may be meaningless",
+                index_model, embedder_config.model
+            )
+        }))
+}
+
+/// Open the on-disk index, failing if none has been built
+pub(crate) fn open_index(
+    path: &Path,
+    output_dir: &str,
+    streaming: bool,
+) -> Result<Arc<dyn VectorStore>> {
+    let index_dir = path.join(output_dir);
+    let search_config = Config::load(path)
+        .and_then(|c| c.search)
+        .unwrap_or_default();
+    let backend = search_config.store_backend.as_str();
+    let store_path = index_path(&index_dir, backend);
+
+    if !store_path.exists() {
+        anyhow::bail!("No search index found. Run `agentlens index` first to build the index.");
+    }
+
+    // SQLite never holds the index in memory, so streaming and HNSW only change the JSON store
+    let store: Arc<dyn VectorStore> = if streaming && backend != "sqlite" {
+        Arc::new(GobStore::streaming(store_path))
+    } else if search_config.hnsw && backend != "sqlite" {
+        Arc::new(GobStore::new(store_path).with_hnsw(true))
+    } else {
+        Arc::from(create_store(&index_dir, backend)?)
+    };
+
+    Ok(store)
+}
+
+fn print_review_human(base_ref: &str, matches: &[ReviewMatch]) {
+    if matches.is_empty() {
+        println!(
+            "No added or modified lines since {}",
+            style(base_ref).italic()
+        );
+        return;
+    }
+
+    println!(
+        "\n{}Reviewing {} hunks changed since {}\n",
+        SEARCH,
+        style(matches.len()).cyan(),
+        style(base_ref).yellow().bold()
+    );
+
+    for m in matches {
+        println!(
+            "{}{} {}",
+            DIFF,
+            style(&m.diff.file_path).green().bold(),
+            style(format!("(L{}-{})", m.diff.start_line, m.diff.end_line)).dim()
+        );
+        if m.related.is_empty() {
+            println!("   {}", style("no related code found").dim());
+        }
+        for result in &m.related {
             println!(
-                "   Score: {} | Type: {:?}",
+                "   {} {} {}",
                 style(format!("{:.3}", result.score)).cyan(),
-                chunk.chunk_type
+                result.chunk.file_path,
+                style(format!(
+                    "(L{}-{})",
+                    result.chunk.start_line, result.chunk.end_line
+                ))
+                .dim()
             );
+        }
+        println!();
+    }
+}
+
+/// Render review matches as CSV, one row per (hunk, related chunk) pair
+fn format_review_csv(matches: &[ReviewMatch]) -> String {
+    let mut output = String::from(
+        "diff_file,diff_start_line,diff_end_line,rank,file,start_line,end_line,score\n",
+    );
+
+    for m in matches {
+        for (i, result) in m.related.iter().enumerate() {
+            output.push_str(&format!(
+                "{},{},{},{},{},{},{},{:.4}\n",
+                csv_field(&m.diff.file_path),
+                m.diff.start_line,
+                m.diff.end_line,
+                i + 1,
+                csv_field(&result.chunk.file_path),
+                result.chunk.start_line,
+                result.chunk.end_line,
+                result.score
+            ));
+        }
+    }
+
+    output
+}
+
+fn print_human(
+    query: &str,
+    results: &[SearchResult],
+    breakdowns: Option<&[ScoreBreakdown]>,
+    snippet: SnippetSize,
+) {
+    if results.is_empty() {
+        println!("No results found for: {}", style(query).italic());
+        return;
+    }
+
+    println!(
+        "\n{}Found {} results for: {}\n",
+        SEARCH,
+        style(results.len()).cyan(),
+        style(query).yellow().bold()
+    );
 
-            // Show preview (first 200 chars of content, skip header)
-            let preview_lines: Vec<&str> = chunk.content.lines().skip(3).take(5).collect();
-            let preview = preview_lines.join("\n");
-            if !preview.is_empty() {
-                let truncated = if preview.len() > 200 {
-                    format!("{}...", &preview[..200])
-                } else {
-                    preview
-                };
-                println!("   {}", style(truncated).dim());
+    for (i, result) in results.iter().enumerate() {
+        let chunk = &result.chunk;
+        println!(
+            "{} {}. {} {}",
+            FILE,
+            style(i + 1).dim(),
+            style(&chunk.file_path).green(),
+            style(format!("(L{}-{})", chunk.start_line, chunk.end_line)).dim()
+        );
+        println!(
+            "   Score: {} | Type: {:?} | Lang: {}",
+            style(format!("{:.3}", result.score)).cyan(),
+            chunk.chunk_type,
+            chunk.language().name()
+        );
+        if let Some(breakdown) = breakdowns.and_then(|b| b.get(i)) {
+            println!("   {}", style(format_breakdown(breakdown)).dim());
+        }
+        if let (Some(name), Some(kind)) = (&chunk.symbol_name, chunk.symbol_kind) {
+            println!("   In: {} {}", kind, style(name).cyan());
+        }
+
+        // Preview around the best-matching line, matched terms in bold
+        let terms = matched_terms(&chunk.content, query);
+        let preview = preview_lines(&chunk.content, &terms, snippet.lines);
+        if !preview.is_empty() {
+            let lines = highlight_preview(&preview, &terms, snippet.chars);
+            println!("   {}", lines.join("\n   "));
+        }
+        println!();
+    }
+}
+
+/// `lines` highlighted until `max_chars` characters of the preview are used;
+/// the line that runs over is cut and the rest dropped
+fn highlight_preview(lines: &[&str], terms: &[String], max_chars: usize) -> Vec<String> {
+    let mut remaining = max_chars;
+    let mut output = Vec::new();
+    for line in lines {
+        output.push(highlight_line(line, terms, remaining));
+        let chars = line.chars().count();
+        if chars >= remaining {
+            break;
+        }
+        remaining -= chars + 1;
+    }
+    output
+}
+
+/// `line` cut to `max_chars` characters (never mid-character), dimmed, with
+/// words in `terms` in bold
+fn highlight_line(line: &str, terms: &[String], max_chars: usize) -> String {
+    let cut = line
+        .char_indices()
+        .nth(max_chars)
+        .map_or(line.len(), |(i, _)| i);
+    let mut output = String::new();
+    let mut last = 0;
+    for span in term_spans(&line[..cut], terms) {
+        output.push_str(&style(&line[last..span.start]).dim().to_string());
+        output.push_str(&style(&line[span.clone()]).bold().to_string());
+        last = span.end;
+    }
+    output.push_str(&style(&line[last..cut]).dim().to_string());
+    if cut < line.len() {
+        output.push_str(&style("...").dim().to_string());
+    }
+    output
+}
+
+/// `--explain` line: where each component came from, `-` for a list that missed
+fn format_breakdown(breakdown: &ScoreBreakdown) -> String {
+    let rank = |rank: Option<usize>| rank.map_or("-".to_string(), |r| format!("#{}", r));
+    format!(
+        "Vector rank: {} | Text rank: {} | RRF: {:.4}",
+        rank(breakdown.vector_rank),
+        rank(breakdown.text_rank),
+        breakdown.rrf_score
+    )
+}
+
+/// Results as JSON, each with the query terms its chunk contains
+fn results_json(
+    query: &str,
+    results: &[SearchResult],
+    breakdowns: Option<&[ScoreBreakdown]>,
+) -> Result<serde_json::Value> {
+    results
+        .iter()
+        .enumerate()
+        .map(|(i, result)| {
+            let mut value = serde_json::to_value(result)?;
+            value["matched_terms"] = serde_json::json!(matched_terms(&result.chunk.content, query));
+            if let Some(breakdown) = breakdowns.and_then(|b| b.get(i)) {
+                value["explain"] = serde_json::to_value(breakdown)?;
             }
-            println!();
+            Ok(value)
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(serde_json::Value::Array)
+}
+
+/// Render results as CSV: rank, file, start_line, end_line, score, chunk_type
+fn format_csv(results: &[SearchResult]) -> String {
+    let mut output = String::from("rank,file,start_line,end_line,score,chunk_type\n");
+
+    for (i, result) in results.iter().enumerate() {
+        let chunk = &result.chunk;
+        output.push_str(&format!(
+            "{},{},{},{},{:.4},{:?}\n",
+            i + 1,
+            csv_field(&chunk.file_path),
+            chunk.start_line,
+            chunk.end_line,
+            result.score,
+            chunk.chunk_type
+        ));
+    }
+
+    output
+}
+
+/// Quote a CSV field if it contains a delimiter, quote, or newline (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::{Chunk, ChunkType, IndexMeta};
+    use crate::test_support::{make_chunk, FixedEmbedder};
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    fn make_result(file_path: &str, score: f32) -> SearchResult {
+        SearchResult::new(
+            Chunk {
+                content: "fn main() {}".to_string(),
+                hash: "abc123".to_string(),
+                chunk_type: ChunkType::Function,
+                ..make_chunk(&format!("{}:block:1", file_path), file_path, vec![0.1, 0.2])
+            },
+            score,
+        )
+    }
+
+    #[test]
+    fn test_highlight_line_cuts_on_char_boundaries() {
+        let line = "let café = \"naïve ünïcödé\";";
+        let cut = console::strip_ansi_codes(&highlight_line(line, &[], 8)).to_string();
+        assert_eq!(cut, "let café...");
+
+        let whole = console::strip_ansi_codes(&highlight_line(line, &[], 200)).to_string();
+        assert_eq!(whole, line);
+    }
+
+    #[test]
+    fn test_default_snippet_caps_the_whole_preview_at_200_chars() {
+        let line = "x".repeat(60);
+        let lines = vec![line.as_str(); DEFAULT_SNIPPET_LINES];
+        let snippet = SnippetSize::default();
+        assert_eq!(snippet.lines, 5);
+        assert_eq!(snippet.chars, 200);
+
+        let preview: Vec<String> = highlight_preview(&lines, &[], snippet.chars)
+            .iter()
+            .map(|l| console::strip_ansi_codes(l).to_string())
+            .collect();
+        // 3 full lines and their breaks use 183 chars, leaving 17 of the fourth
+        assert_eq!(preview.len(), 4);
+        assert_eq!(preview[3], format!("{}...", "x".repeat(17)));
+        assert_eq!(preview[..3].join("\n").chars().count() + 1 + 17, 200);
+    }
+
+    #[test]
+    fn test_format_csv_header_and_rows() {
+        let results = vec![
+            make_result("src/main.rs", 0.9),
+            make_result("src/lib.rs", 0.5),
+        ];
+
+        let csv = format_csv(&results);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], "rank,file,start_line,end_line,score,chunk_type");
+        assert_eq!(lines[1], "1,src/main.rs,1,10,0.9000,Function");
+        assert_eq!(lines[2], "2,src/lib.rs,1,10,0.5000,Function");
+    }
+
+    #[test]
+    fn test_format_csv_quotes_path_with_comma() {
+        let results = vec![make_result("src/a,b \"odd\".rs", 0.75)];
+
+        let csv = format_csv(&results);
+        let row = csv.lines().nth(1).unwrap();
+
+        assert_eq!(row, "1,\"src/a,b \"\"odd\"\".rs\",1,10,0.7500,Function");
+    }
+
+    #[test]
+    fn test_staleness_warning_for_backdated_index() {
+        let now = Utc::now();
+        let backdated = Some(now - Duration::days(10));
+        let warning = staleness_warning(backdated, now, 7).unwrap();
+        assert!(warning.contains("10 days old"));
+        assert!(warning.contains("agentlens index"));
+
+        assert!(staleness_warning(Some(now - Duration::days(2)), now, 7).is_none());
+        assert!(staleness_warning(backdated, now, 0).is_none());
+        assert!(staleness_warning(None, now, 7).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_model_warning_for_index_built_with_other_model() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("index.json");
+        let writer = GobStore::new(path.clone());
+        writer
+            .save_meta(IndexMeta {
+                model: Some("all-minilm".to_string()),
+                dimensions: Some(768),
+            })
+            .await
+            .unwrap();
+        writer.persist().await.unwrap();
+
+        let embedder = FixedEmbedder::new(vec![0.0; 768]);
+        let config = EmbedderConfig::default();
+        let warning = model_warning(&GobStore::new(path.clone()), &config, &embedder)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(warning.contains("'all-minilm'"));
+
+        let same = EmbedderConfig::default().with_overrides(Some("all-minilm".to_string()), None);
+        assert!(model_warning(&GobStore::new(path), &same, &embedder)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_dimension_check_uses_returned_vectors() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("index.json");
+        let writer = GobStore::new(path.clone());
+        writer
+            .save_meta(IndexMeta {
+                model: Some("all-minilm".to_string()),
+                dimensions: Some(384),
+            })
+            .await
+            .unwrap();
+        writer.persist().await.unwrap();
+        let config = EmbedderConfig::default().with_overrides(Some("all-minilm".to_string()), None);
+
+        // The config still says 768, but the model returns 384
+        let embedder = FixedEmbedder::new(vec![0.0; 384]).claiming_dimensions(768);
+        assert!(
+            model_warning(&GobStore::new(path.clone()), &config, &embedder)
+                .await
+                .is_ok()
+        );
+
+        let embedder = FixedEmbedder::new(vec![0.0; 768]).claiming_dimensions(384);
+        let err = model_warning(&GobStore::new(path), &config, &embedder)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("produces 768-dimension"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_json_results_list_matched_terms() {
+        let results = vec![make_result("src/main.rs", 0.9)];
+
+        let record: serde_json::Value = serde_json::from_str(
+            &format_jsonl_record("Main entry fn", &results, None, false).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            record["results"][0]["matched_terms"],
+            serde_json::json!(["main", "fn"])
+        );
+        assert_eq!(record["results"][0]["chunk"]["file_path"], "src/main.rs");
+        assert!(record["results"][0].get("explain").is_none());
+    }
+
+    #[test]
+    fn test_explain_adds_score_breakdown() {
+        let results = vec![make_result("src/main.rs", 0.9)];
+        let breakdown = ScoreBreakdown {
+            vector_rank: Some(3),
+            text_rank: None,
+            rrf_score: 0.25,
+        };
+
+        let json = results_json("main", &results, Some(&[breakdown])).unwrap();
+        assert_eq!(
+            json[0]["explain"],
+            serde_json::json!({"vector_rank": 3, "text_rank": null, "rrf_score": 0.25})
+        );
+        assert_eq!(
+            format_breakdown(&breakdown),
+            "Vector rank: #3 | Text rank: - | RRF: 0.2500"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_batch_emits_one_jsonl_record_per_query() {
+        let temp = TempDir::new().unwrap();
+        let queries_file = temp.path().join("queries.txt");
+        std::fs::write(
+            &queries_file,
+            "parse config\n\nopen database\nretry request\n",
+        )
+        .unwrap();
+
+        let store_path = temp.path().join("index.json");
+        let store = GobStore::new(store_path.clone());
+        store
+            .save_chunks(vec![make_result("src/main.rs", 1.0).chunk])
+            .await
+            .unwrap();
+        store.persist().await.unwrap();
+
+        let store: Arc<dyn VectorStore> = Arc::new(GobStore::new(store_path));
+        let searcher = Searcher::new(
+            store,
+            Arc::new(FixedEmbedder::new(vec![0.1, 0.2])),
+            false,
+            60.0,
+        );
+        let queries = read_batch_queries(&queries_file).unwrap();
+
+        let mut lines = Vec::new();
+        search_queries(
+            &searcher,
+            &queries,
+            5,
+            &SearchFilter::default(),
+            false,
+            None,
+            true,
+            |query, results, breakdowns| {
+                lines.push(format_jsonl_record(query, results, breakdowns, false)?);
+                Ok(())
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(lines.len(), 3);
+        for (line, query) in lines
+            .iter()
+            .zip(["parse config", "open database", "retry request"])
+        {
+            let record: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(record["query"], query);
+            assert_eq!(record["results"][0]["chunk"]["file_path"], "src/main.rs");
         }
     }
 
-    Ok(())
+    #[tokio::test]
+    async fn test_search_appends_one_analytics_record() {
+        let temp = TempDir::new().unwrap();
+        let store = GobStore::new(temp.path().join("index.json"));
+        store
+            .save_chunks(vec![make_result("src/main.rs", 1.0).chunk])
+            .await
+            .unwrap();
+        store.persist().await.unwrap();
+
+        let store: Arc<dyn VectorStore> = Arc::new(store);
+        let searcher = Searcher::new(
+            store,
+            Arc::new(FixedEmbedder::new(vec![0.1, 0.2])),
+            false,
+            60.0,
+        );
+        let analytics = SearchAnalytics::new(temp.path());
+
+        search_queries(
+            &searcher,
+            &["parse config".to_string()],
+            5,
+            &SearchFilter::default(),
+            false,
+            Some(&analytics),
+            true,
+            |_, _, _| Ok(()),
+        )
+        .await
+        .unwrap();
+
+        let content = std::fs::read_to_string(analytics.file_path()).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 1);
+
+        let record: crate::search::SearchRecord = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(record.query, "parse config");
+        assert_eq!(record.mode, "vector");
+        assert_eq!(record.result_ids, vec!["src/main.rs:block:1".to_string()]);
+    }
 }
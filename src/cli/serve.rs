@@ -1,9 +1,19 @@
 use anyhow::{Context, Result};
 use rmcp::{transport::stdio, ServiceExt};
+use serde_json::json;
+use std::future::Future;
 use std::path::Path;
+use std::sync::Arc;
+use tokio::net::TcpListener;
 
+use crate::cli::search::open_index;
 use crate::cli::Args;
 use crate::mcp::AgentlensServer;
+use crate::search::http::{serve_http, Response};
+use crate::search::{serve_store_http, LiveIndex, STORE_TOKEN_ENV};
+
+/// Request bodies larger than this are rejected; searches carry none
+const MAX_REQUEST_BODY: usize = 8 * 1024;
 
 pub async fn run_mcp_server(args: &Args, work_path: &Path) -> Result<()> {
     let output_path = if args.output.is_absolute() {
@@ -17,6 +27,9 @@ pub async fn run_mcp_server(args: &Args, work_path: &Path) -> Result<()> {
     eprintln!("Starting agentlens MCP server (stdio)...");
     eprintln!("Work path: {}", work_path.display());
 
+    let warm = server.clone();
+    tokio::spawn(async move { warm.warmup_search().await });
+
     let service = server
         .serve(stdio())
         .await
@@ -44,3 +57,210 @@ pub async fn run_mcp_http_server(args: &Args, work_path: &Path, port: u16) -> Re
 
     anyhow::bail!("HTTP/SSE transport not yet implemented. Use stdio mode: agentlens serve --mcp")
 }
+
+/// Serve the project's search index to `search --remote` clients on
+/// `bind:port` until Ctrl+C. Writes need the token in `AGENTLENS_STORE_TOKEN`;
+/// without one, only a loopback address is served.
+pub async fn run_store_server(
+    work_path: &Path,
+    output_dir: &str,
+    bind: &str,
+    port: u16,
+) -> Result<()> {
+    let token = std::env::var(STORE_TOKEN_ENV)
+        .ok()
+        .filter(|token| !token.is_empty());
+    if token.is_none() && !is_loopback(bind) {
+        anyhow::bail!(
+            "Refusing to serve on {} without a token: anyone on the network could overwrite or clear the index. \
+             Set {} (clients send it for writes), or bind 127.0.0.1.",
+            bind,
+            STORE_TOKEN_ENV
+        );
+    }
+
+    let store = open_index(work_path, output_dir, false)?;
+    store.load().await?;
+    let stats = store.stats().await?;
+
+    let listener = TcpListener::bind((bind, port))
+        .await
+        .with_context(|| format!("Failed to bind {}:{}", bind, port))?;
+    eprintln!(
+        "Serving search index ({} files, {} chunks) on http://{}:{}",
+        stats.total_files, stats.total_chunks, bind, port
+    );
+    eprintln!(
+        "Query it with: agentlens search --remote http://<host>:{} \"...\"",
+        port
+    );
+    eprintln!("Press Ctrl+C to stop\n");
+
+    serve_store_http(listener, store, token, async {
+        let _ = tokio::signal::ctrl_c().await;
+        eprintln!("\nShutting down...");
+    })
+    .await
+}
+
+/// Whether `bind` only accepts connections from this machine
+fn is_loopback(bind: &str) -> bool {
+    bind.eq_ignore_ascii_case("localhost")
+        || bind
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Serve `GET /search?q=<query>&limit=<n>` as JSON over plain HTTP until
+/// `shutdown` resolves. In-flight requests finish before this returns.
+pub async fn serve_search_http(
+    listener: TcpListener,
+    live: Arc<LiveIndex>,
+    shutdown: impl Future<Output = ()>,
+) -> Result<()> {
+    serve_http(
+        listener,
+        live,
+        MAX_REQUEST_BODY,
+        |live, request| async move {
+            if request.method == "GET" {
+                route(&request.target, &live).await
+            } else {
+                (
+                    "400 Bad Request",
+                    json!({ "error": "expected a GET request" }),
+                )
+            }
+        },
+        shutdown,
+    )
+    .await
+}
+
+async fn route(target: &str, live: &LiveIndex) -> Response {
+    let Ok(url) = reqwest::Url::parse(&format!("http://localhost{}", target)) else {
+        return (
+            "400 Bad Request",
+            json!({ "error": "invalid request target" }),
+        );
+    };
+
+    match url.path() {
+        "/health" => ("200 OK", json!({ "status": "ok" })),
+        "/search" => {
+            let mut query = None;
+            let mut limit = 10;
+            for (key, value) in url.query_pairs() {
+                match key.as_ref() {
+                    "q" => query = Some(value.into_owned()),
+                    "limit" => limit = value.parse().unwrap_or(limit),
+                    _ => {}
+                }
+            }
+
+            let Some(query) = query.filter(|q| !q.trim().is_empty()) else {
+                return ("400 Bad Request", json!({ "error": "missing q parameter" }));
+            };
+            match live.search(&query, limit).await {
+                Ok(results) => ("200 OK", json!({ "query": query, "results": results })),
+                Err(e) => (
+                    "500 Internal Server Error",
+                    json!({ "error": e.to_string() }),
+                ),
+            }
+        }
+        _ => ("404 Not Found", json!({ "error": "not found" })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ChunkingConfig, SearchOptionsConfig};
+    use crate::search::{GobStore, VectorStore};
+    use crate::test_support::KeywordEmbedder;
+    use std::fs;
+    use tempfile::TempDir;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    async fn get(addr: std::net::SocketAddr, target: &str) -> serde_json::Value {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", target).as_bytes())
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        let body = response.split("\r\n\r\n").nth(1).unwrap();
+        serde_json::from_str(body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_search_reflects_file_change() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let file = root.join("net.rs");
+        fs::write(&file, "fn fetch() {\n    // single attempt\n}\n").unwrap();
+
+        let store: Arc<dyn VectorStore> =
+            Arc::new(GobStore::new(root.join(".agentlens/index.json")));
+        let live = Arc::new(
+            LiveIndex::new(
+                root,
+                store,
+                Arc::new(KeywordEmbedder(&["retry"])),
+                &ChunkingConfig::default(),
+                &SearchOptionsConfig {
+                    hybrid_enabled: false,
+                    ..Default::default()
+                },
+            )
+            .unwrap(),
+        );
+        live.build().await.unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve_search_http(listener, Arc::clone(&live), async {
+            let _ = stopped.await;
+        }));
+
+        let before = get(addr, "/search?q=retry%20logic&limit=1").await;
+        assert!(!before["results"][0]["chunk"]["content"]
+            .as_str()
+            .unwrap()
+            .contains("retry"));
+
+        // Simulate the watcher picking up an edit
+        fs::write(&file, "fn fetch() {\n    // retry three times\n}\n").unwrap();
+        let changed = live
+            .apply_changes(&[file.canonicalize().unwrap()])
+            .await
+            .unwrap();
+        assert_eq!(changed, 1);
+
+        let after = get(addr, "/search?q=retry%20logic&limit=1").await;
+        assert_eq!(after["query"], "retry logic");
+        assert!(after["results"][0]["chunk"]["content"]
+            .as_str()
+            .unwrap()
+            .contains("retry three times"));
+
+        stop.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_only_loopback_binds_are_served_without_a_token() {
+        assert!(is_loopback("127.0.0.1"));
+        assert!(is_loopback("::1"));
+        assert!(is_loopback("localhost"));
+        assert!(!is_loopback("0.0.0.0"));
+        assert!(!is_loopback("192.168.1.20"));
+        assert!(!is_loopback("index.internal"));
+    }
+}
@@ -205,7 +205,7 @@ const STRUCTURE_MD: &str = r#"# AgentLens Output Structure
 - Language breakdown
 
 ### outline.md
-- Symbol maps for large files (>500 lines)
+- Symbol maps for large files (>500 lines by default, see `--large-threshold`)
 - Functions, classes, structs, enums, traits
 - Line numbers for quick navigation
 - Visibility (public/private)
@@ -125,7 +125,7 @@ pub fn run_telemetry_all_modules(output_path: &Path) -> Result<()> {
         module_stats.push((slug, module_tokens, module_bytes));
     }
 
-    module_stats.sort_by(|a, b| b.1.cmp(&a.1));
+    module_stats.sort_by_key(|m| std::cmp::Reverse(m.1));
 
     println!("\n| Module | Tokens | Bytes |");
     println!("|--------|--------|-------|");
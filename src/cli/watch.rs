@@ -1,20 +1,103 @@
 use anyhow::{Context, Result};
 use notify::RecursiveMode;
 use notify_debouncer_mini::{new_debouncer, DebouncedEventKind};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::channel;
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::net::TcpListener;
 
+use crate::cli::serve::serve_search_http;
 use crate::cli::Args;
+use crate::config::Config;
+use crate::search::{create_embedder, create_store_for_writing, Embedder, LiveIndex, VectorStore};
 
-pub fn run_watch(args: &Args, debounce_ms: u64) -> Result<()> {
+pub fn run_watch(args: &Args, debounce_ms: u64, index: bool) -> Result<()> {
     let work_path = args.path.canonicalize().context("Failed to resolve path")?;
 
+    if index {
+        return run_watch_index(args, &work_path, debounce_ms);
+    }
+
     eprintln!("Watching: {}", work_path.display());
     eprintln!("Press Ctrl+C to stop\n");
 
     crate::run_analysis_for_watch(args, &work_path)?;
 
+    watch_changes(args, &work_path, debounce_ms, |_| {})
+}
+
+/// Watch mode that also re-embeds just the changed files into the search
+/// index. Saves within one debounce window are embedded and persisted together.
+fn run_watch_index(args: &Args, work_path: &Path, debounce_ms: u64) -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new().context("Failed to create Tokio runtime")?;
+    let live = runtime.block_on(open_live_index(args, work_path))?;
+
+    eprintln!("Watching: {}", work_path.display());
+    eprintln!("Press Ctrl+C to stop\n");
+
+    watch_changes(args, work_path, debounce_ms, |paths| {
+        match runtime.block_on(live.apply_changes(&paths)) {
+            Ok(count) => eprintln!("[{}] Re-indexed {} files", timestamp(), count),
+            Err(e) => eprintln!("[{}] Index error: {}", timestamp(), e),
+        }
+    })
+}
+
+/// Watch mode plus a search endpoint on `127.0.0.1:port` whose index is
+/// re-embedded incrementally as files change. Ctrl+C stops accepting
+/// requests, lets in-flight ones finish and exits.
+pub fn run_watch_serve(args: &Args, debounce_ms: u64, port: u16) -> Result<()> {
+    let work_path = args.path.canonicalize().context("Failed to resolve path")?;
+
+    let runtime = tokio::runtime::Runtime::new().context("Failed to create Tokio runtime")?;
+    runtime.block_on(async {
+        let live = open_live_index(args, &work_path).await?;
+
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .await
+            .with_context(|| format!("Failed to bind port {}", port))?;
+        eprintln!("Watching: {}", work_path.display());
+        eprintln!("Search: http://127.0.0.1:{}/search?q=<query>", port);
+        eprintln!("Press Ctrl+C to stop\n");
+
+        // The debouncer is blocking, so it runs on its own thread and forwards changes
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Vec<PathBuf>>();
+        let watch_args = args.clone();
+        let watch_path = work_path.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = watch_changes(&watch_args, &watch_path, debounce_ms, |paths| {
+                let _ = tx.send(paths);
+            }) {
+                eprintln!("Watch error: {}", e);
+            }
+        });
+
+        let reindex = Arc::clone(&live);
+        tokio::spawn(async move {
+            while let Some(paths) = rx.recv().await {
+                match reindex.apply_changes(&paths).await {
+                    Ok(count) => eprintln!("[{}] Re-indexed {} files", timestamp(), count),
+                    Err(e) => eprintln!("[{}] Index error: {}", timestamp(), e),
+                }
+            }
+        });
+
+        serve_search_http(listener, live, async {
+            let _ = tokio::signal::ctrl_c().await;
+            eprintln!("\nShutting down...");
+        })
+        .await
+    })
+}
+
+/// Regenerate docs on every relevant batch of changes, then hand the changed paths to `on_change`
+fn watch_changes(
+    args: &Args,
+    work_path: &Path,
+    debounce_ms: u64,
+    mut on_change: impl FnMut(Vec<PathBuf>),
+) -> Result<()> {
     let (tx, rx) = channel();
 
     let debounce_duration = Duration::from_millis(debounce_ms);
@@ -23,14 +106,10 @@ pub fn run_watch(args: &Args, debounce_ms: u64) -> Result<()> {
 
     debouncer
         .watcher()
-        .watch(&work_path, RecursiveMode::Recursive)
+        .watch(work_path, RecursiveMode::Recursive)
         .context("Failed to start watching directory")?;
 
-    let output_path = if args.output.is_absolute() {
-        args.output.clone()
-    } else {
-        work_path.join(&args.output)
-    };
+    let output_path = output_path(args, work_path);
 
     loop {
         match rx.recv() {
@@ -54,10 +133,12 @@ pub fn run_watch(args: &Args, debounce_ms: u64) -> Result<()> {
 
                 eprintln!("\n[{}] Changes detected, regenerating...", timestamp());
 
-                match crate::run_analysis_for_watch(args, &work_path) {
+                match crate::run_analysis_for_watch(args, work_path) {
                     Ok(()) => eprintln!("[{}] Done\n", timestamp()),
                     Err(e) => eprintln!("[{}] Error: {}\n", timestamp(), e),
                 }
+
+                on_change(relevant_events.iter().map(|e| e.path.clone()).collect());
             }
             Ok(Err(error)) => {
                 eprintln!("Watch error: {:?}", error);
@@ -72,6 +153,47 @@ pub fn run_watch(args: &Args, debounce_ms: u64) -> Result<()> {
     Ok(())
 }
 
+/// Generate docs, then open the search index under the output directory and
+/// bring it up to date with the tree
+async fn open_live_index(args: &Args, work_path: &Path) -> Result<Arc<LiveIndex>> {
+    let search_config = Config::load(work_path)
+        .and_then(|c| c.search)
+        .unwrap_or_default();
+    let embedder_config = search_config.embedder.to_search_config();
+    let embedder: Arc<dyn Embedder> = Arc::from(create_embedder(&embedder_config));
+    embedder.health_check().await?;
+
+    let store: Arc<dyn VectorStore> = Arc::from(create_store_for_writing(
+        &output_path(args, work_path),
+        &search_config,
+    )?);
+    let live = Arc::new(LiveIndex::new(
+        work_path,
+        store,
+        embedder,
+        &search_config.chunking,
+        &search_config.search,
+    )?);
+
+    crate::run_analysis_for_watch(args, work_path)?;
+    eprintln!("Indexing {}...", work_path.display());
+    let built = live.build().await?;
+    eprintln!(
+        "Indexed {} files ({} unchanged)",
+        built.files_processed, built.files_skipped
+    );
+
+    Ok(live)
+}
+
+fn output_path(args: &Args, work_path: &Path) -> PathBuf {
+    if args.output.is_absolute() {
+        args.output.clone()
+    } else {
+        work_path.join(&args.output)
+    }
+}
+
 fn is_output_path(path: &Path, output_path: &Path) -> bool {
     path.starts_with(output_path)
 }
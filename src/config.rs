@@ -1,14 +1,26 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::emit::LineEnding;
+use crate::scan::ExtensionDenylist;
+use crate::search::{
+    EmbedderProfile, DEFAULT_HYBRID_TEXT_WEIGHT, DEFAULT_HYBRID_VECTOR_WEIGHT,
+    DEFAULT_IO_CONCURRENCY, DEFAULT_SAVE_BATCH_SIZE,
+};
+use crate::telemetry::Encoding;
+use crate::types::Severity;
+
 const CONFIG_FILE_NAME: &str = "agentlens.toml";
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Config {
     pub output: Option<String>,
-    pub threshold: Option<usize>,
+    /// Lines above which a file gets a symbol outline (`threshold` also accepted)
+    #[serde(alias = "threshold")]
+    pub large_file_lines: Option<usize>,
     pub complex_threshold: Option<usize>,
     pub module_depth: Option<usize>,
     pub depth: Option<usize>,
@@ -17,16 +29,82 @@ pub struct Config {
     #[serde(default)]
     pub lang: Vec<String>,
     pub no_gitignore: Option<bool>,
+    pub line_ending: Option<LineEnding>,
+    /// Seconds before a git subprocess is killed (default 30)
+    pub git_timeout_secs: Option<u64>,
+    /// Per-language L2 doc thresholds, keyed by language name (e.g. `[complexity.go]`)
+    #[serde(default)]
+    pub complexity: HashMap<String, ComplexityConfig>,
+    /// Extra memory marker keywords, e.g. `[markers] PERF = { severity = "warning" }`
+    #[serde(default)]
+    pub markers: HashMap<String, MarkerConfig>,
     pub watch: Option<WatchConfig>,
     pub search: Option<SearchConfig>,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ComplexityConfig {
+    pub lines: Option<usize>,
+    pub symbols: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarkerConfig {
+    /// Defaults to the built-in kind's severity, or info for new keywords
+    pub severity: Option<Severity>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct SearchConfig {
+    /// Index storage: "gob" (single index.json, default) or "sqlite" (index.db)
+    pub store_backend: String,
+    /// Maximum number of files read at once while indexing
+    pub io_concurrency: usize,
+    /// Re-embedded files whose chunks are written to the store together
+    pub save_batch_size: usize,
+    /// Store vectors as int8 in index.json (about a quarter of the size)
+    pub quantize: bool,
+    /// Gzip the index as index.json.gz (applies when the index is next written)
+    pub compress_index: bool,
+    /// Search index.json through an in-memory HNSW graph (approximate, faster on large indexes)
+    pub hnsw: bool,
+    /// Extensions never to index, in addition to the built-in noise list
+    pub deny_extensions: Vec<String>,
     pub embedder: EmbedderConfig,
     pub chunking: ChunkingConfig,
     pub search: SearchOptionsConfig,
+    /// Re-score top results with a slower model (`[search.rerank]`); off when absent
+    pub rerank: Option<RerankConfig>,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            store_backend: "gob".to_string(),
+            io_concurrency: DEFAULT_IO_CONCURRENCY,
+            save_batch_size: DEFAULT_SAVE_BATCH_SIZE,
+            quantize: false,
+            compress_index: true,
+            hnsw: false,
+            deny_extensions: Vec::new(),
+            embedder: EmbedderConfig::default(),
+            chunking: ChunkingConfig::default(),
+            search: SearchOptionsConfig::default(),
+            rerank: None,
+        }
+    }
+}
+
+impl SearchConfig {
+    /// Built-in and configured denied extensions, minus those in `include`
+    pub fn extension_denylist(&self, include: &[String]) -> ExtensionDenylist {
+        ExtensionDenylist::default()
+            .deny(&self.deny_extensions)
+            .allow(include)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +114,18 @@ pub struct EmbedderConfig {
     pub model: String,
     pub endpoint: Option<String>,
     pub dimensions: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// Environment variable holding the key; `OPENAI_API_KEY` is only read for
+    /// OpenAI's own API
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+    /// Seconds before an Ollama embedding request is abandoned (default 120)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Named overrides picked with `--embed-profile` (`[[search.embedder.profiles]]`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<EmbedderProfile>,
 }
 
 impl Default for EmbedderConfig {
@@ -45,6 +135,49 @@ impl Default for EmbedderConfig {
             model: "nomic-embed-text".to_string(),
             endpoint: None,
             dimensions: 768,
+            api_key: None,
+            api_key_env: None,
+            timeout_secs: None,
+            profiles: Vec::new(),
+        }
+    }
+}
+
+impl EmbedderConfig {
+    /// These settings as the search module's embedder config
+    pub fn to_search_config(&self) -> crate::search::EmbedderConfig {
+        crate::search::EmbedderConfig {
+            provider: self.provider.clone(),
+            model: self.model.clone(),
+            endpoint: self.endpoint.clone(),
+            dimensions: self.dimensions,
+            api_key: self.api_key.clone(),
+            api_key_env: self.api_key_env.clone(),
+            timeout_secs: self.timeout_secs,
+            profiles: self.profiles.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RerankConfig {
+    /// "ollama" (default) or "none"
+    pub provider: String,
+    pub model: String,
+    pub endpoint: Option<String>,
+    /// Seconds before scoring one candidate is abandoned (default 60)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+impl Default for RerankConfig {
+    fn default() -> Self {
+        Self {
+            provider: "ollama".to_string(),
+            model: "llama3.2".to_string(),
+            endpoint: None,
+            timeout_secs: None,
         }
     }
 }
@@ -55,6 +188,9 @@ pub struct ChunkingConfig {
     pub max_tokens: usize,
     pub overlap_tokens: usize,
     pub strategy: String,
+    /// Count tokens with this BPE vocabulary ("o200k_base" or "cl100k_base")
+    /// instead of estimating 4 chars per token
+    pub tokenizer: Option<Encoding>,
 }
 
 impl Default for ChunkingConfig {
@@ -63,6 +199,7 @@ impl Default for ChunkingConfig {
             max_tokens: 512,
             overlap_tokens: 50,
             strategy: "symbol".to_string(),
+            tokenizer: None,
         }
     }
 }
@@ -72,7 +209,15 @@ impl Default for ChunkingConfig {
 pub struct SearchOptionsConfig {
     pub hybrid_enabled: bool,
     pub hybrid_k: f32,
+    /// RRF weight of the vector result list in hybrid search
+    pub hybrid_vector_weight: f32,
+    /// RRF weight of the text result list in hybrid search
+    pub hybrid_text_weight: f32,
     pub default_limit: usize,
+    /// Warn when the newest indexed chunk is older than this many days (0 disables)
+    pub stale_after_days: u64,
+    /// Append each search to a local analytics/searches.jsonl for retrieval tuning
+    pub analytics: bool,
 }
 
 impl Default for SearchOptionsConfig {
@@ -80,7 +225,11 @@ impl Default for SearchOptionsConfig {
         Self {
             hybrid_enabled: true,
             hybrid_k: 60.0,
+            hybrid_vector_weight: DEFAULT_HYBRID_VECTOR_WEIGHT,
+            hybrid_text_weight: DEFAULT_HYBRID_TEXT_WEIGHT,
             default_limit: 10,
+            stale_after_days: 7,
+            analytics: false,
         }
     }
 }
@@ -111,7 +260,7 @@ impl Config {
 # output = ".agentlens"
 
 # Line threshold for "large" files (generates outline)
-# threshold = 500
+# large_file_lines = 500
 
 # Line threshold for L2 file-level docs (very complex files)
 # complex_threshold = 1000
@@ -131,6 +280,22 @@ impl Config {
 # Don't respect .gitignore
 # no_gitignore = false
 
+# Line endings for generated docs ("lf" or "crlf")
+# line_ending = "lf"
+
+# Seconds before a git call is killed (clones get 10x this)
+# git_timeout_secs = 30
+
+# Per-language thresholds for L2 file docs (defaults: complex_threshold lines, 50 public symbols)
+# [complexity.go]
+# lines = 1500
+# symbols = 80
+
+# Extra memory marker keywords; a trailing "-" matches ticket ids like JIRA-1234
+# [markers]
+# PERF = { severity = "warning" }
+# "JIRA-" = {}
+
 # Watch mode configuration
 # [watch]
 # debounce_ms = 300
@@ -168,7 +333,15 @@ mod tests {
     fn test_parse_minimal_config() {
         let config: Config = toml::from_str("").unwrap();
         assert!(config.output.is_none());
-        assert!(config.threshold.is_none());
+        assert!(config.large_file_lines.is_none());
+    }
+
+    #[test]
+    fn test_large_file_lines_accepts_old_threshold_key() {
+        let config: Config = toml::from_str("large_file_lines = 300").unwrap();
+        assert_eq!(config.large_file_lines, Some(300));
+        let config: Config = toml::from_str("threshold = 250").unwrap();
+        assert_eq!(config.large_file_lines, Some(250));
     }
 
     #[test]
@@ -184,12 +357,89 @@ debounce_ms = 500
 "#;
         let config: Config = toml::from_str(content).unwrap();
         assert_eq!(config.output, Some(".docs".to_string()));
-        assert_eq!(config.threshold, Some(300));
+        assert_eq!(config.large_file_lines, Some(300));
         assert_eq!(config.complex_threshold, Some(500));
         assert_eq!(config.ignore.len(), 2);
         assert_eq!(config.watch.unwrap().debounce_ms, Some(500));
     }
 
+    #[test]
+    fn test_parse_complexity_overrides() {
+        let content = r#"
+[complexity.go]
+lines = 1500
+
+[complexity.python]
+lines = 600
+symbols = 30
+"#;
+        let config: Config = toml::from_str(content).unwrap();
+        assert_eq!(config.complexity["go"].lines, Some(1500));
+        assert_eq!(config.complexity["go"].symbols, None);
+        assert_eq!(config.complexity["python"].symbols, Some(30));
+    }
+
+    #[test]
+    fn test_parse_markers() {
+        let content = r#"
+[markers]
+PERF = { severity = "warning" }
+"JIRA-" = {}
+"#;
+        let config: Config = toml::from_str(content).unwrap();
+        assert_eq!(config.markers["PERF"].severity, Some(Severity::Warning));
+        assert_eq!(config.markers["JIRA-"].severity, None);
+    }
+
+    #[test]
+    fn test_rerank_is_opt_in() {
+        let config: Config = toml::from_str("[search]\nquantize = true").unwrap();
+        assert!(config.search.unwrap().rerank.is_none());
+
+        let config: Config = toml::from_str("[search.rerank]\nmodel = \"qwen2.5\"").unwrap();
+        let rerank = config.search.unwrap().rerank.unwrap();
+        assert_eq!(rerank.provider, "ollama");
+        assert_eq!(rerank.model, "qwen2.5");
+    }
+
+    #[test]
+    fn test_embedder_profiles_from_config() {
+        let toml_str = r#"
+[search.embedder]
+model = "nomic-embed-text"
+
+[[search.embedder.profiles]]
+name = "fast"
+model = "all-minilm"
+dimensions = 384
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let embedder = config.search.unwrap().embedder.to_search_config();
+        assert_eq!(embedder.profiles.len(), 1);
+
+        let fast = embedder.with_profile("fast").unwrap();
+        assert_eq!(fast.model, "all-minilm");
+        assert_eq!(fast.dimensions, 384);
+    }
+
+    #[test]
+    fn test_extension_denylist_from_config() {
+        let config: Config = toml::from_str("[search]\ndeny_extensions = [\".csv\"]").unwrap();
+        let denylist = config
+            .search
+            .unwrap()
+            .extension_denylist(&["min.js".to_string()]);
+        assert!(denylist.denies(Path::new("data/big.csv")));
+        assert!(denylist.denies(Path::new("Cargo.lock")));
+        assert!(!denylist.denies(Path::new("dist/app.min.js")));
+    }
+
+    #[test]
+    fn test_parse_line_ending() {
+        let config: Config = toml::from_str("line_ending = \"crlf\"").unwrap();
+        assert_eq!(config.line_ending, Some(LineEnding::Crlf));
+    }
+
     #[test]
     fn test_find_config_file() {
         let temp = TempDir::new().unwrap();
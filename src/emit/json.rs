@@ -1,11 +1,13 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::Serialize;
+use std::collections::HashMap;
 
-use crate::analyze::ModuleInfo;
+use crate::analyze::{EnvVar, ModuleInfo, ProjectProfile, UnusedSymbol};
 use crate::scan::DiffStat;
 use crate::types::{FileEntry, MemoryEntry, Symbol};
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct JsonOutput {
     pub version: String,
     pub generated_at: DateTime<Utc>,
@@ -19,18 +21,27 @@ pub struct JsonOutput {
     pub hub_files: Vec<HubFile>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub diff: Option<DiffInfo>,
+    /// Possibly unused public symbols (only with --dead-code)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dead_code: Option<Vec<UnusedSymbol>>,
+    /// Environment variables the code reads (only with --env-scan)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env_vars: Option<Vec<EnvVar>>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct ProjectInfo {
     pub path: String,
     pub files_scanned: usize,
     pub large_files_count: usize,
     pub memory_markers_count: usize,
     pub modules_count: usize,
+    /// Primary languages and project type
+    #[serde(flatten)]
+    pub profile: ProjectProfile,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, JsonSchema, Clone)]
 pub struct ModuleOutput {
     pub slug: String,
     pub path: String,
@@ -41,14 +52,39 @@ pub struct ModuleOutput {
     pub warning_count: usize,
     pub symbol_count: usize,
     pub is_hub: bool,
+    /// Files and lines per language, keyed by lowercase language name
+    pub language_breakdown: HashMap<String, LanguageStats>,
+}
+
+/// How much of a module is written in one language
+#[derive(Serialize, JsonSchema, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LanguageStats {
+    pub files: usize,
+    pub lines: usize,
+}
+
+/// Files and lines per language of `files`, keyed by lowercase language name
+pub fn language_breakdown<'a>(
+    files: impl IntoIterator<Item = &'a FileEntry>,
+) -> HashMap<String, LanguageStats> {
+    let mut breakdown: HashMap<String, LanguageStats> = HashMap::new();
+    for file in files {
+        let stats = breakdown
+            .entry(file.language.name().to_string())
+            .or_default();
+        stats.files += 1;
+        stats.lines += file.line_count;
+    }
+    breakdown
 }
 
 impl ModuleOutput {
     pub fn from_module_info(
         module: &ModuleInfo,
+        files: &[FileEntry],
         memory: &[MemoryEntry],
         symbols: &[(FileEntry, Vec<Symbol>)],
-        hub_files: &[(String, usize)],
+        hub_files: &[HubFile],
     ) -> Self {
         let warning_count = memory
             .iter()
@@ -64,7 +100,13 @@ impl ModuleOutput {
         let is_hub = module
             .files
             .iter()
-            .any(|f| hub_files.iter().any(|(path, _)| path == f));
+            .any(|f| hub_files.iter().any(|hub| hub.path == *f));
+
+        let language_breakdown = language_breakdown(
+            files
+                .iter()
+                .filter(|f| module.files.contains(&f.relative_path)),
+        );
 
         Self {
             slug: module.slug.clone(),
@@ -76,11 +118,12 @@ impl ModuleOutput {
             warning_count,
             symbol_count,
             is_hub,
+            language_breakdown,
         }
     }
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct LargeFileEntry {
     pub path: String,
     pub line_count: usize,
@@ -88,19 +131,21 @@ pub struct LargeFileEntry {
     pub symbols: Vec<Symbol>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct CriticalFile {
     pub path: String,
     pub high_priority_markers: usize,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
 pub struct HubFile {
     pub path: String,
     pub imported_by: usize,
+    /// Importers outside the module this file belongs to
+    pub cross_module_importers: usize,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct DiffInfo {
     pub base_ref: String,
     pub files: Vec<DiffStat>,
@@ -110,4 +155,302 @@ impl JsonOutput {
     pub fn to_json(&self) -> String {
         serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
     }
+
+    /// Same document as `to_json`, as YAML
+    pub fn to_yaml(&self) -> String {
+        super::to_yaml(self).unwrap_or_else(|_| "{}\n".to_string())
+    }
+
+    /// JSON Schema for the `--json` output, for integrators validating or generating types
+    pub fn json_schema() -> String {
+        serde_json::to_string_pretty(&schemars::schema_for!(JsonOutput))
+            .unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze::{EnvVarReference, UnusedSymbol};
+    use crate::scan::DiffStatus;
+    use crate::types::{MemoryKind, SymbolKind, Visibility};
+    use serde_json::Value;
+    use std::path::PathBuf;
+
+    /// Minimal validator for the subset of JSON Schema that schemars emits
+    fn validate(root: &Value, schema: &Value, value: &Value, at: &str) -> Result<(), String> {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let name = reference.trim_start_matches("#/$defs/");
+            return validate(root, &root["$defs"][name], value, at);
+        }
+
+        if let Some(options) = schema.get("anyOf").and_then(Value::as_array) {
+            return options
+                .iter()
+                .find(|s| validate(root, s, value, at).is_ok())
+                .map(|_| ())
+                .ok_or_else(|| format!("{}: matches no anyOf branch", at));
+        }
+
+        if let Some(ty) = schema.get("type") {
+            let allowed: Vec<&str> = match ty {
+                Value::String(t) => vec![t.as_str()],
+                Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+                _ => vec![],
+            };
+            let matches = allowed.iter().any(|t| match *t {
+                "object" => value.is_object(),
+                "array" => value.is_array(),
+                "string" => value.is_string(),
+                "integer" => value.is_u64() || value.is_i64(),
+                "number" => value.is_number(),
+                "boolean" => value.is_boolean(),
+                "null" => value.is_null(),
+                _ => false,
+            });
+            if !matches {
+                return Err(format!("{}: expected {:?}, got {}", at, allowed, value));
+            }
+        }
+
+        if let Some(variants) = schema.get("enum").and_then(Value::as_array) {
+            if !variants.contains(value) {
+                return Err(format!("{}: {} not in enum", at, value));
+            }
+        }
+
+        if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+            if value.as_f64().is_some_and(|v| v < min) {
+                return Err(format!("{}: below minimum", at));
+            }
+        }
+
+        if let (Some(required), Some(object)) = (
+            schema.get("required").and_then(Value::as_array),
+            value.as_object(),
+        ) {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(key) {
+                    return Err(format!("{}: missing required '{}'", at, key));
+                }
+            }
+        }
+
+        if let (Some(properties), Some(object)) = (
+            schema.get("properties").and_then(Value::as_object),
+            value.as_object(),
+        ) {
+            for (key, field) in object {
+                let field_schema = properties
+                    .get(key)
+                    .ok_or_else(|| format!("{}: unexpected property '{}'", at, key))?;
+                validate(root, field_schema, field, &format!("{}.{}", at, key))?;
+            }
+        }
+
+        if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+            for (i, item) in array.iter().enumerate() {
+                validate(root, items, item, &format!("{}[{}]", at, i))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_schema_validates_real_output() {
+        let file = FileEntry::new(
+            PathBuf::from("/repo/src/main.rs"),
+            "src/main.rs".to_string(),
+            2048,
+            900,
+            500,
+        );
+        let mut symbol = Symbol::new(
+            SymbolKind::Function,
+            "main".to_string(),
+            1,
+            Visibility::Public,
+        );
+        symbol.signature = Some("fn main()".to_string());
+        let memory = MemoryEntry::new(
+            MemoryKind::Warning,
+            "do not block here".to_string(),
+            "src/main.rs".to_string(),
+            12,
+        );
+        let module = ModuleInfo {
+            slug: "src".to_string(),
+            path: "src".to_string(),
+            boundary_type: crate::analyze::BoundaryType::Implicit,
+            files: vec!["src/main.rs".to_string()],
+            entry_point: Some("src/main.rs".to_string()),
+            parent: None,
+            children: vec![],
+        };
+        let large = vec![(file.clone(), vec![symbol.clone()])];
+
+        let output = JsonOutput {
+            version: "0.0.0".to_string(),
+            generated_at: Utc::now(),
+            project: ProjectInfo {
+                path: "/repo".to_string(),
+                files_scanned: 1,
+                large_files_count: 1,
+                memory_markers_count: 1,
+                modules_count: 1,
+                profile: ProjectProfile::default(),
+            },
+            modules: vec![ModuleOutput::from_module_info(
+                &module,
+                std::slice::from_ref(&file),
+                std::slice::from_ref(&memory),
+                &large,
+                &[],
+            )],
+            files: vec![file],
+            large_files: vec![LargeFileEntry {
+                path: "src/main.rs".to_string(),
+                line_count: 900,
+                language: "Rust".to_string(),
+                symbols: vec![symbol],
+            }],
+            memory: vec![memory],
+            entry_points: vec!["src/main.rs".to_string()],
+            critical_files: vec![CriticalFile {
+                path: "src/main.rs".to_string(),
+                high_priority_markers: 1,
+            }],
+            hub_files: vec![HubFile {
+                path: "src/lib.rs".to_string(),
+                imported_by: 3,
+                cross_module_importers: 2,
+            }],
+            diff: Some(DiffInfo {
+                base_ref: "main".to_string(),
+                files: vec![DiffStat {
+                    path: "src/main.rs".to_string(),
+                    status: DiffStatus::Modified,
+                    additions: 4,
+                    deletions: 1,
+                }],
+            }),
+            dead_code: Some(vec![UnusedSymbol {
+                file: "src/old.rs".to_string(),
+                name: "legacy".to_string(),
+                kind: SymbolKind::Function,
+                line: 3,
+            }]),
+            env_vars: Some(vec![EnvVar {
+                name: "DATABASE_URL".to_string(),
+                required: true,
+                references: vec![EnvVarReference {
+                    file: "src/db.rs".to_string(),
+                    line: 12,
+                }],
+            }]),
+        };
+
+        let schema: Value = serde_json::from_str(&JsonOutput::json_schema()).unwrap();
+        let value: Value = serde_json::from_str(&output.to_json()).unwrap();
+
+        validate(&schema, &schema, &value, "$").unwrap();
+
+        // Guard against a vacuous schema
+        let mut broken = value.clone();
+        broken["project"]["files_scanned"] = Value::String("many".to_string());
+        assert!(validate(&schema, &schema, &broken, "$").is_err());
+    }
+
+    #[test]
+    fn test_module_language_breakdown_counts_only_module_files() {
+        let module = ModuleInfo {
+            slug: "src".to_string(),
+            path: "src".to_string(),
+            boundary_type: crate::analyze::BoundaryType::Implicit,
+            files: vec!["src/main.rs".to_string(), "src/app.ts".to_string()],
+            entry_point: None,
+            parent: None,
+            children: vec![],
+        };
+        let file = |path: &str, lines: usize| {
+            FileEntry::new(PathBuf::from(path), path.to_string(), 100, lines, 0)
+        };
+        let files = [
+            file("src/main.rs", 40),
+            file("src/app.ts", 25),
+            file("web/index.ts", 500),
+        ];
+
+        let output = ModuleOutput::from_module_info(&module, &files, &[], &[], &[]);
+
+        assert_eq!(output.language_breakdown.len(), 2);
+        assert_eq!(
+            output.language_breakdown["rust"],
+            LanguageStats {
+                files: 1,
+                lines: 40
+            }
+        );
+        assert_eq!(
+            output.language_breakdown["typescript"],
+            LanguageStats {
+                files: 1,
+                lines: 25
+            }
+        );
+    }
+
+    #[test]
+    fn test_yaml_output_matches_json_fields() {
+        let mut output = JsonOutput {
+            version: "1".to_string(),
+            generated_at: Utc::now(),
+            project: ProjectInfo {
+                path: "/tmp/demo".to_string(),
+                files_scanned: 2,
+                large_files_count: 0,
+                memory_markers_count: 0,
+                modules_count: 1,
+                profile: ProjectProfile::default(),
+            },
+            modules: vec![],
+            files: vec![],
+            large_files: vec![],
+            memory: vec![],
+            entry_points: vec!["src/main.rs".to_string()],
+            critical_files: vec![],
+            hub_files: vec![],
+            diff: None,
+            dead_code: None,
+            env_vars: None,
+        };
+
+        let yaml = output.to_yaml();
+        let top_level: Vec<&str> = yaml
+            .lines()
+            .filter(|l| !l.starts_with(' ') && !l.starts_with('-'))
+            .filter_map(|l| l.split(':').next())
+            .collect();
+        let json: Value = serde_json::from_str(&output.to_json()).unwrap();
+        let mut json_keys: Vec<&str> = json
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        json_keys.sort();
+        assert_eq!(top_level, json_keys);
+        assert!(!top_level.contains(&"diff"));
+        assert!(yaml.contains("entry_points:\n  - src/main.rs\n"));
+        assert!(yaml.contains("  files_scanned: 2\n"));
+
+        output.diff = Some(DiffInfo {
+            base_ref: "main".to_string(),
+            files: vec![],
+        });
+        assert!(output
+            .to_yaml()
+            .contains("diff:\n  base_ref: main\n  files: []\n"));
+    }
 }
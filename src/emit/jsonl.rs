@@ -0,0 +1,102 @@
+//! JSON Lines rendering of the analysis: one compact record per line, so large
+//! scans can be consumed incrementally instead of parsed as one document.
+
+use serde::Serialize;
+use std::io::{self, Write};
+
+use super::JsonOutput;
+
+/// One output line; the payload sits under `data` because memory entries
+/// already have a `kind` field of their own
+#[derive(Serialize)]
+struct Record<'a, T: Serialize> {
+    kind: &'static str,
+    data: &'a T,
+}
+
+fn write_record<W: Write, T: Serialize>(
+    writer: &mut W,
+    kind: &'static str,
+    data: &T,
+) -> io::Result<()> {
+    serde_json::to_writer(&mut *writer, &Record { kind, data })?;
+    writer.write_all(b"\n")
+}
+
+/// Writes every module, file and memory entry of `data` as its own line,
+/// tagged with `kind` ("module", "file" or "memory")
+pub fn emit_jsonl<W: Write>(writer: &mut W, data: &JsonOutput) -> io::Result<()> {
+    for module in &data.modules {
+        write_record(writer, "module", module)?;
+    }
+    for file in &data.files {
+        write_record(writer, "file", file)?;
+    }
+    for entry in &data.memory {
+        write_record(writer, "memory", entry)?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze::ProjectProfile;
+    use crate::emit::ProjectInfo;
+    use crate::types::{FileEntry, MemoryEntry, MemoryKind};
+    use chrono::Utc;
+    use serde_json::Value;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_each_line_is_a_tagged_record() {
+        let output = JsonOutput {
+            version: "1".to_string(),
+            generated_at: Utc::now(),
+            project: ProjectInfo {
+                path: "/tmp/demo".to_string(),
+                files_scanned: 1,
+                large_files_count: 0,
+                memory_markers_count: 1,
+                modules_count: 0,
+                profile: ProjectProfile::default(),
+            },
+            modules: vec![],
+            files: vec![FileEntry::new(
+                PathBuf::from("/tmp/demo/src/main.rs"),
+                "src/main.rs".to_string(),
+                100,
+                10,
+                500,
+            )],
+            large_files: vec![],
+            memory: vec![MemoryEntry::new(
+                MemoryKind::Todo,
+                "wire up config".to_string(),
+                "src/main.rs".to_string(),
+                3,
+            )],
+            entry_points: vec![],
+            critical_files: vec![],
+            hub_files: vec![],
+            diff: None,
+            dead_code: None,
+            env_vars: None,
+        };
+
+        let mut out = Vec::new();
+        emit_jsonl(&mut out, &output).unwrap();
+        let lines: Vec<Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["kind"], "file");
+        assert_eq!(lines[0]["data"]["relative_path"], "src/main.rs");
+        assert_eq!(lines[1]["kind"], "memory");
+        assert_eq!(lines[1]["data"]["content"], "wire up config");
+        assert!(lines[1]["data"]["kind"].is_string());
+    }
+}
@@ -1,9 +1,16 @@
 mod json;
+mod jsonl;
 mod manifest;
 mod writer;
+mod yaml;
 
 pub use json::{
-    CriticalFile, DiffInfo, HubFile, JsonOutput, LargeFileEntry, ModuleOutput, ProjectInfo,
+    language_breakdown, CriticalFile, DiffInfo, HubFile, JsonOutput, LanguageStats, LargeFileEntry,
+    ModuleOutput, ProjectInfo,
 };
+pub use jsonl::emit_jsonl;
 pub use manifest::{calculate_module_state, current_timestamp, Manifest, ModuleState};
-pub use writer::{slug_to_dir_name, write_hierarchical, HierarchicalOutput, ModuleContent};
+pub use writer::{
+    slug_to_dir_name, write_hierarchical, HierarchicalOutput, LineEnding, ModuleContent,
+};
+pub use yaml::to_yaml;
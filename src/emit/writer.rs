@@ -1,10 +1,37 @@
 //! Output writer for hierarchical content structure.
 
 use anyhow::Result;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
+/// Line ending style for generated markdown
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    /// Unix-style `\n`
+    #[default]
+    Lf,
+    /// Windows-style `\r\n`
+    Crlf,
+}
+
+impl LineEnding {
+    /// Rewrite every line break to this style and guarantee a trailing newline
+    pub fn normalize(self, content: &str) -> String {
+        let mut normalized = content.replace("\r\n", "\n").replace('\r', "\n");
+        if !normalized.ends_with('\n') {
+            normalized.push('\n');
+        }
+        match self {
+            LineEnding::Lf => normalized,
+            LineEnding::Crlf => normalized.replace('\n', "\r\n"),
+        }
+    }
+}
+
 /// Content for a single module
 #[derive(Debug, Clone, Default)]
 pub struct ModuleContent {
@@ -29,6 +56,12 @@ pub struct HierarchicalOutput {
     pub modules: HashMap<String, ModuleContent>,
     /// Optional L2 file-level docs, keyed by file slug
     pub files: HashMap<String, String>,
+    /// dead-code.md content (only with --dead-code)
+    pub dead_code: Option<String>,
+    /// config.md content (only with --env-scan)
+    pub env_vars: Option<String>,
+    /// Line ending applied to every written file
+    pub line_ending: LineEnding,
 }
 
 impl HierarchicalOutput {
@@ -38,13 +71,29 @@ impl HierarchicalOutput {
             agent_md: None,
             modules: HashMap::new(),
             files: HashMap::new(),
+            dead_code: None,
+            env_vars: None,
+            line_ending: LineEnding::default(),
         }
     }
 
+    pub fn with_line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
     pub fn set_agent_md(&mut self, content: String) {
         self.agent_md = Some(content);
     }
 
+    pub fn set_dead_code(&mut self, content: String) {
+        self.dead_code = Some(content);
+    }
+
+    pub fn set_env_vars(&mut self, content: String) {
+        self.env_vars = Some(content);
+    }
+
     /// Add content for a module
     pub fn add_module(&mut self, slug: String, content: ModuleContent) {
         self.modules.insert(slug, content);
@@ -58,7 +107,11 @@ impl HierarchicalOutput {
     /// Count total files that would be written
     pub fn file_count(&self) -> usize {
         let agent_count = if self.agent_md.is_some() { 1 } else { 0 };
+        let dead_code_count = if self.dead_code.is_some() { 1 } else { 0 };
+        let env_vars_count = if self.env_vars.is_some() { 1 } else { 0 };
         1 + agent_count
+            + dead_code_count
+            + env_vars_count
             + self
                 .modules
                 .values()
@@ -96,10 +149,26 @@ pub fn write_hierarchical(
 
     fs::create_dir_all(output_dir)?;
 
-    fs::write(output_dir.join("INDEX.md"), &output.index_md)?;
+    write_doc(
+        &output_dir.join("INDEX.md"),
+        &output.index_md,
+        output.line_ending,
+    )?;
 
     if let Some(ref agent_md) = output.agent_md {
-        fs::write(output_dir.join("AGENT.md"), agent_md)?;
+        write_doc(&output_dir.join("AGENT.md"), agent_md, output.line_ending)?;
+    }
+
+    if let Some(ref dead_code) = output.dead_code {
+        write_doc(
+            &output_dir.join("dead-code.md"),
+            dead_code,
+            output.line_ending,
+        )?;
+    }
+
+    if let Some(ref env_vars) = output.env_vars {
+        write_doc(&output_dir.join("config.md"), env_vars, output.line_ending)?;
     }
 
     let modules_dir = output_dir.join("modules");
@@ -112,16 +181,32 @@ pub fn write_hierarchical(
         fs::create_dir_all(&module_dir)?;
 
         if !content.module_md.is_empty() {
-            fs::write(module_dir.join("MODULE.md"), &content.module_md)?;
+            write_doc(
+                &module_dir.join("MODULE.md"),
+                &content.module_md,
+                output.line_ending,
+            )?;
         }
         if !content.outline.is_empty() {
-            fs::write(module_dir.join("outline.md"), &content.outline)?;
+            write_doc(
+                &module_dir.join("outline.md"),
+                &content.outline,
+                output.line_ending,
+            )?;
         }
         if !content.memory.is_empty() {
-            fs::write(module_dir.join("memory.md"), &content.memory)?;
+            write_doc(
+                &module_dir.join("memory.md"),
+                &content.memory,
+                output.line_ending,
+            )?;
         }
         if !content.imports.is_empty() {
-            fs::write(module_dir.join("imports.md"), &content.imports)?;
+            write_doc(
+                &module_dir.join("imports.md"),
+                &content.imports,
+                output.line_ending,
+            )?;
         }
     }
 
@@ -130,13 +215,23 @@ pub fn write_hierarchical(
         fs::create_dir_all(&files_dir)?;
 
         for (slug, content) in &output.files {
-            fs::write(files_dir.join(format!("{}.md", slug)), content)?;
+            write_doc(
+                &files_dir.join(format!("{}.md", slug)),
+                content,
+                output.line_ending,
+            )?;
         }
     }
 
     Ok(())
 }
 
+/// Write a generated doc with normalized line endings
+fn write_doc(path: &Path, content: &str, line_ending: LineEnding) -> Result<()> {
+    fs::write(path, line_ending.normalize(content))?;
+    Ok(())
+}
+
 /// Print what would be written in dry-run mode
 fn print_hierarchical_dry_run(output_dir: &Path, output: &HierarchicalOutput) {
     println!("Dry run mode - hierarchical structure:");
@@ -147,6 +242,14 @@ fn print_hierarchical_dry_run(output_dir: &Path, output: &HierarchicalOutput) {
         println!("  ├── AGENT.md ({} bytes)", agent_md.len());
     }
 
+    if let Some(ref dead_code) = output.dead_code {
+        println!("  ├── dead-code.md ({} bytes)", dead_code.len());
+    }
+
+    if let Some(ref env_vars) = output.env_vars {
+        println!("  ├── config.md ({} bytes)", env_vars.len());
+    }
+
     if !output.modules.is_empty() {
         println!("  ├── modules/");
 
@@ -232,6 +335,27 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_written_docs_use_lf_with_trailing_newline() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut output =
+            HierarchicalOutput::new("# INDEX\r\n\r\nWindows\rold mac\nunix".to_string());
+        output.add_file("src-main-rs".to_string(), "# File\r\nbody".to_string());
+
+        write_hierarchical(temp.path(), &output, false).unwrap();
+
+        let index = fs::read_to_string(temp.path().join("INDEX.md")).unwrap();
+        assert_eq!(index, "# INDEX\n\nWindows\nold mac\nunix\n");
+        let file_doc = fs::read_to_string(temp.path().join("files/src-main-rs.md")).unwrap();
+        assert_eq!(file_doc, "# File\nbody\n");
+    }
+
+    #[test]
+    fn test_crlf_line_ending() {
+        assert_eq!(LineEnding::Crlf.normalize("a\nb\r\nc"), "a\r\nb\r\nc\r\n");
+        assert_eq!(LineEnding::Lf.normalize("done\n"), "done\n");
+    }
+
     #[test]
     fn test_slug_to_dir_name() {
         assert_eq!(slug_to_dir_name("src-analyze"), "src-analyze");
@@ -0,0 +1,156 @@
+//! YAML rendering of anything serializable, by way of its JSON value, so the
+//! YAML output has exactly the fields (and skipped fields) of the JSON output.
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Block-style YAML document for `value`, with map keys in sorted order
+pub fn to_yaml<T: Serialize>(value: &T) -> serde_json::Result<String> {
+    let value = serde_json::to_value(value)?;
+    let mut out = String::new();
+    if is_block(&value) {
+        write_block(&mut out, &value, 0);
+    } else {
+        out.push_str(&scalar(&value));
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Non-empty maps and sequences are written as indented blocks; everything
+/// else fits on one line
+fn is_block(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => !map.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        _ => false,
+    }
+}
+
+fn write_block(out: &mut String, value: &Value, indent: usize) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                out.push_str(&pad);
+                out.push_str(&string_scalar(key));
+                out.push(':');
+                if is_block(child) {
+                    out.push('\n');
+                    write_block(out, child, indent + 2);
+                } else {
+                    out.push(' ');
+                    out.push_str(&scalar(child));
+                    out.push('\n');
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                out.push_str(&pad);
+                out.push_str("- ");
+                if is_block(item) {
+                    // The item's first line shares the dash's line
+                    let mut nested = String::new();
+                    write_block(&mut nested, item, indent + 2);
+                    out.push_str(&nested[indent + 2..]);
+                } else {
+                    out.push_str(&scalar(item));
+                    out.push('\n');
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => string_scalar(s),
+        Value::Object(_) => "{}".to_string(),
+        Value::Array(_) => "[]".to_string(),
+    }
+}
+
+/// Strings are left plain when YAML would read them back as the same string,
+/// and double-quoted (JSON escapes are valid YAML escapes) otherwise
+fn string_scalar(s: &str) -> String {
+    let plain = s
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '/')
+        && s.chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '/' | ' '))
+        && !s.ends_with(' ')
+        && !matches!(
+            s.to_lowercase().as_str(),
+            "true" | "false" | "null" | "yes" | "no" | "on" | "off" | "y" | "n"
+        );
+
+    if plain {
+        s.to_string()
+    } else {
+        serde_json::to_string(s).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_nested_maps_and_sequences() {
+        let value = json!({
+            "name": "agentlens",
+            "count": 3,
+            "ratio": 0.5,
+            "modules": [
+                {"slug": "src-cli", "files": ["src/cli/mod.rs"]},
+                {"slug": "root", "files": []}
+            ],
+            "diff": null,
+            "tags": [["a", "b"]],
+            "empty": {}
+        });
+
+        assert_eq!(
+            to_yaml(&value).unwrap(),
+            "count: 3\n\
+             diff: null\n\
+             empty: {}\n\
+             modules:\n  \
+               - files:\n      \
+                   - src/cli/mod.rs\n    \
+                 slug: src-cli\n  \
+               - files: []\n    \
+                 slug: root\n\
+             name: agentlens\n\
+             ratio: 0.5\n\
+             tags:\n  \
+               - - a\n    \
+                 - b\n"
+        );
+    }
+
+    #[test]
+    fn test_ambiguous_strings_are_quoted() {
+        for (s, expected) in [
+            ("src/main.rs", "src/main.rs"),
+            ("Rust CLI application", "Rust CLI application"),
+            ("true", "\"true\""),
+            ("No", "\"No\""),
+            ("42", "\"42\""),
+            ("", "\"\""),
+            ("key: value", "\"key: value\""),
+            ("- item", "\"- item\""),
+            ("line\nbreak", "\"line\\nbreak\""),
+            ("#comment", "\"#comment\""),
+        ] {
+            assert_eq!(string_scalar(s), expected, "{:?}", s);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::analyze::UnusedSymbol;
+
+/// Generates dead-code.md listing public symbols no other file appears to import.
+pub fn generate_dead_code(unused: &[UnusedSymbol]) -> String {
+    let mut output = String::new();
+
+    output.push_str("# Possibly Unused Symbols\n\n");
+    output.push_str(
+        "Public symbols that no other file imports and that are not in an entry point. \
+         This is a heuristic based on import statements: dynamic dispatch, macros, \
+         re-exports and external consumers are not detected, so verify before deleting.\n\n",
+    );
+
+    if unused.is_empty() {
+        output.push_str("*No possibly unused symbols found.*\n");
+        return output;
+    }
+
+    let mut current_file: Option<&str> = None;
+    for symbol in unused {
+        if current_file != Some(symbol.file.as_str()) {
+            if current_file.is_some() {
+                output.push('\n');
+            }
+            output.push_str(&format!("## `{}`\n\n", symbol.file));
+            current_file = Some(symbol.file.as_str());
+        }
+        output.push_str(&format!(
+            "- `{}` {} (L{})\n",
+            symbol.name, symbol.kind, symbol.line
+        ));
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SymbolKind;
+
+    #[test]
+    fn test_groups_by_file() {
+        let unused = vec![
+            UnusedSymbol {
+                file: "src/a.rs".to_string(),
+                name: "alpha".to_string(),
+                kind: SymbolKind::Function,
+                line: 3,
+            },
+            UnusedSymbol {
+                file: "src/a.rs".to_string(),
+                name: "Beta".to_string(),
+                kind: SymbolKind::Struct,
+                line: 10,
+            },
+        ];
+
+        let result = generate_dead_code(&unused);
+        assert!(result.contains("Possibly Unused"));
+        assert_eq!(result.matches("## `src/a.rs`").count(), 1);
+        assert!(result.contains("- `alpha` fn (L3)"));
+        assert!(result.contains("- `Beta` struct (L10)"));
+    }
+}
@@ -0,0 +1,63 @@
+use crate::analyze::EnvVar;
+
+/// Generates config.md listing the environment variables the code reads.
+pub fn generate_env_vars(vars: &[EnvVar]) -> String {
+    let mut output = String::new();
+
+    output.push_str("# Environment Variables\n\n");
+    output.push_str(
+        "Variables read through `std::env::var`, `process.env`, `os.environ` and \
+         `os.Getenv`. A variable is optional when every access supplies a fallback \
+         or handles it being unset; names built at runtime are not detected.\n\n",
+    );
+
+    if vars.is_empty() {
+        output.push_str("*No environment variable access found.*\n");
+        return output;
+    }
+
+    output.push_str("| Variable | Required | Referenced in |\n");
+    output.push_str("| -------- | -------- | ------------- |\n");
+    for var in vars {
+        let references: Vec<String> = var
+            .references
+            .iter()
+            .map(|r| format!("`{}:{}`", r.file, r.line))
+            .collect();
+        output.push_str(&format!(
+            "| `{}` | {} | {} |\n",
+            var.name,
+            if var.required { "yes" } else { "no" },
+            references.join(", ")
+        ));
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze::EnvVarReference;
+
+    #[test]
+    fn test_lists_variables_with_references() {
+        let vars = vec![EnvVar {
+            name: "DATABASE_URL".to_string(),
+            required: true,
+            references: vec![
+                EnvVarReference {
+                    file: "src/db.rs".to_string(),
+                    line: 12,
+                },
+                EnvVarReference {
+                    file: "src/main.rs".to_string(),
+                    line: 3,
+                },
+            ],
+        }];
+
+        let result = generate_env_vars(&vars);
+        assert!(result.contains("| `DATABASE_URL` | yes | `src/db.rs:12`, `src/main.rs:3` |"));
+    }
+}
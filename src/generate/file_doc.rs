@@ -3,20 +3,72 @@
 //! Generates deep documentation for exceptionally complex files
 //! that exceed the complexity threshold (>1000 lines OR >50 public symbols).
 
+use std::collections::HashMap;
+
 use crate::analyze::path_to_slug;
-use crate::types::{FileEntry, MemoryEntry, Symbol, Visibility};
+use crate::types::{FileEntry, Language, MemoryEntry, Symbol, Visibility};
 
 /// Default threshold for L2 file generation
 pub const DEFAULT_COMPLEX_LINES_THRESHOLD: usize = 1000;
 pub const DEFAULT_COMPLEX_SYMBOLS_THRESHOLD: usize = 50;
 
+/// Line and public-symbol thresholds for L2 docs, optionally overridden per language
+#[derive(Debug, Clone)]
+pub struct ComplexityThresholds {
+    lines: usize,
+    symbols: usize,
+    per_language: HashMap<String, (usize, usize)>,
+}
+
+impl Default for ComplexityThresholds {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_COMPLEX_LINES_THRESHOLD,
+            DEFAULT_COMPLEX_SYMBOLS_THRESHOLD,
+        )
+    }
+}
+
+impl ComplexityThresholds {
+    pub fn new(lines: usize, symbols: usize) -> Self {
+        Self {
+            lines,
+            symbols,
+            per_language: HashMap::new(),
+        }
+    }
+
+    /// Override thresholds for one language (by `Language::name`); unset values keep the global ones
+    pub fn with_language(
+        mut self,
+        language: &str,
+        lines: Option<usize>,
+        symbols: Option<usize>,
+    ) -> Self {
+        self.per_language.insert(
+            language.to_lowercase(),
+            (lines.unwrap_or(self.lines), symbols.unwrap_or(self.symbols)),
+        );
+        self
+    }
+
+    /// `(lines, symbols)` thresholds that apply to files of `language`
+    pub fn for_language(&self, language: Language) -> (usize, usize) {
+        self.per_language
+            .get(language.name())
+            .copied()
+            .unwrap_or((self.lines, self.symbols))
+    }
+}
+
 /// Check if a file is complex enough for L2 documentation
 pub fn is_complex_file(
     file: &FileEntry,
     symbols: &[Symbol],
-    lines_threshold: usize,
-    symbols_threshold: usize,
+    thresholds: &ComplexityThresholds,
 ) -> bool {
+    let (lines_threshold, symbols_threshold) = thresholds.for_language(file.language);
+
     if file.line_count >= lines_threshold {
         return true;
     }
@@ -93,6 +145,9 @@ pub fn generate_file_doc(
             if let Some(ref sig) = sym.signature {
                 output.push_str(&format!("### `{}`\n\n", sym.name));
                 output.push_str(&format!("```\n{}\n```\n\n", sig));
+                if let Some(summary) = sym.doc_summary() {
+                    output.push_str(&format!("{}\n\n", summary));
+                }
                 output.push_str(&format!(
                     "**Line:** {} | **Kind:** {}\n\n",
                     sym.line_range.start, sym.kind
@@ -119,7 +174,10 @@ pub fn generate_file_doc(
 
             output.push_str(&format!(
                 "### {} `{}` (line {})\n\n> {}\n\n",
-                priority_badge, entry.kind, entry.line_number, entry.content
+                priority_badge,
+                entry.marker(),
+                entry.line_number,
+                entry.content
             ));
         }
     }
@@ -152,11 +210,15 @@ mod tests {
     use std::path::PathBuf;
 
     fn make_file(path: &str, lines: usize) -> FileEntry {
+        make_file_in(path, lines, Language::Rust)
+    }
+
+    fn make_file_in(path: &str, lines: usize, language: Language) -> FileEntry {
         FileEntry {
             path: PathBuf::from(path),
             relative_path: path.to_string(),
-            extension: Some("rs".to_string()),
-            language: Language::Rust,
+            extension: path.rsplit('.').next().map(str::to_string),
+            language,
             size_bytes: 1000,
             line_count: lines,
             is_large: lines > 500,
@@ -170,7 +232,10 @@ mod tests {
             visibility,
             line_range: LineRange { start: 1, end: 10 },
             signature: Some(format!("fn {}()", name)),
+            params: Vec::new(),
+            return_type: None,
             doc_comment: None,
+            parent: None,
         }
     }
 
@@ -179,7 +244,11 @@ mod tests {
         let file = make_file("big.rs", 1500);
         let symbols: Vec<Symbol> = vec![];
 
-        assert!(is_complex_file(&file, &symbols, 1000, 50));
+        assert!(is_complex_file(
+            &file,
+            &symbols,
+            &ComplexityThresholds::new(1000, 50)
+        ));
     }
 
     #[test]
@@ -189,7 +258,11 @@ mod tests {
             .map(|i| make_symbol(&format!("func{}", i), Visibility::Public))
             .collect();
 
-        assert!(is_complex_file(&file, &symbols, 1000, 50));
+        assert!(is_complex_file(
+            &file,
+            &symbols,
+            &ComplexityThresholds::new(1000, 50)
+        ));
     }
 
     #[test]
@@ -199,7 +272,55 @@ mod tests {
             .map(|i| make_symbol(&format!("func{}", i), Visibility::Public))
             .collect();
 
-        assert!(!is_complex_file(&file, &symbols, 1000, 50));
+        assert!(!is_complex_file(
+            &file,
+            &symbols,
+            &ComplexityThresholds::new(1000, 50)
+        ));
+    }
+
+    #[test]
+    fn test_public_api_shows_doc_summary() {
+        let file = make_file("lib.rs", 100);
+        let documented = make_symbol("connect", Visibility::Public)
+            .with_doc_comment("Open a pooled connection.\n\nRetries twice.".to_string());
+        let symbols = vec![documented, make_symbol("close", Visibility::Public)];
+
+        let doc = generate_file_doc(&file, &symbols, &[], "root");
+
+        assert!(doc.contains("```\nfn connect()\n```\n\nOpen a pooled connection.\n\n"));
+        assert!(!doc.contains("Retries twice."));
+    }
+
+    #[test]
+    fn test_per_language_line_thresholds() {
+        let thresholds = ComplexityThresholds::new(1000, 50)
+            .with_language("go", Some(2000), None)
+            .with_language("python", Some(600), None);
+
+        let go = make_file_in("server.go", 1500, Language::Go);
+        let python = make_file_in("views.py", 700, Language::Python);
+        let rust = make_file("lib.rs", 1500);
+
+        assert!(!is_complex_file(&go, &[], &thresholds));
+        assert!(is_complex_file(&python, &[], &thresholds));
+        assert!(is_complex_file(&rust, &[], &thresholds));
+    }
+
+    #[test]
+    fn test_per_language_symbol_threshold_falls_back_to_global() {
+        let thresholds =
+            ComplexityThresholds::new(1000, 50).with_language("typescript", None, Some(20));
+        let symbols: Vec<Symbol> = (0..30)
+            .map(|i| make_symbol(&format!("func{}", i), Visibility::Public))
+            .collect();
+
+        let ts = make_file_in("api.ts", 100, Language::TypeScript);
+        let go = make_file_in("api.go", 100, Language::Go);
+
+        assert!(is_complex_file(&ts, &symbols, &thresholds));
+        assert!(!is_complex_file(&go, &symbols, &thresholds));
+        assert_eq!(thresholds.for_language(Language::TypeScript), (1000, 20));
     }
 
     #[test]
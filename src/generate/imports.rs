@@ -1,6 +1,41 @@
-use crate::analyze::FileGraph;
+use crate::analyze::{FileGraph, ModuleInfo};
+use crate::emit::HubFile;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
 
-pub fn generate_imports(graph: &FileGraph) -> String {
+/// Files imported by at least this many files are hubs
+const HUB_MIN_IMPORTERS: usize = 3;
+
+/// Default cap on files drawn in a diagram; larger sets get no diagram
+pub const DEFAULT_MERMAID_MAX_NODES: usize = 40;
+
+/// Whether and how to draw a Mermaid file dependency diagram in imports.md
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MermaidConfig {
+    pub enabled: bool,
+    /// Skip the diagram when more files than this are involved
+    pub max_nodes: usize,
+}
+
+impl Default for MermaidConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_nodes: DEFAULT_MERMAID_MAX_NODES,
+        }
+    }
+}
+
+impl MermaidConfig {
+    pub fn enabled() -> Self {
+        Self {
+            enabled: true,
+            ..Self::default()
+        }
+    }
+}
+
+pub fn generate_imports(graph: &FileGraph, mermaid: &MermaidConfig) -> String {
     let mut output = String::new();
 
     output.push_str("# imports.md\n\n");
@@ -11,6 +46,15 @@ pub fn generate_imports(graph: &FileGraph) -> String {
         return output;
     }
 
+    let cycles = graph.find_cycles();
+    if !cycles.is_empty() {
+        output.push_str("## ⚠️ Import Cycles\n\n");
+        for cycle in &cycles {
+            output.push_str(&format!("- {}\n", format_cycle(cycle)));
+        }
+        output.push('\n');
+    }
+
     let mut files: Vec<_> = graph.imports.keys().collect();
     files.sort();
 
@@ -47,9 +91,160 @@ pub fn generate_imports(graph: &FileGraph) -> String {
         output.push_str("---\n\n");
     }
 
+    if mermaid.enabled {
+        let mut all_files: Vec<String> = graph.imports.keys().cloned().collect();
+        all_files.sort();
+        if let Some(diagram) = file_dependency_diagram(&all_files, "", graph, mermaid.max_nodes) {
+            output.push_str("## Dependency Diagram\n\n");
+            output.push_str(&diagram);
+        }
+    }
+
     output
 }
 
+/// `a` → `b` → `a`, closing the loop on the first file
+pub fn format_cycle(cycle: &[String]) -> String {
+    let mut parts: Vec<String> = cycle.iter().map(|f| format!("`{}`", f)).collect();
+    if let Some(first) = parts.first().cloned() {
+        parts.push(first);
+    }
+    parts.join(" → ")
+}
+
+/// Mermaid `graph LR` block with an edge from each of `files` to the files
+/// among them it imports, labelled relative to `base`. `None` when there are
+/// more than `max_nodes` files or no edges between them.
+pub fn file_dependency_diagram(
+    files: &[String],
+    base: &str,
+    graph: &FileGraph,
+    max_nodes: usize,
+) -> Option<String> {
+    if files.len() > max_nodes {
+        return None;
+    }
+
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    for (from, file) in files.iter().enumerate() {
+        for import in graph.imports.get(file).into_iter().flatten() {
+            if let Some(to) = resolve_local_import(import, file, files) {
+                if !edges.contains(&(from, to)) {
+                    edges.push((from, to));
+                }
+            }
+        }
+    }
+    if edges.is_empty() {
+        return None;
+    }
+
+    let mut nodes: Vec<usize> = edges.iter().flat_map(|&(a, b)| [a, b]).collect();
+    nodes.sort();
+    nodes.dedup();
+
+    let mut output = String::from("```mermaid\ngraph LR\n");
+    for i in nodes {
+        let label = files[i]
+            .strip_prefix(base)
+            .map(|rest| rest.trim_start_matches('/'))
+            .filter(|rest| !rest.is_empty())
+            .unwrap_or(&files[i]);
+        output.push_str(&format!(
+            "    f{}[\"{}\"]\n",
+            i,
+            label.replace('"', "#quot;")
+        ));
+    }
+    for (from, to) in edges {
+        output.push_str(&format!("    f{} --> f{}\n", from, to));
+    }
+    output.push_str("```\n");
+
+    Some(output)
+}
+
+/// Files of `modules` imported by at least 3 others, with imports resolved to
+/// files as for the file graph diagram. Ranked by importers from other modules,
+/// then by all importers: a file used only inside its own module ranks below
+/// one shared across the codebase. Package imports are not files and never rank.
+pub fn rank_hubs(graph: &FileGraph, modules: &[ModuleInfo]) -> Vec<HubFile> {
+    let module_of: HashMap<&str, &str> = modules
+        .iter()
+        .flat_map(|m| m.files.iter().map(move |f| (f.as_str(), m.slug.as_str())))
+        .collect();
+    let mut files: Vec<String> = module_of.keys().map(|f| f.to_string()).collect();
+    files.sort();
+
+    let mut importers_of: BTreeMap<String, BTreeSet<&str>> = BTreeMap::new();
+    for (import, importers) in &graph.importers {
+        for importer in importers {
+            let target = if module_of.contains_key(import.as_str()) {
+                Some(import.clone())
+            } else {
+                resolve_local_import(import, importer, &files).map(|i| files[i].clone())
+            };
+            match target {
+                Some(target) if target != *importer => {
+                    importers_of.entry(target).or_default().insert(importer);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut hubs: Vec<HubFile> = importers_of
+        .into_iter()
+        .filter(|(_, importers)| importers.len() >= HUB_MIN_IMPORTERS)
+        .map(|(path, importers)| {
+            let home = module_of.get(path.as_str());
+            let cross_module_importers = importers
+                .iter()
+                .filter(|importer| module_of.get(*importer) != home)
+                .count();
+            HubFile {
+                imported_by: importers.len(),
+                cross_module_importers,
+                path,
+            }
+        })
+        .collect();
+
+    hubs.sort_by(|a, b| {
+        b.cross_module_importers
+            .cmp(&a.cross_module_importers)
+            .then(b.imported_by.cmp(&a.imported_by))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    hubs
+}
+
+/// Index of the file in `files` (other than `from`) an import names: the last
+/// segment of `crate::a::parser`, `pkg.parser` or `./parser.js` is matched
+/// against file stems, and against the directory of mod/index/__init__ files
+fn resolve_local_import(import: &str, from: &str, files: &[String]) -> Option<usize> {
+    let segment = if import.contains('/') {
+        Path::new(import).file_stem()?.to_str()?
+    } else {
+        import.rsplit("::").next()?.rsplit('.').next()?
+    };
+    if segment.is_empty() || matches!(segment, "self" | "super" | "crate" | "*") {
+        return None;
+    }
+
+    files.iter().position(|file| {
+        let path = Path::new(file);
+        let stem = path.file_stem().and_then(|s| s.to_str());
+        let dir_name = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str());
+        file != from
+            && (stem == Some(segment)
+                || matches!(stem, Some("mod" | "index" | "__init__")) && dir_name == Some(segment))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -57,7 +252,7 @@ mod tests {
     #[test]
     fn test_empty_graph() {
         let graph = FileGraph::new();
-        let result = generate_imports(&graph);
+        let result = generate_imports(&graph, &MermaidConfig::default());
         assert!(result.contains("No import relationships detected"));
     }
 
@@ -67,8 +262,113 @@ mod tests {
         graph.add_file("main.rs", vec!["lib".to_string(), "utils".to_string()]);
         graph.add_file("lib.rs", vec!["types".to_string()]);
 
-        let result = generate_imports(&graph);
+        let result = generate_imports(&graph, &MermaidConfig::default());
         assert!(result.contains("main.rs"));
         assert!(result.contains("lib, utils"));
+        assert!(!result.contains("mermaid"));
+    }
+
+    #[test]
+    fn test_generate_imports_lists_cycles_first() {
+        let mut graph = FileGraph::new();
+        graph.add_file("a.rs", vec!["b.rs".to_string()]);
+        graph.add_file("b.rs", vec!["a.rs".to_string()]);
+
+        let result = generate_imports(&graph, &MermaidConfig::default());
+
+        let cycles = result.find("## ⚠️ Import Cycles\n\n- `a.rs` → `b.rs` → `a.rs`\n");
+        assert!(cycles.is_some());
+        assert!(cycles < result.find("## `a.rs`"));
+    }
+
+    #[test]
+    fn test_mermaid_diagram_draws_local_edges() {
+        let mut graph = FileGraph::new();
+        graph.add_file(
+            "src/main.rs",
+            vec!["crate::parser".to_string(), "std::fs".to_string()],
+        );
+        graph.add_file("src/parser/mod.rs", vec!["super::types".to_string()]);
+        graph.add_file("src/types.rs", vec![]);
+        graph.add_file("web/app.ts", vec!["./api.js".to_string()]);
+        graph.add_file("web/api.ts", vec![]);
+
+        let result = generate_imports(&graph, &MermaidConfig::enabled());
+
+        let diagram = &result[result.find("```mermaid").unwrap()..];
+        assert_eq!(
+            diagram,
+            "```mermaid\ngraph LR\n    \
+             f0[\"src/main.rs\"]\n    f1[\"src/parser/mod.rs\"]\n    f2[\"src/types.rs\"]\n    \
+             f3[\"web/api.ts\"]\n    f4[\"web/app.ts\"]\n    \
+             f0 --> f1\n    f1 --> f2\n    f4 --> f3\n```\n"
+        );
+    }
+
+    #[test]
+    fn test_mermaid_diagram_skipped_over_node_cap() {
+        let mut graph = FileGraph::new();
+        graph.add_file("a.rs", vec!["crate::b".to_string()]);
+        graph.add_file("b.rs", vec![]);
+        graph.add_file("c.rs", vec![]);
+        let files: Vec<String> = ["a.rs", "b.rs", "c.rs"].map(String::from).to_vec();
+
+        assert!(file_dependency_diagram(&files, "", &graph, 3).is_some());
+        assert!(file_dependency_diagram(&files, "", &graph, 2).is_none());
+    }
+
+    #[test]
+    fn test_rank_hubs_prefers_cross_module_importers() {
+        use crate::analyze::BoundaryType;
+
+        let module = |path: &str, files: &[&str]| {
+            let mut module = ModuleInfo::new(path, BoundaryType::RustModule, None);
+            for f in files {
+                module.add_file(f.to_string());
+            }
+            module
+        };
+        let modules = vec![
+            module("src", &["src/shared.rs"]),
+            module("src/cli", &["src/cli/x.rs", "src/cli/y.rs"]),
+            module(
+                "src/util",
+                &[
+                    "src/util/local.rs",
+                    "src/util/a.rs",
+                    "src/util/b.rs",
+                    "src/util/c.rs",
+                ],
+            ),
+        ];
+
+        let mut graph = FileGraph::new();
+        let imports = |names: &[&str]| names.iter().map(|n| n.to_string()).collect();
+        graph.add_file("src/cli/x.rs", imports(&["crate::shared", "serde"]));
+        graph.add_file("src/cli/y.rs", imports(&["crate::shared", "serde"]));
+        graph.add_file(
+            "src/util/a.rs",
+            imports(&["crate::shared", "super::local", "serde"]),
+        );
+        graph.add_file("src/util/b.rs", imports(&["super::local", "serde"]));
+        graph.add_file("src/util/c.rs", imports(&["super::local"]));
+
+        let hubs = rank_hubs(&graph, &modules);
+
+        assert_eq!(
+            hubs,
+            vec![
+                HubFile {
+                    path: "src/shared.rs".to_string(),
+                    imported_by: 3,
+                    cross_module_importers: 3,
+                },
+                HubFile {
+                    path: "src/util/local.rs".to_string(),
+                    imported_by: 3,
+                    cross_module_importers: 0,
+                },
+            ]
+        );
     }
 }
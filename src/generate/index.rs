@@ -3,7 +3,9 @@
 //! Generates a constant-size O(m) routing table where m = number of modules,
 //! regardless of total file count in the repository.
 
-use crate::analyze::{FileGraph, ModuleInfo};
+use crate::analyze::{FileGraph, ModuleInfo, ProjectProfile};
+use crate::generate::imports::{format_cycle, rank_hubs};
+use crate::scan::InfraFile;
 use crate::types::{FileEntry, MemoryEntry};
 use std::collections::{HashMap, HashSet};
 
@@ -19,10 +21,20 @@ pub struct IndexConfig<'a> {
     pub hub_modules: &'a [(String, usize)],
     /// Project name (from directory or git)
     pub project_name: Option<&'a str>,
+    /// Detected primary languages and project type, shown under the title
+    pub project_profile: Option<&'a ProjectProfile>,
     /// File dependency graph (optional, for Mermaid diagram)
     pub file_graph: Option<&'a FileGraph>,
+    /// Build, container and CI files with their roles
+    pub infra_files: &'a [InfraFile],
 }
 
+/// Import cycles listed in INDEX.md before the rest are summarized
+const MAX_LISTED_CYCLES: usize = 10;
+
+/// Hub files listed in INDEX.md, most imported across modules first
+const MAX_LISTED_HUBS: usize = 10;
+
 /// Generate INDEX.md content
 pub fn generate_index_md(config: &IndexConfig) -> String {
     let mut output = String::new();
@@ -30,6 +42,9 @@ pub fn generate_index_md(config: &IndexConfig) -> String {
     // Header
     let project_name = config.project_name.unwrap_or("Project");
     output.push_str(&format!("# {}\n\n", project_name));
+    if let Some(summary) = config.project_profile.and_then(ProjectProfile::summary) {
+        output.push_str(&format!("{}\n\n", summary));
+    }
 
     // Reading protocol
     output.push_str("## Reading Protocol\n\n");
@@ -60,6 +75,24 @@ pub fn generate_index_md(config: &IndexConfig) -> String {
         output.push_str("Check each module's `memory.md` for details.\n\n");
     }
 
+    // Import cycles
+    if let Some(graph) = config.file_graph {
+        let cycles = graph.find_cycles();
+        if !cycles.is_empty() {
+            output.push_str("## ⚠️ Import Cycles\n\n");
+            for cycle in cycles.iter().take(MAX_LISTED_CYCLES) {
+                output.push_str(&format!("- {}\n", format_cycle(cycle)));
+            }
+            if cycles.len() > MAX_LISTED_CYCLES {
+                output.push_str(&format!(
+                    "- _...and {} more_\n",
+                    cycles.len() - MAX_LISTED_CYCLES
+                ));
+            }
+            output.push('\n');
+        }
+    }
+
     // Module table
     output.push_str("## Modules\n\n");
 
@@ -104,6 +137,32 @@ pub fn generate_index_md(config: &IndexConfig) -> String {
         output.push('\n');
     }
 
+    // Files most imported from other modules
+    if let Some(graph) = config.file_graph {
+        let hubs = rank_hubs(graph, config.modules);
+        if !hubs.is_empty() {
+            output.push_str("## Hub Files\n\n");
+            output.push_str("| File | Imported by | From other modules |\n");
+            output.push_str("| ---- | ----------- | ------------------ |\n");
+            for hub in hubs.iter().take(MAX_LISTED_HUBS) {
+                output.push_str(&format!(
+                    "| `{}` | {} | {} |\n",
+                    hub.path, hub.imported_by, hub.cross_module_importers
+                ));
+            }
+            output.push('\n');
+        }
+    }
+
+    // Project infrastructure
+    if !config.infra_files.is_empty() {
+        output.push_str("## Infrastructure\n\n");
+        for file in config.infra_files {
+            output.push_str(&format!("- `{}` - {}\n", file.relative_path, file.role));
+        }
+        output.push('\n');
+    }
+
     // Module dependency graph (Mermaid)
     if let Some(graph) = config.file_graph {
         let module_deps = compute_module_dependencies(config.modules, graph);
@@ -239,7 +298,7 @@ pub fn detect_entry_points(files: &[FileEntry]) -> Vec<String> {
 mod tests {
     use super::*;
     use crate::analyze::BoundaryType;
-    use crate::types::{Language, MemoryKind, Priority};
+    use crate::types::{Language, MemoryKind, Priority, Severity};
 
     fn make_module(path: &str, files: Vec<String>) -> ModuleInfo {
         let mut module = ModuleInfo::new(path, BoundaryType::RustModule, None);
@@ -256,6 +315,8 @@ mod tests {
             source_file: file.to_string(),
             line_number: 10,
             priority: Priority::High,
+            severity: Severity::Warning,
+            keyword: None,
         }
     }
 
@@ -269,7 +330,9 @@ mod tests {
             entry_points: &["src/main.rs".to_string()],
             hub_modules: &[],
             project_name: Some("TestProject"),
+            project_profile: None,
             file_graph: None,
+            infra_files: &[],
         };
 
         let result = generate_index_md(&config);
@@ -281,6 +344,30 @@ mod tests {
         assert!(result.contains("[src](modules/src/MODULE.md)"));
     }
 
+    #[test]
+    fn test_generate_index_md_lists_infrastructure() {
+        let infra = vec![InfraFile {
+            relative_path: "Dockerfile".to_string(),
+            role: "container definition",
+        }];
+
+        let config = IndexConfig {
+            modules: &[],
+            memory_entries: &[],
+            entry_points: &[],
+            hub_modules: &[],
+            project_name: None,
+            project_profile: None,
+            file_graph: None,
+            infra_files: &infra,
+        };
+
+        let result = generate_index_md(&config);
+
+        assert!(result.contains("## Infrastructure"));
+        assert!(result.contains("- `Dockerfile` - container definition"));
+    }
+
     #[test]
     fn test_generate_index_md_with_warnings() {
         let modules = vec![make_module("src/auth", vec!["src/auth/mod.rs".to_string()])];
@@ -292,7 +379,9 @@ mod tests {
             entry_points: &[],
             hub_modules: &[],
             project_name: None,
+            project_profile: None,
             file_graph: None,
+            infra_files: &[],
         };
 
         let result = generate_index_md(&config);
@@ -314,7 +403,9 @@ mod tests {
             entry_points: &[],
             hub_modules: &[("src/utils".to_string(), 10)],
             project_name: None,
+            project_profile: None,
             file_graph: None,
+            infra_files: &[],
         };
 
         let result = generate_index_md(&config);
@@ -322,6 +413,67 @@ mod tests {
         assert!(result.contains("★")); // Hub indicator
     }
 
+    #[test]
+    fn test_generate_index_md_lists_hub_files() {
+        let modules = vec![
+            make_module("src", vec!["src/types.rs".to_string()]),
+            make_module(
+                "src/cli",
+                vec![
+                    "src/cli/a.rs".to_string(),
+                    "src/cli/b.rs".to_string(),
+                    "src/cli/c.rs".to_string(),
+                ],
+            ),
+        ];
+        let mut graph = FileGraph::new();
+        for file in ["src/cli/a.rs", "src/cli/b.rs", "src/cli/c.rs"] {
+            graph.add_file(file, vec!["crate::types".to_string()]);
+        }
+
+        let config = IndexConfig {
+            modules: &modules,
+            memory_entries: &[],
+            entry_points: &[],
+            hub_modules: &[],
+            project_name: None,
+            project_profile: None,
+            file_graph: Some(&graph),
+            infra_files: &[],
+        };
+
+        let result = generate_index_md(&config);
+
+        assert!(result.contains("## Hub Files"));
+        assert!(result.contains("| `src/types.rs` | 3 | 3 |"));
+    }
+
+    #[test]
+    fn test_generate_index_md_lists_import_cycles() {
+        let modules = vec![make_module(
+            "src",
+            vec!["src/a.rs".to_string(), "src/b.rs".to_string()],
+        )];
+        let mut graph = FileGraph::new();
+        graph.add_file("src/a.rs", vec!["src/b.rs".to_string()]);
+        graph.add_file("src/b.rs", vec!["src/a.rs".to_string()]);
+
+        let config = IndexConfig {
+            modules: &modules,
+            memory_entries: &[],
+            entry_points: &[],
+            hub_modules: &[],
+            project_name: None,
+            project_profile: None,
+            file_graph: Some(&graph),
+            infra_files: &[],
+        };
+
+        let result = generate_index_md(&config);
+
+        assert!(result.contains("## ⚠️ Import Cycles\n\n- `src/a.rs` → `src/b.rs` → `src/a.rs`\n"));
+    }
+
     #[test]
     fn test_entry_points_limited_to_5() {
         let entry_points: Vec<String> = (0..10).map(|i| format!("file{}.rs", i)).collect();
@@ -332,7 +484,9 @@ mod tests {
             entry_points: &entry_points,
             hub_modules: &[],
             project_name: None,
+            project_profile: None,
             file_graph: None,
+            infra_files: &[],
         };
 
         let result = generate_index_md(&config);
@@ -351,7 +505,9 @@ mod tests {
             entry_points: &[],
             hub_modules: &[],
             project_name: None,
+            project_profile: None,
             file_graph: None,
+            infra_files: &[],
         };
 
         let result = generate_index_md(&config);
@@ -364,7 +520,7 @@ mod tests {
         FileEntry {
             path: PathBuf::from(relative_path),
             relative_path: relative_path.to_string(),
-            extension: relative_path.split('.').last().map(|s| s.to_string()),
+            extension: relative_path.split('.').next_back().map(|s| s.to_string()),
             language: Language::Rust,
             size_bytes: 1000,
             line_count,
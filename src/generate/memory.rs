@@ -1,11 +1,16 @@
-use crate::types::{MemoryEntry, Priority};
+use crate::types::{MemoryEntry, Priority, Severity};
 use std::collections::BTreeMap;
 
 /// Generates memory.md content from extracted memory entries.
 ///
 /// Groups entries by category (Warnings, Business Rules, Technical Debt, Notes),
 /// sorts by priority within categories, and formats with emoji headers.
-pub fn generate_memory(entries: &[MemoryEntry]) -> String {
+/// Entries below `min_severity` are left out.
+pub fn generate_memory(entries: &[MemoryEntry], min_severity: Severity) -> String {
+    let entries: Vec<&MemoryEntry> = entries
+        .iter()
+        .filter(|e| e.severity >= min_severity)
+        .collect();
     if entries.is_empty() {
         return "# Memory\n\nNo memory markers found in this repository.".to_string();
     }
@@ -24,8 +29,14 @@ pub fn generate_memory(entries: &[MemoryEntry]) -> String {
             .push(entry);
     }
 
-    // Category order: Warnings first, then Business Rules, Tech Debt, Notes
-    let category_order = ["Warnings", "Business Rules", "Technical Debt", "Notes"];
+    // Category order: Warnings first, then Business Rules, Tech Debt, Notes, custom keywords
+    let category_order = [
+        "Warnings",
+        "Business Rules",
+        "Technical Debt",
+        "Notes",
+        "Custom Markers",
+    ];
 
     // Summary table
     output.push_str("## Summary\n\n");
@@ -81,7 +92,10 @@ pub fn generate_memory(entries: &[MemoryEntry]) -> String {
 
                 output.push_str(&format!(
                     "### {} `{}` ({}:{})\n\n",
-                    priority_badge, entry.kind, entry.source_file, entry.line_number
+                    priority_badge,
+                    entry.marker(),
+                    entry.source_file,
+                    entry.line_number
                 ));
                 output.push_str(&format!("> {}\n\n", entry.content));
             }
@@ -93,12 +107,12 @@ pub fn generate_memory(entries: &[MemoryEntry]) -> String {
     output
 }
 
-/// Returns files with high-priority memory entries for AGENTS.md critical files section.
-/// Returns tuples of (file_path, count_of_high_priority_entries)
+/// Returns files with warning or critical memory entries for AGENTS.md critical files section.
+/// Returns tuples of (file_path, count_of_warning_or_critical_entries)
 pub fn get_critical_files(entries: &[MemoryEntry]) -> Vec<(String, usize)> {
     let mut file_counts: BTreeMap<&str, usize> = BTreeMap::new();
 
-    for entry in entries.iter().filter(|e| e.priority == Priority::High) {
+    for entry in entries.iter().filter(|e| e.severity >= Severity::Warning) {
         *file_counts.entry(&entry.source_file).or_default() += 1;
     }
 
@@ -108,7 +122,7 @@ pub fn get_critical_files(entries: &[MemoryEntry]) -> Vec<(String, usize)> {
         .collect();
 
     // Sort by count descending
-    result.sort_by(|a, b| b.1.cmp(&a.1));
+    result.sort_by_key(|r| std::cmp::Reverse(r.1));
     result
 }
 
@@ -119,7 +133,7 @@ mod tests {
 
     #[test]
     fn test_empty_entries() {
-        let result = generate_memory(&[]);
+        let result = generate_memory(&[], Severity::Info);
         assert!(result.contains("No memory markers found"));
     }
 
@@ -140,12 +154,48 @@ mod tests {
             ),
         ];
 
-        let result = generate_memory(&entries);
+        let result = generate_memory(&entries, Severity::Info);
         assert!(result.contains("## Summary"));
         assert!(result.contains("Warnings"));
         assert!(result.contains("Technical Debt"));
     }
 
+    #[test]
+    fn test_min_severity_filters_entries() {
+        let entries = vec![
+            MemoryEntry::new(
+                MemoryKind::Fixme,
+                "Off by one".to_string(),
+                "src/lib.rs".to_string(),
+                3,
+            ),
+            MemoryEntry::new(
+                MemoryKind::Deprecated,
+                "Use the v2 API".to_string(),
+                "src/lib.rs".to_string(),
+                8,
+            ),
+            MemoryEntry::new(
+                MemoryKind::Note,
+                "Called once per request".to_string(),
+                "src/main.rs".to_string(),
+                12,
+            ),
+        ];
+
+        let warnings = generate_memory(&entries, Severity::Warning);
+        assert!(warnings.contains("Off by one"));
+        assert!(warnings.contains("Use the v2 API"));
+        assert!(!warnings.contains("Called once per request"));
+
+        let critical = generate_memory(&entries, Severity::Critical);
+        assert!(critical.contains("Off by one"));
+        assert!(!critical.contains("Use the v2 API"));
+
+        let none = generate_memory(&entries[2..], Severity::Critical);
+        assert!(none.contains("No memory markers found"));
+    }
+
     #[test]
     fn test_critical_files() {
         let entries = vec![
@@ -170,7 +220,7 @@ mod tests {
         ];
 
         let critical = get_critical_files(&entries);
-        assert_eq!(critical.len(), 1); // Only danger.rs has high-priority
+        assert_eq!(critical.len(), 1); // The TODO in other.rs is only informational
         assert_eq!(critical[0].0, "src/danger.rs");
         assert_eq!(critical[0].1, 2);
     }
@@ -1,4 +1,6 @@
 mod agent;
+mod dead_code;
+mod env_vars;
 mod file_doc;
 mod imports;
 mod index;
@@ -8,11 +10,13 @@ mod outline;
 mod templates;
 
 pub use agent::{generate_agent_md, AgentConfig, ProjectSize};
+pub use dead_code::generate_dead_code;
+pub use env_vars::generate_env_vars;
 pub use file_doc::{
-    file_path_to_slug, generate_file_doc, is_complex_file, DEFAULT_COMPLEX_LINES_THRESHOLD,
-    DEFAULT_COMPLEX_SYMBOLS_THRESHOLD,
+    file_path_to_slug, generate_file_doc, is_complex_file, ComplexityThresholds,
+    DEFAULT_COMPLEX_LINES_THRESHOLD, DEFAULT_COMPLEX_SYMBOLS_THRESHOLD,
 };
-pub use imports::generate_imports;
+pub use imports::{generate_imports, rank_hubs, MermaidConfig};
 pub use index::{detect_entry_points, generate_index_md, IndexConfig};
 pub use memory::{generate_memory, get_critical_files};
 pub use module_content::generate_module_content;
@@ -7,8 +7,11 @@
 //! - imports.md: Dependencies within/from this module
 
 use crate::analyze::{FileGraph, ModuleInfo};
-use crate::emit::ModuleContent;
-use crate::types::{FileEntry, MemoryEntry, Priority, Symbol};
+use crate::emit::{language_breakdown, LanguageStats, ModuleContent};
+use crate::generate::imports::{file_dependency_diagram, MermaidConfig};
+use crate::generate::outline::{push_symbol_table, push_tests_section};
+use crate::scan::InfraFile;
+use crate::types::{FileEntry, MemoryEntry, Priority, Symbol, SymbolKind};
 
 const INLINE_THRESHOLD: usize = 500;
 
@@ -39,17 +42,30 @@ pub fn generate_module_content(
     symbols: &[(FileEntry, Vec<Symbol>)],
     memory: &[MemoryEntry],
     graph: &FileGraph,
+    infra_files: &[InfraFile],
+    mermaid: &MermaidConfig,
 ) -> ModuleContent {
     let module_files: Vec<&FileEntry> = files
         .iter()
         .filter(|f| module.files.contains(&f.relative_path))
         .collect();
+    let module_infra: Vec<&InfraFile> = infra_files
+        .iter()
+        .filter(|f| parent_dir(&f.relative_path) == module.path)
+        .collect();
 
     let outline = SectionContent::new(generate_module_outline(module, symbols));
     let memory_content = SectionContent::new(generate_module_memory(module, memory));
-    let imports = SectionContent::new(generate_module_imports(module, graph));
+    let imports = SectionContent::new(generate_module_imports(module, graph, mermaid));
 
-    let module_md = generate_module_md(module, &module_files, &outline, &memory_content, &imports);
+    let module_md = generate_module_md(
+        module,
+        &module_files,
+        &module_infra,
+        &outline,
+        &memory_content,
+        &imports,
+    );
 
     ModuleContent {
         module_md,
@@ -75,6 +91,7 @@ pub fn generate_module_content(
 fn generate_module_md(
     module: &ModuleInfo,
     files: &[&FileEntry],
+    infra_files: &[&InfraFile],
     outline: &SectionContent,
     memory: &SectionContent,
     imports: &SectionContent,
@@ -104,6 +121,25 @@ fn generate_module_md(
         output.push_str(&format!("**Entry point:** `{}`\n\n", entry));
     }
 
+    // Languages by lines, largest first
+    let mut languages: Vec<(String, LanguageStats)> = language_breakdown(files.iter().copied())
+        .into_iter()
+        .collect();
+    languages.sort_by(|a, b| b.1.lines.cmp(&a.1.lines).then_with(|| a.0.cmp(&b.0)));
+    if !languages.is_empty() {
+        output.push_str("## Languages\n\n");
+        output.push_str("| Language | Files | Lines |\n");
+        output.push_str("| -------- | ----- | ----- |\n");
+
+        for (language, stats) in &languages {
+            output.push_str(&format!(
+                "| {} | {} | {} |\n",
+                language, stats.files, stats.lines
+            ));
+        }
+        output.push('\n');
+    }
+
     // Files in this module
     if !files.is_empty() {
         output.push_str("## Files\n\n");
@@ -120,6 +156,18 @@ fn generate_module_md(
         output.push('\n');
     }
 
+    // Build, container and CI files living next to the sources
+    if !infra_files.is_empty() {
+        output.push_str("## Infrastructure\n\n");
+        output.push_str("| File | Role |\n");
+        output.push_str("| ---- | ---- |\n");
+
+        for file in infra_files {
+            output.push_str(&format!("| `{}` | {} |\n", file.relative_path, file.role));
+        }
+        output.push('\n');
+    }
+
     // Child modules
     if !module.children.is_empty() {
         output.push_str("## Child Modules\n\n");
@@ -162,6 +210,10 @@ fn generate_module_md(
     output
 }
 
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
 fn strip_navigation_header(content: &str) -> String {
     content
         .lines()
@@ -204,16 +256,12 @@ fn generate_module_outline(module: &ModuleInfo, symbols: &[(FileEntry, Vec<Symbo
             continue;
         }
 
-        output.push_str("| Line | Kind | Name | Visibility |\n");
-        output.push_str("| ---- | ---- | ---- | ---------- |\n");
-
-        for sym in syms {
-            output.push_str(&format!(
-                "| {} | {} | {} | {} |\n",
-                sym.line_range.start, sym.kind, sym.name, sym.visibility
-            ));
+        let (tests, syms): (Vec<&Symbol>, Vec<&Symbol>) =
+            syms.iter().partition(|s| s.kind == SymbolKind::Test);
+        if !syms.is_empty() {
+            push_symbol_table(&mut output, &syms);
         }
-        output.push('\n');
+        push_tests_section(&mut output, &tests);
     }
 
     output
@@ -266,7 +314,10 @@ fn generate_module_memory(module: &ModuleInfo, memory: &[MemoryEntry]) -> String
         {
             output.push_str(&format!(
                 "### `{}` ({}:{})\n\n> {}\n\n",
-                entry.kind, entry.source_file, entry.line_number, entry.content
+                entry.marker(),
+                entry.source_file,
+                entry.line_number,
+                entry.content
             ));
         }
     }
@@ -279,7 +330,10 @@ fn generate_module_memory(module: &ModuleInfo, memory: &[MemoryEntry]) -> String
         {
             output.push_str(&format!(
                 "### `{}` ({}:{})\n\n> {}\n\n",
-                entry.kind, entry.source_file, entry.line_number, entry.content
+                entry.marker(),
+                entry.source_file,
+                entry.line_number,
+                entry.content
             ));
         }
     }
@@ -289,7 +343,10 @@ fn generate_module_memory(module: &ModuleInfo, memory: &[MemoryEntry]) -> String
         for entry in module_memory.iter().filter(|e| e.priority == Priority::Low) {
             output.push_str(&format!(
                 "### `{}` ({}:{})\n\n> {}\n\n",
-                entry.kind, entry.source_file, entry.line_number, entry.content
+                entry.marker(),
+                entry.source_file,
+                entry.line_number,
+                entry.content
             ));
         }
     }
@@ -298,7 +355,11 @@ fn generate_module_memory(module: &ModuleInfo, memory: &[MemoryEntry]) -> String
 }
 
 /// Generate module-scoped imports.md
-fn generate_module_imports(module: &ModuleInfo, graph: &FileGraph) -> String {
+fn generate_module_imports(
+    module: &ModuleInfo,
+    graph: &FileGraph,
+    mermaid: &MermaidConfig,
+) -> String {
     let mut output = String::new();
 
     output.push_str("# Imports\n\n");
@@ -402,6 +463,17 @@ fn generate_module_imports(module: &ModuleInfo, graph: &FileGraph) -> String {
         output.push('\n');
     }
 
+    if mermaid.enabled {
+        if let Some(diagram) =
+            file_dependency_diagram(&module.files, &module.path, graph, mermaid.max_nodes)
+        {
+            output.push_str("## File Graph\n\n");
+            output.push_str("Imports between files of this module:\n\n");
+            output.push_str(&diagram);
+            output.push('\n');
+        }
+    }
+
     output
 }
 
@@ -427,7 +499,7 @@ fn path_to_module_name(path: &str) -> String {
 mod tests {
     use super::*;
     use crate::analyze::BoundaryType;
-    use crate::types::{Language, MemoryKind};
+    use crate::types::{Language, MemoryKind, Severity};
     use std::path::PathBuf;
 
     fn make_module(path: &str, files: Vec<String>) -> ModuleInfo {
@@ -453,7 +525,7 @@ mod tests {
     #[test]
     fn test_generate_module_md_with_separate_files() {
         let module = make_module("src/analyze", vec!["src/analyze/mod.rs".to_string()]);
-        let files = vec![make_file("src/analyze/mod.rs", 100)];
+        let files = [make_file("src/analyze/mod.rs", 100)];
         let file_refs: Vec<&FileEntry> = files.iter().collect();
 
         let outline = SectionContent {
@@ -469,7 +541,7 @@ mod tests {
             should_inline: false,
         };
 
-        let result = generate_module_md(&module, &file_refs, &outline, &memory, &imports);
+        let result = generate_module_md(&module, &file_refs, &[], &outline, &memory, &imports);
 
         assert!(result.contains("# Module: src/analyze"));
         assert!(result.contains("Back to INDEX"));
@@ -482,7 +554,7 @@ mod tests {
     #[test]
     fn test_generate_module_md_with_inline_content() {
         let module = make_module("src/analyze", vec!["src/analyze/mod.rs".to_string()]);
-        let files = vec![make_file("src/analyze/mod.rs", 100)];
+        let files = [make_file("src/analyze/mod.rs", 100)];
         let file_refs: Vec<&FileEntry> = files.iter().collect();
 
         let imports_content = "# Imports\n\n[nav]\n\nExternal: crate_a".to_string();
@@ -499,7 +571,7 @@ mod tests {
             should_inline: true,
         };
 
-        let result = generate_module_md(&module, &file_refs, &outline, &memory, &imports);
+        let result = generate_module_md(&module, &file_refs, &[], &outline, &memory, &imports);
 
         assert!(result.contains("# Module: src/analyze"));
         assert!(!result.contains("imports.md"));
@@ -509,7 +581,7 @@ mod tests {
     #[test]
     fn test_generate_module_md_no_docs() {
         let module = make_module("src/analyze", vec!["src/analyze/mod.rs".to_string()]);
-        let files = vec![make_file("src/analyze/mod.rs", 100)];
+        let files = [make_file("src/analyze/mod.rs", 100)];
         let file_refs: Vec<&FileEntry> = files.iter().collect();
 
         let outline = SectionContent {
@@ -525,7 +597,7 @@ mod tests {
             should_inline: false,
         };
 
-        let result = generate_module_md(&module, &file_refs, &outline, &memory, &imports);
+        let result = generate_module_md(&module, &file_refs, &[], &outline, &memory, &imports);
 
         assert!(result.contains("# Module: src/analyze"));
         assert!(!result.contains("outline.md"));
@@ -533,6 +605,76 @@ mod tests {
         assert!(!result.contains("imports.md"));
     }
 
+    #[test]
+    fn test_module_md_lists_languages_by_lines() {
+        let module = make_module(
+            "web",
+            vec![
+                "web/app.js".to_string(),
+                "web/util.js".to_string(),
+                "web/lib.rs".to_string(),
+            ],
+        );
+        let file = |path: &str, lines: usize| {
+            FileEntry::new(PathBuf::from(path), path.to_string(), 1000, lines, 500)
+        };
+        let files = [
+            file("web/app.js", 300),
+            file("web/util.js", 50),
+            file("web/lib.rs", 120),
+            file("other/main.rs", 999),
+        ];
+
+        let content = generate_module_content(
+            &module,
+            &files,
+            &[],
+            &[],
+            &FileGraph::new(),
+            &[],
+            &MermaidConfig::default(),
+        );
+
+        assert!(content.module_md.contains(
+            "| Language | Files | Lines |\n\
+             | -------- | ----- | ----- |\n\
+             | javascript | 2 | 350 |\n\
+             | rust | 1 | 120 |\n"
+        ));
+    }
+
+    #[test]
+    fn test_dockerfile_annotated_as_container_definition() {
+        let module = make_module("services/api", vec!["services/api/main.rs".to_string()]);
+        let files = [make_file("services/api/main.rs", 40)];
+        let infra = [
+            InfraFile {
+                relative_path: "services/api/Dockerfile".to_string(),
+                role: crate::scan::file_role("services/api/Dockerfile").unwrap(),
+            },
+            InfraFile {
+                relative_path: "Makefile".to_string(),
+                role: "build automation",
+            },
+        ];
+
+        let content = generate_module_content(
+            &module,
+            &files,
+            &[],
+            &[],
+            &FileGraph::new(),
+            &infra,
+            &MermaidConfig::default(),
+        );
+
+        assert!(content
+            .module_md
+            .contains("| `services/api/Dockerfile` | container definition |"));
+        // Files outside the module directory belong to other modules
+        assert!(!content.module_md.contains("Makefile"));
+    }
+
     #[test]
     fn test_generate_module_outline_empty() {
         let module = make_module("src/small", vec!["src/small/mod.rs".to_string()]);
@@ -543,6 +685,32 @@ mod tests {
         assert!(result.is_empty());
     }
 
+    #[test]
+    fn test_generate_module_outline_groups_tests() {
+        use crate::types::Visibility;
+
+        let module = make_module("src/auth", vec!["src/auth/login.rs".to_string()]);
+        let symbols = vec![(
+            make_file("src/auth/login.rs", 600),
+            vec![
+                Symbol::new(SymbolKind::Function, "login".into(), 1, Visibility::Public),
+                Symbol::new(
+                    SymbolKind::Test,
+                    "login_works".into(),
+                    40,
+                    Visibility::Private,
+                ),
+            ],
+        )];
+
+        let result = generate_module_outline(&module, &symbols);
+
+        let (main, tests) = result.split_once("<summary>Tests (1)</summary>").unwrap();
+        assert!(main.contains("| 1 | fn | login | pub |"));
+        assert!(!main.contains("login_works"));
+        assert!(tests.contains("| 40 | test | login_works | (private) |"));
+    }
+
     #[test]
     fn test_generate_module_memory_empty() {
         let module = make_module("src/clean", vec!["src/clean/mod.rs".to_string()]);
@@ -562,6 +730,8 @@ mod tests {
             source_file: "src/warn/mod.rs".to_string(),
             line_number: 10,
             priority: Priority::High,
+            severity: Severity::Warning,
+            keyword: None,
         }];
 
         let result = generate_module_memory(&module, &memory);
@@ -575,8 +745,35 @@ mod tests {
         let module = make_module("src/isolated", vec!["src/isolated/mod.rs".to_string()]);
         let graph = FileGraph::new();
 
-        let result = generate_module_imports(&module, &graph);
+        let result = generate_module_imports(&module, &graph, &MermaidConfig::default());
 
         assert!(result.contains("No import relationships"));
     }
+
+    #[test]
+    fn test_generate_module_imports_file_graph() {
+        let module = make_module(
+            "src/search",
+            vec![
+                "src/search/mod.rs".to_string(),
+                "src/search/indexer.rs".to_string(),
+                "src/search/store.rs".to_string(),
+            ],
+        );
+        let mut graph = FileGraph::new();
+        graph.add_file(
+            "src/search/indexer.rs",
+            vec!["super::store".to_string(), "crate::config".to_string()],
+        );
+        graph.add_file("src/search/store.rs", vec![]);
+
+        let result = generate_module_imports(&module, &graph, &MermaidConfig::default());
+        assert!(!result.contains("## File Graph"));
+
+        let result = generate_module_imports(&module, &graph, &MermaidConfig::enabled());
+        assert!(result.contains(
+            "## File Graph\n\nImports between files of this module:\n\n\
+             ```mermaid\ngraph LR\n    f1[\"indexer.rs\"]\n    f2[\"store.rs\"]\n    f1 --> f2\n```\n"
+        ));
+    }
 }
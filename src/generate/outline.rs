@@ -1,4 +1,4 @@
-use crate::types::{FileEntry, Symbol};
+use crate::types::{FileEntry, Symbol, SymbolKind};
 
 pub fn generate_outline(files: &[(FileEntry, Vec<Symbol>)]) -> String {
     if files.is_empty() {
@@ -29,19 +29,13 @@ pub fn generate_outline(files: &[(FileEntry, Vec<Symbol>)]) -> String {
             file.relative_path, file.line_count
         ));
 
-        if symbols.is_empty() {
+        let (tests, symbols): (Vec<&Symbol>, Vec<&Symbol>) =
+            symbols.iter().partition(|s| s.kind == SymbolKind::Test);
+
+        if symbols.is_empty() && tests.is_empty() {
             output.push_str("_No symbols extracted._\n\n");
-        } else {
-            output.push_str("| Line | Kind | Name | Visibility |\n");
-            output.push_str("| ---- | ---- | ---- | ---------- |\n");
-
-            for sym in symbols {
-                output.push_str(&format!(
-                    "| {} | {} | {} | {} |\n",
-                    sym.line_range.start, sym.kind, sym.name, sym.visibility
-                ));
-            }
-            output.push('\n');
+        } else if !symbols.is_empty() {
+            push_symbol_table(&mut output, &symbols);
 
             let key_entries: Vec<_> = symbols
                 .iter()
@@ -64,8 +58,123 @@ pub fn generate_outline(files: &[(FileEntry, Vec<Symbol>)]) -> String {
             }
         }
 
+        push_tests_section(&mut output, &tests);
+
         output.push_str("---\n\n");
     }
 
     output
 }
+
+/// Collapsed table of test functions, kept out of the main table so it lists
+/// production code only. Writes nothing when there are no tests.
+pub(crate) fn push_tests_section(output: &mut String, tests: &[&Symbol]) {
+    if tests.is_empty() {
+        return;
+    }
+
+    output.push_str(&format!(
+        "<details>\n<summary>Tests ({})</summary>\n\n",
+        tests.len()
+    ));
+    push_symbol_table(output, tests);
+    output.push_str("</details>\n\n");
+}
+
+/// Symbol table in tree order, with nested symbols indented under their
+/// parent; a Summary column with each doc comment's first line is added when
+/// any symbol is documented
+pub(crate) fn push_symbol_table(output: &mut String, symbols: &[&Symbol]) {
+    let documented = symbols.iter().any(|s| s.doc_summary().is_some());
+    if documented {
+        output.push_str("| Line | Kind | Name | Visibility | Summary |\n");
+        output.push_str("| ---- | ---- | ---- | ---------- | ------- |\n");
+    } else {
+        output.push_str("| Line | Kind | Name | Visibility |\n");
+        output.push_str("| ---- | ---- | ---- | ---------- |\n");
+    }
+
+    for (sym, depth) in tree_order(symbols) {
+        let name = match depth {
+            0 => sym.name.clone(),
+            _ => format!("{}└ {}", "\u{a0}\u{a0}".repeat(depth - 1), sym.name),
+        };
+        output.push_str(&format!(
+            "| {} | {} | {} | {} |",
+            sym.line_range.start, sym.kind, name, sym.visibility
+        ));
+        if documented {
+            let summary = sym.doc_summary().unwrap_or("").replace('|', "\\|");
+            output.push_str(&format!(" {} |", summary));
+        }
+        output.push('\n');
+    }
+    output.push('\n');
+}
+
+/// Symbols sorted so each parent precedes its children, paired with their
+/// nesting depth. Parents missing from `symbols` (e.g. filtered out) don't
+/// count, so their children are shown one level up.
+fn tree_order<'a>(symbols: &[&'a Symbol]) -> Vec<(&'a Symbol, usize)> {
+    let mut sorted = symbols.to_vec();
+    sorted.sort_by_key(|s| (s.line_range.start, std::cmp::Reverse(s.line_range.end)));
+
+    sorted
+        .iter()
+        .map(|sym| {
+            let mut depth = 0;
+            let mut current = *sym;
+            while let Some(parent) = current.parent.as_deref().and_then(|name| {
+                sorted
+                    .iter()
+                    .filter(|s| s.name == name && s.contains(current))
+                    .min_by_key(|s| s.line_range.end - s.line_range.start)
+            }) {
+                depth += 1;
+                current = parent;
+            }
+            (*sym, depth)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Visibility;
+
+    fn make_symbol(kind: SymbolKind, name: &str, lines: (usize, usize)) -> Symbol {
+        Symbol::new(kind, name.to_string(), lines.0, Visibility::Public)
+            .with_line_range(lines.0, lines.1)
+    }
+
+    #[test]
+    fn test_symbol_table_nests_methods_under_class() {
+        let mut method = make_symbol(SymbolKind::Method, "render", (3, 8));
+        method.parent = Some("View".to_string());
+        let mut helper = make_symbol(SymbolKind::Function, "pad", (5, 6));
+        helper.parent = Some("render".to_string());
+        let class = make_symbol(SymbolKind::Class, "View", (1, 10));
+        let free = make_symbol(SymbolKind::Function, "main", (12, 14));
+
+        let mut output = String::new();
+        // Parsers often emit methods before their class
+        push_symbol_table(&mut output, &[&helper, &method, &free, &class]);
+
+        let rows: Vec<&str> = output.lines().skip(2).filter(|l| !l.is_empty()).collect();
+        assert_eq!(
+            rows,
+            vec![
+                "| 1 | class | View | pub |",
+                "| 3 | method | └ render | pub |",
+                "| 5 | fn | \u{a0}\u{a0}└ pad | pub |",
+                "| 12 | fn | main | pub |",
+            ]
+        );
+
+        // Without the class in the table, the method is top-level
+        let mut output = String::new();
+        push_symbol_table(&mut output, &[&method]);
+        assert!(output.contains("| 3 | method | render | pub |"));
+    }
+}
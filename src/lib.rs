@@ -8,6 +8,8 @@ pub mod runner;
 pub mod scan;
 pub mod search;
 pub mod telemetry;
+#[cfg(test)]
+pub(crate) mod test_support;
 pub mod types;
 
 pub use cli::Args;
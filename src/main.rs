@@ -2,42 +2,64 @@ use anyhow::{Context, Result};
 use chrono::Utc;
 use clap::Parser;
 use std::collections::HashMap;
-use std::fs;
 
 use agentlens::analyze::{
-    detect_modules, extract_imports, extract_memory_markers, extract_symbols, FileGraph, ModuleInfo,
+    detect_project, detect_project_modules, extract_imports, extract_memory_markers,
+    extract_public_imports, extract_symbols, find_unused_symbols, scan_env_vars, EnvVar, FileGraph,
+    ModuleInfo, SymbolFilter, UnusedSymbol,
 };
 use agentlens::cli::{
     execute_setup, install_hooks_with_manager, install_skills, is_interactive, list_skills,
-    remove_hooks, remove_skills, run_check, run_index, run_index_clear, run_index_status,
-    run_interactive_init, run_mcp_http_server, run_mcp_server, run_search,
-    run_telemetry_all_modules, run_telemetry_module, run_templates, run_update, run_watch, Args,
-    Command, HooksAction, IndexAction, SkillsAction, TelemetryAction,
+    read_batch_queries, remove_hooks, remove_skills, run_check, run_explain_chunking, run_index,
+    run_index_clear, run_index_status, run_interactive_init, run_mcp_http_server, run_mcp_server,
+    run_review, run_search, run_similar_files, run_store_server, run_telemetry_all_modules,
+    run_telemetry_module, run_templates, run_update, run_watch, run_watch_serve, Args, Command,
+    HooksAction, IndexAction, OutputFormat, SearchFormat, SkillsAction, SnippetSize,
+    TelemetryAction,
 };
 use agentlens::emit::{
-    calculate_module_state, current_timestamp, write_hierarchical, CriticalFile, DiffInfo,
-    HierarchicalOutput, HubFile, JsonOutput, LargeFileEntry, Manifest, ModuleOutput, ProjectInfo,
+    calculate_module_state, current_timestamp, emit_jsonl, write_hierarchical, CriticalFile,
+    DiffInfo, HierarchicalOutput, HubFile, JsonOutput, LargeFileEntry, Manifest, ModuleOutput,
+    ProjectInfo,
 };
 use agentlens::generate::{
-    detect_entry_points, file_path_to_slug, generate_agent_md, generate_file_doc,
-    generate_index_md, generate_module_content, get_critical_files, is_complex_file, AgentConfig,
-    IndexConfig,
+    detect_entry_points, file_path_to_slug, generate_agent_md, generate_dead_code,
+    generate_env_vars, generate_file_doc, generate_index_md, generate_module_content,
+    get_critical_files, is_complex_file, rank_hubs, AgentConfig, IndexConfig,
 };
 use agentlens::scan::{
-    cleanup_temp, clone_to_temp, get_default_branch, get_diff_files, get_git_head, is_git_repo,
-    scan_directory, DiffStat,
+    cleanup_temp, clone_to_temp, extract_to_temp, get_default_branch, get_diff_files, get_git_head,
+    is_git_repo, read_source, scan_directory, scan_infra_files, set_git_timeout, DiffStat,
 };
+use agentlens::search::{EmbedderConfig, SearchFilter};
 use agentlens::types::{FileEntry, MemoryEntry, Symbol};
 use agentlens::Config;
 
 fn main() -> Result<()> {
     let args = Args::parse();
 
+    // Every command may shell out to git, so apply the timeout before dispatching
+    let config = match &args.config {
+        Some(path) => Config::load_from_path(path),
+        None => Config::load(&args.path),
+    };
+    if let Some(secs) = config.as_ref().and_then(|c| c.git_timeout_secs) {
+        set_git_timeout(std::time::Duration::from_secs(secs));
+    }
+
     match args.command.clone() {
         Some(Command::Update) => return run_update(),
-        Some(Command::Watch { debounce }) => {
+        Some(Command::Watch {
+            debounce,
+            index,
+            serve,
+            port,
+        }) => {
             let args = args.with_config();
-            return run_watch(&args, debounce);
+            if serve {
+                return run_watch_serve(&args, debounce, port);
+            }
+            return run_watch(&args, debounce, index);
         }
         Some(Command::Hooks { action }) => {
             let path = args.path.canonicalize().unwrap_or(args.path.clone());
@@ -68,16 +90,32 @@ fn main() -> Result<()> {
                 return execute_setup(&options, &path, &output_str);
             }
         }
-        Some(Command::Serve { mcp, port }) => {
-            if !mcp && port.is_none() {
+        Some(Command::Serve {
+            mcp,
+            port,
+            store,
+            bind,
+        }) => {
+            if !mcp && !store && port.is_none() {
                 eprintln!("Usage: agentlens serve --mcp [--port PORT]");
+                eprintln!("       agentlens serve --store [--port PORT] [--bind ADDR]");
                 eprintln!("  --mcp        Run in MCP mode (stdio transport)");
                 eprintln!("  --port PORT  Use HTTP/SSE transport on specified port");
+                eprintln!("  --store      Serve the search index for `search --remote`");
                 std::process::exit(1);
             }
             let args = args.with_config();
             let work_path = args.path.canonicalize().unwrap_or(args.path.clone());
             let runtime = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+            if store {
+                let output_str = args.output.to_string_lossy().to_string();
+                return runtime.block_on(run_store_server(
+                    &work_path,
+                    &output_str,
+                    &bind,
+                    port.unwrap_or(7879),
+                ));
+            }
             return runtime.block_on(async {
                 if let Some(p) = port {
                     run_mcp_http_server(&args, &work_path, p).await
@@ -113,33 +151,190 @@ fn main() -> Result<()> {
         }
         Some(Command::Index {
             action,
+            archive,
             force,
+            since,
             prune,
+            prune_threshold,
+            force_prune,
+            hotness,
+            hotness_commits,
+            max_chunks_per_file,
+            checkpoint_interval,
+            visibility,
+            module_summaries,
+            include_ext,
+            collection,
+            model,
+            embed_endpoint,
+            embed_profile,
         }) => {
+            let embedder_config = embedder_config(
+                config.as_ref(),
+                embed_profile.as_deref(),
+                model,
+                embed_endpoint,
+            )?;
             let path = args.path.canonicalize().unwrap_or(args.path.clone());
             let output_str = args.output.to_string_lossy().to_string();
             let runtime = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
             return runtime.block_on(async {
                 match action {
-                    Some(IndexAction::Status) => run_index_status(&path, &output_str).await,
+                    Some(IndexAction::Status { json }) => {
+                        run_index_status(&path, &output_str, json).await
+                    }
                     Some(IndexAction::Clear) => run_index_clear(&path, &output_str).await,
-                    None => run_index(&path, force, prune, &output_str, args.verbose > 0).await,
+                    None => {
+                        let hotness_commits = hotness.then_some(hotness_commits);
+                        let (temp_dir, source_root) = match &archive {
+                            Some(archive) => {
+                                eprintln!("Extracting archive: {}", archive);
+                                let (temp, root) = extract_to_temp(archive).await?;
+                                (Some(temp), root)
+                            }
+                            None => (None, path.clone()),
+                        };
+                        let result = run_index(
+                            &source_root,
+                            &path,
+                            &embedder_config,
+                            force,
+                            since.as_deref(),
+                            prune,
+                            prune_threshold,
+                            force_prune,
+                            hotness_commits,
+                            max_chunks_per_file,
+                            checkpoint_interval,
+                            visibility,
+                            module_summaries,
+                            &include_ext,
+                            collection.as_deref(),
+                            &output_str,
+                            args.verbose > 0,
+                        )
+                        .await;
+                        if let Some(temp) = temp_dir {
+                            cleanup_temp(&temp);
+                        }
+                        result
+                    }
                 }
             });
         }
+        Some(Command::Schema) => {
+            println!("{}", JsonOutput::json_schema());
+            return Ok(());
+        }
         Some(Command::Search {
             query,
+            batch,
             limit,
             hybrid,
             json,
+            format,
+            snippet_lines,
+            snippet_chars,
+            streaming,
+            grep,
+            facets,
+            explain,
+            reindex_on_mismatch,
+            rerank_by_path_affinity,
+            focus,
+            path_prefix,
+            chunk_types,
+            review,
+            remote,
+            model,
+            embed_endpoint,
+            embed_profile,
         }) => {
+            let embedder_config = embedder_config(
+                config.as_ref(),
+                embed_profile.as_deref(),
+                model,
+                embed_endpoint,
+            )?;
             let path = args.path.canonicalize().unwrap_or(args.path.clone());
             let output_str = args.output.to_string_lossy().to_string();
+            let format = if json { SearchFormat::Json } else { format };
+            let queries = match &batch {
+                Some(file) => {
+                    if matches!(
+                        format,
+                        SearchFormat::Json | SearchFormat::Csv | SearchFormat::Yaml
+                    ) {
+                        anyhow::bail!("--batch supports --format human or jsonl");
+                    }
+                    read_batch_queries(file)?
+                }
+                None => vec![query.unwrap_or_default()],
+            };
+            let focus = focus.or_else(|| {
+                rerank_by_path_affinity
+                    .then(|| std::env::current_dir().ok())
+                    .flatten()
+                    .and_then(|cwd| cwd.canonicalize().ok())
+                    .and_then(|cwd| {
+                        cwd.strip_prefix(&path)
+                            .ok()
+                            .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+                    })
+            });
             let runtime = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
             return runtime.block_on(async {
-                run_search(&path, &query, limit, hybrid, json, &output_str).await
+                if let Some(base_ref) = review {
+                    return run_review(
+                        &path,
+                        &embedder_config,
+                        &base_ref,
+                        limit,
+                        format,
+                        &output_str,
+                    )
+                    .await;
+                }
+                run_search(
+                    &path,
+                    &embedder_config,
+                    &queries,
+                    limit,
+                    hybrid,
+                    streaming,
+                    grep,
+                    reindex_on_mismatch,
+                    facets,
+                    explain,
+                    focus.as_deref(),
+                    SearchFilter {
+                        path_prefix,
+                        chunk_types: (!chunk_types.is_empty()).then_some(chunk_types),
+                        ..SearchFilter::default()
+                    },
+                    format,
+                    SnippetSize {
+                        lines: snippet_lines,
+                        chars: snippet_chars,
+                    },
+                    remote.as_deref(),
+                    &output_str,
+                    args.quiet,
+                )
+                .await
             });
         }
+        Some(Command::SimilarFiles { file, limit, json }) => {
+            let path = args.path.canonicalize().unwrap_or(args.path.clone());
+            let output_str = args.output.to_string_lossy().to_string();
+            let runtime = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+            return runtime.block_on(run_similar_files(&path, &file, limit, json, &output_str));
+        }
+        Some(Command::ExplainChunking { file, json }) => {
+            let path = args.path.canonicalize().unwrap_or(args.path.clone());
+            let output_str = args.output.to_string_lossy().to_string();
+            return run_explain_chunking(&path, &file, json, &output_str);
+        }
         None => {}
     }
 
@@ -151,21 +346,22 @@ fn main() -> Result<()> {
 
     let (work_path, temp_dir) = if args.is_remote() {
         let url = args.path.to_string_lossy().to_string();
-        if args.verbosity() > 0 && !args.json {
+        if args.verbosity() > 0 && args.output_format().is_none() {
             eprintln!("Cloning remote repository: {}", url);
         }
-        let temp = clone_to_temp(&url).context("Failed to clone remote repository")?;
+        let temp = clone_to_temp(&url, args.ref_name.as_deref())
+            .context("Failed to clone remote repository")?;
         (temp.clone(), Some(temp))
     } else {
         (args.path.clone(), None)
     };
 
     if args.check {
-        let exit_code = run_check(&args, &work_path)?;
+        let exit_code = run_check(&args, &work_path);
         if let Some(ref temp) = temp_dir {
             cleanup_temp(temp);
         }
-        std::process::exit(exit_code);
+        std::process::exit(exit_code?);
     }
 
     let result = run_analysis(&args, &work_path);
@@ -177,8 +373,27 @@ fn main() -> Result<()> {
     result
 }
 
+/// Embedder settings for `index` and `search`: the defaults, or the config's
+/// `[search.embedder]` with the named profile applied, then the CLI overrides
+fn embedder_config(
+    config: Option<&Config>,
+    profile: Option<&str>,
+    model: Option<String>,
+    endpoint: Option<String>,
+) -> Result<EmbedderConfig> {
+    let base = match profile {
+        Some(name) => config
+            .and_then(|c| c.search.as_ref())
+            .map(|s| s.embedder.to_search_config())
+            .unwrap_or_default()
+            .with_profile(name)?,
+        None => EmbedderConfig::default(),
+    };
+    Ok(base.with_overrides(model, endpoint))
+}
+
 fn run_analysis(args: &Args, work_path: &std::path::Path) -> Result<()> {
-    if args.verbosity() > 0 && !args.json {
+    if args.verbosity() > 0 && args.output_format().is_none() {
         eprintln!("Scanning: {}", work_path.display());
     }
 
@@ -193,7 +408,7 @@ fn run_analysis(args: &Args, work_path: &std::path::Path) -> Result<()> {
                 .or_else(|| get_default_branch(work_path))
                 .unwrap_or_else(|| "main".to_string());
 
-            if args.verbosity() > 0 && !args.json {
+            if args.verbosity() > 0 && args.output_format().is_none() {
                 eprintln!("  Diff mode: comparing against {}", base_ref_owned);
             }
             get_diff_files(work_path, &base_ref_owned)
@@ -224,28 +439,40 @@ fn run_analysis(args: &Args, work_path: &std::path::Path) -> Result<()> {
         files
     };
 
-    if args.verbosity() > 0 && !args.json {
+    if args.verbosity() > 0 && args.output_format().is_none() {
         eprintln!("  Files scanned: {}", files.len());
     }
 
+    let output_path = if args.output.is_absolute() {
+        args.output.clone()
+    } else {
+        work_path.join(&args.output)
+    };
+
+    let symbol_filter = SymbolFilter::load(&output_path);
+    let markers = args.memory_markers();
     let mut all_memory: Vec<MemoryEntry> = Vec::new();
     let mut all_symbols: HashMap<String, Vec<Symbol>> = HashMap::new();
     let mut large_file_symbols: Vec<(FileEntry, Vec<Symbol>)> = Vec::new();
     let mut file_graph = FileGraph::new();
 
     for file in &files {
-        let content = match fs::read_to_string(&file.path) {
+        let content = match read_source(&file.path) {
             Ok(c) => c,
             Err(_) => continue,
         };
 
-        let memory_entries = extract_memory_markers(&content, &file.relative_path);
+        let memory_entries = extract_memory_markers(&content, &file.relative_path, &markers);
         all_memory.extend(memory_entries);
 
-        let imports = extract_imports(file, &content);
+        let imports = if args.public_imports {
+            extract_public_imports(file, &content)
+        } else {
+            extract_imports(file, &content)
+        };
         file_graph.add_file(&file.relative_path, imports);
 
-        let symbols = extract_symbols(file, &content);
+        let symbols = symbol_filter.apply(extract_symbols(file, &content));
         all_symbols.insert(file.relative_path.clone(), symbols.clone());
 
         if file.is_large {
@@ -253,7 +480,9 @@ fn run_analysis(args: &Args, work_path: &std::path::Path) -> Result<()> {
         }
     }
 
-    if args.verbosity() > 0 && !args.json {
+    all_memory.retain(|m| m.severity >= args.min_severity);
+
+    if args.verbosity() > 0 && args.output_format().is_none() {
         eprintln!(
             "  Large files (>{} lines): {}",
             args.threshold,
@@ -263,40 +492,50 @@ fn run_analysis(args: &Args, work_path: &std::path::Path) -> Result<()> {
     }
 
     let entry_points = detect_entry_points(&files);
-    let hub_files = file_graph.hub_files();
+    if args.verbosity() > 0 && args.output_format().is_none() {
+        eprintln!(
+            "  Hub files (3+ importers): {}",
+            file_graph.hub_files().len()
+        );
+    }
+
+    let unused_symbols = args
+        .dead_code
+        .then(|| find_unused_symbols(&file_graph, &all_symbols, &entry_points));
 
-    if args.verbosity() > 0 && !args.json {
-        eprintln!("  Hub files (3+ importers): {}", hub_files.len());
+    if let Some(ref unused) = unused_symbols {
+        if args.verbosity() > 0 && args.output_format().is_none() {
+            eprintln!("  Possibly unused symbols: {}", unused.len());
+        }
     }
 
+    let env_vars = args.env_scan.then(|| scan_env_vars(&files));
+
     let diff_base_ref = args
         .diff
         .clone()
         .or_else(|| get_default_branch(work_path))
         .unwrap_or_else(|| "main".to_string());
 
-    let modules = detect_modules(&files);
+    let modules = detect_project_modules(work_path, &files);
 
-    if args.json {
+    if let Some(format) = args.output_format() {
         return run_json_output(
+            format,
             work_path,
             &files,
             &modules,
             &large_file_symbols,
             &all_memory,
             &entry_points,
-            &hub_files,
+            &rank_hubs(&file_graph, &modules),
             diff_stats.as_ref(),
             &diff_base_ref,
+            unused_symbols,
+            env_vars,
         );
     }
 
-    let output_path = if args.output.is_absolute() {
-        args.output.clone()
-    } else {
-        work_path.join(&args.output)
-    };
-
     run_hierarchical_output(
         args,
         work_path,
@@ -306,26 +545,32 @@ fn run_analysis(args: &Args, work_path: &std::path::Path) -> Result<()> {
         &all_memory,
         &file_graph,
         &entry_points,
-        &hub_files,
+        unused_symbols.as_deref(),
+        env_vars.as_deref(),
     )
 }
 
 #[allow(clippy::too_many_arguments)]
 fn run_json_output(
+    format: OutputFormat,
     work_path: &std::path::Path,
     files: &[FileEntry],
     modules: &[ModuleInfo],
     large_file_symbols: &[(FileEntry, Vec<Symbol>)],
     all_memory: &[MemoryEntry],
     entry_points: &[String],
-    hub_files: &[(String, usize)],
+    hub_files: &[HubFile],
     diff_stats: Option<&Vec<DiffStat>>,
     diff_base_ref: &str,
+    unused_symbols: Option<Vec<UnusedSymbol>>,
+    env_vars: Option<Vec<EnvVar>>,
 ) -> Result<()> {
     let critical_files = get_critical_files(all_memory);
     let module_outputs: Vec<ModuleOutput> = modules
         .iter()
-        .map(|m| ModuleOutput::from_module_info(m, all_memory, large_file_symbols, hub_files))
+        .map(|m| {
+            ModuleOutput::from_module_info(m, files, all_memory, large_file_symbols, hub_files)
+        })
         .collect();
 
     let json_output = JsonOutput {
@@ -337,6 +582,7 @@ fn run_json_output(
             large_files_count: large_file_symbols.len(),
             memory_markers_count: all_memory.len(),
             modules_count: modules.len(),
+            profile: detect_project(work_path, files),
         },
         modules: module_outputs,
         files: files.to_vec(),
@@ -358,19 +604,22 @@ fn run_json_output(
                 high_priority_markers: *count,
             })
             .collect(),
-        hub_files: hub_files
-            .iter()
-            .map(|(path, count)| HubFile {
-                path: path.clone(),
-                imported_by: *count,
-            })
-            .collect(),
+        hub_files: hub_files.to_vec(),
         diff: diff_stats.map(|stats| DiffInfo {
             base_ref: diff_base_ref.to_string(),
             files: stats.clone(),
         }),
+        dead_code: unused_symbols,
+        env_vars,
     };
-    println!("{}", json_output.to_json());
+    match format {
+        OutputFormat::Json => println!("{}", json_output.to_json()),
+        OutputFormat::Yaml => print!("{}", json_output.to_yaml()),
+        OutputFormat::Jsonl => {
+            let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+            emit_jsonl(&mut out, &json_output)?;
+        }
+    }
     Ok(())
 }
 
@@ -384,9 +633,10 @@ fn run_hierarchical_output(
     all_memory: &[MemoryEntry],
     file_graph: &FileGraph,
     entry_points: &[String],
-    hub_files: &[(String, usize)],
+    unused_symbols: Option<&[UnusedSymbol]>,
+    env_vars: Option<&[EnvVar]>,
 ) -> Result<()> {
-    let modules = detect_modules(files);
+    let modules = detect_project_modules(work_path, files);
 
     if args.verbosity() > 0 {
         eprintln!("  Modules detected: {}", modules.len());
@@ -436,26 +686,37 @@ fn run_hierarchical_output(
         );
     }
 
-    let hub_module_slugs: Vec<(String, usize)> = hub_files
+    // Modules holding a file imported from other modules
+    let hub_modules: Vec<(String, usize)> = rank_hubs(file_graph, &modules)
         .iter()
-        .filter_map(|(path, count)| {
+        .filter(|hub| hub.cross_module_importers > 0)
+        .filter_map(|hub| {
             modules
                 .iter()
-                .find(|m| m.files.contains(path))
-                .map(|m| (m.slug.clone(), *count))
+                .find(|m| m.files.contains(&hub.path))
+                .map(|m| (m.path.clone(), hub.cross_module_importers))
         })
         .collect();
 
+    let infra_files = scan_infra_files(
+        work_path,
+        !args.no_gitignore,
+        (args.depth > 0).then_some(args.depth),
+    )
+    .unwrap_or_default();
+    let project_profile = detect_project(work_path, files);
     let index_config = IndexConfig {
         modules: &modules,
         memory_entries: all_memory,
         entry_points,
-        hub_modules: &hub_module_slugs,
+        hub_modules: &hub_modules,
         project_name: None,
+        project_profile: Some(&project_profile),
         file_graph: Some(file_graph),
+        infra_files: &infra_files,
     };
     let index_md = generate_index_md(&index_config);
-    let mut output = HierarchicalOutput::new(index_md);
+    let mut output = HierarchicalOutput::new(index_md).with_line_ending(args.line_ending);
 
     let warning_count = all_memory
         .iter()
@@ -474,6 +735,14 @@ fn run_hierarchical_output(
     let agent_md = generate_agent_md(&agent_config);
     output.set_agent_md(agent_md);
 
+    if let Some(unused) = unused_symbols {
+        output.set_dead_code(generate_dead_code(unused));
+    }
+
+    if let Some(vars) = env_vars {
+        output.set_env_vars(generate_env_vars(vars));
+    }
+
     let large_file_symbols: Vec<(FileEntry, Vec<Symbol>)> = files
         .iter()
         .filter(|f| f.is_large)
@@ -484,6 +753,8 @@ fn run_hierarchical_output(
         })
         .collect();
 
+    let complexity_thresholds = args.complexity_thresholds();
+    let mermaid = args.mermaid_config();
     for module in &modules_to_regenerate {
         let module_memory: Vec<_> = all_memory
             .iter()
@@ -497,6 +768,8 @@ fn run_hierarchical_output(
             &large_file_symbols,
             &module_memory,
             file_graph,
+            &infra_files,
+            &mermaid,
         );
 
         output.add_module(module.slug.clone(), content);
@@ -507,7 +780,7 @@ fn run_hierarchical_output(
                 None => continue,
             };
             let symbols = all_symbols.get(file_path).map_or(&[][..], |v| v);
-            if is_complex_file(file, symbols, args.complex_threshold, 50) {
+            if is_complex_file(file, symbols, &complexity_thresholds) {
                 let file_memory: Vec<_> = all_memory
                     .iter()
                     .filter(|m| &m.source_file == file_path)
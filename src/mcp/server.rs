@@ -14,16 +14,25 @@ use serde_json::json;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{OnceCell, RwLock};
 
-use crate::analyze::extract_symbols;
+use crate::analyze::{extract_symbols, SymbolFilter};
 use crate::cli::check::check_staleness;
 use crate::cli::Args;
 use crate::config::Config;
 use crate::scan::scan_directory;
-use crate::search::{create_embedder, EmbedderConfig, GobStore, Searcher};
+use crate::search::{
+    create_embedder, create_store, index_path, Embedder, ResultCache, SearchAnalytics,
+    SearchFilter, Searcher, VectorStore,
+};
 use crate::types::{Symbol, Visibility};
 
+/// Distinct (query, limit, mode) results kept per index version
+const SEARCH_CACHE_CAPACITY: usize = 64;
+
+/// Store and embedder shared by every search call
+type SearchBackend = (Arc<dyn VectorStore>, Arc<dyn Embedder>);
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct GetModuleParams {
     #[schemars(description = "Module slug (e.g., 'src-analyze' or 'src-cli')")]
@@ -48,6 +57,8 @@ pub struct SemanticSearchParams {
         description = "Enable hybrid search combining vector and text matching (default: true)"
     )]
     pub hybrid: Option<bool>,
+    #[schemars(description = "Index collection to search (default: the global collection)")]
+    pub collection: Option<String>,
 }
 
 #[derive(Clone)]
@@ -55,6 +66,8 @@ pub struct AgentlensServer {
     work_path: Arc<PathBuf>,
     output_path: Arc<PathBuf>,
     args: Arc<RwLock<Args>>,
+    search_cache: Arc<ResultCache>,
+    search_backend: Arc<OnceCell<SearchBackend>>,
     tool_router: ToolRouter<Self>,
 }
 
@@ -65,6 +78,8 @@ impl AgentlensServer {
             work_path: Arc::new(work_path),
             output_path: Arc::new(output_path),
             args: Arc::new(RwLock::new(args)),
+            search_cache: Arc::new(ResultCache::new(SEARCH_CACHE_CAPACITY)),
+            search_backend: Arc::new(OnceCell::new()),
             tool_router: Self::tool_router(),
         }
     }
@@ -193,7 +208,8 @@ impl AgentlensServer {
                 let content = fs::read_to_string(&full_path)
                     .map_err(|e| McpError::internal_error(format!("Read failed: {}", e), None))?;
 
-                let symbols: Vec<Symbol> = extract_symbols(entry, &content);
+                let symbols: Vec<Symbol> =
+                    SymbolFilter::load(&self.output_path).apply(extract_symbols(entry, &content));
 
                 let outline = format_symbols_as_outline(file, &symbols);
                 Ok(CallToolResult::success(vec![Content::text(outline)]))
@@ -205,65 +221,158 @@ impl AgentlensServer {
         }
     }
 
-    #[tool(description = "Semantic search across the codebase using natural language queries")]
+    #[tool(description = "Search the codebase index using natural language queries")]
+    async fn search(
+        &self,
+        Parameters(params): Parameters<SemanticSearchParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.run_search(params).await
+    }
+
+    #[tool(description = "Semantic search across the codebase (same as `search`)")]
     async fn semantic_search(
         &self,
         Parameters(params): Parameters<SemanticSearchParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.run_search(params).await
+    }
+}
+
+impl AgentlensServer {
+    async fn run_search(&self, params: SemanticSearchParams) -> Result<CallToolResult, McpError> {
         let query = &params.query;
         let limit = params.limit.unwrap_or(10);
         let hybrid = params.hybrid.unwrap_or(true);
 
-        let config = Config::load(&self.work_path);
-        let search_config = config.and_then(|c| c.search).unwrap_or_default();
-
-        let embedder_config = EmbedderConfig {
-            provider: search_config.embedder.provider.clone(),
-            model: search_config.embedder.model.clone(),
-            endpoint: search_config.embedder.endpoint.clone(),
-            dimensions: search_config.embedder.dimensions,
-        };
-        let embedder = Arc::from(create_embedder(&embedder_config));
-
-        let index_path = self.output_path.join("index.json");
-        let store = Arc::new(GobStore::new(index_path));
-
-        let searcher = Searcher::new(store, embedder, hybrid, search_config.search.hybrid_k);
+        let mut searcher = self.build_searcher(hybrid).await?;
+        if let Some(collection) = params.collection {
+            searcher = searcher.with_collection(collection);
+        }
 
-        let results = searcher
-            .smart_search(query, limit)
+        let started = std::time::Instant::now();
+        let outcome = searcher
+            .search_or_keywords(query, limit, SearchFilter::default(), false)
             .await
             .map_err(|e| McpError::internal_error(format!("Search failed: {}", e), None))?;
+        if let Some(analytics) = self.search_analytics() {
+            analytics.record(query, outcome.mode, &outcome.results, started.elapsed());
+        }
+        let degraded = outcome.degraded();
+        let results = outcome.results;
 
         if results.is_empty() {
-            return Ok(CallToolResult::success(vec![Content::text(
-                "No results found. Make sure the index exists (run `agentlens index` first).",
-            )]));
+            let text = if degraded {
+                "No results found (keyword-only: embedding server unreachable)."
+            } else {
+                "No results found."
+            };
+            return Ok(CallToolResult::success(vec![Content::text(text)]));
         }
 
         let formatted: Vec<serde_json::Value> = results
             .iter()
             .map(|r| {
-                json!({
+                let mut entry = json!({
                     "file": r.chunk.file_path,
                     "score": format!("{:.3}", r.score),
                     "lines": format!("{}-{}", r.chunk.start_line, r.chunk.end_line),
                     "type": format!("{:?}", r.chunk.chunk_type),
                     "content_preview": r.chunk.content.chars().take(200).collect::<String>(),
-                })
+                });
+                // Point agents at the outline of the file that defines the symbol
+                if let (Some(name), Some(kind)) = (&r.chunk.symbol_name, r.chunk.symbol_kind) {
+                    entry["symbol"] = json!({
+                        "name": name,
+                        "kind": kind.to_string(),
+                        "outline": {"tool": "get_outline", "file": r.chunk.file_path},
+                    });
+                }
+                entry
             })
             .collect();
 
-        let response = json!({
+        let mut response = json!({
             "query": query,
             "result_count": results.len(),
             "results": formatted,
         });
+        if degraded {
+            response["warning"] = json!("Embedding server unreachable; results are keyword-only");
+        }
 
         Ok(CallToolResult::success(vec![Content::text(
             serde_json::to_string_pretty(&response).unwrap_or_default(),
         )]))
     }
+
+    /// Searcher over the shared store and embedder, opened on first use.
+    /// Fails with an MCP error when no index has been built yet.
+    async fn build_searcher(&self, hybrid: bool) -> Result<Searcher, McpError> {
+        let search_config = Config::load(&self.work_path)
+            .and_then(|c| c.search)
+            .unwrap_or_default();
+
+        let index = index_path(&self.output_path, &search_config.store_backend);
+        if !index.exists() {
+            return Err(McpError::invalid_request(
+                "No search index found. Run `agentlens index` first.",
+                Some(json!({ "index": index.display().to_string() })),
+            ));
+        }
+
+        let (store, embedder) = self
+            .search_backend
+            .get_or_try_init(|| async {
+                let embedder_config = search_config.embedder.to_search_config();
+                let embedder: Arc<dyn Embedder> = Arc::from(create_embedder(&embedder_config));
+                let store: Arc<dyn VectorStore> = Arc::from(create_store(
+                    &self.output_path,
+                    &search_config.store_backend,
+                )?);
+                anyhow::Ok((store, embedder))
+            })
+            .await
+            .map_err(|e| McpError::internal_error(format!("Failed to open index: {}", e), None))?;
+
+        Ok(Searcher::new(
+            Arc::clone(store),
+            Arc::clone(embedder),
+            hybrid,
+            search_config.search.hybrid_k,
+        )
+        .with_hybrid_weights(
+            search_config.search.hybrid_vector_weight,
+            search_config.search.hybrid_text_weight,
+        )
+        .with_cache(Arc::clone(&self.search_cache)))
+    }
+
+    /// Search analytics logger, when `[search.search] analytics` is enabled
+    fn search_analytics(&self) -> Option<SearchAnalytics> {
+        let enabled = Config::load(&self.work_path)
+            .and_then(|c| c.search)
+            .is_some_and(|s| s.search.analytics);
+        SearchAnalytics::when_enabled(&self.output_path, enabled)
+    }
+
+    /// Prime the embedder and page cache so the first semantic_search call is fast.
+    /// Does nothing when no index has been built.
+    pub async fn warmup_search(&self) {
+        let backend = Config::load(&self.work_path)
+            .and_then(|c| c.search)
+            .unwrap_or_default()
+            .store_backend;
+        if !index_path(&self.output_path, &backend).exists() {
+            return;
+        }
+        let warmup = match self.build_searcher(false).await {
+            Ok(searcher) => searcher.warmup().await.map_err(|e| e.to_string()),
+            Err(e) => Err(e.message.to_string()),
+        };
+        if let Err(e) = warmup {
+            eprintln!("Search warmup skipped: {}", e);
+        }
+    }
 }
 
 fn format_symbols_as_outline(file_path: &str, symbols: &[Symbol]) -> String {
@@ -349,3 +458,84 @@ impl ServerHandler for AgentlensServer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ChunkingConfig;
+    use crate::search::{GobStore, Indexer};
+    use crate::test_support::FixedEmbedder;
+    use clap::Parser;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_search_without_index_explains_how_to_build_one() {
+        let temp = TempDir::new().unwrap();
+        let server = AgentlensServer::new(
+            temp.path().to_path_buf(),
+            temp.path().join(".agentlens"),
+            Args::parse_from(["agentlens"]),
+        );
+
+        let err = server
+            .run_search(SemanticSearchParams {
+                query: "error handling".to_string(),
+                limit: None,
+                hybrid: None,
+                collection: None,
+            })
+            .await
+            .unwrap_err();
+
+        assert!(err.message.contains("agentlens index"));
+        assert!(server.search_backend.get().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_search_is_scoped_to_the_indexed_collection() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let store: Arc<dyn VectorStore> =
+            Arc::new(GobStore::new(root.join(".agentlens/index.json")));
+        let embedder: Arc<dyn Embedder> = Arc::new(FixedEmbedder::new(vec![1.0, 0.0]));
+
+        // What `agentlens index --collection` runs, once per namespace
+        for (dir, collection) in [("docs", Some("docs")), ("src", None)] {
+            let source = root.join(dir);
+            std::fs::create_dir_all(&source).unwrap();
+            std::fs::write(source.join(format!("{}.rs", dir)), "fn retry() {}\n").unwrap();
+            let mut indexer = Indexer::new(
+                Arc::clone(&store),
+                Arc::clone(&embedder),
+                &ChunkingConfig::default(),
+            );
+            if let Some(collection) = collection {
+                indexer = indexer.with_collection(collection);
+            }
+            indexer.index_all(&source, true, false).await.unwrap();
+        }
+
+        let server = AgentlensServer::new(
+            root.to_path_buf(),
+            root.join(".agentlens"),
+            Args::parse_from(["agentlens"]),
+        );
+        assert!(server.search_backend.set((store, embedder)).is_ok());
+
+        for (collection, file) in [(Some("docs"), "docs.rs"), (None, "src.rs")] {
+            let result = server
+                .run_search(SemanticSearchParams {
+                    query: "retry".to_string(),
+                    limit: None,
+                    hybrid: Some(false),
+                    collection: collection.map(str::to_string),
+                })
+                .await
+                .unwrap();
+            let text = &result.content[0].as_text().unwrap().text;
+            let response: serde_json::Value = serde_json::from_str(text).unwrap();
+            assert_eq!(response["result_count"], 1);
+            assert_eq!(response["results"][0]["file"], file);
+        }
+    }
+}
@@ -1,24 +1,29 @@
 use anyhow::{Context, Result};
 use std::collections::HashMap;
-use std::fs;
 use std::path::Path;
 
 use crate::analyze::{
-    detect_modules, extract_imports, extract_memory_markers, extract_symbols, FileGraph,
+    detect_project, detect_project_modules, extract_imports, extract_memory_markers,
+    extract_public_imports, extract_symbols, find_unused_symbols, scan_env_vars, EnvVar, FileGraph,
+    MemoryMarkers, SymbolFilter, UnusedSymbol,
 };
 use crate::cli::Args;
 use crate::emit::{
     calculate_module_state, current_timestamp, write_hierarchical, HierarchicalOutput, Manifest,
 };
 use crate::generate::{
-    detect_entry_points, file_path_to_slug, generate_agent_md, generate_file_doc,
-    generate_index_md, generate_module_content, is_complex_file, AgentConfig, IndexConfig,
+    detect_entry_points, file_path_to_slug, generate_agent_md, generate_dead_code,
+    generate_env_vars, generate_file_doc, generate_index_md, generate_module_content,
+    is_complex_file, rank_hubs, AgentConfig, IndexConfig,
+};
+use crate::scan::{
+    get_default_branch, get_diff_files, get_git_head, is_git_repo, read_source, scan_directory,
+    scan_infra_files,
 };
-use crate::scan::{get_default_branch, get_diff_files, get_git_head, is_git_repo, scan_directory};
 use crate::types::{FileEntry, MemoryEntry, Symbol};
 
 pub fn run_analysis(args: &Args, work_path: &Path) -> Result<()> {
-    if args.verbosity() > 0 && !args.json {
+    if args.verbosity() > 0 && args.output_format().is_none() {
         eprintln!("Scanning: {}", work_path.display());
     }
 
@@ -42,13 +47,26 @@ pub fn run_analysis(args: &Args, work_path: &Path) -> Result<()> {
         files
     };
 
-    if args.verbosity() > 0 && !args.json {
+    if args.verbosity() > 0 && args.output_format().is_none() {
         eprintln!("  Files scanned: {}", files.len());
     }
 
-    let (all_memory, all_symbols, large_file_symbols, file_graph) = analyze_files(&files)?;
+    let output_path = if args.output.is_absolute() {
+        args.output.clone()
+    } else {
+        work_path.join(&args.output)
+    };
 
-    if args.verbosity() > 0 && !args.json {
+    let symbol_filter = SymbolFilter::load(&output_path);
+    let (mut all_memory, all_symbols, large_file_symbols, file_graph) = analyze_files(
+        &files,
+        &symbol_filter,
+        args.public_imports,
+        &args.memory_markers(),
+    )?;
+    all_memory.retain(|m| m.severity >= args.min_severity);
+
+    if args.verbosity() > 0 && args.output_format().is_none() {
         eprintln!(
             "  Large files (>{} lines): {}",
             args.threshold,
@@ -58,17 +76,24 @@ pub fn run_analysis(args: &Args, work_path: &Path) -> Result<()> {
     }
 
     let entry_points = detect_entry_points(&files);
-    let hub_files = file_graph.hub_files();
+    if args.verbosity() > 0 && args.output_format().is_none() {
+        eprintln!(
+            "  Hub files (3+ importers): {}",
+            file_graph.hub_files().len()
+        );
+    }
+
+    let unused_symbols = args
+        .dead_code
+        .then(|| find_unused_symbols(&file_graph, &all_symbols, &entry_points));
 
-    if args.verbosity() > 0 && !args.json {
-        eprintln!("  Hub files (3+ importers): {}", hub_files.len());
+    if let Some(ref unused) = unused_symbols {
+        if args.verbosity() > 0 && args.output_format().is_none() {
+            eprintln!("  Possibly unused symbols: {}", unused.len());
+        }
     }
 
-    let output_path = if args.output.is_absolute() {
-        args.output.clone()
-    } else {
-        work_path.join(&args.output)
-    };
+    let env_vars = args.env_scan.then(|| scan_env_vars(&files));
 
     run_hierarchical_output(
         args,
@@ -79,7 +104,8 @@ pub fn run_analysis(args: &Args, work_path: &Path) -> Result<()> {
         &all_memory,
         &file_graph,
         &entry_points,
-        &hub_files,
+        unused_symbols.as_deref(),
+        env_vars.as_deref(),
     )
 }
 
@@ -97,7 +123,7 @@ fn get_diff_file_set(args: &Args, work_path: &Path) -> Option<std::collections::
         .or_else(|| get_default_branch(work_path))
         .unwrap_or_else(|| "main".to_string());
 
-    if args.verbosity() > 0 && !args.json {
+    if args.verbosity() > 0 && args.output_format().is_none() {
         eprintln!("  Diff mode: comparing against {}", base_ref);
     }
 
@@ -111,25 +137,34 @@ type AnalysisResult = (
     FileGraph,
 );
 
-fn analyze_files(files: &[FileEntry]) -> Result<AnalysisResult> {
+fn analyze_files(
+    files: &[FileEntry],
+    symbol_filter: &SymbolFilter,
+    public_imports: bool,
+    markers: &MemoryMarkers,
+) -> Result<AnalysisResult> {
     let mut all_memory: Vec<MemoryEntry> = Vec::new();
     let mut all_symbols: HashMap<String, Vec<Symbol>> = HashMap::new();
     let mut large_file_symbols: Vec<(FileEntry, Vec<Symbol>)> = Vec::new();
     let mut file_graph = FileGraph::new();
 
     for file in files {
-        let content = match fs::read_to_string(&file.path) {
+        let content = match read_source(&file.path) {
             Ok(c) => c,
             Err(_) => continue,
         };
 
-        let memory_entries = extract_memory_markers(&content, &file.relative_path);
+        let memory_entries = extract_memory_markers(&content, &file.relative_path, markers);
         all_memory.extend(memory_entries);
 
-        let imports = extract_imports(file, &content);
+        let imports = if public_imports {
+            extract_public_imports(file, &content)
+        } else {
+            extract_imports(file, &content)
+        };
         file_graph.add_file(&file.relative_path, imports);
 
-        let symbols = extract_symbols(file, &content);
+        let symbols = symbol_filter.apply(extract_symbols(file, &content));
         all_symbols.insert(file.relative_path.clone(), symbols.clone());
 
         if file.is_large {
@@ -150,9 +185,10 @@ fn run_hierarchical_output(
     all_memory: &[MemoryEntry],
     file_graph: &FileGraph,
     entry_points: &[String],
-    hub_files: &[(String, usize)],
+    unused_symbols: Option<&[UnusedSymbol]>,
+    env_vars: Option<&[EnvVar]>,
 ) -> Result<()> {
-    let modules = detect_modules(files);
+    let modules = detect_project_modules(work_path, files);
 
     if args.verbosity() > 0 {
         eprintln!("  Modules detected: {}", modules.len());
@@ -202,26 +238,37 @@ fn run_hierarchical_output(
         );
     }
 
-    let hub_module_slugs: Vec<(String, usize)> = hub_files
+    // Modules holding a file imported from other modules
+    let hub_modules: Vec<(String, usize)> = rank_hubs(file_graph, &modules)
         .iter()
-        .filter_map(|(path, count)| {
+        .filter(|hub| hub.cross_module_importers > 0)
+        .filter_map(|hub| {
             modules
                 .iter()
-                .find(|m| m.files.contains(path))
-                .map(|m| (m.slug.clone(), *count))
+                .find(|m| m.files.contains(&hub.path))
+                .map(|m| (m.path.clone(), hub.cross_module_importers))
         })
         .collect();
 
+    let infra_files = scan_infra_files(
+        work_path,
+        !args.no_gitignore,
+        (args.depth > 0).then_some(args.depth),
+    )
+    .unwrap_or_default();
+    let project_profile = detect_project(work_path, files);
     let index_config = IndexConfig {
         modules: &modules,
         memory_entries: all_memory,
         entry_points,
-        hub_modules: &hub_module_slugs,
+        hub_modules: &hub_modules,
         project_name: None,
+        project_profile: Some(&project_profile),
         file_graph: Some(file_graph),
+        infra_files: &infra_files,
     };
     let index_md = generate_index_md(&index_config);
-    let mut output = HierarchicalOutput::new(index_md);
+    let mut output = HierarchicalOutput::new(index_md).with_line_ending(args.line_ending);
 
     let warning_count = all_memory
         .iter()
@@ -240,6 +287,14 @@ fn run_hierarchical_output(
     let agent_md = generate_agent_md(&agent_config);
     output.set_agent_md(agent_md);
 
+    if let Some(unused) = unused_symbols {
+        output.set_dead_code(generate_dead_code(unused));
+    }
+
+    if let Some(vars) = env_vars {
+        output.set_env_vars(generate_env_vars(vars));
+    }
+
     let large_file_symbols: Vec<(FileEntry, Vec<Symbol>)> = files
         .iter()
         .filter(|f| f.is_large)
@@ -250,6 +305,8 @@ fn run_hierarchical_output(
         })
         .collect();
 
+    let complexity_thresholds = args.complexity_thresholds();
+    let mermaid = args.mermaid_config();
     for module in &modules_to_regenerate {
         let module_memory: Vec<_> = all_memory
             .iter()
@@ -263,6 +320,8 @@ fn run_hierarchical_output(
             &large_file_symbols,
             &module_memory,
             file_graph,
+            &infra_files,
+            &mermaid,
         );
 
         output.add_module(module.slug.clone(), content);
@@ -273,7 +332,7 @@ fn run_hierarchical_output(
                 None => continue,
             };
             let symbols = all_symbols.get(file_path).map_or(&[][..], |v| v);
-            if is_complex_file(file, symbols, args.complex_threshold, 50) {
+            if is_complex_file(file, symbols, &complexity_thresholds) {
                 let file_memory: Vec<_> = all_memory
                     .iter()
                     .filter(|m| &m.source_file == file_path)
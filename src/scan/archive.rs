@@ -0,0 +1,346 @@
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use std::fs::{self, File};
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    TarGz,
+    Zip,
+}
+
+impl ArchiveKind {
+    /// Detect the archive type from a file name or URL
+    pub fn detect(source: &str) -> Option<Self> {
+        let name = source
+            .split(['?', '#'])
+            .next()
+            .unwrap_or(source)
+            .to_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveKind::TarGz)
+        } else if name.ends_with(".zip") {
+            Some(ArchiveKind::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether `source` (a file name or URL) names a `.tar.gz`/`.tgz`/`.zip` archive
+pub fn is_archive(source: &str) -> bool {
+    ArchiveKind::detect(source).is_some()
+}
+
+/// Fetch a local or `http(s)://` archive and extract it into a fresh temp
+/// directory. Returns `(temp_dir, root)`: remove `temp_dir` with
+/// `cleanup_temp` when done; `root` is the directory holding the sources.
+pub async fn extract_to_temp(source: &str) -> Result<(PathBuf, PathBuf)> {
+    let kind = ArchiveKind::detect(source).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unsupported archive type (expected .tar.gz, .tgz or .zip): {}",
+            source
+        )
+    })?;
+
+    let temp_dir = std::env::temp_dir().join(format!("agentlens-archive-{}", std::process::id()));
+    if temp_dir.exists() {
+        fs::remove_dir_all(&temp_dir)?;
+    }
+    fs::create_dir_all(&temp_dir).context("Failed to create temp directory")?;
+
+    match fetch_and_extract(source, kind, &temp_dir).await {
+        Ok(root) => Ok((temp_dir, root)),
+        Err(e) => {
+            let _ = fs::remove_dir_all(&temp_dir);
+            Err(e)
+        }
+    }
+}
+
+async fn fetch_and_extract(source: &str, kind: ArchiveKind, temp_dir: &Path) -> Result<PathBuf> {
+    let dest = temp_dir.join("source");
+    fs::create_dir_all(&dest)?;
+
+    if !(source.starts_with("http://") || source.starts_with("https://")) {
+        let file = File::open(source).with_context(|| format!("Failed to read {}", source))?;
+        return extract_archive(file, kind, &dest);
+    }
+
+    // Spool the download to disk chunk by chunk rather than holding it in memory
+    let mut response = reqwest::get(source)
+        .await
+        .and_then(|r| r.error_for_status())
+        .with_context(|| format!("Failed to download {}", source))?;
+    let download = temp_dir.join("download");
+    let mut file = File::create(&download)?;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .with_context(|| format!("Failed to download {}", source))?
+    {
+        file.write_all(&chunk)?;
+    }
+    drop(file);
+
+    let root = extract_archive(File::open(&download)?, kind, &dest);
+    let _ = fs::remove_file(&download);
+    root
+}
+
+/// Extract the archive in `reader` into `dest` and return the source root
+/// inside it. Entries that would escape `dest` are refused.
+pub fn extract_archive(
+    reader: impl Read + Seek,
+    kind: ArchiveKind,
+    dest: &Path,
+) -> Result<PathBuf> {
+    match kind {
+        ArchiveKind::TarGz => tar::Archive::new(GzDecoder::new(reader))
+            .unpack(dest)
+            .context("Failed to extract tarball")?,
+        ArchiveKind::Zip => super::zip::unpack(reader, dest).context("Failed to extract zip")?,
+    }
+
+    Ok(resolve_root(dest))
+}
+
+/// Descend through single top-level directories, as in `repo-1.2.0/src/...`
+fn resolve_root(dir: &Path) -> PathBuf {
+    let mut root = dir.to_path_buf();
+
+    loop {
+        let entries: Vec<PathBuf> = match fs::read_dir(&root) {
+            Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path()).collect(),
+            Err(_) => return root,
+        };
+        match entries.as_slice() {
+            [only] if only.is_dir() => root = only.clone(),
+            _ => return root,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ChunkingConfig;
+    use crate::search::{GobStore, Indexer, VectorStore};
+    use crate::test_support::FixedEmbedder;
+    use flate2::write::{DeflateEncoder, GzEncoder};
+    use flate2::{Compression, Crc};
+    use std::io::Cursor;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn tarball(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        for (path, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, content.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    /// Zip of `files`, deflating every other entry so both methods are covered
+    fn zip(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut directory = Vec::new();
+        for (i, (name, content)) in files.iter().enumerate() {
+            let (method, data) = if i % 2 == 0 {
+                let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(content.as_bytes()).unwrap();
+                (8u16, encoder.finish().unwrap())
+            } else {
+                (0u16, content.as_bytes().to_vec())
+            };
+            let mut crc = Crc::new();
+            crc.update(content.as_bytes());
+
+            // Fields shared by the local header and the central directory entry
+            let mut fields = Vec::new();
+            fields.extend_from_slice(&20u16.to_le_bytes()); // version needed
+            fields.extend_from_slice(&0u16.to_le_bytes()); // flags
+            fields.extend_from_slice(&method.to_le_bytes());
+            fields.extend_from_slice(&[0; 4]); // mod time and date
+            fields.extend_from_slice(&crc.sum().to_le_bytes());
+            fields.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            fields.extend_from_slice(&(content.len() as u32).to_le_bytes());
+            fields.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            fields.extend_from_slice(&0u16.to_le_bytes()); // extra length
+
+            directory.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+            directory.extend_from_slice(&20u16.to_le_bytes()); // version made by
+            directory.extend_from_slice(&fields);
+            directory.extend_from_slice(&[0; 6]); // comment length, disk, internal attributes
+            directory.extend_from_slice(&(0o100644u32 << 16).to_le_bytes());
+            directory.extend_from_slice(&(out.len() as u32).to_le_bytes());
+            directory.extend_from_slice(name.as_bytes());
+
+            out.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+            out.extend_from_slice(&fields);
+            out.extend_from_slice(name.as_bytes());
+            out.extend_from_slice(&data);
+        }
+
+        let directory_offset = out.len() as u32;
+        out.extend_from_slice(&directory);
+        out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        out.extend_from_slice(&[0; 4]); // disk numbers
+        out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+        out.extend_from_slice(&directory_offset.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        out
+    }
+
+    #[test]
+    fn test_detect_archive() {
+        assert!(is_archive("drop.tar.gz"));
+        assert!(is_archive("https://example.com/src.TGZ?token=1"));
+        assert!(is_archive("drop.zip"));
+        assert_eq!(ArchiveKind::detect("a.tgz"), Some(ArchiveKind::TarGz));
+        assert_eq!(ArchiveKind::detect("a.ZIP"), Some(ArchiveKind::Zip));
+        assert!(!is_archive("src/main.rs"));
+    }
+
+    #[tokio::test]
+    async fn test_extract_local_tarball_to_temp() {
+        let temp = TempDir::new().unwrap();
+        let archive = temp.path().join("drop.tgz");
+        fs::write(
+            &archive,
+            tarball(&[
+                ("repo/src/main.rs", "fn main() {}\n"),
+                ("repo/Cargo.toml", "[package]\n"),
+            ]),
+        )
+        .unwrap();
+
+        let (temp_dir, root) = extract_to_temp(archive.to_str().unwrap()).await.unwrap();
+        assert!(root.ends_with("repo"));
+        assert!(root.join("src/main.rs").is_file());
+        fs::remove_dir_all(temp_dir).unwrap();
+
+        assert!(extract_to_temp("drop.7z").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_index_extracted_tarball() {
+        let bytes = tarball(&[
+            (
+                "project-1.0/src/lib.rs",
+                "pub fn greet() {\n    println!(\"hi\");\n}\n",
+            ),
+            ("project-1.0/README.md", "# Project\n"),
+        ]);
+
+        let temp = TempDir::new().unwrap();
+        let root = extract_archive(Cursor::new(bytes), ArchiveKind::TarGz, temp.path()).unwrap();
+        assert_eq!(root, temp.path().join("project-1.0"));
+
+        let store_dir = TempDir::new().unwrap();
+        let store: Arc<dyn VectorStore> =
+            Arc::new(GobStore::new(store_dir.path().join("index.json")));
+        let result = Indexer::new(
+            Arc::clone(&store),
+            Arc::new(FixedEmbedder::new(vec![1.0, 0.0])),
+            &ChunkingConfig::default(),
+        )
+        .index_all(&root, false, false)
+        .await
+        .unwrap();
+
+        assert!(result.errors.is_empty());
+        let docs = store.list_documents(None).await.unwrap();
+        assert!(docs.contains(&"src/lib.rs".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_index_extracted_zip() {
+        let bytes = zip(&[
+            (
+                "project-1.0/src/lib.rs",
+                "pub fn greet() {\n    println!(\"hi\");\n}\n",
+            ),
+            ("project-1.0/README.md", "# Project\n"),
+            ("project-1.0/docs/", ""),
+        ]);
+
+        let temp = TempDir::new().unwrap();
+        let root = extract_archive(Cursor::new(bytes), ArchiveKind::Zip, temp.path()).unwrap();
+        assert_eq!(root, temp.path().join("project-1.0"));
+        assert!(root.join("docs").is_dir());
+
+        let store_dir = TempDir::new().unwrap();
+        let store: Arc<dyn VectorStore> =
+            Arc::new(GobStore::new(store_dir.path().join("index.json")));
+        let result = Indexer::new(
+            Arc::clone(&store),
+            Arc::new(FixedEmbedder::new(vec![1.0, 0.0])),
+            &ChunkingConfig::default(),
+        )
+        .index_all(&root, false, false)
+        .await
+        .unwrap();
+
+        assert!(result.errors.is_empty());
+        let docs = store.list_documents(None).await.unwrap();
+        assert!(docs.contains(&"src/lib.rs".to_string()));
+        assert!(docs.contains(&"README.md".to_string()));
+    }
+
+    #[test]
+    fn test_zip_entries_cannot_escape_dest() {
+        for name in [
+            "../evil.rs",
+            "src/../../evil.rs",
+            "/tmp/evil.rs",
+            "..\\evil.rs",
+        ] {
+            let temp = TempDir::new().unwrap();
+            let dest = temp.path().join("dest");
+            fs::create_dir(&dest).unwrap();
+            let bytes = zip(&[("ok.rs", "fn ok() {}\n"), (name, "fn evil() {}\n")]);
+
+            let error = extract_archive(Cursor::new(bytes), ArchiveKind::Zip, &dest).unwrap_err();
+            assert!(
+                format!("{:#}", error).contains("Refusing zip entry"),
+                "{:#}",
+                error
+            );
+            // Nothing is written once any entry is refused
+            assert!(!dest.join("ok.rs").exists());
+            assert!(!temp.path().join("evil.rs").exists());
+        }
+    }
+
+    #[test]
+    fn test_corrupt_zip_is_rejected() {
+        let mut bytes = zip(&[("a.rs", "fn a() {}\n"), ("b.rs", "fn b() {}\n")]);
+        // Flip a byte of the stored entry's data so its CRC no longer matches
+        let at = bytes.windows(4).position(|w| w == b"fn b").unwrap();
+        bytes[at] ^= 0xff;
+
+        let temp = TempDir::new().unwrap();
+        let error = extract_archive(Cursor::new(bytes), ArchiveKind::Zip, temp.path()).unwrap_err();
+        assert!(
+            format!("{:#}", error).contains("CRC mismatch"),
+            "{:#}",
+            error
+        );
+        assert!(extract_archive(
+            Cursor::new(b"not a zip".to_vec()),
+            ArchiveKind::Zip,
+            temp.path()
+        )
+        .is_err());
+    }
+}
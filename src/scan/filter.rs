@@ -1,12 +1,86 @@
 use crate::types::Language;
 use std::path::Path;
 
-pub fn should_include_file(path: &Path, allowed_languages: &[String]) -> bool {
+/// Extensions of lockfiles, minified bundles, source maps, images and
+/// generated code, which only add noise to a search index
+pub const DEFAULT_DENIED_EXTENSIONS: &[&str] = &[
+    "lock", "min.js", "min.mjs", "min.css", "map", "svg", "pb.go", "snap",
+];
+
+/// File extensions that are never indexed. Entries may span several dots
+/// (`min.js`) and match the end of the file name, case-insensitively.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionDenylist {
+    denied: Vec<String>,
+}
+
+impl Default for ExtensionDenylist {
+    fn default() -> Self {
+        Self::new(DEFAULT_DENIED_EXTENSIONS.iter().copied())
+    }
+}
+
+impl ExtensionDenylist {
+    pub fn new<S: AsRef<str>>(extensions: impl IntoIterator<Item = S>) -> Self {
+        Self::empty().deny(extensions)
+    }
+
+    /// Denylist that lets every file through
+    pub fn empty() -> Self {
+        Self { denied: Vec::new() }
+    }
+
+    /// Also deny `extensions`
+    pub fn deny<S: AsRef<str>>(mut self, extensions: impl IntoIterator<Item = S>) -> Self {
+        for ext in extensions {
+            let ext = normalize(ext.as_ref());
+            if !ext.is_empty() && !self.denied.contains(&ext) {
+                self.denied.push(ext);
+            }
+        }
+        self
+    }
+
+    /// Stop denying `extensions`, e.g. from `--include-ext`
+    pub fn allow<S: AsRef<str>>(mut self, extensions: impl IntoIterator<Item = S>) -> Self {
+        let allowed: Vec<String> = extensions
+            .into_iter()
+            .map(|e| normalize(e.as_ref()))
+            .collect();
+        self.denied.retain(|ext| !allowed.contains(ext));
+        self
+    }
+
+    /// Whether the file at `path` has a denied extension
+    pub fn denies(&self, path: &Path) -> bool {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            return false;
+        };
+        let name = name.to_lowercase();
+        self.denied
+            .iter()
+            .any(|ext| name.len() > ext.len() + 1 && name.ends_with(&format!(".{}", ext)))
+    }
+}
+
+fn normalize(ext: &str) -> String {
+    ext.trim().trim_start_matches('.').to_lowercase()
+}
+
+pub fn should_include_file(
+    path: &Path,
+    allowed_languages: &[String],
+    denylist: &ExtensionDenylist,
+) -> bool {
     let extension = match path.extension().and_then(|e| e.to_str()) {
         Some(ext) => ext,
         None => return false,
     };
 
+    if denylist.denies(path) {
+        return false;
+    }
+
     let language = Language::from_extension(extension);
 
     if matches!(language, Language::Unknown) {
@@ -17,24 +91,58 @@ pub fn should_include_file(path: &Path, allowed_languages: &[String]) -> bool {
         return true;
     }
 
-    let lang_name = match language {
-        Language::Rust => "rust",
-        Language::Python => "python",
-        Language::JavaScript => "javascript",
-        Language::TypeScript => "typescript",
-        Language::Go => "go",
-        Language::Php => "php",
-        Language::Java => "java",
-        Language::CSharp => "csharp",
-        Language::C => "c",
-        Language::Cpp => "cpp",
-        Language::Ruby => "ruby",
-        Language::Dart => "dart",
-        Language::Swift => "swift",
-        Language::Unknown => return false,
-    };
+    let lang_name = language.name();
 
     allowed_languages
         .iter()
         .any(|l| l.to_lowercase() == lang_name)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_js_excluded_by_default() {
+        let denylist = ExtensionDenylist::default();
+
+        assert!(!should_include_file(
+            Path::new("dist/app.min.js"),
+            &[],
+            &denylist
+        ));
+        assert!(!should_include_file(
+            Path::new("api/user.pb.go"),
+            &[],
+            &denylist
+        ));
+        assert!(should_include_file(Path::new("src/app.js"), &[], &denylist));
+        assert!(should_include_file(
+            Path::new("src/user.go"),
+            &[],
+            &denylist
+        ));
+
+        let included = denylist.allow([".min.js"]);
+        assert!(should_include_file(
+            Path::new("dist/app.min.js"),
+            &[],
+            &included
+        ));
+        assert!(!should_include_file(
+            Path::new("api/user.pb.go"),
+            &[],
+            &included
+        ));
+    }
+
+    #[test]
+    fn test_denylist_matches_whole_suffix() {
+        let denylist = ExtensionDenylist::new(["map"]);
+        assert!(denylist.denies(Path::new("app.js.map")));
+        assert!(denylist.denies(Path::new("APP.JS.MAP")));
+        assert!(!denylist.denies(Path::new("sitemap")));
+        assert!(!denylist.denies(Path::new("src/heatmap.rs")));
+        assert!(!denylist.denies(Path::new(".map")));
+    }
+}
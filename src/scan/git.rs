@@ -1,9 +1,135 @@
+use schemars::JsonSchema;
 use serde::Serialize;
 use std::collections::HashMap;
+use std::io::Read;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone, Serialize)]
+/// Default limit for a single git invocation
+pub const DEFAULT_GIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+static GIT_TIMEOUT_MS: AtomicU64 = AtomicU64::new(DEFAULT_GIT_TIMEOUT.as_millis() as u64);
+
+#[derive(Debug, thiserror::Error)]
+#[error("`{command}` timed out after {}s", .timeout.as_secs_f32())]
+pub struct CommandTimeout {
+    pub command: String,
+    pub timeout: Duration,
+}
+
+/// Set the timeout applied to every git subprocess
+pub fn set_git_timeout(timeout: Duration) {
+    GIT_TIMEOUT_MS.store(timeout.as_millis() as u64, Ordering::Relaxed);
+}
+
+pub fn git_timeout() -> Duration {
+    Duration::from_millis(GIT_TIMEOUT_MS.load(Ordering::Relaxed))
+}
+
+/// Run git in `path` with the configured timeout
+pub fn run_git(path: &Path, args: &[&str]) -> anyhow::Result<Output> {
+    run_git_with_timeout(path, args, git_timeout())
+}
+
+fn run_git_with_timeout(path: &Path, args: &[&str], timeout: Duration) -> anyhow::Result<Output> {
+    let mut command = Command::new("git");
+    command.args(args).current_dir(path);
+    run_with_timeout(command, timeout)
+}
+
+/// Run git once more if the first attempt fails or times out, calling
+/// `before_retry` in between. Meant for operations that touch the network,
+/// where failures are often transient.
+pub fn run_git_with_retry(
+    path: &Path,
+    args: &[&str],
+    timeout: Duration,
+    mut before_retry: impl FnMut(),
+) -> anyhow::Result<Output> {
+    match run_git_with_timeout(path, args, timeout) {
+        Ok(output) if output.status.success() => Ok(output),
+        _ => {
+            before_retry();
+            run_git_with_timeout(path, args, timeout)
+        }
+    }
+}
+
+/// Run a command to completion, killing it once `timeout` elapses.
+/// stdout and stderr are drained on separate threads so a chatty child cannot block.
+pub fn run_with_timeout(mut command: Command, timeout: Duration) -> anyhow::Result<Output> {
+    let label = format!(
+        "{} {}",
+        command.get_program().to_string_lossy(),
+        command
+            .get_args()
+            .map(|a| a.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Failed to run `{}`: {}", label, e))?;
+
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(CommandTimeout {
+                command: label,
+                timeout,
+            }
+            .into());
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+fn drain(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+/// `run_git` for callers that treat failure as "no answer": a timeout is
+/// reported on stderr so a hung repository does not fail silently.
+fn git_output(path: &Path, args: &[&str]) -> Option<Output> {
+    match run_git(path, args) {
+        Ok(output) => Some(output),
+        Err(e) => {
+            if e.downcast_ref::<CommandTimeout>().is_some() {
+                eprintln!("Warning: {}", e);
+            }
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct DiffStat {
     pub path: String,
     pub status: DiffStatus,
@@ -11,7 +137,7 @@ pub struct DiffStat {
     pub deletions: usize,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, JsonSchema)]
 pub enum DiffStatus {
     Added,
     Modified,
@@ -31,20 +157,13 @@ impl DiffStatus {
 }
 
 pub fn is_git_repo(path: &Path) -> bool {
-    Command::new("git")
-        .args(["rev-parse", "--git-dir"])
-        .current_dir(path)
-        .output()
+    git_output(path, &["rev-parse", "--git-dir"])
         .map(|o| o.status.success())
         .unwrap_or(false)
 }
 
 pub fn get_git_head(path: &Path) -> Option<String> {
-    let output = Command::new("git")
-        .args(["rev-parse", "--short=7", "HEAD"])
-        .current_dir(path)
-        .output()
-        .ok()?;
+    let output = git_output(path, &["rev-parse", "--short=7", "HEAD"])?;
 
     if output.status.success() {
         Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
@@ -54,11 +173,10 @@ pub fn get_git_head(path: &Path) -> Option<String> {
 }
 
 pub fn get_default_branch(path: &Path) -> Option<String> {
-    let output = Command::new("git")
-        .args(["symbolic-ref", "refs/remotes/origin/HEAD", "--short"])
-        .current_dir(path)
-        .output()
-        .ok()?;
+    let output = git_output(
+        path,
+        &["symbolic-ref", "refs/remotes/origin/HEAD", "--short"],
+    )?;
 
     if output.status.success() {
         let branch = String::from_utf8_lossy(&output.stdout);
@@ -66,11 +184,7 @@ pub fn get_default_branch(path: &Path) -> Option<String> {
     }
 
     for branch in ["main", "master"] {
-        let check = Command::new("git")
-            .args(["rev-parse", "--verify", branch])
-            .current_dir(path)
-            .output()
-            .ok()?;
+        let check = git_output(path, &["rev-parse", "--verify", branch])?;
         if check.status.success() {
             return Some(branch.to_string());
         }
@@ -82,17 +196,15 @@ pub fn get_default_branch(path: &Path) -> Option<String> {
 pub fn get_diff_files(path: &Path, base_ref: &str) -> Option<Vec<DiffStat>> {
     let ref_to_use = resolve_ref(path, base_ref);
 
-    let numstat = Command::new("git")
-        .args(["diff", "--numstat", &format!("{}...HEAD", ref_to_use)])
-        .current_dir(path)
-        .output()
-        .ok()?;
+    let numstat = git_output(
+        path,
+        &["diff", "--numstat", &format!("{}...HEAD", ref_to_use)],
+    )?;
 
-    let name_status = Command::new("git")
-        .args(["diff", "--name-status", &format!("{}...HEAD", ref_to_use)])
-        .current_dir(path)
-        .output()
-        .ok()?;
+    let name_status = git_output(
+        path,
+        &["diff", "--name-status", &format!("{}...HEAD", ref_to_use)],
+    )?;
 
     if !numstat.status.success() || !name_status.status.success() {
         return None;
@@ -138,16 +250,125 @@ pub fn get_diff_files(path: &Path, base_ref: &str) -> Option<Vec<DiffStat>> {
     Some(results)
 }
 
+/// Lines added or modified by a diff, grouped per hunk
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiffHunk {
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub content: String,
+}
+
+/// Collect the added/modified hunks between `base_ref` and HEAD.
+/// Pure deletions produce no hunk since there is nothing new to look at.
+pub fn get_diff_hunks(path: &Path, base_ref: &str) -> Option<Vec<DiffHunk>> {
+    let ref_to_use = resolve_ref(path, base_ref);
+
+    let output = git_output(
+        path,
+        &[
+            "diff",
+            "-U0",
+            "--no-color",
+            "--relative",
+            &format!("{}...HEAD", ref_to_use),
+        ],
+    )?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(parse_diff_hunks(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn parse_diff_hunks(diff: &str) -> Vec<DiffHunk> {
+    let mut hunks = Vec::new();
+    let mut current_file: Option<String> = None;
+    let mut current: Option<DiffHunk> = None;
+    let mut in_header = false;
+
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            hunks.extend(current.take());
+            current_file = None;
+            in_header = true;
+        } else if in_header && line.starts_with("+++ ") {
+            current_file = line.strip_prefix("+++ b/").map(str::to_string);
+        } else if let Some(range) = line.strip_prefix("@@ ") {
+            hunks.extend(current.take());
+            in_header = false;
+
+            let (start, count) = parse_new_range(range).unwrap_or((0, 0));
+            if let (Some(file), true) = (&current_file, count > 0) {
+                current = Some(DiffHunk {
+                    path: file.clone(),
+                    start_line: start,
+                    end_line: start + count - 1,
+                    content: String::new(),
+                });
+            }
+        } else if !in_header {
+            if let (Some(hunk), Some(added)) = (current.as_mut(), line.strip_prefix('+')) {
+                hunk.content.push_str(added);
+                hunk.content.push('\n');
+            }
+        }
+    }
+    hunks.extend(current);
+
+    hunks
+}
+
+/// Parse the `+start,count` part of a `@@ -a,b +c,d @@` hunk header
+fn parse_new_range(range: &str) -> Option<(usize, usize)> {
+    let new = range.split_whitespace().find(|p| p.starts_with('+'))?;
+    let mut parts = new[1..].splitn(2, ',');
+    let start = parts.next()?.parse().ok()?;
+    let count = match parts.next() {
+        Some(c) => c.parse().ok()?,
+        None => 1,
+    };
+    Some((start, count))
+}
+
+/// Count how often each file was touched in the last `max_commits` commits.
+/// Paths are relative to `path`, matching `FileEntry::relative_path`.
+pub fn get_commit_counts(path: &Path, max_commits: usize) -> Option<HashMap<String, usize>> {
+    let output = git_output(
+        path,
+        &[
+            "log",
+            "-n",
+            &max_commits.to_string(),
+            "--name-only",
+            "--relative",
+            "--pretty=format:",
+        ],
+    )?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let file = line.trim();
+        if !file.is_empty() {
+            *counts.entry(file.to_string()).or_insert(0) += 1;
+        }
+    }
+
+    Some(counts)
+}
+
 fn resolve_ref(path: &Path, base_ref: &str) -> String {
     if base_ref.starts_with("origin/") {
         return base_ref.to_string();
     }
 
     let origin_ref = format!("origin/{}", base_ref);
-    let check = Command::new("git")
-        .args(["rev-parse", "--verify", &origin_ref])
-        .current_dir(path)
-        .output();
+    let check = git_output(path, &["rev-parse", "--verify", &origin_ref]);
 
     if check.map(|o| o.status.success()).unwrap_or(false) {
         return origin_ref;
@@ -166,4 +387,52 @@ mod tests {
         assert_eq!(DiffStatus::Modified.as_str(), "modified");
         assert_eq!(DiffStatus::Deleted.as_str(), "deleted");
     }
+
+    #[test]
+    fn test_parse_diff_hunks() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -3,0 +4,2 @@ fn existing() {
++fn added() {}
++fn also_added() {}
+@@ -10 +11,0 @@ fn other() {
+-fn removed() {}
+diff --git a/old.rs b/old.rs
+deleted file mode 100644
+--- a/old.rs
++++ /dev/null
+@@ -1 +0,0 @@
+-fn gone() {}
+";
+        let hunks = parse_diff_hunks(diff);
+
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].path, "src/lib.rs");
+        assert_eq!(hunks[0].start_line, 4);
+        assert_eq!(hunks[0].end_line, 5);
+        assert_eq!(hunks[0].content, "fn added() {}\nfn also_added() {}\n");
+    }
+
+    #[test]
+    fn test_slow_command_times_out() {
+        let mut slow = Command::new("sleep");
+        slow.arg("5");
+
+        let start = Instant::now();
+        let err = run_with_timeout(slow, Duration::from_millis(100)).unwrap_err();
+
+        assert!(start.elapsed() < Duration::from_secs(4));
+        let timeout = err.downcast_ref::<CommandTimeout>().unwrap();
+        assert_eq!(timeout.command, "sleep 5");
+        assert!(err.to_string().contains("timed out"));
+
+        let mut fast = Command::new("echo");
+        fast.arg("ok");
+        let output = run_with_timeout(fast, Duration::from_secs(5)).unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "ok");
+    }
 }
@@ -0,0 +1,137 @@
+//! Well-known build, container and CI files.
+//!
+//! These are not source files, so `scan_directory` skips them, but they tell
+//! an agent how the project is built, packaged and tested.
+
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InfraFile {
+    pub relative_path: String,
+    /// Short description of the file's role, e.g. "container definition"
+    pub role: &'static str,
+}
+
+/// Role of a well-known infrastructure file, judged by its path
+pub fn file_role(relative_path: &str) -> Option<&'static str> {
+    let path = relative_path.replace('\\', "/");
+    let name = path.rsplit('/').next().unwrap_or(&path);
+    let lower = name.to_lowercase();
+
+    if path.starts_with(".github/workflows/") && is_yaml(&lower) {
+        return Some("CI workflow");
+    }
+    if path == ".circleci/config.yml" {
+        return Some("CI pipeline");
+    }
+    if lower == "dockerfile" || lower.starts_with("dockerfile.") || lower.ends_with(".dockerfile") {
+        return Some("container definition");
+    }
+
+    let role = match lower.as_str() {
+        "containerfile" => "container definition",
+        "docker-compose.yml" | "docker-compose.yaml" | "compose.yml" | "compose.yaml" => {
+            "container orchestration"
+        }
+        "cargo.toml" => "Rust build config",
+        "package.json" => "npm package manifest",
+        "pyproject.toml" | "requirements.txt" | "pipfile" => "Python build config",
+        "go.mod" => "Go module definition",
+        "pom.xml" | "build.gradle" | "build.gradle.kts" => "JVM build config",
+        "cmakelists.txt" => "CMake build config",
+        "makefile" | "gnumakefile" | "justfile" => "build automation",
+        "tsconfig.json" => "TypeScript compiler config",
+        ".gitlab-ci.yml" | ".travis.yml" | "azure-pipelines.yml" | "jenkinsfile" => "CI pipeline",
+        _ => return None,
+    };
+    Some(role)
+}
+
+fn is_yaml(name: &str) -> bool {
+    name.ends_with(".yml") || name.ends_with(".yaml")
+}
+
+/// Find infrastructure files under `root`, sorted by path. Hidden directories
+/// are searched (CI configs live in `.github/`), `.git` and `node_modules` are not.
+pub fn scan_infra_files(
+    root: &Path,
+    respect_gitignore: bool,
+    max_depth: Option<usize>,
+) -> Result<Vec<InfraFile>> {
+    let root = root
+        .canonicalize()
+        .context("Failed to canonicalize root path")?;
+
+    let mut builder = WalkBuilder::new(&root);
+    builder
+        .hidden(false)
+        .git_ignore(respect_gitignore)
+        .git_global(respect_gitignore)
+        .git_exclude(respect_gitignore)
+        .filter_entry(|entry| {
+            let name = entry.file_name();
+            name != ".git" && name != "node_modules"
+        });
+    if let Some(depth) = max_depth {
+        builder.max_depth(Some(depth));
+    }
+
+    let mut files: Vec<InfraFile> = builder
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .filter_map(|entry| {
+            let relative_path = entry
+                .path()
+                .strip_prefix(&root)
+                .ok()?
+                .to_string_lossy()
+                .replace('\\', "/");
+            let role = file_role(&relative_path)?;
+            Some(InfraFile {
+                relative_path,
+                role,
+            })
+        })
+        .collect();
+
+    files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_file_role() {
+        assert_eq!(file_role("Dockerfile"), Some("container definition"));
+        assert_eq!(
+            file_role("deploy/Dockerfile.prod"),
+            Some("container definition")
+        );
+        assert_eq!(file_role("Cargo.toml"), Some("Rust build config"));
+        assert_eq!(file_role(".github/workflows/ci.yml"), Some("CI workflow"));
+        assert_eq!(file_role("config.yml"), None);
+        assert_eq!(file_role("src/main.rs"), None);
+    }
+
+    #[test]
+    fn test_scan_finds_hidden_ci_configs() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join(".github/workflows")).unwrap();
+        fs::write(temp.path().join(".github/workflows/test.yml"), "on: push\n").unwrap();
+        fs::write(temp.path().join("Dockerfile"), "FROM rust\n").unwrap();
+        fs::write(temp.path().join("main.rs"), "fn main() {}\n").unwrap();
+
+        let files = scan_infra_files(temp.path(), false, None).unwrap();
+
+        let paths: Vec<&str> = files.iter().map(|f| f.relative_path.as_str()).collect();
+        assert_eq!(paths, vec![".github/workflows/test.yml", "Dockerfile"]);
+    }
+}
@@ -1,11 +1,23 @@
+pub mod archive;
 mod filter;
 pub mod git;
+mod infra;
+mod read;
 pub mod remote;
 mod walker;
+mod zip;
 
-pub use filter::should_include_file;
+pub use archive::{extract_archive, extract_to_temp, is_archive, ArchiveKind};
+pub use filter::{should_include_file, ExtensionDenylist, DEFAULT_DENIED_EXTENSIONS};
 pub use git::{
-    get_default_branch, get_diff_files, get_git_head, is_git_repo, DiffStat, DiffStatus,
+    get_commit_counts, get_default_branch, get_diff_files, get_diff_hunks, get_git_head,
+    is_git_repo, run_git, run_with_timeout, set_git_timeout, CommandTimeout, DiffHunk, DiffStat,
+    DiffStatus,
 };
+pub use infra::{file_role, scan_infra_files, InfraFile};
+pub use read::{is_binary_content, read_source, BinaryContent};
 pub use remote::{cleanup_temp, clone_to_temp, is_remote_url};
-pub use walker::scan_directory;
+pub use walker::{
+    is_permission_denied, permission_denied_summary, scan_directory, scan_directory_report,
+    scan_index_report, ScanReport, AGENTLENS_IGNORE_FILE, DEFAULT_LARGE_FILE_LINES,
+};
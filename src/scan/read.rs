@@ -0,0 +1,68 @@
+//! Reading scanned files as text when their encoding can't be trusted: the
+//! scan's binary check only looks at the first 8 KiB, and repositories carry
+//! latin-1 sources and blobs with source extensions.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Error wrapped in an `InvalidData` IO error for files that hold NUL bytes
+#[derive(Debug)]
+pub struct BinaryContent;
+
+impl fmt::Display for BinaryContent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "file contains binary data")
+    }
+}
+
+impl std::error::Error for BinaryContent {}
+
+/// Read `path` as text. Invalid UTF-8 is decoded lossily (U+FFFD per bad
+/// sequence) rather than failing; files with NUL bytes are rejected as binary.
+pub fn read_source(path: &Path) -> io::Result<String> {
+    let bytes = fs::read(path)?;
+    if bytes.contains(&0) {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, BinaryContent));
+    }
+    Ok(String::from_utf8(bytes)
+        .unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned()))
+}
+
+/// Whether `err` (or an error it wraps) is a `read_source` binary rejection
+pub fn is_binary_content(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<io::Error>()
+            .and_then(|e| e.get_ref())
+            .is_some_and(|inner| inner.is::<BinaryContent>())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_invalid_utf8_is_decoded_lossily() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("latin1.py");
+        fs::write(&path, b"# caf\xe9\nx = 1\n").unwrap();
+
+        assert_eq!(read_source(&path).unwrap(), "# caf\u{FFFD}\nx = 1\n");
+    }
+
+    #[test]
+    fn test_nul_bytes_are_binary() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("blob.rs");
+        fs::write(&path, b"fn main() {}\n\x00\x01\x02").unwrap();
+
+        let err = anyhow::Error::from(read_source(&path).unwrap_err());
+        assert!(is_binary_content(&err));
+        let missing = anyhow::Error::from(read_source(&temp.path().join("none.rs")).unwrap_err());
+        assert!(!is_binary_content(&missing));
+    }
+}
@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+
+use super::git::{git_timeout, run_git_with_retry};
 
 pub fn is_remote_url(path: &str) -> bool {
     path.starts_with("https://github.com")
@@ -25,30 +26,152 @@ pub fn normalize_git_url(path: &str) -> String {
     }
 }
 
-pub fn clone_to_temp(url: &str) -> Result<PathBuf> {
+/// Clones move far more data than local git calls, so they get a longer budget
+const CLONE_TIMEOUT_FACTOR: u32 = 10;
+
+/// Environment variables holding a token for HTTPS clones from github.com,
+/// in order of preference
+const GITHUB_TOKEN_VARS: &[&str] = &["GITHUB_TOKEN", "GIT_TOKEN"];
+/// The same for any other HTTPS host; a GitHub token is never sent elsewhere
+const GIT_TOKEN_VARS: &[&str] = &["GIT_TOKEN"];
+
+/// Host of an `https://` URL, without any user info or port
+fn https_host(git_url: &str) -> Option<&str> {
+    let authority = git_url.strip_prefix("https://")?.split('/').next()?;
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+    (!host.is_empty()).then_some(host)
+}
+
+/// Variables that may hold a token for `git_url`: none unless it is HTTPS
+fn token_vars(git_url: &str) -> &'static [&'static str] {
+    match https_host(git_url) {
+        Some(host) if host.eq_ignore_ascii_case("github.com") => GITHUB_TOKEN_VARS,
+        Some(_) => GIT_TOKEN_VARS,
+        None => &[],
+    }
+}
+
+/// First of `git_url`'s `token_vars` for which `is_set` holds
+fn token_var(git_url: &str, is_set: impl Fn(&str) -> bool) -> Option<&'static str> {
+    token_vars(git_url).iter().copied().find(|var| is_set(var))
+}
+
+/// Shallow-clone `url` into a temp directory, checking out the branch or tag
+/// `ref_name` or else the default branch. The directory is removed if the
+/// clone fails; otherwise the caller removes it with `cleanup_temp`.
+pub fn clone_to_temp(url: &str, ref_name: Option<&str>) -> Result<PathBuf> {
     let temp_dir = std::env::temp_dir().join(format!("agentlens-{}", std::process::id()));
     std::fs::create_dir_all(&temp_dir).context("Failed to create temp directory")?;
 
+    let result = clone_into(url, ref_name, &temp_dir);
+    if result.is_err() {
+        cleanup_temp(&temp_dir);
+    }
+    result.map(|_| temp_dir)
+}
+
+fn clone_into(url: &str, ref_name: Option<&str>, dest: &Path) -> Result<()> {
     let git_url = normalize_git_url(url);
+    let token_var = token_var(&git_url, |var| {
+        std::env::var(var).is_ok_and(|v| !v.is_empty())
+    });
+    let args = clone_args(&git_url, ref_name, dest.to_str().unwrap(), token_var);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
 
-    let output = Command::new("git")
-        .args([
-            "clone",
-            "--depth",
-            "1",
-            "--single-branch",
-            &git_url,
-            temp_dir.to_str().unwrap(),
-        ])
-        .output()
-        .context("Failed to run git clone")?;
+    // A failed clone can leave a partial checkout behind, which blocks the retry
+    let output = run_git_with_retry(
+        &std::env::temp_dir(),
+        &args,
+        git_timeout() * CLONE_TIMEOUT_FACTOR,
+        || {
+            let _ = std::fs::remove_dir_all(dest);
+            let _ = std::fs::create_dir_all(dest);
+        },
+    )
+    .context("Failed to run git clone")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
+        if let Some(hint) = auth_hint(&git_url, &stderr, token_var) {
+            return Err(anyhow::anyhow!(
+                "Git clone failed: {}\n{}",
+                stderr.trim(),
+                hint
+            ));
+        }
         return Err(anyhow::anyhow!("Git clone failed: {}", stderr));
     }
 
-    Ok(temp_dir)
+    Ok(())
+}
+
+/// `git clone` arguments. With `token_var`, an inline credential helper
+/// answers with the token read from that variable when git asks, so the
+/// token never appears in the arguments, the URL or the clone's config. The
+/// helper is scoped to `git_url`'s host, so it is never asked for another.
+fn clone_args(
+    git_url: &str,
+    ref_name: Option<&str>,
+    dest: &str,
+    token_var: Option<&str>,
+) -> Vec<String> {
+    let mut args: Vec<String> = Vec::new();
+    if let (Some(var), Some(host)) = (token_var, https_host(git_url)) {
+        let key = format!("credential.https://{}.helper", host);
+        args.extend([
+            "-c".to_string(),
+            // An empty value first drops the helpers configured for this host
+            format!("{}=", key),
+            "-c".to_string(),
+            format!(
+                "{}=!f() {{ test \"$1\" = get && echo username=x-access-token && echo \"password=${}\"; }}; f",
+                key, var
+            ),
+        ]);
+    }
+    args.extend(["clone", "--depth", "1", "--single-branch"].map(String::from));
+    if let Some(ref_name) = ref_name {
+        args.extend(["--branch".to_string(), ref_name.to_string()]);
+    }
+    args.extend([git_url.to_string(), dest.to_string()]);
+    args
+}
+
+/// What to do about a clone that failed for lack of access, if that is why.
+/// Private GitHub repositories answer unauthenticated clones with "not found".
+fn auth_hint(git_url: &str, stderr: &str, token_var: Option<&str>) -> Option<String> {
+    let lower = stderr.to_lowercase();
+    if !git_url.starts_with("https://") {
+        return lower.contains("permission denied (publickey)").then(|| {
+            "SSH authentication failed; check that your SSH agent holds a key with access to the repository".to_string()
+        });
+    }
+
+    let denied = [
+        "authentication failed",
+        "could not read username",
+        "repository not found",
+        "403",
+        "401",
+    ]
+    .iter()
+    .any(|marker| lower.contains(marker));
+    if !denied {
+        return None;
+    }
+    Some(match token_var {
+        Some(var) => format!(
+            "The token in {} was rejected; check that it has read access to the repository",
+            var
+        ),
+        None => format!(
+            "For a private repository, set {} to a token with read access",
+            match token_vars(git_url) {
+                [preferred, fallback] => format!("{} (or {})", preferred, fallback),
+                vars => vars.join(" or "),
+            }
+        ),
+    })
 }
 
 pub fn cleanup_temp(path: &PathBuf) {
@@ -68,6 +191,160 @@ mod tests {
         assert!(!is_remote_url("/path/to/local"));
     }
 
+    #[test]
+    fn test_clone_args_select_ref() {
+        assert_eq!(
+            clone_args(
+                "https://github.com/user/repo.git",
+                Some("v1.2.0"),
+                "/tmp/x",
+                None
+            ),
+            vec![
+                "clone",
+                "--depth",
+                "1",
+                "--single-branch",
+                "--branch",
+                "v1.2.0",
+                "https://github.com/user/repo.git",
+                "/tmp/x"
+            ]
+        );
+        assert!(
+            !clone_args("https://github.com/user/repo.git", None, "/tmp/x", None)
+                .contains(&"--branch".to_string())
+        );
+    }
+
+    #[test]
+    fn test_token_is_read_by_credential_helper_not_passed() {
+        let args = clone_args(
+            "https://github.com/user/private.git",
+            None,
+            "/tmp/x",
+            Some("GITHUB_TOKEN"),
+        );
+
+        assert_eq!(
+            &args[..3],
+            ["-c", "credential.https://github.com.helper=", "-c"]
+        );
+        assert!(args[3].starts_with("credential.https://github.com.helper=!f()"));
+        assert!(args[3].contains("password=$GITHUB_TOKEN"));
+        assert_eq!(args[4], "clone");
+        assert_eq!(args.last().unwrap(), "/tmp/x");
+        assert!(args.contains(&"https://github.com/user/private.git".to_string()));
+    }
+
+    #[test]
+    fn test_github_token_is_only_offered_to_github() {
+        let github_set = |var: &str| var == "GITHUB_TOKEN";
+        let both_set = |var: &str| var == "GITHUB_TOKEN" || var == "GIT_TOKEN";
+
+        let github = "https://github.com/user/private.git";
+        assert_eq!(token_var(github, github_set), Some("GITHUB_TOKEN"));
+        assert_eq!(
+            token_var(github, |var| var == "GIT_TOKEN"),
+            Some("GIT_TOKEN")
+        );
+
+        // Another host never sees GITHUB_TOKEN, so it gets no helper
+        let gitlab = "https://gitlab.com/user/private.git";
+        let token = token_var(gitlab, github_set);
+        assert_eq!(token, None);
+        assert_eq!(clone_args(gitlab, None, "/tmp/x", token)[0], "clone");
+        assert_eq!(token_var(gitlab, both_set), Some("GIT_TOKEN"));
+        let args = clone_args(gitlab, None, "/tmp/x", Some("GIT_TOKEN"));
+        assert!(args[3].starts_with("credential.https://gitlab.com.helper=!f()"));
+
+        // Lookalike hosts and SSH URLs get nothing
+        assert_eq!(
+            token_var("https://github.com.evil.example/x.git", github_set),
+            None
+        );
+        assert_eq!(token_var("git@github.com:user/private.git", both_set), None);
+        assert_eq!(
+            https_host("https://me@github.com:443/x.git"),
+            Some("github.com")
+        );
+    }
+
+    #[test]
+    fn test_auth_failures_point_at_token_or_ssh_agent() {
+        let https = "https://github.com/user/private.git";
+        let not_found = "remote: Repository not found.\nfatal: repository 'https://github.com/user/private.git/' not found";
+
+        assert!(auth_hint(https, not_found, None)
+            .unwrap()
+            .contains("set GITHUB_TOKEN (or GIT_TOKEN)"));
+        assert!(
+            auth_hint("https://gitlab.com/user/private.git", not_found, None)
+                .unwrap()
+                .contains("set GIT_TOKEN to")
+        );
+        assert!(auth_hint(
+            https,
+            "fatal: unable to access: The requested URL returned error: 403",
+            Some("GIT_TOKEN")
+        )
+        .unwrap()
+        .contains("token in GIT_TOKEN was rejected"));
+        assert!(auth_hint(
+            https,
+            "fatal: unable to access: Could not resolve host",
+            None
+        )
+        .is_none());
+
+        let ssh = "git@github.com:user/private.git";
+        assert!(
+            auth_hint(ssh, "git@github.com: Permission denied (publickey).", None)
+                .unwrap()
+                .contains("SSH agent")
+        );
+        assert!(auth_hint(ssh, not_found, None).is_none());
+    }
+
+    #[test]
+    fn test_clone_checks_out_ref_and_cleans_up_on_failure() {
+        let source = tempfile::TempDir::new().unwrap();
+        let repo = source.path().join("repo.git");
+        std::fs::create_dir_all(&repo).unwrap();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .current_dir(&repo)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {:?}", args);
+        };
+        git(&["init", "-q", "-b", "main"]);
+        std::fs::write(repo.join("a.txt"), "a").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-qm", "first"]);
+        git(&["tag", "v1"]);
+        std::fs::write(repo.join("b.txt"), "b").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-qm", "second"]);
+        let url = format!("file://{}", repo.display());
+
+        let clone = clone_to_temp(&url, Some("v1")).unwrap();
+        assert!(clone.join("a.txt").exists());
+        assert!(!clone.join("b.txt").exists());
+        cleanup_temp(&clone);
+
+        let clone = clone_to_temp(&url, None).unwrap();
+        assert!(clone.join("b.txt").exists());
+        cleanup_temp(&clone);
+
+        let err = clone_to_temp(&url, Some("no-such-ref")).unwrap_err();
+        assert!(err.to_string().contains("Git clone failed"), "{}", err);
+        assert!(!clone.exists());
+    }
+
     #[test]
     fn test_normalize_git_url() {
         assert_eq!(
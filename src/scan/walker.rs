@@ -1,20 +1,88 @@
-use crate::types::{FileEntry, Language};
+use super::filter::ExtensionDenylist;
+use crate::types::{is_markdown_extension, FileEntry, Language};
 use anyhow::{Context, Result};
 use ignore::WalkBuilder;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read};
+use std::io::{self, BufRead, BufReader, Read};
 use std::path::Path;
 
 const BINARY_CHECK_SIZE: usize = 8192;
+/// Default line count above which a file is large and gets a symbol outline
+pub const DEFAULT_LARGE_FILE_LINES: usize = 500;
 const MINIFIED_LINE_LENGTH_THRESHOLD: usize = 200;
+/// Unreadable paths named in a permission summary before "and N more"
+const PERMISSION_SAMPLE_SIZE: usize = 3;
+/// Gitignore-syntax file excluding paths from agentlens only, honored even
+/// with gitignore handling off
+pub const AGENTLENS_IGNORE_FILE: &str = ".agentlensignore";
 
+/// Files found by a scan, plus paths skipped because they could not be read
+#[derive(Debug, Default)]
+pub struct ScanReport {
+    pub files: Vec<FileEntry>,
+    /// Files and directories (relative to the root) denied by permissions
+    pub permission_denied: Vec<String>,
+}
+
+/// Scan for source files, warning on stderr when some paths were unreadable
 pub fn scan_directory(
     root: &Path,
     threshold: usize,
     respect_gitignore: bool,
     max_depth: Option<usize>,
 ) -> Result<Vec<FileEntry>> {
+    let report = scan_directory_report(root, threshold, respect_gitignore, max_depth)?;
+    if let Some(summary) = permission_denied_summary(&report.permission_denied) {
+        eprintln!("Warning: {}", summary);
+    }
+    Ok(report.files)
+}
+
+/// Like `scan_directory`, but returns unreadable paths instead of printing them
+pub fn scan_directory_report(
+    root: &Path,
+    threshold: usize,
+    respect_gitignore: bool,
+    max_depth: Option<usize>,
+) -> Result<ScanReport> {
+    let denylist = ExtensionDenylist::empty();
+    scan_report(
+        root,
+        threshold,
+        respect_gitignore,
+        max_depth,
+        false,
+        &denylist,
+    )
+}
+
+/// Files the search index covers: source files plus markdown documents,
+/// minus those with a denied extension
+pub fn scan_index_report(
+    root: &Path,
+    respect_gitignore: bool,
+    denylist: &ExtensionDenylist,
+) -> Result<ScanReport> {
+    scan_report(
+        root,
+        DEFAULT_LARGE_FILE_LINES,
+        respect_gitignore,
+        None,
+        true,
+        denylist,
+    )
+}
+
+fn scan_report(
+    root: &Path,
+    threshold: usize,
+    respect_gitignore: bool,
+    max_depth: Option<usize>,
+    include_markdown: bool,
+    denylist: &ExtensionDenylist,
+) -> Result<ScanReport> {
     let mut entries = Vec::new();
+    let mut permission_denied = Vec::new();
     let root = root
         .canonicalize()
         .context("Failed to canonicalize root path")?;
@@ -24,7 +92,8 @@ pub fn scan_directory(
         .hidden(true)
         .git_ignore(respect_gitignore)
         .git_global(respect_gitignore)
-        .git_exclude(respect_gitignore);
+        .git_exclude(respect_gitignore)
+        .add_custom_ignore_filename(AGENTLENS_IGNORE_FILE);
 
     if let Some(depth) = max_depth {
         builder.max_depth(Some(depth));
@@ -36,7 +105,13 @@ pub fn scan_directory(
         let entry = match result {
             Ok(e) => e,
             Err(err) => {
-                eprintln!("Warning: {}", err);
+                let denied = err
+                    .io_error()
+                    .is_some_and(|e| e.kind() == io::ErrorKind::PermissionDenied);
+                match error_path(&err) {
+                    Some(path) if denied => permission_denied.push(relative_to(&root, path)),
+                    _ => eprintln!("Warning: {}", err),
+                }
                 continue;
             }
         };
@@ -52,26 +127,35 @@ pub fn scan_directory(
             None => continue,
         };
 
+        if denylist.denies(path) {
+            continue;
+        }
+
         let language = Language::from_extension(extension);
-        if matches!(language, Language::Unknown) {
+        let markdown = include_markdown && is_markdown_extension(extension);
+        if matches!(language, Language::Unknown) && !markdown {
             continue;
         }
 
-        if is_binary_file(path)? {
+        let relative_path = relative_to(&root, path);
+
+        let is_binary = match is_binary_file(path) {
+            Err(err) if is_permission_denied(&err) => {
+                permission_denied.push(relative_path);
+                continue;
+            }
+            other => other?,
+        };
+        if is_binary {
             continue;
         }
 
+        // Prose paragraphs are often one long line, so markdown is never "minified"
         let (line_count, is_minified) = count_lines_and_check_minified(path)?;
-        if is_minified {
+        if is_minified && !markdown {
             continue;
         }
 
-        let relative_path = path
-            .strip_prefix(&root)
-            .unwrap_or(path)
-            .to_string_lossy()
-            .to_string();
-
         let size_bytes = path.metadata().map(|m| m.len()).unwrap_or(0);
 
         entries.push(FileEntry::new(
@@ -84,8 +168,59 @@ pub fn scan_directory(
     }
 
     entries.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    permission_denied.sort();
+
+    Ok(ScanReport {
+        files: entries,
+        permission_denied,
+    })
+}
+
+/// Whether `err` (or an error it wraps) is an IO permission-denied error
+pub fn is_permission_denied(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<io::Error>()
+            .is_some_and(|e| e.kind() == io::ErrorKind::PermissionDenied)
+    })
+}
+
+/// One line saying how many paths were unreadable, naming the first few
+pub fn permission_denied_summary(paths: &[String]) -> Option<String> {
+    if paths.is_empty() {
+        return None;
+    }
 
-    Ok(entries)
+    let mut sample = paths[..paths.len().min(PERMISSION_SAMPLE_SIZE)].join(", ");
+    if paths.len() > PERMISSION_SAMPLE_SIZE {
+        sample.push_str(&format!(
+            " and {} more",
+            paths.len() - PERMISSION_SAMPLE_SIZE
+        ));
+    }
+    Some(format!(
+        "skipped {} unreadable path(s) (permission denied): {}",
+        paths.len(),
+        sample
+    ))
+}
+
+fn relative_to(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
+
+/// The path an ignore error refers to, looking through depth and line wrappers
+fn error_path(err: &ignore::Error) -> Option<&Path> {
+    match err {
+        ignore::Error::WithPath { path, .. } => Some(path),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+            error_path(err)
+        }
+        _ => None,
+    }
 }
 
 fn is_binary_file(path: &Path) -> Result<bool> {
@@ -106,8 +241,12 @@ fn count_lines_and_check_minified(path: &Path) -> Result<(usize, bool)> {
     let mut total_chars = 0;
     let mut non_empty_lines = 0;
 
-    for line in reader.lines() {
-        let line = line.context("Failed to read line")?;
+    // Bytes rather than `lines()`, which fails on the first invalid UTF-8
+    for line in reader.split(b'\n') {
+        let mut line = line.context("Failed to read line")?;
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
         line_count += 1;
         let len = line.len();
         if len > 0 {
@@ -116,13 +255,121 @@ fn count_lines_and_check_minified(path: &Path) -> Result<(usize, bool)> {
         }
     }
 
-    let avg_line_length = if non_empty_lines > 0 {
-        total_chars / non_empty_lines
-    } else {
-        0
-    };
+    let avg_line_length = total_chars.checked_div(non_empty_lines).unwrap_or(0);
 
     let is_minified = avg_line_length > MINIFIED_LINE_LENGTH_THRESHOLD;
 
     Ok((line_count, is_minified))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_is_permission_denied_through_context() {
+        let denied = Err::<(), _>(io::Error::from(io::ErrorKind::PermissionDenied))
+            .context("Failed to open file for binary check")
+            .unwrap_err();
+        let missing = anyhow::Error::from(io::Error::from(io::ErrorKind::NotFound));
+
+        assert!(is_permission_denied(&denied));
+        assert!(!is_permission_denied(&missing));
+    }
+
+    #[test]
+    fn test_permission_denied_summary_samples_paths() {
+        assert!(permission_denied_summary(&[]).is_none());
+
+        let paths: Vec<String> = ["a.rs", "b.rs", "c.rs", "d.rs", "e.rs"]
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        assert_eq!(
+            permission_denied_summary(&paths).unwrap(),
+            "skipped 5 unreadable path(s) (permission denied): a.rs, b.rs, c.rs and 2 more"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unreadable_file_reported_not_fatal() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(root.join("open.rs"), "fn open() {}\n").unwrap();
+        let secret = root.join("secret.rs");
+        fs::write(&secret, "fn secret() {}\n").unwrap();
+        fs::set_permissions(&secret, fs::Permissions::from_mode(0o000)).unwrap();
+        if File::open(&secret).is_ok() {
+            // Running as root: permissions are not enforced
+            return;
+        }
+
+        let report = scan_directory_report(root, 500, false, None).unwrap();
+
+        let files: Vec<&str> = report
+            .files
+            .iter()
+            .map(|f| f.relative_path.as_str())
+            .collect();
+        assert_eq!(files, vec!["open.rs"]);
+        assert_eq!(report.permission_denied, vec!["secret.rs".to_string()]);
+    }
+
+    #[test]
+    fn test_index_scan_includes_markdown() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(root.join("lib.rs"), "fn lib() {}\n").unwrap();
+        let prose = "word ".repeat(100);
+        fs::write(root.join("README.md"), format!("# Guide\n\n{}\n", prose)).unwrap();
+
+        let names = |report: ScanReport| -> Vec<String> {
+            report.files.into_iter().map(|f| f.relative_path).collect()
+        };
+        assert_eq!(
+            names(scan_directory_report(root, 500, false, None).unwrap()),
+            vec!["lib.rs"]
+        );
+        assert_eq!(
+            names(scan_index_report(root, false, &ExtensionDenylist::default()).unwrap()),
+            vec!["README.md", "lib.rs"]
+        );
+    }
+
+    #[test]
+    fn test_agentlensignore_excludes_paths() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        for dir in ["src/generated", "src/api/generated", "vendor/dep"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in [
+            "src/lib.rs",
+            "src/generated/schema.rs",
+            "src/api/generated/client.rs",
+            "vendor/dep/lib.rs",
+        ] {
+            fs::write(root.join(file), "fn f() {}\n").unwrap();
+        }
+        fs::write(
+            root.join(AGENTLENS_IGNORE_FILE),
+            "**/generated/**\nvendor/\n",
+        )
+        .unwrap();
+
+        // Applies with gitignore handling on and off
+        for respect_gitignore in [true, false] {
+            let files: Vec<String> = scan_directory(root, 500, respect_gitignore, None)
+                .unwrap()
+                .into_iter()
+                .map(|f| f.relative_path)
+                .collect();
+            assert_eq!(files, vec!["src/lib.rs"]);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Minimal zip reader for `index <archive>.zip`: stored and deflated entries,
+//! no zip64, no encryption. Entries are checked against their CRC-32.
+
+use anyhow::{Context, Result};
+use flate2::read::DeflateDecoder;
+use flate2::CrcReader;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+const CENTRAL_DIRECTORY_ENTRY: u32 = 0x0201_4b50;
+const LOCAL_FILE_HEADER: u32 = 0x0403_4b50;
+/// End of central directory record without its trailing comment
+const EOCD_LEN: usize = 22;
+/// The trailing comment is at most this long
+const MAX_COMMENT_LEN: usize = u16::MAX as usize;
+
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATED: u16 = 8;
+
+/// One file or directory listed in the central directory
+struct Entry {
+    name: String,
+    method: u16,
+    crc: u32,
+    compressed_size: u64,
+    size: u64,
+    header_offset: u64,
+    is_symlink: bool,
+}
+
+/// Extract every entry of the zip in `reader` under `dest`. Entries whose
+/// names are absolute or climb out with `..` are refused; symlinks are skipped.
+pub fn unpack<R: Read + Seek>(mut reader: R, dest: &Path) -> Result<()> {
+    // Check every name before writing anything
+    let entries = read_central_directory(&mut reader)?
+        .into_iter()
+        .filter(|entry| !entry.is_symlink)
+        .map(|entry| {
+            let path = entry_path(&entry.name)
+                .with_context(|| format!("Refusing zip entry {:?}", entry.name))?;
+            Ok((entry, dest.join(path)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    for (entry, path) in entries {
+        if entry.name.ends_with('/') {
+            fs::create_dir_all(&path)?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        extract_entry(&mut reader, &entry, &path)
+            .with_context(|| format!("Failed to extract {}", entry.name))?;
+    }
+    Ok(())
+}
+
+/// Map an entry name onto a relative path, or fail if it would leave `dest`
+fn entry_path(name: &str) -> Result<PathBuf> {
+    if name.starts_with(['/', '\\']) {
+        anyhow::bail!("absolute path");
+    }
+    let mut path = PathBuf::new();
+    for part in name.split(['/', '\\']) {
+        match Path::new(part).components().next() {
+            None | Some(Component::CurDir) => {}
+            Some(Component::Normal(part)) => path.push(part),
+            // `..`, or a drive prefix on Windows
+            _ => anyhow::bail!("path escapes the archive"),
+        }
+    }
+    Ok(path)
+}
+
+fn extract_entry<R: Read + Seek>(reader: &mut R, entry: &Entry, path: &Path) -> Result<()> {
+    reader.seek(SeekFrom::Start(entry.header_offset))?;
+    let mut header = [0u8; 30];
+    reader.read_exact(&mut header)?;
+    if u32_at(&header, 0) != LOCAL_FILE_HEADER {
+        anyhow::bail!("missing local file header");
+    }
+    let skip = u16_at(&header, 26) as i64 + u16_at(&header, 28) as i64;
+    reader.seek(SeekFrom::Current(skip))?;
+
+    let data = reader.by_ref().take(entry.compressed_size);
+    let mut contents: CrcReader<Box<dyn Read + '_>> = CrcReader::new(match entry.method {
+        METHOD_STORED => Box::new(data),
+        METHOD_DEFLATED => Box::new(DeflateDecoder::new(data)),
+        method => anyhow::bail!("unsupported compression method {}", method),
+    });
+
+    let written = io::copy(&mut contents, &mut File::create(path)?)?;
+    if written != entry.size || contents.crc().sum() != entry.crc {
+        anyhow::bail!("corrupt entry (size or CRC mismatch)");
+    }
+    Ok(())
+}
+
+fn read_central_directory<R: Read + Seek>(reader: &mut R) -> Result<Vec<Entry>> {
+    // The end of central directory record sits before an optional comment
+    let len = reader.seek(SeekFrom::End(0))?;
+    let tail_len = len.min((EOCD_LEN + MAX_COMMENT_LEN) as u64);
+    reader.seek(SeekFrom::Start(len - tail_len))?;
+    let mut tail = vec![0u8; tail_len as usize];
+    reader.read_exact(&mut tail)?;
+
+    let eocd = (0..tail.len().saturating_sub(EOCD_LEN - 1))
+        .rev()
+        .find(|&i| u32_at(&tail, i) == END_OF_CENTRAL_DIRECTORY)
+        .context("Not a zip archive (no end of central directory)")?;
+    let count = u16_at(&tail, eocd + 10);
+    let directory_len = u32_at(&tail, eocd + 12);
+    let directory_offset = u32_at(&tail, eocd + 16);
+    if count == u16::MAX || directory_offset == u32::MAX {
+        anyhow::bail!("zip64 archives are not supported");
+    }
+
+    reader.seek(SeekFrom::Start(directory_offset as u64))?;
+    let mut directory = vec![0u8; directory_len as usize];
+    reader
+        .read_exact(&mut directory)
+        .context("Truncated zip central directory")?;
+
+    let mut entries = Vec::with_capacity(count as usize);
+    let mut at = 0;
+    for _ in 0..count {
+        if directory.len() < at + 46 || u32_at(&directory, at) != CENTRAL_DIRECTORY_ENTRY {
+            anyhow::bail!("Corrupt zip central directory");
+        }
+        let name_len = u16_at(&directory, at + 28) as usize;
+        let extra_len = u16_at(&directory, at + 30) as usize;
+        let comment_len = u16_at(&directory, at + 32) as usize;
+        let name = directory
+            .get(at + 46..at + 46 + name_len)
+            .context("Corrupt zip central directory")?;
+        let name = String::from_utf8_lossy(name).into_owned();
+
+        if u16_at(&directory, at + 8) & 1 != 0 {
+            anyhow::bail!("Encrypted zip entry {:?} is not supported", name);
+        }
+        // Unix mode in the high half of the external attributes
+        let mode = u32_at(&directory, at + 38) >> 16;
+        entries.push(Entry {
+            method: u16_at(&directory, at + 10),
+            crc: u32_at(&directory, at + 16),
+            compressed_size: u32_at(&directory, at + 20) as u64,
+            size: u32_at(&directory, at + 24) as u64,
+            header_offset: u32_at(&directory, at + 42) as u64,
+            is_symlink: mode & 0o170000 == 0o120000,
+            name,
+        });
+        at += 46 + name_len + extra_len + comment_len;
+    }
+    Ok(entries)
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
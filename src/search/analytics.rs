@@ -0,0 +1,79 @@
+//! Opt-in, local-only log of searches for tuning retrieval.
+//!
+//! Enabled with `analytics = true` under `[search.search]`. Each search appends
+//! one JSON line to `<output>/analytics/searches.jsonl`; nothing leaves the machine.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::store::SearchResult;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchRecord {
+    pub timestamp: DateTime<Utc>,
+    pub query: String,
+    /// "vector", "hybrid" or "grep"
+    pub mode: String,
+    /// Chunk ids in ranked order
+    pub result_ids: Vec<String>,
+    pub latency_ms: u64,
+}
+
+impl SearchRecord {
+    pub fn new(query: &str, mode: &str, results: &[SearchResult], latency: Duration) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            query: query.to_string(),
+            mode: mode.to_string(),
+            result_ids: results.iter().map(|r| r.chunk.id.clone()).collect(),
+            latency_ms: latency.as_millis() as u64,
+        }
+    }
+}
+
+pub struct SearchAnalytics {
+    file: PathBuf,
+}
+
+impl SearchAnalytics {
+    pub fn new(output_path: &Path) -> Self {
+        Self {
+            file: output_path.join("analytics").join("searches.jsonl"),
+        }
+    }
+
+    /// The logger for `output_path`, or `None` unless analytics are enabled
+    pub fn when_enabled(output_path: &Path, enabled: bool) -> Option<Self> {
+        enabled.then(|| Self::new(output_path))
+    }
+
+    pub fn append(&self, record: &SearchRecord) -> Result<()> {
+        if let Some(dir) = self.file.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.file)?;
+
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+        Ok(())
+    }
+
+    /// Append a record for one search; failures are reported, never fatal
+    pub fn record(&self, query: &str, mode: &str, results: &[SearchResult], latency: Duration) {
+        let record = SearchRecord::new(query, mode, results, latency);
+        if let Err(e) = self.append(&record) {
+            eprintln!("[analytics] Failed to write record: {}", e);
+        }
+    }
+
+    pub fn file_path(&self) -> &Path {
+        &self.file
+    }
+}
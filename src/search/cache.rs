@@ -0,0 +1,98 @@
+//! Bounded LRU cache of search results for repeated queries.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use super::store::SearchResult;
+
+/// Everything that determines a search's results. `index_modified` ties an
+/// entry to one version of the index file, so rebuilding it invalidates the cache.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheKey {
+    pub query: String,
+    pub limit: usize,
+    pub hybrid: bool,
+    pub collection: Option<String>,
+    pub focus: Option<String>,
+    pub index_modified: SystemTime,
+}
+
+pub struct ResultCache {
+    capacity: usize,
+    entries: Mutex<VecDeque<(CacheKey, Vec<SearchResult>)>>,
+}
+
+impl ResultCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Look up a key, marking it as most recently used
+    pub fn get(&self, key: &CacheKey) -> Option<Vec<SearchResult>> {
+        let mut entries = self.entries.lock().ok()?;
+        let pos = entries.iter().position(|(k, _)| k == key)?;
+        let entry = entries.remove(pos)?;
+        let results = entry.1.clone();
+        entries.push_back(entry);
+        Some(results)
+    }
+
+    /// Insert results, evicting the least recently used entry when full
+    pub fn insert(&self, key: CacheKey, results: Vec<SearchResult>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        entries.retain(|(k, _)| *k != key);
+        while entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back((key, results));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().map(|e| e.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(query: &str) -> CacheKey {
+        CacheKey {
+            query: query.to_string(),
+            limit: 10,
+            hybrid: false,
+            collection: None,
+            focus: None,
+            index_modified: SystemTime::UNIX_EPOCH,
+        }
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = ResultCache::new(2);
+        cache.insert(key("a"), vec![]);
+        cache.insert(key("b"), vec![]);
+
+        // Touch "a" so "b" becomes the eviction candidate
+        assert!(cache.get(&key("a")).is_some());
+        cache.insert(key("c"), vec![]);
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&key("a")).is_some());
+        assert!(cache.get(&key("b")).is_none());
+        assert!(cache.get(&key("c")).is_some());
+    }
+}
@@ -1,5 +1,9 @@
-use crate::types::{FileEntry, Symbol, SymbolKind};
+use crate::config::ChunkingConfig;
+use crate::telemetry::TokenCounter;
+use crate::types::{FileEntry, Symbol, SymbolKind, Visibility};
+use serde::Serialize;
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 
 use super::store::ChunkType;
 
@@ -13,11 +17,51 @@ pub struct ChunkInfo {
     pub content: String,
     pub hash: String,
     pub chunk_type: ChunkType,
+    pub symbol_name: Option<String>,
+    pub symbol_kind: Option<SymbolKind>,
+    pub origin: ChunkOrigin,
 }
 
+/// How the chunker arrived at a chunk's boundaries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChunkOrigin {
+    /// One whole symbol
+    Symbol,
+    /// Sliding window over a file without usable symbols
+    Window,
+    /// Piece of a symbol too large for one chunk
+    Split,
+    /// Summary of a whole module rather than a span of one file
+    Module,
+    /// One whole markdown section, from a heading to the next
+    Section,
+}
+
+impl std::fmt::Display for ChunkOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChunkOrigin::Symbol => write!(f, "symbol"),
+            ChunkOrigin::Window => write!(f, "window"),
+            ChunkOrigin::Split => write!(f, "split"),
+            ChunkOrigin::Module => write!(f, "module"),
+            ChunkOrigin::Section => write!(f, "section"),
+        }
+    }
+}
+
+/// Line length assumed when converting a char overlap to lines
+const AVG_LINE_CHARS: usize = 80;
+
 pub struct Chunker {
     max_chars: usize,
-    overlap_chars: usize,
+    /// Rows each char-based window repeats from the end of the previous one
+    overlap_lines: usize,
+    max_tokens: usize,
+    overlap_tokens: usize,
+    /// Measures chunks in real tokens; without it sizes are judged in chars
+    tokenizer: Option<TokenCounter>,
+    min_visibility: Option<Visibility>,
 }
 
 impl Default for Chunker {
@@ -27,16 +71,66 @@ impl Default for Chunker {
 }
 
 impl Chunker {
+    /// Chunker overlapping windows by about `overlap_chars`, rounded up to
+    /// whole lines so any non-zero overlap repeats at least one line
     pub fn new(max_chars: usize, overlap_chars: usize) -> Self {
+        Self {
+            overlap_tokens: overlap_chars / 4,
+            ..Self::with_overlap_lines(max_chars, overlap_chars.div_ceil(AVG_LINE_CHARS))
+        }
+    }
+
+    /// Chunker whose windows repeat exactly `overlap_lines` rows of the previous window
+    pub fn with_overlap_lines(max_chars: usize, overlap_lines: usize) -> Self {
         Self {
             max_chars,
-            overlap_chars,
+            overlap_lines,
+            max_tokens: max_chars / 4,
+            overlap_tokens: overlap_lines * AVG_LINE_CHARS / 4,
+            tokenizer: None,
+            min_visibility: None,
         }
     }
 
     /// Create chunker from token config (rough conversion: 1 token ~ 4 chars)
     pub fn from_tokens(max_tokens: usize, overlap_tokens: usize) -> Self {
-        Self::new(max_tokens * 4, overlap_tokens * 4)
+        Self {
+            max_tokens,
+            overlap_tokens,
+            ..Self::new(max_tokens * 4, overlap_tokens * 4)
+        }
+    }
+
+    /// Chunker for `config`, counting real tokens when it names a tokenizer
+    pub fn from_config(config: &ChunkingConfig) -> Self {
+        let chunker = Self::from_tokens(config.max_tokens, config.overlap_tokens);
+        match config.tokenizer {
+            Some(encoding) => chunker.with_tokenizer(TokenCounter::with_encoding(encoding)),
+            None => chunker,
+        }
+    }
+
+    /// Measure chunks with `tokenizer` so no chunk, header included, exceeds
+    /// `max_tokens`. A single line longer than the budget is truncated.
+    pub fn with_tokenizer(mut self, tokenizer: TokenCounter) -> Self {
+        self.tokenizer = Some(tokenizer);
+        self
+    }
+
+    /// Tokens in `text`: counted when a tokenizer is set, else estimated at 4 chars each
+    pub fn count_tokens(&self, text: &str) -> usize {
+        match &self.tokenizer {
+            Some(tokenizer) => tokenizer.count(text),
+            None => text.len().div_ceil(4),
+        }
+    }
+
+    /// Only chunk symbols visible at least as widely as `min`. Bodies of less
+    /// visible symbols are left out, even when nested in an included class,
+    /// and files are never window-chunked, so private code is never embedded.
+    pub fn with_min_visibility(mut self, min: Visibility) -> Self {
+        self.min_visibility = Some(min);
+        self
     }
 
     /// Chunk by symbols (functions, classes) - preferred for code
@@ -48,6 +142,8 @@ impl Chunker {
     ) -> Vec<ChunkInfo> {
         let mut chunks = Vec::new();
         let lines: Vec<&str> = content.lines().collect();
+        // Occurrences of each symbol ID so far, to tell identical symbols apart
+        let mut seen_ids: HashMap<String, usize> = HashMap::new();
 
         // Process function-level symbols
         let functions: Vec<&Symbol> = symbols
@@ -59,10 +155,19 @@ impl Chunker {
                         | SymbolKind::Method
                         | SymbolKind::Class
                         | SymbolKind::Struct
+                        | SymbolKind::Test
                 )
             })
             .collect();
 
+        let (functions, hidden): (Vec<&Symbol>, Vec<&Symbol>) =
+            functions
+                .into_iter()
+                .partition(|s| match self.min_visibility {
+                    Some(min) => s.visibility.is_at_least(min),
+                    None => true,
+                });
+
         for symbol in functions {
             let start_idx = symbol.line_range.start.saturating_sub(1);
             let end_idx = symbol.line_range.end.min(lines.len());
@@ -71,50 +176,64 @@ impl Chunker {
                 continue;
             }
 
-            let chunk_lines: Vec<&str> = lines[start_idx..end_idx].to_vec();
+            // Drop the lines of hidden symbols nested inside this one
+            let chunk_lines: Vec<&str> = (start_idx..end_idx)
+                .filter(|&i| {
+                    !hidden.iter().any(|h| {
+                        h.line_range.start > symbol.line_range.start
+                            && h.line_range.start <= i + 1
+                            && i < h.line_range.end
+                    })
+                })
+                .map(|i| lines[i])
+                .collect();
             let chunk_content = chunk_lines.join("\n");
 
             if chunk_content.trim().is_empty() {
                 continue;
             }
 
+            let header = format!(
+                "File: {}\nSymbol: {} ({})\nLines: {}-{}\n\n",
+                file.relative_path,
+                symbol.name,
+                symbol.kind,
+                start_idx + 1,
+                end_idx
+            );
+
             // If chunk is too large, split it
-            if chunk_content.len() > self.max_chars {
+            if self.too_large(&header, &chunk_content) {
                 let sub_chunks = self.split_large_chunk(
                     &file.relative_path,
                     &chunk_content,
                     start_idx + 1,
                     symbol_to_chunk_type(symbol.kind),
+                    Some(symbol),
+                    None,
                 );
                 chunks.extend(sub_chunks);
             } else {
-                let formatted = format!(
-                    "File: {}\nSymbol: {} ({})\nLines: {}-{}\n\n{}",
-                    file.relative_path,
-                    symbol.name,
-                    symbol.kind,
-                    start_idx + 1,
-                    end_idx,
-                    chunk_content
-                );
+                let formatted = format!("{}{}", header, chunk_content);
+                let hash = hash_content(&chunk_content);
 
                 chunks.push(ChunkInfo {
-                    id: format!(
-                        "{}:{}:{}",
-                        file.relative_path, symbol.name, symbol.line_range.start
-                    ),
+                    id: symbol_chunk_id(&mut seen_ids, &file.relative_path, &symbol.name, &hash),
                     file_path: file.relative_path.clone(),
                     start_line: start_idx + 1,
                     end_line: end_idx,
                     content: formatted,
-                    hash: hash_content(&chunk_content),
+                    hash,
                     chunk_type: symbol_to_chunk_type(symbol.kind),
+                    symbol_name: Some(symbol.name.clone()),
+                    symbol_kind: Some(symbol.kind),
+                    origin: ChunkOrigin::Symbol,
                 });
             }
         }
 
         // If no symbol chunks, fall back to window-based chunking
-        if chunks.is_empty() {
+        if chunks.is_empty() && self.min_visibility.is_none() {
             chunks = self.chunk_by_window(file, content);
         }
 
@@ -123,55 +242,102 @@ impl Chunker {
 
     /// Fallback: sliding window chunking for files without symbols
     pub fn chunk_by_window(&self, file: &FileEntry, content: &str) -> Vec<ChunkInfo> {
-        let mut chunks = Vec::new();
         let lines: Vec<&str> = content.lines().collect();
+        let header = |start: usize, end: usize| {
+            format!("File: {}\nLines: {}-{}\n\n", file.relative_path, start, end)
+        };
+
+        self.windows(&lines, 1, &header)
+            .into_iter()
+            .filter(|w| !w.content.trim().is_empty())
+            .map(|w| ChunkInfo {
+                id: format!("{}:block:{}", file.relative_path, w.start_line),
+                file_path: file.relative_path.clone(),
+                start_line: w.start_line,
+                end_line: w.end_line,
+                content: format!("{}{}", header(w.start_line, w.end_line), w.content),
+                hash: hash_content(&w.content),
+                chunk_type: ChunkType::Block,
+                symbol_name: None,
+                symbol_kind: None,
+                origin: ChunkOrigin::Window,
+            })
+            .collect()
+    }
 
-        if lines.is_empty() {
-            return chunks;
-        }
-
-        let mut start_line = 0;
-
-        while start_line < lines.len() {
-            let mut current_len = 0;
-            let mut end_line = start_line;
+    /// Chunk a markdown document on heading boundaries, one chunk per section.
+    /// Only sections longer than `max_chars` are split further. Headings
+    /// inside fenced code blocks are ignored.
+    pub fn chunk_by_markdown(&self, file: &FileEntry, content: &str) -> Vec<ChunkInfo> {
+        let lines: Vec<&str> = content.lines().collect();
 
-            // Accumulate lines until max_chars
-            while end_line < lines.len() && current_len < self.max_chars {
-                current_len += lines[end_line].len() + 1; // +1 for newline
-                end_line += 1;
+        // (start line index, heading path) of each section
+        let mut sections: Vec<(usize, Option<String>)> = vec![(0, None)];
+        let mut headings: Vec<(usize, &str)> = Vec::new();
+        let mut fence: Option<&str> = None;
+        for (i, line) in lines.iter().enumerate() {
+            let trimmed = line.trim_start();
+            if let Some(open) = fence {
+                if trimmed.starts_with(open) {
+                    fence = None;
+                }
+                continue;
             }
-
-            let chunk_lines: Vec<&str> = lines[start_line..end_line].to_vec();
-            let chunk_content = chunk_lines.join("\n");
-
-            if !chunk_content.trim().is_empty() {
-                let formatted = format!(
-                    "File: {}\nLines: {}-{}\n\n{}",
-                    file.relative_path,
-                    start_line + 1,
-                    end_line,
-                    chunk_content
-                );
-
-                chunks.push(ChunkInfo {
-                    id: format!("{}:block:{}", file.relative_path, start_line + 1),
-                    file_path: file.relative_path.clone(),
-                    start_line: start_line + 1,
-                    end_line,
-                    content: formatted,
-                    hash: hash_content(&chunk_content),
-                    chunk_type: ChunkType::Block,
-                });
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                fence = Some(&trimmed[..3]);
+                continue;
+            }
+            let Some((level, title)) = parse_heading(line) else {
+                continue;
+            };
+            while headings.last().is_some_and(|(l, _)| *l >= level) {
+                headings.pop();
             }
+            headings.push((level, title));
+            let path: Vec<&str> = headings.iter().map(|(_, t)| *t).collect();
+            sections.push((i, Some(path.join(" > "))));
+        }
 
-            // Move forward with overlap
-            let overlap_lines = self.overlap_chars / 80; // Assume ~80 chars per line
-            start_line = end_line.saturating_sub(overlap_lines);
+        let mut chunks = Vec::new();
+        for (n, (start, section)) in sections.iter().enumerate() {
+            let end = sections.get(n + 1).map_or(lines.len(), |(next, _)| *next);
+            let chunk_content = lines[*start..end].join("\n");
+            if chunk_content.trim().is_empty() {
+                continue;
+            }
 
-            if start_line >= lines.len() || end_line >= lines.len() {
-                break;
+            let header = format!(
+                "File: {}\n{}Lines: {}-{}\n\n",
+                file.relative_path,
+                section_line(section.as_deref()),
+                start + 1,
+                end
+            );
+            if self.too_large(&header, &chunk_content) {
+                chunks.extend(self.split_large_chunk(
+                    &file.relative_path,
+                    &chunk_content,
+                    start + 1,
+                    ChunkType::Block,
+                    None,
+                    section.as_deref(),
+                ));
+                continue;
             }
+
+            let formatted = format!("{}{}", header, chunk_content);
+            chunks.push(ChunkInfo {
+                id: format!("{}:section:{}", file.relative_path, start + 1),
+                file_path: file.relative_path.clone(),
+                start_line: start + 1,
+                end_line: end,
+                content: formatted,
+                hash: hash_content(&chunk_content),
+                chunk_type: ChunkType::Block,
+                symbol_name: section.clone(),
+                symbol_kind: None,
+                origin: ChunkOrigin::Section,
+            });
         }
 
         chunks
@@ -184,58 +350,170 @@ impl Chunker {
         content: &str,
         base_line: usize,
         chunk_type: ChunkType,
+        symbol: Option<&Symbol>,
+        section: Option<&str>,
     ) -> Vec<ChunkInfo> {
-        let mut chunks = Vec::new();
         let lines: Vec<&str> = content.lines().collect();
+        let header = |start: usize, end: usize| {
+            format!(
+                "File: {}\n{}Lines: {}-{}\n\n",
+                file_path,
+                section_line(section),
+                start,
+                end
+            )
+        };
+
+        self.windows(&lines, base_line, &header)
+            .into_iter()
+            .filter(|w| !w.content.trim().is_empty())
+            .map(|w| ChunkInfo {
+                id: format!("{}:split:{}", file_path, w.start_line),
+                file_path: file_path.to_string(),
+                start_line: w.start_line,
+                end_line: w.end_line,
+                content: format!("{}{}", header(w.start_line, w.end_line), w.content),
+                hash: hash_content(&w.content),
+                chunk_type,
+                symbol_name: symbol
+                    .map(|s| s.name.clone())
+                    .or_else(|| section.map(str::to_string)),
+                symbol_kind: symbol.map(|s| s.kind),
+                origin: ChunkOrigin::Split,
+            })
+            .collect()
+    }
+
+    /// Whether `content`, embedded under `header`, is over the chunk budget
+    fn too_large(&self, header: &str, content: &str) -> bool {
+        match &self.tokenizer {
+            Some(tokenizer) => tokenizer.count(&format!("{}{}", header, content)) > self.max_tokens,
+            None => content.len() > self.max_chars,
+        }
+    }
+
+    /// Cut `lines` into overlapping windows within the chunk budget. Line
+    /// numbers start at `base_line`; `header` formats a window's header
+    /// from its first and last line number.
+    fn windows(
+        &self,
+        lines: &[&str],
+        base_line: usize,
+        header: &dyn Fn(usize, usize) -> String,
+    ) -> Vec<Window> {
+        match &self.tokenizer {
+            Some(tokenizer) => self.token_windows(tokenizer, lines, base_line, header),
+            None => self.char_windows(lines, base_line),
+        }
+    }
+
+    /// Windows of roughly `max_chars`, each repeating the last `overlap_lines`
+    /// rows of the one before
+    fn char_windows(&self, lines: &[&str], base_line: usize) -> Vec<Window> {
+        let mut windows = Vec::new();
         let mut start = 0;
 
         while start < lines.len() {
             let mut current_len = 0;
             let mut end = start;
 
+            // Accumulate lines until max_chars
             while end < lines.len() && current_len < self.max_chars {
-                current_len += lines[end].len() + 1;
+                current_len += lines[end].len() + 1; // +1 for newline
                 end += 1;
             }
 
-            let chunk_lines = &lines[start..end];
-            let chunk_content = chunk_lines.join("\n");
+            windows.push(Window {
+                start_line: base_line + start,
+                end_line: base_line + end - 1,
+                content: lines[start..end].join("\n"),
+            });
 
-            if !chunk_content.trim().is_empty() {
-                let start_line = base_line + start;
-                let end_line = base_line + end - 1;
+            if end >= lines.len() {
+                break;
+            }
+            // Move forward with overlap, but always move forward
+            start = end.saturating_sub(self.overlap_lines).max(start + 1);
+        }
 
-                let formatted = format!(
-                    "File: {}\nLines: {}-{}\n\n{}",
-                    file_path, start_line, end_line, chunk_content
-                );
+        windows
+    }
 
-                chunks.push(ChunkInfo {
-                    id: format!("{}:split:{}", file_path, start_line),
-                    file_path: file_path.to_string(),
-                    start_line,
-                    end_line,
-                    content: formatted,
-                    hash: hash_content(&chunk_content),
-                    chunk_type: chunk_type.clone(),
-                });
+    /// Windows of at most `max_tokens` including the header, overlapping by at
+    /// most `overlap_tokens`
+    fn token_windows(
+        &self,
+        tokenizer: &TokenCounter,
+        lines: &[&str],
+        base_line: usize,
+        header: &dyn Fn(usize, usize) -> String,
+    ) -> Vec<Window> {
+        let mut windows = Vec::new();
+        // Per-line counts pick a window cheaply; the joined text is then counted exactly
+        let line_tokens: Vec<usize> = lines.iter().map(|l| tokenizer.count(l) + 1).collect();
+        let header_budget = tokenizer.count(&header(base_line, base_line + lines.len()));
+        let budget = self.max_tokens.saturating_sub(header_budget);
+        let mut start = 0;
+
+        while start < lines.len() {
+            let mut used = 0;
+            let mut end = start;
+            while end < lines.len() && (end == start || used + line_tokens[end] <= budget) {
+                used += line_tokens[end];
+                end += 1;
+            }
+
+            let mut content = lines[start..end].join("\n");
+            loop {
+                let header = header(base_line + start, base_line + end - 1);
+                let total = tokenizer.count(&format!("{}{}", header, content));
+                if total <= self.max_tokens {
+                    break;
+                }
+                if end > start + 1 {
+                    end -= 1;
+                    content = lines[start..end].join("\n");
+                } else {
+                    // One line alone is over budget
+                    let room = self.max_tokens.saturating_sub(tokenizer.count(&header));
+                    content = tokenizer.truncate(&content, room);
+                    break;
+                }
             }
 
-            let overlap_lines = self.overlap_chars / 80;
-            start = end.saturating_sub(overlap_lines);
+            windows.push(Window {
+                start_line: base_line + start,
+                end_line: base_line + end - 1,
+                content,
+            });
 
-            if start >= lines.len() {
+            if end >= lines.len() {
                 break;
             }
+            // Step back over trailing lines that fit in the overlap
+            let mut next = end;
+            let mut overlap = 0;
+            while next > start + 1 && overlap + line_tokens[next - 1] <= self.overlap_tokens {
+                overlap += line_tokens[next - 1];
+                next -= 1;
+            }
+            start = next;
         }
 
-        chunks
+        windows
     }
 }
 
+/// Lines of a window chunk, before the header is added
+struct Window {
+    start_line: usize,
+    end_line: usize,
+    content: String,
+}
+
 fn symbol_to_chunk_type(kind: SymbolKind) -> ChunkType {
     match kind {
-        SymbolKind::Function => ChunkType::Function,
+        SymbolKind::Function | SymbolKind::Test => ChunkType::Function,
         SymbolKind::Method => ChunkType::Method,
         SymbolKind::Class | SymbolKind::Struct => ChunkType::Class,
         SymbolKind::Module => ChunkType::Module,
@@ -243,6 +521,45 @@ fn symbol_to_chunk_type(kind: SymbolKind) -> ChunkType {
     }
 }
 
+/// Level and title of an ATX heading such as `## Installation`
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    let title = rest.trim().trim_end_matches('#').trim_end();
+    (!title.is_empty()).then_some((level, title))
+}
+
+/// Header line naming the markdown section a chunk belongs to
+fn section_line(section: Option<&str>) -> String {
+    section
+        .map(|s| format!("Section: {}\n", s))
+        .unwrap_or_default()
+}
+
+/// `{file}:{symbol}:{hash}`, so a symbol keeps its ID while its body is
+/// unchanged, wherever it moves in the file. Later symbols with the same name
+/// and body get `:2`, `:3`, ... in file order.
+fn symbol_chunk_id(
+    seen: &mut HashMap<String, usize>,
+    file_path: &str,
+    symbol_name: &str,
+    hash: &str,
+) -> String {
+    let id = format!("{}:{}:{}", file_path, symbol_name, hash);
+    let count = seen.entry(id.clone()).or_insert(0);
+    *count += 1;
+    match *count {
+        1 => id,
+        n => format!("{}:{}", id, n),
+    }
+}
+
 fn hash_content(content: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(content.as_bytes());
@@ -305,6 +622,123 @@ mod tests {
         assert_eq!(chunks.len(), 2);
         assert!(chunks[0].content.contains("foo"));
         assert!(chunks[1].content.contains("bar"));
+        assert_eq!(chunks[0].symbol_name.as_deref(), Some("foo"));
+        assert_eq!(chunks[0].symbol_kind, Some(SymbolKind::Function));
+    }
+
+    #[test]
+    fn test_symbol_ids_survive_line_shifts() {
+        let chunker = Chunker::new(500, 50);
+        let file = make_file("test.rs", 20);
+        let foo = |line: usize| {
+            Symbol::new(
+                SymbolKind::Function,
+                "foo".to_string(),
+                line,
+                Visibility::Public,
+            )
+            .with_line_range(line, line + 2)
+        };
+        let body = "fn foo() {\n    run();\n}";
+
+        let before = chunker.chunk_by_symbols(&file, body, &[foo(1)]);
+        let shifted = format!("use std::io;\n\n{}", body);
+        let after = chunker.chunk_by_symbols(&file, &shifted, &[foo(3)]);
+
+        assert_eq!(before[0].id, after[0].id);
+        assert_eq!(before[0].id, format!("test.rs:foo:{}", before[0].hash));
+        assert_eq!(after[0].start_line, 3);
+
+        // Identical same-named symbols (e.g. in two impl blocks) stay distinct
+        let twice = format!("{}\n{}", body, body);
+        let chunks = chunker.chunk_by_symbols(&file, &twice, &[foo(1), foo(4)]);
+        assert_eq!(chunks[1].id, format!("{}:2", chunks[0].id));
+    }
+
+    #[test]
+    fn test_min_visibility_skips_private_symbols() {
+        let chunker = Chunker::new(500, 50).with_min_visibility(Visibility::Public);
+        let file = make_file("test.rs", 20);
+        let content = "pub struct Api {\n\
+                       \x20   key: String,\n\
+                       }\n\
+                       \n\
+                       pub fn open() {\n\
+                       \x20   secret_sauce();\n\
+                       }\n\
+                       \n\
+                       fn secret_sauce() {\n\
+                       \x20   let formula = 42;\n\
+                       }";
+
+        let symbols = vec![
+            Symbol::new(SymbolKind::Struct, "Api".to_string(), 1, Visibility::Public)
+                .with_line_range(1, 3),
+            Symbol::new(
+                SymbolKind::Function,
+                "open".to_string(),
+                5,
+                Visibility::Public,
+            )
+            .with_line_range(5, 7),
+            Symbol::new(
+                SymbolKind::Function,
+                "secret_sauce".to_string(),
+                9,
+                Visibility::Private,
+            )
+            .with_line_range(9, 11),
+        ];
+
+        let chunks = chunker.chunk_by_symbols(&file, content, &symbols);
+        let names: Vec<_> = chunks
+            .iter()
+            .filter_map(|c| c.symbol_name.as_deref())
+            .collect();
+        assert_eq!(names, vec!["Api", "open"]);
+        assert!(chunks.iter().all(|c| !c.content.contains("formula")));
+
+        // A file with only private symbols yields nothing, not window chunks
+        let chunks = chunker.chunk_by_symbols(&file, content, &symbols[2..]);
+        assert!(chunks.is_empty());
+    }
+
+    #[test]
+    fn test_min_visibility_drops_private_methods_inside_public_class() {
+        let chunker = Chunker::new(500, 50).with_min_visibility(Visibility::Public);
+        let file = make_file("Api.java", 10);
+        let content = "public class Api {\n\
+                       \x20   public void open() {}\n\
+                       \x20   private void leak() {\n\
+                       \x20       String token = \"s3cr3t\";\n\
+                       \x20   }\n\
+                       }";
+
+        let symbols = vec![
+            Symbol::new(SymbolKind::Class, "Api".to_string(), 1, Visibility::Public)
+                .with_line_range(1, 6),
+            Symbol::new(
+                SymbolKind::Method,
+                "leak".to_string(),
+                3,
+                Visibility::Private,
+            )
+            .with_line_range(3, 5),
+        ];
+
+        let chunks = chunker.chunk_by_symbols(&file, content, &symbols);
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].content.contains("public void open()"));
+        assert!(!chunks[0].content.contains("s3cr3t"));
+    }
+
+    #[test]
+    fn test_window_chunks_have_no_symbol() {
+        let chunker = Chunker::new(100, 20);
+        let file = make_file("notes.txt", 3);
+
+        let chunks = chunker.chunk_by_window(&file, "one\ntwo\nthree");
+        assert!(chunks.iter().all(|c| c.symbol_name.is_none()));
     }
 
     #[test]
@@ -317,4 +751,143 @@ mod tests {
         assert_ne!(hash1, hash3);
         assert_eq!(hash1.len(), 16);
     }
+
+    #[test]
+    fn test_chunk_by_markdown_splits_on_headings() {
+        let chunker = Chunker::new(500, 50);
+        let file = make_file("README.md", 12);
+        let content = "Intro text.\n\
+                       # Install\n\
+                       Run the installer.\n\
+                       ## Requirements\n\
+                       Rust 1.80.\n\
+                       ```sh\n\
+                       # not a heading\n\
+                       ```\n\
+                       # Usage\n\
+                       Run it.";
+
+        let chunks = chunker.chunk_by_markdown(&file, content);
+
+        let spans: Vec<(usize, usize)> =
+            chunks.iter().map(|c| (c.start_line, c.end_line)).collect();
+        assert_eq!(spans, vec![(1, 1), (2, 3), (4, 8), (9, 10)]);
+        assert_eq!(
+            chunks[2].symbol_name.as_deref(),
+            Some("Install > Requirements")
+        );
+        assert!(chunks[2]
+            .content
+            .starts_with("File: README.md\nSection: Install > Requirements\nLines: 4-8"));
+        assert!(chunks[0].content.starts_with("File: README.md\nLines: 1-1"));
+        assert_eq!(chunks[3].symbol_name.as_deref(), Some("Usage"));
+        assert!(chunks.iter().all(|c| c.origin == ChunkOrigin::Section));
+    }
+
+    #[test]
+    fn test_chunk_by_markdown_splits_long_section() {
+        let chunker = Chunker::new(100, 0);
+        let file = make_file("docs/guide.md", 11);
+        let body: Vec<String> = (0..10)
+            .map(|i| format!("Paragraph line number {}.", i))
+            .collect();
+        let content = format!("## Setup\n{}", body.join("\n"));
+
+        let chunks = chunker.chunk_by_markdown(&file, &content);
+
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.origin == ChunkOrigin::Split));
+        assert!(chunks
+            .iter()
+            .all(|c| c.content.contains("Section: Setup\n")));
+        assert!(chunks.iter().all(|c| c.content.len() < 200));
+    }
+
+    #[test]
+    fn test_token_budget_never_exceeded() {
+        let budget = 64;
+        let counter = TokenCounter::new();
+        let chunker = Chunker::from_tokens(budget, 8).with_tokenizer(counter.clone());
+        let tokens = |c: &ChunkInfo| counter.count(&c.content);
+
+        // Symbol-less file with one line far longer than the budget
+        let mut content: String = (0..60)
+            .map(|i| format!("let value_{} = compute(\"{}\", {});\n", i, "x".repeat(i), i))
+            .collect();
+        content.push_str(&"minified();".repeat(200));
+        let file = make_file("data.rs", 61);
+        let chunks = chunker.chunk_by_window(&file, &content);
+        assert!(chunks.len() > 1);
+        assert!(
+            chunks.iter().all(|c| tokens(c) <= budget),
+            "window chunk over budget"
+        );
+
+        // One symbol too large for a single chunk
+        let body: String = (0..80)
+            .map(|i| format!("    total += item_{};\n", i))
+            .collect();
+        let content = format!("fn sum() {{\n{}}}\n", body);
+        let symbols = vec![Symbol::new(
+            SymbolKind::Function,
+            "sum".to_string(),
+            1,
+            Visibility::Public,
+        )
+        .with_line_range(1, 82)];
+        let chunks = chunker.chunk_by_symbols(&file, &content, &symbols);
+        assert!(chunks.iter().all(|c| c.origin == ChunkOrigin::Split));
+        assert!(
+            chunks.iter().all(|c| tokens(c) <= budget),
+            "split chunk over budget"
+        );
+        assert_eq!(chunks.last().unwrap().end_line, 82);
+
+        // Markdown sections
+        let doc = format!("# Guide\n{}", "Some words in a paragraph.\n".repeat(40));
+        let chunks = chunker.chunk_by_markdown(&make_file("README.md", 41), &doc);
+        assert!(
+            chunks.iter().all(|c| tokens(c) <= budget),
+            "section chunk over budget"
+        );
+    }
+
+    #[test]
+    fn test_split_with_overlap_terminates() {
+        let chunker = Chunker::new(200, 200);
+        let file = make_file("big.rs", 100);
+        let content: String = (0..100)
+            .map(|i| format!("    let v{} = {};\n", i, i))
+            .collect();
+
+        let chunks = chunker.split_large_chunk(
+            &file.relative_path,
+            &content,
+            1,
+            ChunkType::Block,
+            None,
+            None,
+        );
+
+        assert_eq!(chunks.last().unwrap().end_line, 100);
+        assert!(chunks.windows(2).all(|w| w[1].start_line > w[0].start_line));
+    }
+
+    #[test]
+    fn test_window_steps_back_exact_overlap_lines() {
+        let chunker = Chunker::with_overlap_lines(40, 2);
+        let file = make_file("notes.rs", 12);
+        let content: Vec<String> = (1..=12).map(|i| format!("line {:02}", i)).collect();
+
+        let chunks = chunker.chunk_by_window(&file, &content.join("\n"));
+
+        // 5 lines of 8 chars reach 40; each next window starts 2 rows back
+        let spans: Vec<(usize, usize)> =
+            chunks.iter().map(|c| (c.start_line, c.end_line)).collect();
+        assert_eq!(spans, vec![(1, 5), (4, 8), (7, 11), (10, 12)]);
+
+        // A small char overlap still repeats a line instead of rounding to zero
+        assert_eq!(Chunker::new(40, 10).overlap_lines, 1);
+        assert_eq!(Chunker::from_tokens(512, 50).overlap_lines, 3);
+    }
 }
@@ -0,0 +1,95 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::Embedder;
+
+/// Wraps an embedder and reshapes its vectors to a fixed dimension, padding
+/// with zeros or truncating.
+///
+/// This is a bridge for model transitions, not a substitute for re-indexing:
+/// zero padding keeps cosine similarity between vectors of the same model
+/// intact, but truncation drops information, and vectors from different
+/// models are still not meaningfully comparable even once their lengths match.
+pub struct DimensionAdapter<E: Embedder> {
+    inner: E,
+    target: usize,
+}
+
+impl<E: Embedder> DimensionAdapter<E> {
+    pub fn new(inner: E, target: usize) -> Self {
+        Self { inner, target }
+    }
+
+    fn reshape(&self, mut vector: Vec<f32>) -> Vec<f32> {
+        vector.resize(self.target, 0.0);
+        vector
+    }
+}
+
+#[async_trait]
+impl<E: Embedder> Embedder for DimensionAdapter<E> {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        Ok(self.reshape(self.inner.embed(text).await?))
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let vectors = self.inner.embed_batch(texts).await?;
+        Ok(vectors.into_iter().map(|v| self.reshape(v)).collect())
+    }
+
+    fn dimensions(&self) -> usize {
+        self.target
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.inner.health_check().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct ThreeDim;
+
+    #[async_trait]
+    impl Embedder for ThreeDim {
+        async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+            Ok(vec![0.1, 0.2, 0.3])
+        }
+
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            Ok(texts.iter().map(|_| vec![0.1, 0.2, 0.3]).collect())
+        }
+
+        fn dimensions(&self) -> usize {
+            3
+        }
+
+        async fn health_check(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pads_and_truncates_to_target() {
+        let padded = DimensionAdapter::new(ThreeDim, 5);
+        assert_eq!(padded.dimensions(), 5);
+        assert_eq!(
+            padded.embed("x").await.unwrap(),
+            vec![0.1, 0.2, 0.3, 0.0, 0.0]
+        );
+
+        let truncated = DimensionAdapter::new(ThreeDim, 2);
+        assert_eq!(truncated.dimensions(), 2);
+        let batch = truncated
+            .embed_batch(&["a".to_string(), "b".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(batch, vec![vec![0.1, 0.2], vec![0.1, 0.2]]);
+
+        // Same dimension passes vectors through untouched
+        let same = DimensionAdapter::new(ThreeDim, 3);
+        assert_eq!(same.embed("x").await.unwrap(), vec![0.1, 0.2, 0.3]);
+    }
+}
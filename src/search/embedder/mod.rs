@@ -1,9 +1,19 @@
+mod adapter;
 mod ollama;
+mod openai;
 
+pub use adapter::DimensionAdapter;
 pub use ollama::OllamaEmbedder;
+pub use openai::OpenAiEmbedder;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+use ollama::{DEFAULT_HEALTH_CHECK_TIMEOUT, DEFAULT_REQUEST_TIMEOUT};
+use openai::{api_key_for, OPENAI_ENDPOINT};
 
 #[async_trait]
 pub trait Embedder: Send + Sync {
@@ -13,12 +23,68 @@ pub trait Embedder: Send + Sync {
     async fn health_check(&self) -> Result<()>;
 }
 
+/// Length of the vectors `embedder` actually returns. This can differ from
+/// its configured `dimensions()`, e.g. after switching models without
+/// updating `search.embedder.dimensions`.
+pub async fn probe_dimensions(embedder: &dyn Embedder) -> Result<usize> {
+    Ok(embedder.embed("dimension probe").await?.len())
+}
+
+/// Error for an embedding server that could not be connected to at all, as
+/// opposed to one that answered with an error (e.g. an unknown model)
+#[derive(Debug)]
+pub struct EmbedderUnreachable(pub String);
+
+impl fmt::Display for EmbedderUnreachable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for EmbedderUnreachable {}
+
+/// Whether `err` (or an error it wraps) is an `EmbedderUnreachable`
+pub fn is_unreachable(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| cause.is::<EmbedderUnreachable>())
+}
+
 #[derive(Debug, Clone)]
 pub struct EmbedderConfig {
     pub provider: String,
     pub model: String,
     pub endpoint: Option<String>,
     pub dimensions: usize,
+    /// API key for the "openai" provider
+    pub api_key: Option<String>,
+    /// Environment variable holding the key, when `api_key` is unset. Without
+    /// either, `OPENAI_API_KEY` is used, but only for OpenAI's own API
+    pub api_key_env: Option<String>,
+    /// Seconds before an Ollama embedding request is abandoned (default 120)
+    pub timeout_secs: Option<u64>,
+    /// Named alternatives selectable with `with_profile`
+    pub profiles: Vec<EmbedderProfile>,
+}
+
+/// A named set of embedder settings, e.g. a fast local model for indexing
+/// and a stronger one for queries; unset fields keep the base config's value
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmbedderProfile {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
 }
 
 impl Default for EmbedderConfig {
@@ -28,18 +94,154 @@ impl Default for EmbedderConfig {
             model: "nomic-embed-text".to_string(),
             endpoint: None,
             dimensions: 768,
+            api_key: None,
+            api_key_env: None,
+            timeout_secs: None,
+            profiles: Vec::new(),
         }
     }
 }
 
+impl EmbedderConfig {
+    /// Apply per-invocation overrides (e.g. `--model`, `--embed-endpoint`)
+    pub fn with_overrides(mut self, model: Option<String>, endpoint: Option<String>) -> Self {
+        if let Some(model) = model {
+            self.model = model;
+        }
+        if endpoint.is_some() {
+            self.endpoint = endpoint;
+        }
+        self
+    }
+
+    /// These settings with those of the profile called `name` applied on top
+    pub fn with_profile(mut self, name: &str) -> Result<Self> {
+        let Some(profile) = self.profiles.iter().find(|p| p.name == name).cloned() else {
+            let known: Vec<&str> = self.profiles.iter().map(|p| p.name.as_str()).collect();
+            return Err(anyhow!(
+                "Unknown embedder profile '{}' (configured: {})",
+                name,
+                if known.is_empty() {
+                    "none".to_string()
+                } else {
+                    known.join(", ")
+                }
+            ));
+        };
+
+        if let Some(provider) = profile.provider {
+            self.provider = provider;
+        }
+        if let Some(model) = profile.model {
+            self.model = model;
+        }
+        if let Some(dimensions) = profile.dimensions {
+            self.dimensions = dimensions;
+        }
+        // The base key is for the base endpoint; a profile pointing elsewhere
+        // brings its own
+        if profile.endpoint.is_some() {
+            self.api_key = profile.api_key;
+            self.api_key_env = profile.api_key_env;
+        } else {
+            self.api_key = profile.api_key.or(self.api_key);
+            self.api_key_env = profile.api_key_env.or(self.api_key_env);
+        }
+        self.endpoint = profile.endpoint.or(self.endpoint);
+        self.timeout_secs = profile.timeout_secs.or(self.timeout_secs);
+        Ok(self)
+    }
+}
+
+/// Build the embedder for `config.provider` ("ollama" or "openai").
+/// Unknown providers fall back to Ollama.
 pub fn create_embedder(config: &EmbedderConfig) -> Box<dyn Embedder> {
-    let endpoint = config
-        .endpoint
-        .clone()
-        .unwrap_or_else(|| "http://localhost:11434".to_string());
-    Box::new(OllamaEmbedder::new(
-        &endpoint,
-        &config.model,
-        config.dimensions,
-    ))
+    match config.provider.as_str() {
+        "openai" => {
+            let endpoint = config
+                .endpoint
+                .clone()
+                .unwrap_or_else(|| OPENAI_ENDPOINT.to_string());
+            let api_key = api_key_for(
+                &endpoint,
+                config.api_key.as_deref(),
+                config.api_key_env.as_deref(),
+                |name| std::env::var(name).ok(),
+            );
+            Box::new(OpenAiEmbedder::new(
+                &endpoint,
+                &config.model,
+                config.dimensions,
+                api_key,
+            ))
+        }
+        _ => {
+            let endpoint = config
+                .endpoint
+                .clone()
+                .unwrap_or_else(|| "http://localhost:11434".to_string());
+            let request_timeout = config
+                .timeout_secs
+                .map_or(DEFAULT_REQUEST_TIMEOUT, Duration::from_secs);
+            Box::new(
+                OllamaEmbedder::new(&endpoint, &config.model, config.dimensions)
+                    .with_timeouts(request_timeout, DEFAULT_HEALTH_CHECK_TIMEOUT),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_overrides_only_its_fields() {
+        let config = EmbedderConfig {
+            endpoint: Some("http://gpu-box:11434".to_string()),
+            profiles: vec![EmbedderProfile {
+                name: "fast".to_string(),
+                model: Some("all-minilm".to_string()),
+                dimensions: Some(384),
+                ..EmbedderProfile::default()
+            }],
+            ..EmbedderConfig::default()
+        };
+
+        let fast = config.clone().with_profile("fast").unwrap();
+        assert_eq!(fast.provider, "ollama");
+        assert_eq!(fast.model, "all-minilm");
+        assert_eq!(fast.dimensions, 384);
+        assert_eq!(fast.endpoint.as_deref(), Some("http://gpu-box:11434"));
+
+        let error = config.clone().with_profile("best").unwrap_err();
+        assert!(error.to_string().contains("configured: fast"), "{}", error);
+    }
+
+    #[test]
+    fn test_profile_with_own_endpoint_drops_base_key() {
+        let config = EmbedderConfig {
+            provider: "openai".to_string(),
+            api_key: Some("sk-openai".to_string()),
+            profiles: vec![
+                EmbedderProfile {
+                    name: "local".to_string(),
+                    endpoint: Some("http://localhost:8080".to_string()),
+                    ..EmbedderProfile::default()
+                },
+                EmbedderProfile {
+                    name: "small".to_string(),
+                    model: Some("text-embedding-3-small".to_string()),
+                    ..EmbedderProfile::default()
+                },
+            ],
+            ..EmbedderConfig::default()
+        };
+
+        assert_eq!(config.clone().with_profile("local").unwrap().api_key, None);
+        assert_eq!(
+            config.with_profile("small").unwrap().api_key.as_deref(),
+            Some("sk-openai")
+        );
+    }
 }
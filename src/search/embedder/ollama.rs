@@ -2,17 +2,44 @@ use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
 use std::time::Duration;
+use tokio::time::Instant;
 
-use super::Embedder;
+use super::{Embedder, EmbedderUnreachable};
+
+/// Retries after the first failed request of a batch
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+/// Time allowed for one embedding request
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+/// Time allowed for the health check, which only lists installed models
+pub const DEFAULT_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Time one batch may spend on retries and bisection after its first attempt
+const DEFAULT_RETRY_BUDGET: Duration = Duration::from_secs(240);
 
 pub struct OllamaEmbedder {
     endpoint: String,
     model: String,
     dimensions: usize,
     client: Client,
+    max_retries: u32,
+    retry_base_delay: Duration,
+    request_timeout: Duration,
+    health_check_timeout: Duration,
+    retry_budget: Duration,
+}
+
+/// Why a request failed: transient failures (timeouts, 5xx) are retried,
+/// fatal ones (Ollama unreachable, model missing) are reported at once
+enum RequestError {
+    Transient(anyhow::Error),
+    Fatal(anyhow::Error),
 }
 
+type BatchFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<Vec<f32>>>> + Send + 'a>>;
+
 #[derive(Serialize)]
 struct EmbedRequest {
     model: String,
@@ -38,7 +65,6 @@ struct OllamaModel {
 impl OllamaEmbedder {
     pub fn new(endpoint: &str, model: &str, dimensions: usize) -> Self {
         let client = Client::builder()
-            .timeout(Duration::from_secs(120))
             .build()
             .expect("Failed to create HTTP client");
 
@@ -47,25 +73,112 @@ impl OllamaEmbedder {
             model: model.to_string(),
             dimensions,
             client,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_base_delay: DEFAULT_RETRY_BASE_DELAY,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            health_check_timeout: DEFAULT_HEALTH_CHECK_TIMEOUT,
+            retry_budget: DEFAULT_RETRY_BUDGET,
         }
     }
-}
 
-#[async_trait]
-impl Embedder for OllamaEmbedder {
-    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
-        let results = self.embed_batch(&[text.to_string()]).await?;
-        results
-            .into_iter()
-            .next()
-            .ok_or_else(|| anyhow!("No embedding returned"))
+    /// Give up on an embedding request after `request` (large batches on slow
+    /// hardware need more) and on the health check after `health_check`
+    pub fn with_timeouts(mut self, request: Duration, health_check: Duration) -> Self {
+        self.request_timeout = request;
+        self.health_check_timeout = health_check;
+        self
     }
 
-    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
-        if texts.is_empty() {
-            return Ok(Vec::new());
+    /// Retry a failing batch up to `max_retries` times, waiting `base_delay`
+    /// and doubling it after each attempt
+    pub fn with_retry(mut self, max_retries: u32, base_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_base_delay = base_delay;
+        self
+    }
+
+    /// Cap the time one batch spends on retries and bisection after its first
+    /// attempt, so a slow input cannot stall indexing for a timeout per retry
+    /// at every level of bisection
+    pub fn with_retry_budget(mut self, budget: Duration) -> Self {
+        self.retry_budget = budget;
+        self
+    }
+
+    /// Embed `texts`, splitting the batch in half when it keeps failing so one
+    /// bad input does not take the healthy ones down with it. Gives up at
+    /// `deadline`.
+    fn embed_bisecting<'a>(
+        &'a self,
+        texts: &'a [String],
+        offset: usize,
+        deadline: Instant,
+    ) -> BatchFuture<'a> {
+        Box::pin(async move {
+            match self.request_with_retry(texts, deadline).await {
+                Ok(embeddings) => Ok(embeddings),
+                Err(RequestError::Transient(e)) if Instant::now() >= deadline => {
+                    Err(e.context(format!(
+                        "Gave up embedding inputs {}-{} after the {}s retry budget",
+                        offset,
+                        offset + texts.len() - 1,
+                        self.retry_budget.as_secs_f32()
+                    )))
+                }
+                Err(RequestError::Transient(_)) if texts.len() > 1 => {
+                    let mid = texts.len() / 2;
+                    let mut embeddings = self
+                        .embed_bisecting(&texts[..mid], offset, deadline)
+                        .await?;
+                    embeddings.extend(
+                        self.embed_bisecting(&texts[mid..], offset + mid, deadline)
+                            .await?,
+                    );
+                    Ok(embeddings)
+                }
+                Err(RequestError::Transient(e)) => Err(e.context(format!(
+                    "Embedding input {} failed after {} retries",
+                    offset, self.max_retries
+                ))),
+                Err(RequestError::Fatal(e)) => Err(e),
+            }
+        })
+    }
+
+    async fn request_with_retry(
+        &self,
+        texts: &[String],
+        deadline: Instant,
+    ) -> Result<Vec<Vec<f32>>, RequestError> {
+        let mut delay = self.retry_base_delay;
+        let mut attempt = 0;
+
+        loop {
+            match self.request(texts, deadline).await {
+                Err(RequestError::Transient(_))
+                    if attempt < self.max_retries && Instant::now() + delay < deadline =>
+                {
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+                result => return result,
+            }
         }
+    }
 
+    /// One request, cut short at `deadline` if that comes before the request timeout
+    async fn request(
+        &self,
+        texts: &[String],
+        deadline: Instant,
+    ) -> Result<Vec<Vec<f32>>, RequestError> {
+        let timeout = self
+            .request_timeout
+            .min(deadline.saturating_duration_since(Instant::now()));
+        if timeout.is_zero() {
+            return Err(RequestError::Transient(anyhow!("Retry budget spent")));
+        }
         let request = EmbedRequest {
             model: self.model.clone(),
             input: texts.to_vec(),
@@ -75,19 +188,22 @@ impl Embedder for OllamaEmbedder {
         let response = self
             .client
             .post(format!("{}/api/embed", self.endpoint))
+            .timeout(timeout)
             .json(&request)
             .send()
             .await
             .map_err(|e| {
-                if e.is_connect() {
-                    anyhow!(
+                if e.is_timeout() {
+                    RequestError::Transient(timed_out(timeout))
+                } else if e.is_connect() {
+                    RequestError::Fatal(anyhow::Error::new(EmbedderUnreachable(format!(
                         "Cannot connect to Ollama at {}. Is Ollama running?\n\
                          Install: https://ollama.ai\n\
                          Start: ollama serve",
                         self.endpoint
-                    )
+                    ))))
                 } else {
-                    anyhow!("Ollama request failed: {}", e)
+                    RequestError::Transient(anyhow!("Ollama request failed: {}", e))
                 }
             })?;
 
@@ -96,19 +212,57 @@ impl Embedder for OllamaEmbedder {
             let body = response.text().await.unwrap_or_default();
 
             if status.as_u16() == 404 || body.contains("not found") {
-                return Err(anyhow!(
+                return Err(RequestError::Fatal(anyhow!(
                     "Model '{}' not found. Pull it with:\n  ollama pull {}",
                     self.model,
                     self.model
-                ));
+                )));
             }
 
-            return Err(anyhow!("Ollama error ({}): {}", status, body));
+            let error = anyhow!("Ollama error ({}): {}", status, body);
+            return Err(if status.is_server_error() || status.as_u16() == 429 {
+                RequestError::Transient(error)
+            } else {
+                RequestError::Fatal(error)
+            });
         }
 
-        let embed_response: EmbedResponse = response.json().await?;
+        let embed_response: EmbedResponse = response.json().await.map_err(|e| {
+            RequestError::Transient(if e.is_timeout() {
+                timed_out(timeout)
+            } else {
+                anyhow!("Invalid Ollama response: {}", e)
+            })
+        })?;
         Ok(embed_response.embeddings)
     }
+}
+
+fn timed_out(timeout: Duration) -> anyhow::Error {
+    anyhow!(
+        "Ollama did not answer within {}s; raise search.embedder.timeout_secs for slow hardware",
+        timeout.as_secs_f32()
+    )
+}
+
+#[async_trait]
+impl Embedder for OllamaEmbedder {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let results = self.embed_batch(&[text.to_string()]).await?;
+        results
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No embedding returned"))
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let deadline = Instant::now() + self.request_timeout + self.retry_budget;
+        self.embed_bisecting(texts, 0, deadline).await
+    }
 
     fn dimensions(&self) -> usize {
         self.dimensions
@@ -118,15 +272,24 @@ impl Embedder for OllamaEmbedder {
         let response = self
             .client
             .get(format!("{}/api/tags", self.endpoint))
+            .timeout(self.health_check_timeout)
             .send()
             .await
-            .map_err(|_| {
-                anyhow!(
-                    "Cannot connect to Ollama at {}. Is Ollama running?\n\
-                     Install: https://ollama.ai\n\
-                     Start: ollama serve",
-                    self.endpoint
-                )
+            .map_err(|e| {
+                if e.is_timeout() {
+                    anyhow!(
+                        "Ollama at {} did not respond within {}s. It may be hung; try restarting it.",
+                        self.endpoint,
+                        self.health_check_timeout.as_secs_f32()
+                    )
+                } else {
+                    anyhow!(
+                        "Cannot connect to Ollama at {}. Is Ollama running?\n\
+                         Install: https://ollama.ai\n\
+                         Start: ollama serve",
+                        self.endpoint
+                    )
+                }
             })?;
 
         if !response.status().is_success() {
@@ -150,3 +313,196 @@ impl Embedder for OllamaEmbedder {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{read_body, write_json_response};
+    use std::sync::{Arc, Mutex};
+    use std::time::Instant;
+    use tokio::net::TcpListener;
+
+    /// Fake `/api/embed` that records every batch it receives. It fails with
+    /// a 500 whenever the batch contains "poison", never answers a batch
+    /// containing "hang", and only answers "slow" when it is sent alone.
+    async fn spawn_server() -> (String, Arc<Mutex<Vec<Vec<String>>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&batches);
+
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let seen = Arc::clone(&seen);
+                tokio::spawn(async move {
+                    let body = read_body(&mut stream).await;
+                    let input: Vec<String> = serde_json::from_str::<serde_json::Value>(&body)
+                        .unwrap()["input"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|v| v.as_str().unwrap().to_string())
+                        .collect();
+                    seen.lock().unwrap().push(input.clone());
+
+                    let hangs = input.iter().any(|t| t == "hang")
+                        || (input.len() > 1 && input.iter().any(|t| t == "slow"));
+                    if hangs {
+                        tokio::time::sleep(Duration::from_secs(60)).await;
+                        return;
+                    }
+                    let (status, reply) = if input.iter().any(|t| t == "poison") {
+                        (
+                            "500 Internal Server Error",
+                            "{\"error\":\"boom\"}".to_string(),
+                        )
+                    } else {
+                        let embeddings: Vec<Vec<f32>> =
+                            input.iter().map(|t| vec![t.len() as f32]).collect();
+                        (
+                            "200 OK",
+                            serde_json::json!({ "embeddings": embeddings }).to_string(),
+                        )
+                    };
+                    write_json_response(&mut stream, status, &reply).await;
+                });
+            }
+        });
+
+        (endpoint, batches)
+    }
+
+    fn texts(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_failing_batch_is_bisected_down_to_the_bad_input() {
+        let (endpoint, batches) = spawn_server().await;
+        let embedder =
+            OllamaEmbedder::new(&endpoint, "test-model", 1).with_retry(1, Duration::from_millis(1));
+
+        let error = embedder
+            .embed_batch(&texts(&["a", "bb", "poison", "dddd"]))
+            .await
+            .unwrap_err();
+
+        assert!(format!("{:#}", error).contains("Embedding input 2 failed"));
+        let batches = batches.lock().unwrap();
+        // The healthy half went through on its own
+        assert!(batches.contains(&texts(&["a", "bb"])));
+        assert!(batches.contains(&texts(&["poison"])));
+        // Each failing request was retried once
+        let full_batch_attempts = batches.iter().filter(|b| b.len() == 4).count();
+        assert_eq!(full_batch_attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_healthy_batch_keeps_input_order() {
+        let (endpoint, _) = spawn_server().await;
+        let embedder = OllamaEmbedder::new(&endpoint, "test-model", 1);
+
+        let vectors = embedder
+            .embed_batch(&texts(&["a", "bb", "ccc"]))
+            .await
+            .unwrap();
+
+        assert_eq!(vectors, vec![vec![1.0], vec![2.0], vec![3.0]]);
+    }
+
+    #[tokio::test]
+    async fn test_connection_error_fails_fast() {
+        // Bind then drop to get a port nothing listens on
+        let port = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let embedder = OllamaEmbedder::new(&format!("http://127.0.0.1:{}", port), "m", 1)
+            .with_retry(3, Duration::from_secs(10));
+
+        let started = Instant::now();
+        let error = embedder.embed_batch(&texts(&["a", "b"])).await.unwrap_err();
+
+        assert!(error.to_string().contains("Cannot connect to Ollama"));
+        assert!(super::super::is_unreachable(&error));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_batch_is_bisected_and_the_rest_succeeds() {
+        let (endpoint, batches) = spawn_server().await;
+        let embedder = OllamaEmbedder::new(&endpoint, "test-model", 1)
+            .with_retry(1, Duration::from_millis(1))
+            .with_timeouts(Duration::from_millis(200), Duration::from_millis(100));
+
+        let vectors = embedder
+            .embed_batch(&texts(&["a", "slow", "ccc", "dddd"]))
+            .await
+            .unwrap();
+
+        assert_eq!(vectors, vec![vec![1.0], vec![4.0], vec![3.0], vec![4.0]]);
+        let batches = batches.lock().unwrap();
+        // The timed out batch was retried once before being split
+        let full_batch_attempts = batches.iter().filter(|b| b.len() == 4).count();
+        assert_eq!(full_batch_attempts, 2);
+        assert!(batches.contains(&texts(&["ccc", "dddd"])));
+        assert!(batches.contains(&texts(&["slow"])));
+    }
+
+    #[tokio::test]
+    async fn test_retry_budget_bounds_a_hanging_input() {
+        let (endpoint, _) = spawn_server().await;
+        let embedder = OllamaEmbedder::new(&endpoint, "test-model", 1)
+            .with_retry(3, Duration::from_millis(1))
+            .with_timeouts(Duration::from_millis(200), Duration::from_millis(100))
+            .with_retry_budget(Duration::from_millis(500));
+
+        let started = Instant::now();
+        let error = embedder
+            .embed_batch(&texts(&["a", "hang", "ccc", "dddd"]))
+            .await
+            .unwrap_err();
+
+        assert!(
+            format!("{:#}", error).contains("after the 0.5s retry budget"),
+            "{:#}",
+            error
+        );
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_hung_server_times_out() {
+        // Accepts connections but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut open = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                open.push(socket);
+            }
+        });
+        let embedder = OllamaEmbedder::new(&endpoint, "m", 1)
+            .with_retry(0, Duration::from_millis(1))
+            .with_timeouts(Duration::from_millis(200), Duration::from_millis(100));
+
+        let started = Instant::now();
+        let error = embedder.health_check().await.unwrap_err();
+        assert!(
+            error.to_string().contains("did not respond within 0.1s"),
+            "{}",
+            error
+        );
+
+        let error = embedder.embed_batch(&texts(&["a"])).await.unwrap_err();
+        assert!(
+            format!("{:#}", error).contains("did not answer within 0.2s"),
+            "{:#}",
+            error
+        );
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,230 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::{Embedder, EmbedderUnreachable};
+
+/// Embedder for OpenAI and servers exposing the same `/v1/embeddings` API
+pub struct OpenAiEmbedder {
+    endpoint: String,
+    model: String,
+    dimensions: usize,
+    api_key: Option<String>,
+    client: Client,
+}
+
+#[derive(Serialize)]
+struct EmbeddingsRequest {
+    model: String,
+    input: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+    #[serde(default)]
+    index: usize,
+}
+
+/// OpenAI's own API, the default endpoint and the only one `OPENAI_API_KEY`
+/// is sent to
+pub const OPENAI_ENDPOINT: &str = "https://api.openai.com";
+
+/// The key to send to `endpoint`: `api_key`, else the variable named by
+/// `api_key_env`, else `OPENAI_API_KEY` if `endpoint` is OpenAI's API, so a
+/// custom endpoint never receives that key unless configured to. `env` reads
+/// a variable.
+pub fn api_key_for(
+    endpoint: &str,
+    api_key: Option<&str>,
+    api_key_env: Option<&str>,
+    env: impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    let is_openai = reqwest::Url::parse(endpoint)
+        .is_ok_and(|url| url.scheme() == "https" && url.host_str() == Some("api.openai.com"));
+    let key = match (api_key, api_key_env) {
+        (Some(key), _) => Some(key.to_string()),
+        (None, Some(name)) => env(name),
+        (None, None) if is_openai => env("OPENAI_API_KEY"),
+        (None, None) => None,
+    };
+    key.filter(|key| !key.is_empty())
+}
+
+impl OpenAiEmbedder {
+    /// `api_key` is sent as a bearer token; without one, requests are sent
+    /// unauthenticated (fine for local servers). See `api_key_for`.
+    pub fn new(endpoint: &str, model: &str, dimensions: usize, api_key: Option<String>) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(120))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            dimensions,
+            api_key,
+            client,
+        }
+    }
+}
+
+#[async_trait]
+impl Embedder for OpenAiEmbedder {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let results = self.embed_batch(&[text.to_string()]).await?;
+        results
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No embedding returned"))
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let request = EmbeddingsRequest {
+            model: self.model.clone(),
+            input: texts.to_vec(),
+        };
+
+        let mut builder = self
+            .client
+            .post(format!("{}/v1/embeddings", self.endpoint))
+            .json(&request);
+        if let Some(key) = &self.api_key {
+            builder = builder.bearer_auth(key);
+        }
+
+        let response = builder.send().await.map_err(|e| {
+            if e.is_connect() {
+                anyhow::Error::new(EmbedderUnreachable(format!(
+                    "Cannot connect to embeddings API at {}",
+                    self.endpoint
+                )))
+            } else {
+                anyhow!("Embeddings request failed: {}", e)
+            }
+        })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+
+            if status.as_u16() == 401 {
+                return Err(anyhow!(
+                    "Embeddings API rejected the credentials. Set search.embedder.api_key or \
+                     api_key_env (OPENAI_API_KEY is only sent to {})",
+                    OPENAI_ENDPOINT
+                ));
+            }
+
+            return Err(anyhow!("Embeddings API error ({}): {}", status, body));
+        }
+
+        let mut response: EmbeddingsResponse = response.json().await?;
+        if response.data.len() != texts.len() {
+            return Err(anyhow!(
+                "Expected {} embeddings, got {}",
+                texts.len(),
+                response.data.len()
+            ));
+        }
+
+        // The API may return items out of order; `index` ties them to the input
+        response.data.sort_by_key(|d| d.index);
+        Ok(response.data.into_iter().map(|d| d.embedding).collect())
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        // A one-token embed validates the endpoint, the model and the credentials
+        self.embed("ok").await.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{read_request, write_json_response};
+    use tokio::net::TcpListener;
+
+    /// Answer one request with `body` and return the raw request text
+    async fn serve_once(listener: TcpListener, body: &'static str) -> String {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let request = read_request(&mut stream).await;
+        write_json_response(&mut stream, "200 OK", body).await;
+        request
+    }
+
+    #[tokio::test]
+    async fn test_embed_batch_parses_data_in_index_order() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve_once(
+            listener,
+            r#"{"data":[{"index":1,"embedding":[0.0,1.0]},{"index":0,"embedding":[1.0,0.0]}]}"#,
+        ));
+
+        let embedder = OpenAiEmbedder::new(
+            &endpoint,
+            "text-embedding-3-small",
+            2,
+            Some("sk-test".into()),
+        );
+        let vectors = embedder
+            .embed_batch(&["first".to_string(), "second".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(vectors, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /v1/embeddings "));
+        assert!(request
+            .to_lowercase()
+            .contains("authorization: bearer sk-test"));
+        assert!(request.contains(r#""input":["first","second"]"#));
+    }
+
+    #[test]
+    fn test_openai_key_is_only_sent_to_openai() {
+        let env = |name: &str| match name {
+            "OPENAI_API_KEY" => Some("sk-openai".to_string()),
+            "LOCAL_KEY" => Some("sk-local".to_string()),
+            _ => None,
+        };
+
+        let key = |endpoint, api_key, api_key_env| api_key_for(endpoint, api_key, api_key_env, env);
+        assert_eq!(
+            key(OPENAI_ENDPOINT, None, None).as_deref(),
+            Some("sk-openai")
+        );
+        assert_eq!(key("http://localhost:8080", None, None), None);
+        assert_eq!(key("https://api.openai.com.evil.test", None, None), None);
+        assert_eq!(
+            key("http://localhost:8080", None, Some("LOCAL_KEY")).as_deref(),
+            Some("sk-local")
+        );
+        assert_eq!(
+            key(
+                "http://localhost:8080",
+                Some("sk-explicit"),
+                Some("LOCAL_KEY")
+            )
+            .as_deref(),
+            Some("sk-explicit")
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! Report how a single file is chunked, without embedding anything, to debug
+//! files that retrieve poorly.
+
+use serde::Serialize;
+
+use super::chunker::{ChunkOrigin, Chunker};
+use super::store::ChunkType;
+use crate::types::{FileEntry, Symbol};
+
+/// One chunk the indexer would embed for a file
+#[derive(Debug, Clone, Serialize)]
+pub struct ChunkExplanation {
+    pub id: String,
+    pub chunk_type: ChunkType,
+    pub origin: ChunkOrigin,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub symbol_name: Option<String>,
+    /// Size of the embedded text, including the file/symbol header
+    pub chars: usize,
+    /// Counted by the chunker's tokenizer, else estimated at 4 chars per token
+    pub tokens: usize,
+}
+
+/// Chunks `chunker` produces for `content` given its parsed `symbols`
+pub fn explain_chunking(
+    chunker: &Chunker,
+    file: &FileEntry,
+    content: &str,
+    symbols: &[Symbol],
+) -> Vec<ChunkExplanation> {
+    let chunks = if file.is_markdown() {
+        chunker.chunk_by_markdown(file, content)
+    } else {
+        chunker.chunk_by_symbols(file, content, symbols)
+    };
+    chunks
+        .into_iter()
+        .map(|chunk| ChunkExplanation {
+            chars: chunk.content.len(),
+            tokens: chunker.count_tokens(&chunk.content),
+            id: chunk.id,
+            chunk_type: chunk.chunk_type,
+            origin: chunk.origin,
+            start_line: chunk.start_line,
+            end_line: chunk.end_line,
+            symbol_name: chunk.symbol_name,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze::extract_symbols;
+    use std::path::PathBuf;
+
+    fn make_file(path: &str, content: &str) -> FileEntry {
+        FileEntry::new(
+            PathBuf::from(path),
+            path.to_string(),
+            content.len() as u64,
+            content.lines().count(),
+            500,
+        )
+    }
+
+    #[test]
+    fn test_two_function_file_yields_two_symbol_chunks() {
+        let content = "fn parse(input: &str) -> u32 {\n    input.len() as u32\n}\n\nfn render(n: u32) -> String {\n    n.to_string()\n}\n";
+        let file = make_file("src/lib.rs", content);
+        let symbols = extract_symbols(&file, content);
+
+        let report = explain_chunking(&Chunker::default(), &file, content, &symbols);
+
+        assert_eq!(report.len(), 2);
+        assert!(report.iter().all(|c| c.origin == ChunkOrigin::Symbol));
+        assert_eq!(report[0].symbol_name.as_deref(), Some("parse"));
+        assert_eq!((report[1].start_line, report[1].end_line), (5, 7));
+        assert_eq!(report[0].tokens, report[0].chars.div_ceil(4));
+    }
+
+    #[test]
+    fn test_oversized_symbol_reports_splits_and_symbolless_file_windows() {
+        let body: String = (0..40)
+            .map(|i| format!("    let v{} = {};\n", i, i))
+            .collect();
+        let content = format!("fn big() {{\n{}}}\n", body);
+        let file = make_file("src/big.rs", &content);
+        let symbols = extract_symbols(&file, &content);
+
+        let report = explain_chunking(&Chunker::new(200, 0), &file, &content, &symbols);
+        assert!(report.len() > 1);
+        assert!(report.iter().all(|c| c.origin == ChunkOrigin::Split));
+
+        let content = "// Notes\n\n// Nothing to parse here.\n";
+        let file = make_file("src/notes.rs", content);
+        let report = explain_chunking(&Chunker::default(), &file, content, &[]);
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].origin, ChunkOrigin::Window);
+    }
+}
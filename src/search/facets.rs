@@ -0,0 +1,111 @@
+//! Breakdown of search results by language and module.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::store::SearchResult;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SearchFacets {
+    pub languages: Vec<FacetCount>,
+    /// Counts per parent directory of the result's file ("." for the root)
+    pub modules: Vec<FacetCount>,
+}
+
+pub fn compute_facets(results: &[SearchResult]) -> SearchFacets {
+    let mut languages: HashMap<String, usize> = HashMap::new();
+    let mut modules: HashMap<String, usize> = HashMap::new();
+
+    for result in results {
+        *languages
+            .entry(result.chunk.language().name().to_string())
+            .or_insert(0) += 1;
+        *modules
+            .entry(module_of(&result.chunk.file_path))
+            .or_insert(0) += 1;
+    }
+
+    SearchFacets {
+        languages: sorted_counts(languages),
+        modules: sorted_counts(modules),
+    }
+}
+
+fn module_of(file_path: &str) -> String {
+    match Path::new(file_path).parent().and_then(|p| p.to_str()) {
+        Some("") | None => ".".to_string(),
+        Some(parent) => parent.to_string(),
+    }
+}
+
+/// Most frequent first, ties broken alphabetically for stable output
+fn sorted_counts(counts: HashMap<String, usize>) -> Vec<FacetCount> {
+    let mut counts: Vec<FacetCount> = counts
+        .into_iter()
+        .map(|(value, count)| FacetCount { value, count })
+        .collect();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::Chunk;
+    use crate::test_support::make_chunk;
+    use crate::types::Language;
+
+    fn result(file_path: &str, language: Option<Language>) -> SearchResult {
+        SearchResult::new(
+            Chunk {
+                end_line: 2,
+                language,
+                ..make_chunk(&format!("{}:block:1", file_path), file_path, vec![])
+            },
+            0.5,
+        )
+    }
+
+    #[test]
+    fn test_counts_per_language_in_mixed_results() {
+        let results = vec![
+            result("src/api/handler.rs", Some(Language::Rust)),
+            result("src/api/routes.rs", Some(Language::Rust)),
+            result("web/app.ts", Some(Language::TypeScript)),
+            // Older index without a recorded language: inferred from the extension
+            result("scripts/build.py", None),
+            result("src/db.rs", Some(Language::Rust)),
+        ];
+
+        let facets = compute_facets(&results);
+
+        let count = |value: &str| {
+            facets
+                .languages
+                .iter()
+                .find(|f| f.value == value)
+                .map(|f| f.count)
+        };
+        assert_eq!(facets.languages[0].value, "rust");
+        assert_eq!(count("rust"), Some(3));
+        assert_eq!(count("typescript"), Some(1));
+        assert_eq!(count("python"), Some(1));
+        assert_eq!(facets.languages.len(), 3);
+
+        assert_eq!(
+            facets.modules[0],
+            FacetCount {
+                value: "src/api".to_string(),
+                count: 2
+            }
+        );
+        assert_eq!(facets.modules.len(), 4);
+    }
+}
@@ -0,0 +1,96 @@
+//! Embedding-free lexical search over the working tree.
+//!
+//! Used when no index has been built yet: files are scanned and chunked in
+//! memory and scored with `text_search`, so results are available without an
+//! embedding server at the cost of matching words rather than meaning.
+
+use anyhow::Result;
+use chrono::Utc;
+use std::path::Path;
+
+use crate::analyze::extract_symbols;
+use crate::config::ChunkingConfig;
+use crate::scan::{read_source, scan_directory, DEFAULT_LARGE_FILE_LINES};
+
+use super::chunker::Chunker;
+use super::hybrid::text_search;
+use super::store::{Chunk, SearchFilter, SearchResult};
+
+pub fn grep_search(
+    root: &Path,
+    query: &str,
+    limit: usize,
+    respect_gitignore: bool,
+    filter: &SearchFilter,
+) -> Result<Vec<SearchResult>> {
+    let config = ChunkingConfig::default();
+    let chunker = Chunker::from_config(&config);
+    let files = scan_directory(root, DEFAULT_LARGE_FILE_LINES, respect_gitignore, None)?;
+
+    let mut chunks = Vec::new();
+    for file in files {
+        // Binary or non-UTF-8 files carry nothing to match
+        let Ok(content) = read_source(&file.path) else {
+            continue;
+        };
+        let symbols = extract_symbols(&file, &content);
+
+        for info in chunker.chunk_by_symbols(&file, &content, &symbols) {
+            chunks.push(Chunk {
+                id: info.id,
+                file_path: info.file_path,
+                start_line: info.start_line,
+                end_line: info.end_line,
+                content: info.content,
+                vector: Vec::new(),
+                hash: info.hash,
+                updated_at: Utc::now(),
+                chunk_type: info.chunk_type,
+                hotness: None,
+                language: Some(file.language),
+                symbol_name: info.symbol_name,
+                symbol_kind: info.symbol_kind,
+                collection: None,
+                norm: None,
+            });
+        }
+    }
+
+    chunks.retain(|chunk| filter.matches(chunk));
+    Ok(text_search(&chunks, query, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_grep_search_finds_matches_without_index() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("auth.rs"),
+            "pub fn refresh_token() {\n    // rotate the session token\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            temp.path().join("math.rs"),
+            "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+        )
+        .unwrap();
+
+        let results = grep_search(
+            temp.path(),
+            "session token",
+            5,
+            false,
+            &SearchFilter::default(),
+        )
+        .unwrap();
+
+        assert!(!temp.path().join(".agentlens/index.json").exists());
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].chunk.file_path, "auth.rs");
+    }
+}
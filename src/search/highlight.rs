@@ -0,0 +1,120 @@
+//! Which query terms a result matched, and where, for previews that show the
+//! matching lines rather than the top of the chunk.
+
+use std::ops::Range;
+
+use super::hybrid::tokenize;
+
+/// Query terms, tokenized as for text search, that occur in `content`, in query order
+pub fn matched_terms(content: &str, query: &str) -> Vec<String> {
+    let content_tokens: std::collections::HashSet<String> = tokenize(content).into_iter().collect();
+    let mut terms: Vec<String> = Vec::new();
+    for term in tokenize(query) {
+        if content_tokens.contains(&term) && !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    terms
+}
+
+/// Byte ranges of the words in `line` that equal one of `terms` (lowercased)
+pub fn term_spans(line: &str, terms: &[String]) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in line
+        .char_indices()
+        .chain(std::iter::once((line.len(), ' ')))
+    {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                if terms.contains(&line[s..i].to_lowercase()) {
+                    spans.push(s..i);
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    spans
+}
+
+/// Up to `size` lines of `content` after its `File:` header, centered on the
+/// line with the most matches of `terms`; the first lines when nothing matches
+pub fn preview_lines<'a>(content: &'a str, terms: &[String], size: usize) -> Vec<&'a str> {
+    let mut lines: Vec<&str> = content.lines().collect();
+    if content.starts_with("File: ") {
+        let body = lines.iter().position(|l| l.is_empty()).map_or(0, |i| i + 1);
+        lines.drain(..body);
+    }
+
+    let best = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| (i, term_spans(line, terms).len()))
+        .filter(|(_, matches)| *matches > 0)
+        // Earliest line among equals
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+        .map_or(0, |(i, _)| i);
+
+    let start = best
+        .saturating_sub(size / 2)
+        .min(lines.len().saturating_sub(size));
+    lines.into_iter().skip(start).take(size).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_matched_terms_use_text_search_tokens() {
+        let content = "fn refresh_token(session: &Session) {\n    rotate(session)\n}";
+
+        assert_eq!(
+            matched_terms(content, "Refresh the session token"),
+            terms(&["refresh", "session", "token"])
+        );
+        assert!(matched_terms(content, "database").is_empty());
+    }
+
+    #[test]
+    fn test_term_spans_match_whole_words_case_insensitively() {
+        let line = "let Token = tokens.refresh_token();";
+        let spans = term_spans(line, &terms(&["token"]));
+
+        let words: Vec<&str> = spans.iter().map(|s| &line[s.clone()]).collect();
+        assert_eq!(words, vec!["Token", "token"]);
+    }
+
+    #[test]
+    fn test_preview_centers_on_best_matching_line() {
+        let body: Vec<String> = (1..=20).map(|i| format!("line {}", i)).collect();
+        let mut body = body.join("\n");
+        body = body.replace("line 14", "retry the request");
+        let content = format!(
+            "File: src/http.rs\nSymbol: send (function)\nLines: 1-20\n\n{}",
+            body
+        );
+
+        let preview = preview_lines(&content, &terms(&["retry"]), 5);
+        assert_eq!(
+            preview,
+            vec![
+                "line 12",
+                "line 13",
+                "retry the request",
+                "line 15",
+                "line 16"
+            ]
+        );
+
+        // No match: the top of the body, header skipped
+        let preview = preview_lines(&content, &terms(&["missing"]), 2);
+        assert_eq!(preview, vec!["line 1", "line 2"]);
+    }
+}
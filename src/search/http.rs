@@ -0,0 +1,155 @@
+//! Minimal HTTP/1.1 server behind `serve --store` and `watch --serve`: one
+//! request per connection, every response JSON.
+
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::future::Future;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Request heads larger than this are rejected
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+
+/// One parsed request
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    /// Path and query, e.g. `/search?q=auth`
+    pub target: String,
+    headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Value of the header `name`, matched case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Status line (e.g. "200 OK") and JSON body
+pub type Response = (&'static str, Value);
+
+/// Answer requests on `listener` with `handle`, passing it `state`, until
+/// `shutdown` resolves. Bodies over `max_body` bytes get a 400. In-flight
+/// requests finish before this returns.
+pub async fn serve_http<S, H, F>(
+    listener: TcpListener,
+    state: Arc<S>,
+    max_body: usize,
+    handle: H,
+    shutdown: impl Future<Output = ()>,
+) -> Result<()>
+where
+    S: ?Sized + Send + Sync + 'static,
+    H: Fn(Arc<S>, Request) -> F + Copy + Send + 'static,
+    F: Future<Output = Response> + Send + 'static,
+{
+    let mut connections = tokio::task::JoinSet::new();
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            accepted = listener.accept() => {
+                let (stream, _) = accepted?;
+                let state = Arc::clone(&state);
+                connections.spawn(async move {
+                    if let Err(e) = handle_connection(stream, state, max_body, handle).await {
+                        eprintln!("HTTP error: {}", e);
+                    }
+                });
+            }
+        }
+    }
+
+    while connections.join_next().await.is_some() {}
+    Ok(())
+}
+
+async fn handle_connection<S, H, F>(
+    mut stream: TcpStream,
+    state: Arc<S>,
+    max_body: usize,
+    handle: H,
+) -> Result<()>
+where
+    S: ?Sized,
+    H: Fn(Arc<S>, Request) -> F,
+    F: Future<Output = Response>,
+{
+    let (status, body) = match read_request(&mut stream, max_body).await {
+        Ok(request) => handle(state, request).await,
+        Err(e) => ("400 Bad Request", json!({ "error": e.to_string() })),
+    };
+
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+async fn read_request(stream: &mut TcpStream, max_body: usize) -> Result<Request> {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 8192];
+    let head_end = loop {
+        if let Some(i) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+            break i + 4;
+        }
+        if data.len() > MAX_REQUEST_HEAD {
+            anyhow::bail!("request head too large");
+        }
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            anyhow::bail!("connection closed before the request head ended");
+        }
+        data.extend_from_slice(&buf[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&data[..head_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or("").split_whitespace();
+    let method = request_line.next().unwrap_or("").to_string();
+    let target = request_line.next().unwrap_or("").to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    let content_length = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .map(|(_, value)| value.parse::<usize>())
+        .transpose()?
+        .unwrap_or(0);
+    if content_length > max_body {
+        anyhow::bail!("request body too large");
+    }
+
+    let mut body = data.split_off(head_end);
+    while body.len() < content_length {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            anyhow::bail!("connection closed before the request body ended");
+        }
+        body.extend_from_slice(&buf[..n]);
+    }
+    body.truncate(content_length);
+
+    Ok(Request {
+        method,
+        target,
+        headers,
+        body,
+    })
+}
@@ -1,3 +1,4 @@
+use serde::Serialize;
 use std::collections::HashMap;
 
 use super::store::{Chunk, SearchResult};
@@ -10,74 +11,164 @@ pub fn reciprocal_rank_fusion(
     limit: usize,
     result_lists: Vec<Vec<SearchResult>>,
 ) -> Vec<SearchResult> {
+    let weighted_lists = result_lists.into_iter().map(|list| (1.0, list)).collect();
+    reciprocal_rank_fusion_weighted(k, limit, weighted_lists)
+}
+
+/// Reciprocal Rank Fusion where each list's `1 / (k + rank + 1)` contribution
+/// is multiplied by its weight, e.g. to trust vector results over text matches
+pub fn reciprocal_rank_fusion_weighted(
+    k: f32,
+    limit: usize,
+    weighted_lists: Vec<(f32, Vec<SearchResult>)>,
+) -> Vec<SearchResult> {
+    reciprocal_rank_fusion_ranked(k, limit, weighted_lists)
+        .into_iter()
+        .map(|(result, _)| result)
+        .collect()
+}
+
+/// Weighted RRF that also returns, for each fused result, its 1-based rank in
+/// each input list (in list order), or `None` where a list didn't contain it
+pub fn reciprocal_rank_fusion_ranked(
+    k: f32,
+    limit: usize,
+    weighted_lists: Vec<(f32, Vec<SearchResult>)>,
+) -> Vec<(SearchResult, Vec<Option<usize>>)> {
+    let list_count = weighted_lists.len();
     let mut scores: HashMap<String, f32> = HashMap::new();
+    let mut ranks: HashMap<String, Vec<Option<usize>>> = HashMap::new();
     let mut chunk_map: HashMap<String, Chunk> = HashMap::new();
 
-    for list in result_lists {
+    for (list_index, (weight, list)) in weighted_lists.into_iter().enumerate() {
         for (rank, result) in list.iter().enumerate() {
             let id = &result.chunk.id;
-            *scores.entry(id.clone()).or_insert(0.0) += 1.0 / (k + rank as f32 + 1.0);
+            *scores.entry(id.clone()).or_insert(0.0) += weight / (k + rank as f32 + 1.0);
+            ranks
+                .entry(id.clone())
+                .or_insert_with(|| vec![None; list_count])[list_index]
+                .get_or_insert(rank + 1);
             chunk_map.entry(id.clone()).or_insert(result.chunk.clone());
         }
     }
 
-    let mut results: Vec<SearchResult> = scores
+    let mut results: Vec<(SearchResult, Vec<Option<usize>>)> = scores
         .into_iter()
         .map(|(id, score)| {
-            SearchResult::new(
+            let result = SearchResult::new(
                 chunk_map.remove(&id).expect("Chunk must exist in map"),
                 score,
-            )
+            );
+            (result, ranks.remove(&id).unwrap_or_default())
         })
         .collect();
 
     results.sort_by(|a, b| {
-        b.score
-            .partial_cmp(&a.score)
+        b.0.score
+            .partial_cmp(&a.0.score)
             .unwrap_or(std::cmp::Ordering::Equal)
     });
     results.truncate(limit);
     results
 }
 
-/// Simple text search for hybrid mode
-/// Scores chunks based on word match ratio
+/// Where a hybrid result's score came from: its 1-based rank in the vector
+/// and text lists (`None` when absent) and its fused RRF score before reranking
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ScoreBreakdown {
+    pub vector_rank: Option<usize>,
+    pub text_rank: Option<usize>,
+    pub rrf_score: f32,
+}
+
+/// BM25 tuning parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bm25Params {
+    /// Term frequency saturation: higher values let repeated terms keep adding score
+    pub k1: f32,
+    /// Length normalization: 0.0 ignores chunk length, 1.0 fully normalizes it
+    pub b: f32,
+}
+
+impl Default for Bm25Params {
+    fn default() -> Self {
+        Self { k1: 1.2, b: 0.75 }
+    }
+}
+
+/// Lowercased alphanumeric runs of at least two characters, so identifiers
+/// like `refresh_token` match the words `refresh` and `token`
+pub(crate) fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| t.chars().count() >= 2)
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+/// Lexical search for hybrid mode, scored with BM25 using default parameters
 pub fn text_search(chunks: &[Chunk], query: &str, limit: usize) -> Vec<SearchResult> {
-    let query_lower = query.to_lowercase();
-    let words: Vec<String> = query_lower
-        .split_whitespace()
-        .filter(|w| w.len() >= 2)
-        .map(|s| s.to_string())
-        .collect();
+    text_search_with_params(chunks, query, limit, Bm25Params::default())
+}
+
+/// BM25 over `chunks`: document frequencies and average length are computed
+/// from the chunks passed in, so scores are only comparable within one call
+pub fn text_search_with_params(
+    chunks: &[Chunk],
+    query: &str,
+    limit: usize,
+    params: Bm25Params,
+) -> Vec<SearchResult> {
+    let mut terms = tokenize(query);
+    terms.sort();
+    terms.dedup();
 
-    if words.is_empty() {
+    if terms.is_empty() || chunks.is_empty() {
         return Vec::new();
     }
 
+    let docs: Vec<Vec<String>> = chunks.iter().map(|c| tokenize(&c.content)).collect();
+    let avg_len = docs.iter().map(Vec::len).sum::<usize>() as f32 / docs.len() as f32;
+    let avg_len = avg_len.max(1.0);
+
+    let term_freqs: Vec<HashMap<&str, usize>> = docs
+        .iter()
+        .map(|tokens| {
+            let mut freqs = HashMap::new();
+            for token in tokens {
+                *freqs.entry(token.as_str()).or_insert(0) += 1;
+            }
+            freqs
+        })
+        .collect();
+
+    let total = docs.len() as f32;
+    let idf: Vec<f32> = terms
+        .iter()
+        .map(|term| {
+            let df = term_freqs
+                .iter()
+                .filter(|f| f.contains_key(term.as_str()))
+                .count() as f32;
+            // The +1 keeps terms found in most chunks from scoring negative
+            (1.0 + (total - df + 0.5) / (df + 0.5)).ln()
+        })
+        .collect();
+
     let mut results: Vec<SearchResult> = chunks
         .iter()
-        .filter_map(|chunk| {
-            let content_lower = chunk.content.to_lowercase();
-
-            // Exact phrase match bonus
-            let phrase_bonus = if content_lower.contains(&query_lower) {
-                0.5
-            } else {
-                0.0
-            };
-
-            // Word match score
-            let match_count = words
+        .zip(docs.iter().zip(&term_freqs))
+        .filter_map(|(chunk, (tokens, freqs))| {
+            let norm = params.k1 * (1.0 - params.b + params.b * tokens.len() as f32 / avg_len);
+            let score: f32 = terms
                 .iter()
-                .filter(|w| content_lower.contains(w.as_str()))
-                .count();
-
-            if match_count > 0 {
-                let base_score = match_count as f32 / words.len() as f32;
-                Some(SearchResult::new(chunk.clone(), base_score + phrase_bonus))
-            } else {
-                None
-            }
+                .zip(&idf)
+                .filter_map(|(term, idf)| {
+                    let tf = *freqs.get(term.as_str())? as f32;
+                    Some(idf * tf * (params.k1 + 1.0) / (tf + norm))
+                })
+                .sum();
+
+            (score > 0.0).then(|| SearchResult::new(chunk.clone(), score))
         })
         .collect();
 
@@ -93,27 +184,21 @@ pub fn text_search(chunks: &[Chunk], query: &str, limit: usize) -> Vec<SearchRes
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Utc;
+    use crate::test_support::make_chunk;
 
-    fn make_chunk(id: &str, content: &str) -> Chunk {
+    fn text_chunk(id: &str, content: &str) -> Chunk {
         Chunk {
-            id: id.to_string(),
-            file_path: "test.rs".to_string(),
-            start_line: 1,
-            end_line: 10,
             content: content.to_string(),
-            vector: vec![0.1, 0.2, 0.3],
-            hash: "abc123".to_string(),
-            updated_at: Utc::now(),
             chunk_type: super::super::store::ChunkType::Function,
+            ..make_chunk(id, "test.rs", vec![0.1, 0.2, 0.3])
         }
     }
 
     #[test]
     fn test_rrf_single_list() {
         let list = vec![
-            SearchResult::new(make_chunk("a", "content a"), 0.9),
-            SearchResult::new(make_chunk("b", "content b"), 0.8),
+            SearchResult::new(text_chunk("a", "content a"), 0.9),
+            SearchResult::new(text_chunk("b", "content b"), 0.8),
         ];
 
         let results = reciprocal_rank_fusion(60.0, 10, vec![list]);
@@ -125,12 +210,12 @@ mod tests {
     #[test]
     fn test_rrf_multiple_lists() {
         let list1 = vec![
-            SearchResult::new(make_chunk("a", "a"), 0.9),
-            SearchResult::new(make_chunk("b", "b"), 0.8),
+            SearchResult::new(text_chunk("a", "a"), 0.9),
+            SearchResult::new(text_chunk("b", "b"), 0.8),
         ];
         let list2 = vec![
-            SearchResult::new(make_chunk("b", "b"), 0.9),
-            SearchResult::new(make_chunk("c", "c"), 0.8),
+            SearchResult::new(text_chunk("b", "b"), 0.9),
+            SearchResult::new(text_chunk("c", "c"), 0.8),
         ];
 
         let results = reciprocal_rank_fusion(60.0, 10, vec![list1, list2]);
@@ -139,12 +224,61 @@ mod tests {
         assert_eq!(results[0].chunk.id, "b");
     }
 
+    #[test]
+    fn test_rrf_weighted_prefers_heavier_list() {
+        let vector = vec![SearchResult::new(text_chunk("v", "v"), 0.9)];
+        let text = vec![SearchResult::new(text_chunk("t", "t"), 0.9)];
+
+        let results = reciprocal_rank_fusion_weighted(60.0, 10, vec![(0.3, text), (0.7, vector)]);
+        assert_eq!(results[0].chunk.id, "v");
+        assert!((results[0].score - 0.7 / 61.0).abs() < 1e-6);
+        assert!((results[1].score - 0.3 / 61.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rrf_ranked_reports_rank_in_each_list() {
+        let vector = vec![
+            SearchResult::new(text_chunk("a", "a"), 0.9),
+            SearchResult::new(text_chunk("b", "b"), 0.8),
+        ];
+        let text = vec![SearchResult::new(text_chunk("b", "b"), 3.0)];
+
+        let results = reciprocal_rank_fusion_ranked(60.0, 10, vec![(0.7, vector), (0.3, text)]);
+        let ranks: Vec<(&str, Vec<Option<usize>>)> = results
+            .iter()
+            .map(|(r, ranks)| (r.chunk.id.as_str(), ranks.clone()))
+            .collect();
+        assert_eq!(
+            ranks,
+            vec![("b", vec![Some(2), Some(1)]), ("a", vec![Some(1), None])]
+        );
+        assert!((results[0].0.score - (0.7 / 62.0 + 0.3 / 61.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rrf_unweighted_matches_unit_weights() {
+        let list = || {
+            vec![
+                SearchResult::new(text_chunk("a", "a"), 0.9),
+                SearchResult::new(text_chunk("b", "b"), 0.8),
+            ]
+        };
+
+        let plain = reciprocal_rank_fusion(60.0, 10, vec![list(), list()]);
+        let weighted =
+            reciprocal_rank_fusion_weighted(60.0, 10, vec![(1.0, list()), (1.0, list())]);
+        for (p, w) in plain.iter().zip(&weighted) {
+            assert_eq!(p.chunk.id, w.chunk.id);
+            assert_eq!(p.score, w.score);
+        }
+    }
+
     #[test]
     fn test_text_search_basic() {
         let chunks = vec![
-            make_chunk("1", "This is authentication code"),
-            make_chunk("2", "Database connection handler"),
-            make_chunk("3", "User login authentication flow"),
+            text_chunk("1", "This is authentication code"),
+            text_chunk("2", "Database connection handler"),
+            text_chunk("3", "User login authentication flow"),
         ];
 
         let results = text_search(&chunks, "authentication", 10);
@@ -155,22 +289,47 @@ mod tests {
     }
 
     #[test]
-    fn test_text_search_phrase_bonus() {
+    fn test_text_search_prefers_shorter_match() {
         let chunks = vec![
-            make_chunk("1", "user authentication"),
-            make_chunk("2", "authentication for user accounts"),
+            text_chunk("1", "user authentication"),
+            text_chunk("2", "authentication for user accounts"),
         ];
 
         let results = text_search(&chunks, "user authentication", 10);
         assert_eq!(results.len(), 2);
-        // Exact phrase match should have higher score
+        // Same terms in fewer words means a denser match
         assert!(results[0].score > results[1].score);
         assert_eq!(results[0].chunk.id, "1");
     }
 
+    #[test]
+    fn test_text_search_repetition_does_not_outrank_focused_match() {
+        let repetitive = format!(
+            "{} refresh handler {}",
+            "token ".repeat(200),
+            "padding text for a long unrelated chunk ".repeat(20)
+        );
+        let chunks = vec![
+            text_chunk("long", &repetitive),
+            text_chunk("focused", "fn refresh_token(session: &Session) -> Token"),
+            text_chunk("other", "fn connect(url: &str) -> Connection"),
+        ];
+
+        let results = text_search(&chunks, "refresh token", 10);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].chunk.id, "focused");
+    }
+
+    #[test]
+    fn test_text_search_matches_whole_words_only() {
+        let chunks = vec![text_chunk("1", "fn authenticate_user()")];
+        assert!(text_search(&chunks, "auth", 10).is_empty());
+        assert_eq!(text_search(&chunks, "USER", 10).len(), 1);
+    }
+
     #[test]
     fn test_text_search_no_matches() {
-        let chunks = vec![make_chunk("1", "hello world")];
+        let chunks = vec![text_chunk("1", "hello world")];
         let results = text_search(&chunks, "foobar", 10);
         assert!(results.is_empty());
     }
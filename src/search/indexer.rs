@@ -1,29 +1,100 @@
 use anyhow::Result;
 use chrono::Utc;
-use std::fs;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::Path;
 use std::sync::Arc;
+use tokio::sync::OnceCell;
 
-use crate::analyze::extract_symbols;
+use crate::analyze::{detect_project_modules, extract_symbols, SymbolFilter};
 use crate::config::ChunkingConfig;
-use crate::scan::scan_directory;
-use crate::types::FileEntry;
+use crate::scan::{
+    get_diff_files, is_binary_content, is_permission_denied, permission_denied_summary,
+    scan_index_report, DiffStatus, ExtensionDenylist,
+};
+use crate::types::{FileEntry, SymbolKind, Visibility};
 
-use super::chunker::{ChunkInfo, Chunker};
-use super::embedder::Embedder;
-use super::store::{Chunk, Document, VectorStore};
+use super::chunker::{ChunkInfo, ChunkOrigin, Chunker};
+use super::embedder::{probe_dimensions, Embedder};
+use super::module_summary::{module_doc, module_document_path, summarize_module};
+use super::read_limit::ReadLimiter;
+use super::store::{vector_norm, Chunk, ChunkType, Document, IndexMeta, VectorStore};
 
 pub struct Indexer {
     store: Arc<dyn VectorStore>,
     embedder: Arc<dyn Embedder>,
     chunker: Chunker,
+    hotness: HashMap<String, f32>,
+    symbol_filter: SymbolFilter,
+    collection: Option<String>,
+    max_chunks_per_file: Option<usize>,
+    checkpoint_interval: usize,
+    reads: ReadLimiter,
+    module_summaries: bool,
+    denylist: ExtensionDenylist,
+    save_batch_size: usize,
+    /// Length of the vectors the embedder returns, probed on first use
+    dimensions: OnceCell<usize>,
+}
+
+/// Default number of re-embedded files between index checkpoints
+const DEFAULT_CHECKPOINT_INTERVAL: usize = 100;
+
+/// Default number of re-embedded files whose chunks are saved in one `save_chunks` call
+pub const DEFAULT_SAVE_BATCH_SIZE: usize = 32;
+
+/// Embedded chunks of one document, waiting to replace its stored chunks
+struct PendingSave {
+    path: String,
+    hash: String,
+    chunks: Vec<Chunk>,
+    /// Chunks that kept their stored vector instead of being re-embedded
+    reused: usize,
+    /// Chunks that took the vector of an identical chunk embedded this run
+    deduplicated: usize,
+    /// Ids of the document's chunks already in the store; empty replaces them all
+    stored_ids: Vec<String>,
+}
+
+/// Where the first chunk embedded this run with each dedup key (see
+/// `dedup_key`) lives, so later copies read its vector back from the unsaved
+/// batch or the store rather than the run holding a second copy of it
+type EmbeddedChunks = HashMap<String, ChunkRef>;
+
+/// File path and id of a chunk embedded earlier in the run
+#[derive(Clone)]
+struct ChunkRef {
+    file_path: String,
+    id: String,
+}
+
+/// A file whose content changed since it was indexed, chunked but not embedded
+struct ChangedFile {
+    hash: String,
+    chunk_infos: Vec<ChunkInfo>,
+    /// Chunks before the per-file cap
+    total_chunks: usize,
+    /// Stored vectors of chunks that come back with the same id and hash, by id
+    reusable: HashMap<String, Vec<f32>>,
+    stored_ids: Vec<String>,
 }
 
 pub struct IndexResult {
     pub files_processed: usize,
+    /// Chunks embedded in this run
     pub chunks_created: usize,
+    /// Chunks of changed files that were unchanged, so kept their stored vector
+    pub chunks_reused: usize,
+    /// Created chunks identical to one embedded earlier in the run, which
+    /// took its vector instead of being embedded again
+    pub chunks_deduplicated: usize,
     pub files_skipped: usize,
+    /// Files not indexed because they turned out to hold binary data
+    pub files_skipped_binary: usize,
     pub errors: Vec<String>,
+    /// Files cut off at the per-file chunk cap, e.g. "big.rs: kept 100 of 2345 chunks"
+    pub truncated: Vec<String>,
+    /// Files and directories skipped because they could not be read
+    pub permission_denied: Vec<String>,
 }
 
 impl Indexer {
@@ -32,14 +103,96 @@ impl Indexer {
         embedder: Arc<dyn Embedder>,
         config: &ChunkingConfig,
     ) -> Self {
-        let chunker = Chunker::from_tokens(config.max_tokens, config.overlap_tokens);
+        let chunker = Chunker::from_config(config);
         Self {
             store,
             embedder,
             chunker,
+            hotness: HashMap::new(),
+            symbol_filter: SymbolFilter::default(),
+            collection: None,
+            max_chunks_per_file: None,
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
+            reads: ReadLimiter::default(),
+            module_summaries: false,
+            denylist: ExtensionDenylist::default(),
+            save_batch_size: DEFAULT_SAVE_BATCH_SIZE,
+            dimensions: OnceCell::new(),
         }
     }
 
+    /// Index into a named collection instead of the global one
+    pub fn with_collection(mut self, collection: impl Into<String>) -> Self {
+        self.collection = Some(collection.into());
+        self
+    }
+
+    /// Skip symbols matching the filter when building symbol-based chunks
+    pub fn with_symbol_filter(mut self, symbol_filter: SymbolFilter) -> Self {
+        self.symbol_filter = symbol_filter;
+        self
+    }
+
+    /// Only chunk and embed symbols visible at least as widely as `min`
+    pub fn with_min_visibility(mut self, min: Visibility) -> Self {
+        self.chunker = self.chunker.with_min_visibility(min);
+        self
+    }
+
+    /// Cap how many chunks one file contributes; chunks past the cap are skipped
+    pub fn with_max_chunks_per_file(mut self, max_chunks: usize) -> Self {
+        self.max_chunks_per_file = Some(max_chunks);
+        self
+    }
+
+    /// Persist the index after every `interval` re-embedded files during
+    /// `index_all`, so an interrupted run keeps its progress. 0 disables.
+    pub fn with_checkpoint_interval(mut self, interval: usize) -> Self {
+        self.checkpoint_interval = interval;
+        self
+    }
+
+    /// During `index_all`, hold the chunks of up to `files` re-embedded files
+    /// and write them with one `save_chunks` call, so the store's lock is taken
+    /// once per batch rather than once per file. 1 saves every file on its own.
+    pub fn with_save_batch_size(mut self, files: usize) -> Self {
+        self.save_batch_size = files.max(1);
+        self
+    }
+
+    /// Read at most `max_concurrent` files at once, independent of CPU parallelism
+    pub fn with_io_concurrency(mut self, max_concurrent: usize) -> Self {
+        self.reads = ReadLimiter::new(max_concurrent);
+        self
+    }
+
+    /// Never index files with these extensions; defaults to
+    /// `DEFAULT_DENIED_EXTENSIONS`
+    pub fn with_extension_denylist(mut self, denylist: ExtensionDenylist) -> Self {
+        self.denylist = denylist;
+        self
+    }
+
+    /// Also embed one `ChunkType::Module` summary per detected module during
+    /// `index_all`, so queries about a subsystem match the module as a whole
+    pub fn with_module_summaries(mut self) -> Self {
+        self.module_summaries = true;
+        self
+    }
+
+    /// Attach per-file commit counts; stored on chunks as hotness normalized to
+    /// 0.0-1.0. `index_all` also updates it on files that are not re-embedded.
+    pub fn with_hotness(mut self, commit_counts: HashMap<String, usize>) -> Self {
+        let max = commit_counts.values().copied().max().unwrap_or(0);
+        if max > 0 {
+            self.hotness = commit_counts
+                .into_iter()
+                .map(|(path, count)| (path, count as f32 / max as f32))
+                .collect();
+        }
+        self
+    }
+
     /// Index all files in a directory
     pub async fn index_all(
         &self,
@@ -47,130 +200,842 @@ impl Indexer {
         respect_gitignore: bool,
         force: bool,
     ) -> Result<IndexResult> {
-        let files = scan_directory(root, 500, respect_gitignore, None)?;
+        self.index_all_with_checkpoints(root, respect_gitignore, force, &|_| {})
+            .await
+    }
+
+    /// Like `index_all`, calling `on_checkpoint` with the running totals each
+    /// time the index is persisted mid-run
+    pub async fn index_all_with_checkpoints(
+        &self,
+        root: &Path,
+        respect_gitignore: bool,
+        force: bool,
+        on_checkpoint: &(dyn Fn(&IndexResult) + Send + Sync),
+    ) -> Result<IndexResult> {
+        let scan = scan_index_report(root, respect_gitignore, &self.denylist)?;
 
         let mut result = IndexResult {
             files_processed: 0,
             chunks_created: 0,
+            chunks_reused: 0,
+            chunks_deduplicated: 0,
             files_skipped: 0,
+            files_skipped_binary: 0,
             errors: Vec::new(),
+            truncated: Vec::new(),
+            permission_denied: scan.permission_denied,
         };
 
-        // Load existing index
+        // Load existing index; unchanged files keep their vectors, so they must fit the embedder
         self.store.load().await?;
+        if !force {
+            self.check_dimensions().await?;
+        }
+
+        let scanned = if self.module_summaries {
+            scan.files.clone()
+        } else {
+            Vec::new()
+        };
+
+        // Chunks embedded this run by dedup key, so copy-pasted code is embedded once
+        let mut embedded = EmbeddedChunks::new();
+
+        // Read ahead of the embedder, up to the IO concurrency limit
+        let mut files = scan.files.into_iter();
+        let mut pending = VecDeque::new();
+        let mut unsaved = Vec::new();
+        loop {
+            while pending.len() < self.reads.max_concurrent() {
+                let Some(file) = files.next() else { break };
+                let reads = self.reads.clone();
+                pending.push_back(tokio::spawn(async move {
+                    let content = reads.read_to_string(&file.path).await;
+                    (file, content)
+                }));
+            }
+            let Some(read) = pending.pop_front() else {
+                break;
+            };
 
-        for file in files {
-            match self.index_file(&file, force).await {
-                Ok(Some(chunks_count)) => {
+            let (file, content) = read.await?;
+            let indexed = match content {
+                Ok(content) => {
+                    self.embed_content(&file, content, force, &mut embedded, &unsaved)
+                        .await
+                }
+                Err(e) => Err(e.into()),
+            };
+            match indexed {
+                Ok(Some((save, total))) => {
+                    let chunks_count = save.chunks.len();
+                    let reused = save.reused;
+                    result.chunks_deduplicated += save.deduplicated;
+                    unsaved.push(save);
+                    if unsaved.len() >= self.save_batch_size {
+                        self.save_batch(std::mem::take(&mut unsaved)).await?;
+                    }
                     result.files_processed += 1;
-                    result.chunks_created += chunks_count;
+                    result.chunks_created += chunks_count - reused;
+                    result.chunks_reused += reused;
+                    if chunks_count < total {
+                        result.truncated.push(format!(
+                            "{}: kept {} of {} chunks",
+                            file.relative_path, chunks_count, total
+                        ));
+                    }
+                    if self.checkpoint_interval > 0
+                        && result
+                            .files_processed
+                            .is_multiple_of(self.checkpoint_interval)
+                    {
+                        self.save_batch(std::mem::take(&mut unsaved)).await?;
+                        self.store.persist().await?;
+                        on_checkpoint(&result);
+                    }
                 }
                 Ok(None) => {
+                    if !self.hotness.is_empty() {
+                        self.refresh_hotness(&file).await?;
+                    }
                     result.files_skipped += 1;
                 }
+                Err(e) if is_permission_denied(&e) => {
+                    result.permission_denied.push(file.relative_path);
+                }
+                Err(e) if is_binary_content(&e) => result.files_skipped_binary += 1,
                 Err(e) => {
                     result.errors.push(format!("{}: {}", file.relative_path, e));
                 }
             }
         }
 
+        self.save_batch(unsaved).await?;
+
+        if self.module_summaries {
+            result.chunks_created += self.index_modules(root, &scanned, force).await?;
+        }
+
         // Persist the index
         self.store.persist().await?;
 
         Ok(result)
     }
 
+    /// Re-index only the files changed between `base_ref` and HEAD: added,
+    /// modified and renamed files are indexed, deleted ones removed from the
+    /// index. Diff paths are relative to the repository root, so `root` should
+    /// be it. A renamed file's old path stays indexed until `prune_deleted`.
+    pub async fn index_diff(&self, root: &Path, base_ref: &str) -> Result<IndexResult> {
+        let diff = get_diff_files(root, base_ref).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Could not diff {} against '{}'; is it a git repository with that ref?",
+                root.display(),
+                base_ref
+            )
+        })?;
+
+        // Changed files still go through the scanner's ignore rules and filters
+        let scan = scan_index_report(root, true, &self.denylist)?;
+        let mut scanned: HashMap<String, FileEntry> = scan
+            .files
+            .into_iter()
+            .map(|f| (f.relative_path.clone(), f))
+            .collect();
+
+        let mut result = IndexResult {
+            files_processed: 0,
+            chunks_created: 0,
+            chunks_reused: 0,
+            chunks_deduplicated: 0,
+            files_skipped: 0,
+            files_skipped_binary: 0,
+            errors: Vec::new(),
+            truncated: Vec::new(),
+            permission_denied: scan.permission_denied,
+        };
+
+        self.store.load().await?;
+        self.check_dimensions().await?;
+
+        let mut embedded = EmbeddedChunks::new();
+        for stat in diff {
+            if stat.status == DiffStatus::Deleted {
+                self.store
+                    .delete_by_file(&stat.path, self.collection.as_deref())
+                    .await?;
+                continue;
+            }
+            // Ignored, denied, binary or not a source file
+            let Some(file) = scanned.remove(&stat.path) else {
+                continue;
+            };
+
+            let indexed = match self.reads.read_to_string(&file.path).await {
+                Ok(content) => {
+                    self.embed_content(&file, content, false, &mut embedded, &[])
+                        .await
+                }
+                Err(e) => Err(e.into()),
+            };
+            match indexed {
+                Ok(Some((save, total))) => {
+                    let chunks_count = save.chunks.len();
+                    let reused = save.reused;
+                    result.chunks_deduplicated += save.deduplicated;
+                    self.save_batch(vec![save]).await?;
+                    result.files_processed += 1;
+                    result.chunks_created += chunks_count - reused;
+                    result.chunks_reused += reused;
+                    if chunks_count < total {
+                        result.truncated.push(format!(
+                            "{}: kept {} of {} chunks",
+                            file.relative_path, chunks_count, total
+                        ));
+                    }
+                }
+                Ok(None) => result.files_skipped += 1,
+                Err(e) if is_permission_denied(&e) => {
+                    result.permission_denied.push(file.relative_path);
+                }
+                Err(e) if is_binary_content(&e) => result.files_skipped_binary += 1,
+                Err(e) => {
+                    result.errors.push(format!("{}: {}", file.relative_path, e));
+                }
+            }
+        }
+
+        self.store.persist().await?;
+
+        Ok(result)
+    }
+
+    /// Rebuild the whole index when it was built with a model other than `model`
+    /// or holds vectors of a different length than the embedder produces.
+    /// Returns whether a rebuild happened; indexes without a recorded model are kept.
+    pub async fn reindex_if_mismatched(
+        &self,
+        root: &Path,
+        model: &str,
+        respect_gitignore: bool,
+    ) -> Result<bool> {
+        self.store.load().await?;
+        let meta = self.store.get_meta().await?;
+        let dimensions = self.vector_dimensions().await?;
+        let model_changed = meta.model.as_deref().is_some_and(|m| m != model);
+        if !model_changed && meta.check_dimensions(dimensions).is_ok() {
+            return Ok(false);
+        }
+
+        self.index_all(root, respect_gitignore, true).await?;
+        self.prune_deleted(root, respect_gitignore).await?;
+        self.store
+            .save_meta(IndexMeta {
+                model: Some(model.to_string()),
+                dimensions: Some(dimensions),
+            })
+            .await?;
+        self.store.persist().await?;
+
+        Ok(true)
+    }
+
+    /// Length of the vectors the embedder actually returns, which is what the
+    /// index must record; the configured `dimensions()` may be wrong
+    pub async fn vector_dimensions(&self) -> Result<usize> {
+        self.dimensions
+            .get_or_try_init(|| probe_dimensions(self.embedder.as_ref()))
+            .await
+            .copied()
+    }
+
+    /// Fail when the loaded index holds vectors of another length than the
+    /// embedder returns. Empty indexes are not checked, so no probe is sent.
+    async fn check_dimensions(&self) -> Result<()> {
+        let meta = self.store.get_meta().await?;
+        if meta.dimensions.is_some() {
+            meta.check_dimensions(self.vector_dimensions().await?)?;
+        }
+        Ok(())
+    }
+
     /// Index a single file
     /// Returns Some(chunk_count) if indexed, None if skipped (unchanged)
     pub async fn index_file(&self, file: &FileEntry, force: bool) -> Result<Option<usize>> {
-        let content = fs::read_to_string(&file.path)?;
-        let content_hash = hash_content(&content);
+        Ok(self
+            .index_file_capped(file, force)
+            .await?
+            .map(|(chunks_count, _)| chunks_count))
+    }
+
+    /// Like `index_file`, but returns `(chunks_stored, chunks_before_cap)`
+    async fn index_file_capped(
+        &self,
+        file: &FileEntry,
+        force: bool,
+    ) -> Result<Option<(usize, usize)>> {
+        let content = self.reads.read_to_string(&file.path).await?;
+        self.index_content(file, content, force).await
+    }
+
+    /// Index already-read `content` of `file`
+    async fn index_content(
+        &self,
+        file: &FileEntry,
+        content: String,
+        force: bool,
+    ) -> Result<Option<(usize, usize)>> {
+        let Some((save, total_chunks)) = self
+            .embed_content(file, content, force, &mut EmbeddedChunks::new(), &[])
+            .await?
+        else {
+            return Ok(None);
+        };
+        let chunk_count = save.chunks.len();
+        self.save_batch(vec![save]).await?;
+
+        Ok(Some((chunk_count, total_chunks)))
+    }
+
+    /// Chunk and embed `content` of `file` unless it is unchanged. Returns the
+    /// chunks to save and how many there were before the per-file cap.
+    /// `embedded` is shared across calls and `unsaved` holds the saves not yet
+    /// in the store, as in `embed_chunks`.
+    async fn embed_content(
+        &self,
+        file: &FileEntry,
+        content: String,
+        force: bool,
+        embedded: &mut EmbeddedChunks,
+        unsaved: &[PendingSave],
+    ) -> Result<Option<(PendingSave, usize)>> {
+        let Some(changed) = self.chunk_changed(file, &content, force).await? else {
+            return Ok(None);
+        };
+
+        // Embed chunks in batches
+        let reused = changed.reusable.len();
+        let (chunks, deduplicated) = self
+            .embed_chunks(changed.chunk_infos, changed.reusable, embedded, unsaved)
+            .await?;
+        let mut save = self.file_save(file, changed.hash, chunks);
+        save.reused = reused;
+        save.deduplicated = deduplicated;
+        save.stored_ids = changed.stored_ids;
+        Ok(Some((save, changed.total_chunks)))
+    }
+
+    /// Re-index `files` with one embedding pass over all of their chunks, so
+    /// a burst of saves costs one round of embedder requests. Unchanged files
+    /// are skipped. Returns how many files were re-indexed; the caller persists.
+    pub async fn index_files(&self, files: &[FileEntry], force: bool) -> Result<usize> {
+        let mut changed = Vec::new();
+        for file in files.iter().filter(|f| !self.denylist.denies(&f.path)) {
+            let content = match self
+                .reads
+                .read_to_string(&file.path)
+                .await
+                .map_err(anyhow::Error::from)
+            {
+                Ok(content) => content,
+                Err(e) if is_binary_content(&e) => continue,
+                Err(e) => return Err(e),
+            };
+            if let Some(chunked) = self.chunk_changed(file, &content, force).await? {
+                changed.push((file, chunked));
+            }
+        }
+
+        let mut per_file = Vec::with_capacity(changed.len());
+        let mut chunk_infos = Vec::new();
+        let mut reusable = HashMap::new();
+        for (file, chunked) in changed {
+            per_file.push((
+                file,
+                chunked.hash,
+                chunked.chunk_infos.len(),
+                chunked.reusable.len(),
+                chunked.stored_ids,
+            ));
+            chunk_infos.extend(chunked.chunk_infos);
+            reusable.extend(chunked.reusable);
+        }
+
+        let (chunks, _) = self
+            .embed_chunks(chunk_infos, reusable, &mut EmbeddedChunks::new(), &[])
+            .await?;
+        let mut chunks = chunks.into_iter();
+        let file_count = per_file.len();
+        let saves: Vec<PendingSave> = per_file
+            .into_iter()
+            .map(|(file, hash, count, reused, stored_ids)| {
+                let file_chunks = chunks.by_ref().take(count).collect();
+                PendingSave {
+                    reused,
+                    stored_ids,
+                    ..self.file_save(file, hash, file_chunks)
+                }
+            })
+            .collect();
+        self.save_batch(saves).await?;
+
+        Ok(file_count)
+    }
+
+    /// Chunk `content` unless `file` is indexed with the same hash (and not `force`)
+    async fn chunk_changed(
+        &self,
+        file: &FileEntry,
+        content: &str,
+        force: bool,
+    ) -> Result<Option<ChangedFile>> {
+        let content_hash = hash_content(content);
 
         // Check if file has changed
+        let mut indexed_before = false;
         if !force {
-            if let Some(doc) = self.store.get_document(&file.relative_path).await? {
+            if let Some(doc) = self
+                .store
+                .get_document(&file.relative_path, self.collection.as_deref())
+                .await?
+            {
                 if doc.hash == content_hash {
                     return Ok(None); // File unchanged
                 }
+                indexed_before = true;
             }
         }
 
-        // Delete old chunks for this file
-        self.store.delete_by_file(&file.relative_path).await?;
-
         // Extract symbols for symbol-based chunking
-        let symbols = extract_symbols(file, &content);
+        let symbols = self.symbol_filter.apply(extract_symbols(file, content));
+
+        // Create chunks; markdown is split on headings rather than symbols
+        let mut chunk_infos = if file.is_markdown() {
+            self.chunker.chunk_by_markdown(file, content)
+        } else {
+            self.chunker.chunk_by_symbols(file, content, &symbols)
+        };
+        let total_chunks = chunk_infos.len();
+        if let Some(max_chunks) = self.max_chunks_per_file {
+            chunk_infos.truncate(max_chunks);
+        }
+
+        // Chunks that come back unchanged keep their vectors
+        let mut reusable = HashMap::new();
+        let mut stored_ids = Vec::new();
+        if indexed_before {
+            let new_hashes: HashMap<&str, &str> = chunk_infos
+                .iter()
+                .map(|c| (c.id.as_str(), c.hash.as_str()))
+                .collect();
+            for stored in self
+                .store
+                .get_chunks_by_file(&file.relative_path, self.collection.as_deref())
+                .await?
+            {
+                if new_hashes.get(stored.id.as_str()) == Some(&stored.hash.as_str())
+                    && !stored.vector.is_empty()
+                {
+                    reusable.insert(stored.id.clone(), stored.vector);
+                }
+                stored_ids.push(stored.id);
+            }
+        }
+
+        Ok(Some(ChangedFile {
+            hash: content_hash,
+            chunk_infos,
+            total_chunks,
+            reusable,
+            stored_ids,
+        }))
+    }
+
+    /// Embed a summary chunk for each module of the project at `root` detected
+    /// among `files`, skipping
+    /// modules whose summary is unchanged. Returns how many were re-embedded.
+    async fn index_modules(&self, root: &Path, files: &[FileEntry], force: bool) -> Result<usize> {
+        let files: Vec<FileEntry> = files.iter().filter(|f| !f.is_markdown()).cloned().collect();
+        let by_path: HashMap<&str, &FileEntry> = files
+            .iter()
+            .map(|f| (f.relative_path.as_str(), f))
+            .collect();
+
+        let mut changed = Vec::new();
+        let mut chunk_infos = Vec::new();
+        for module in detect_project_modules(root, &files) {
+            let mut doc = None;
+            let mut symbol_names = Vec::new();
+            for path in &module.files {
+                let Some(file) = by_path.get(path.as_str()) else {
+                    continue;
+                };
+                let Ok(content) = self.reads.read_to_string(&file.path).await else {
+                    continue;
+                };
+                if module.entry_point.as_deref() == Some(path.as_str()) {
+                    doc = module_doc(&content);
+                }
+                symbol_names.extend(
+                    self.symbol_filter
+                        .apply(extract_symbols(file, &content))
+                        .into_iter()
+                        .filter(|s| s.kind != SymbolKind::Test)
+                        .map(|s| s.name),
+                );
+            }
 
-        // Create chunks
-        let chunk_infos = self.chunker.chunk_by_symbols(file, &content, &symbols);
+            let content = summarize_module(&module, doc.as_deref(), &symbol_names);
+            let hash = hash_content(&content);
+            let doc_path = module_document_path(&module);
+            if !force {
+                if let Some(existing) = self
+                    .store
+                    .get_document(&doc_path, self.collection.as_deref())
+                    .await?
+                {
+                    if existing.hash == hash {
+                        continue;
+                    }
+                }
+            }
 
-        if chunk_infos.is_empty() {
-            return Ok(Some(0));
+            chunk_infos.push(ChunkInfo {
+                id: format!("{}:module", doc_path),
+                file_path: doc_path.clone(),
+                start_line: 1,
+                end_line: 1,
+                hash: hash.clone(),
+                content,
+                chunk_type: ChunkType::Module,
+                symbol_name: Some(doc_path.clone()),
+                symbol_kind: Some(SymbolKind::Module),
+                origin: ChunkOrigin::Module,
+            });
+            changed.push((doc_path, hash));
         }
 
-        // Embed chunks in batches
-        let chunks = self.embed_chunks(chunk_infos).await?;
-        let chunk_count = chunks.len();
-        let chunk_ids: Vec<String> = chunks.iter().map(|c| c.id.clone()).collect();
+        let (chunks, _) = self
+            .embed_chunks(chunk_infos, HashMap::new(), &mut EmbeddedChunks::new(), &[])
+            .await?;
+        let saves: Vec<PendingSave> = changed
+            .iter()
+            .zip(chunks)
+            .map(|((path, hash), chunk)| PendingSave {
+                path: path.clone(),
+                hash: hash.clone(),
+                chunks: vec![chunk],
+                reused: 0,
+                deduplicated: 0,
+                stored_ids: Vec::new(),
+            })
+            .collect();
+        self.save_batch(saves).await?;
 
-        // Save chunks
-        self.store.save_chunks(chunks).await?;
+        Ok(changed.len())
+    }
+
+    /// Bring the hotness stored on an unchanged file's chunks up to date
+    /// without re-embedding them
+    async fn refresh_hotness(&self, file: &FileEntry) -> Result<()> {
+        let hotness = self.hotness.get(&file.relative_path).copied();
+        let stale: Vec<Chunk> = self
+            .store
+            .get_chunks_by_file(&file.relative_path, self.collection.as_deref())
+            .await?
+            .into_iter()
+            .filter(|chunk| chunk.hotness != hotness)
+            .map(|chunk| Chunk { hotness, ..chunk })
+            .collect();
+        if !stale.is_empty() {
+            self.store.save_chunks(stale).await?;
+        }
+        Ok(())
+    }
 
-        // Save document metadata
-        let doc = Document {
+    /// `file`'s embedded chunks, tagged with its language and hotness
+    fn file_save(&self, file: &FileEntry, hash: String, mut chunks: Vec<Chunk>) -> PendingSave {
+        let hotness = self.hotness.get(&file.relative_path).copied();
+        for chunk in &mut chunks {
+            chunk.hotness = hotness;
+            chunk.language = Some(file.language);
+        }
+        PendingSave {
             path: file.relative_path.clone(),
-            hash: content_hash,
-            mod_time: Utc::now(),
-            chunk_ids,
-        };
-        self.store.save_document(doc).await?;
+            hash,
+            chunks,
+            reused: 0,
+            deduplicated: 0,
+            stored_ids: Vec::new(),
+        }
+    }
+
+    /// Replace each document's stored chunks, writing all new chunks with one
+    /// `save_chunks` call. Documents left without chunks are only cleared.
+    async fn save_batch(&self, batch: Vec<PendingSave>) -> Result<()> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let mut all_chunks = Vec::new();
+        let mut docs = Vec::new();
+        for save in batch {
+            if save.stored_ids.is_empty() || save.chunks.is_empty() {
+                // Delete old chunks for this document
+                self.store
+                    .delete_by_file(&save.path, self.collection.as_deref())
+                    .await?;
+                if save.chunks.is_empty() {
+                    continue;
+                }
+            } else {
+                // Only chunks that are gone; the rest are overwritten in place
+                let kept: HashSet<&str> = save.chunks.iter().map(|c| c.id.as_str()).collect();
+                let removed: Vec<String> = save
+                    .stored_ids
+                    .iter()
+                    .filter(|id| !kept.contains(id.as_str()))
+                    .cloned()
+                    .collect();
+                if !removed.is_empty() {
+                    self.store
+                        .delete_chunks(&removed, self.collection.as_deref())
+                        .await?;
+                }
+            }
 
-        Ok(Some(chunk_count))
+            docs.push(Document {
+                path: save.path,
+                hash: save.hash,
+                mod_time: Utc::now(),
+                chunk_ids: save.chunks.iter().map(|c| c.id.clone()).collect(),
+                collection: self.collection.clone(),
+            });
+            all_chunks.extend(save.chunks);
+        }
+
+        if !all_chunks.is_empty() {
+            self.store.save_chunks(all_chunks).await?;
+        }
+        for doc in docs {
+            self.store.save_document(doc).await?;
+        }
+
+        Ok(())
     }
 
-    /// Embed chunks and return full Chunk objects
-    async fn embed_chunks(&self, chunk_infos: Vec<ChunkInfo>) -> Result<Vec<Chunk>> {
+    /// Embed chunks and return full Chunk objects, in order. Chunks whose id
+    /// is in `reusable` take that vector instead of being embedded. Content is
+    /// embedded once per `dedup_key`: chunks repeated within `chunk_infos`
+    /// share one vector, and chunks whose key is in `embedded` copy the vector
+    /// of that earlier chunk, found in `unsaved` or the store. Newly embedded
+    /// chunks are added to `embedded`. Also returns how many chunks shared a
+    /// vector.
+    async fn embed_chunks(
+        &self,
+        chunk_infos: Vec<ChunkInfo>,
+        mut reusable: HashMap<String, Vec<f32>>,
+        embedded: &mut EmbeddedChunks,
+        unsaved: &[PendingSave],
+    ) -> Result<(Vec<Chunk>, usize)> {
         const BATCH_SIZE: usize = 32;
-        let mut chunks = Vec::with_capacity(chunk_infos.len());
+        let mut vectors: Vec<Option<Vec<f32>>> = chunk_infos
+            .iter()
+            .map(|info| reusable.remove(&info.id))
+            .collect();
+
+        let keys: Vec<String> = chunk_infos.iter().map(|c| dedup_key(&c.content)).collect();
+        let mut earlier = Vec::new();
+        let mut repeats = Vec::new();
+        let mut first_here: HashMap<&str, usize> = HashMap::new();
+        for (i, key) in keys.iter().enumerate() {
+            if vectors[i].is_some() {
+                continue;
+            }
+            if let Some(chunk) = embedded.get(key) {
+                earlier.push((i, chunk.clone()));
+            } else if let Some(&first) = first_here.get(key.as_str()) {
+                // Filled in once the first chunk with this key is embedded
+                repeats.push((i, first));
+            } else {
+                first_here.insert(key, i);
+            }
+        }
+
+        let mut deduplicated = repeats.len();
+        for (i, vector) in self.earlier_vectors(&earlier, unsaved).await? {
+            vectors[i] = Some(vector);
+            deduplicated += 1;
+        }
+        // Copies whose earlier chunk is gone are embedded themselves
+        let mut missing: Vec<usize> = first_here.into_values().collect();
+        missing.extend(
+            earlier
+                .iter()
+                .map(|(i, _)| *i)
+                .filter(|&i| vectors[i].is_none()),
+        );
+        missing.sort_unstable();
 
-        for batch in chunk_infos.chunks(BATCH_SIZE) {
-            let texts: Vec<String> = batch.iter().map(|c| c.content.clone()).collect();
+        for batch in missing.chunks(BATCH_SIZE) {
+            let texts: Vec<String> = batch
+                .iter()
+                .map(|&i| chunk_infos[i].content.clone())
+                .collect();
             let embeddings = self.embedder.embed_batch(&texts).await?;
+            if embeddings.len() != texts.len() {
+                anyhow::bail!(
+                    "Embedder returned {} vectors for {} chunks",
+                    embeddings.len(),
+                    texts.len()
+                );
+            }
+            for (&i, vector) in batch.iter().zip(embeddings) {
+                embedded.insert(
+                    keys[i].clone(),
+                    ChunkRef {
+                        file_path: chunk_infos[i].file_path.clone(),
+                        id: chunk_infos[i].id.clone(),
+                    },
+                );
+                vectors[i] = Some(vector);
+            }
+        }
+        for (i, first) in repeats {
+            vectors[i] = vectors[first].clone();
+        }
 
-            for (info, vector) in batch.iter().zip(embeddings.into_iter()) {
-                chunks.push(Chunk {
-                    id: info.id.clone(),
-                    file_path: info.file_path.clone(),
+        let chunks = chunk_infos
+            .into_iter()
+            .zip(vectors)
+            .map(|(info, vector)| {
+                let vector = vector.unwrap_or_default();
+                Chunk {
+                    id: info.id,
+                    file_path: info.file_path,
                     start_line: info.start_line,
                     end_line: info.end_line,
-                    content: info.content.clone(),
+                    content: info.content,
+                    norm: Some(vector_norm(&vector)),
                     vector,
-                    hash: info.hash.clone(),
+                    hash: info.hash,
                     updated_at: Utc::now(),
-                    chunk_type: info.chunk_type.clone(),
-                });
-            }
+                    chunk_type: info.chunk_type,
+                    hotness: None,
+                    language: None,
+                    symbol_name: info.symbol_name,
+                    symbol_kind: info.symbol_kind,
+                    collection: self.collection.clone(),
+                }
+            })
+            .collect();
+        Ok((chunks, deduplicated))
+    }
+
+    /// Vectors of the chunks embedded earlier in the run that `earlier` points
+    /// at, by index into the caller's chunks, looked up in `unsaved` before the
+    /// store. Chunks no longer in either are left out.
+    async fn earlier_vectors(
+        &self,
+        earlier: &[(usize, ChunkRef)],
+        unsaved: &[PendingSave],
+    ) -> Result<Vec<(usize, Vec<f32>)>> {
+        let mut by_file: HashMap<&str, Vec<(usize, &str)>> = HashMap::new();
+        for (i, chunk) in earlier {
+            by_file
+                .entry(chunk.file_path.as_str())
+                .or_default()
+                .push((*i, chunk.id.as_str()));
         }
 
-        Ok(chunks)
+        let mut vectors = Vec::with_capacity(earlier.len());
+        for (file_path, wanted) in by_file {
+            let stored;
+            let chunks = match unsaved.iter().rev().find(|s| s.path == file_path) {
+                Some(save) => &save.chunks,
+                None => {
+                    stored = self
+                        .store
+                        .get_chunks_by_file(file_path, self.collection.as_deref())
+                        .await?;
+                    &stored
+                }
+            };
+            for (i, id) in wanted {
+                if let Some(chunk) = chunks.iter().find(|c| c.id == id) {
+                    vectors.push((i, chunk.vector.clone()));
+                }
+            }
+        }
+        Ok(vectors)
     }
 
     /// Remove files from index that no longer exist
     pub async fn prune_deleted(&self, root: &Path, respect_gitignore: bool) -> Result<usize> {
-        let existing_files = scan_directory(root, 500, respect_gitignore, None)?;
-        let existing_paths: std::collections::HashSet<String> = existing_files
+        self.prune_deleted_with_progress(root, respect_gitignore, &|_, _, _| {})
+            .await
+    }
+
+    /// Like `prune_deleted`, reporting `(checked, total, pruned)` after each indexed document
+    pub async fn prune_deleted_with_progress(
+        &self,
+        root: &Path,
+        respect_gitignore: bool,
+        progress: &(dyn Fn(usize, usize, usize) + Send + Sync),
+    ) -> Result<usize> {
+        let stale = self.stale_documents(root, respect_gitignore).await?;
+        self.prune_documents(&stale, progress).await
+    }
+
+    /// Indexed documents whose files no longer exist under `root`, without
+    /// removing them, so callers can decide whether pruning is safe
+    pub async fn stale_documents(
+        &self,
+        root: &Path,
+        respect_gitignore: bool,
+    ) -> Result<StaleDocuments> {
+        let scan = scan_index_report(root, respect_gitignore, &self.denylist)?;
+        if let Some(summary) = permission_denied_summary(&scan.permission_denied) {
+            eprintln!("Warning: {}", summary);
+        }
+        let existing_paths: HashSet<String> =
+            scan.files.iter().map(|f| f.relative_path.clone()).collect();
+
+        let indexed_paths = self
+            .store
+            .list_documents(self.collection.as_deref())
+            .await?;
+        let paths = indexed_paths
             .iter()
-            .map(|f| f.relative_path.clone())
+            // Module summaries are stored under their directory
+            .filter(|path| !existing_paths.contains(*path) && !root.join(path).is_dir())
+            .cloned()
             .collect();
 
-        let indexed_paths = self.store.list_documents().await?;
+        Ok(StaleDocuments {
+            paths,
+            indexed: indexed_paths,
+        })
+    }
+
+    /// Remove `stale` documents from the index, reporting `(checked, total, pruned)`
+    pub async fn prune_documents(
+        &self,
+        stale: &StaleDocuments,
+        progress: &(dyn Fn(usize, usize, usize) + Send + Sync),
+    ) -> Result<usize> {
+        let total = stale.indexed.len();
         let mut pruned = 0;
 
-        for path in indexed_paths {
-            if !existing_paths.contains(&path) {
-                self.store.delete_by_file(&path).await?;
+        for (i, path) in stale.indexed.iter().enumerate() {
+            if stale.paths.contains(path) {
+                self.store
+                    .delete_by_file(path, self.collection.as_deref())
+                    .await?;
                 pruned += 1;
             }
+            progress(i + 1, total, pruned);
         }
 
         if pruned > 0 {
@@ -181,6 +1046,44 @@ impl Indexer {
     }
 }
 
+/// Documents `prune_deleted` would remove
+#[derive(Debug, Clone, Default)]
+pub struct StaleDocuments {
+    /// Indexed paths missing from the scanned tree
+    pub paths: HashSet<String>,
+    /// Every indexed path, stale or not
+    pub indexed: Vec<String>,
+}
+
+impl StaleDocuments {
+    /// Percentage of indexed documents that would be removed
+    pub fn percent(&self) -> f64 {
+        if self.indexed.is_empty() {
+            0.0
+        } else {
+            self.paths.len() as f64 * 100.0 / self.indexed.len() as f64
+        }
+    }
+}
+
+/// Key under which identical chunks share one embedding: the hash of the
+/// embedded text without its `File:` and `Lines:` header lines. Copies of the
+/// same code differ only there, so one vector stands in for all of them, while
+/// symbol names and section headings stay in the key since they change what
+/// the chunk means.
+fn dedup_key(content: &str) -> String {
+    let Some(rest) = content.strip_prefix("File: ") else {
+        return hash_content(content);
+    };
+    let (header, body) = rest.split_once("\n\n").unwrap_or((rest, ""));
+    let kept: Vec<&str> = header
+        .lines()
+        .skip(1)
+        .filter(|line| !line.starts_with("Lines: "))
+        .collect();
+    hash_content(&format!("{}\n\n{}", kept.join("\n"), body))
+}
+
 fn hash_content(content: &str) -> String {
     use sha2::{Digest, Sha256};
     let mut hasher = Sha256::new();
@@ -191,6 +1094,673 @@ fn hash_content(content: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::scan::scan_directory;
+    use crate::search::store::{GobStore, IndexStats, SearchFilter, SearchResult};
+    use crate::test_support::{make_chunk, FixedEmbedder, KeywordEmbedder};
+    use async_trait::async_trait;
+    use std::fs;
+    use std::process::Command;
+    use std::sync::atomic::Ordering;
+    use std::sync::Mutex;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    /// GobStore that counts `save_chunks` calls
+    struct CountingStore {
+        inner: GobStore,
+        saves: Mutex<usize>,
+    }
+
+    #[async_trait]
+    impl VectorStore for CountingStore {
+        async fn save_chunks(&self, chunks: Vec<Chunk>) -> Result<()> {
+            *self.saves.lock().unwrap() += 1;
+            self.inner.save_chunks(chunks).await
+        }
+        async fn delete_by_file(&self, file_path: &str, collection: Option<&str>) -> Result<()> {
+            self.inner.delete_by_file(file_path, collection).await
+        }
+        async fn delete_chunks(&self, ids: &[String], collection: Option<&str>) -> Result<()> {
+            self.inner.delete_chunks(ids, collection).await
+        }
+        async fn get_chunks_by_file(
+            &self,
+            file_path: &str,
+            collection: Option<&str>,
+        ) -> Result<Vec<Chunk>> {
+            self.inner.get_chunks_by_file(file_path, collection).await
+        }
+        async fn search(
+            &self,
+            query_vector: &[f32],
+            limit: usize,
+            collection: Option<&str>,
+        ) -> Result<Vec<SearchResult>> {
+            self.inner.search(query_vector, limit, collection).await
+        }
+        async fn search_filtered(
+            &self,
+            query_vector: &[f32],
+            limit: usize,
+            filter: SearchFilter,
+        ) -> Result<Vec<SearchResult>> {
+            self.inner
+                .search_filtered(query_vector, limit, filter)
+                .await
+        }
+        async fn get_document(
+            &self,
+            file_path: &str,
+            collection: Option<&str>,
+        ) -> Result<Option<Document>> {
+            self.inner.get_document(file_path, collection).await
+        }
+        async fn save_document(&self, doc: Document) -> Result<()> {
+            self.inner.save_document(doc).await
+        }
+        async fn list_documents(&self, collection: Option<&str>) -> Result<Vec<String>> {
+            self.inner.list_documents(collection).await
+        }
+        async fn get_meta(&self) -> Result<IndexMeta> {
+            self.inner.get_meta().await
+        }
+        async fn save_meta(&self, meta: IndexMeta) -> Result<()> {
+            self.inner.save_meta(meta).await
+        }
+        async fn get_all_chunks(&self, collection: Option<&str>) -> Result<Vec<Chunk>> {
+            self.inner.get_all_chunks(collection).await
+        }
+        async fn persist(&self) -> Result<()> {
+            self.inner.persist().await
+        }
+        async fn load(&self) -> Result<()> {
+            self.inner.load().await
+        }
+        async fn stats(&self) -> Result<IndexStats> {
+            self.inner.stats().await
+        }
+        async fn clear(&self) -> Result<()> {
+            self.inner.clear().await
+        }
+        async fn last_modified(&self) -> Result<Option<SystemTime>> {
+            self.inner.last_modified().await
+        }
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[tokio::test]
+    async fn test_max_chunks_per_file_truncates() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let big: String = (0..6)
+            .map(|i| format!("fn f{}() {{\n    let x = {};\n}}\n\n", i, i))
+            .collect();
+        fs::write(root.join("big.rs"), big).unwrap();
+        fs::write(root.join("small.rs"), "fn only() {\n    let y = 1;\n}\n").unwrap();
+
+        let store: Arc<dyn VectorStore> =
+            Arc::new(GobStore::new(root.join(".agentlens/index.json")));
+        let indexer = Indexer::new(
+            Arc::clone(&store),
+            Arc::new(FixedEmbedder::new(vec![1.0, 0.0, 0.0])),
+            &ChunkingConfig::default(),
+        )
+        .with_max_chunks_per_file(2);
+
+        let result = indexer.index_all(root, false, false).await.unwrap();
+
+        let big_doc = store.get_document("big.rs", None).await.unwrap().unwrap();
+        assert_eq!(big_doc.chunk_ids.len(), 2);
+        let small_doc = store.get_document("small.rs", None).await.unwrap().unwrap();
+        assert_eq!(small_doc.chunk_ids.len(), 1);
+        assert_eq!(result.chunks_created, 3);
+        assert_eq!(
+            result.truncated,
+            vec!["big.rs: kept 2 of 6 chunks".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_utf8_is_indexed_and_binary_is_skipped() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(
+            root.join("latin1.rs"),
+            b"// caf\xe9\nfn brew() {\n    let x = 1;\n}\n",
+        )
+        .unwrap();
+        // NUL past the scanner's 8 KiB binary sniff
+        let mut blob = "// padding\n".repeat(1000).into_bytes();
+        blob.extend_from_slice(b"\x00\x01\x02");
+        fs::write(root.join("blob.rs"), blob).unwrap();
+
+        let store: Arc<dyn VectorStore> =
+            Arc::new(GobStore::new(root.join(".agentlens/index.json")));
+        let indexer = Indexer::new(
+            Arc::clone(&store),
+            Arc::new(FixedEmbedder::new(vec![1.0, 0.0, 0.0])),
+            &ChunkingConfig::default(),
+        );
+
+        let result = indexer.index_all(root, false, false).await.unwrap();
+
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(result.files_processed, 1);
+        assert_eq!(result.files_skipped_binary, 1);
+        assert!(store
+            .get_document("latin1.rs", None)
+            .await
+            .unwrap()
+            .is_some());
+        assert!(store.get_document("blob.rs", None).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_changed_file_reembeds_only_changed_symbols() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let source = |changed: i32| -> String {
+            (0..30)
+                .map(|i| {
+                    let value = if i == 7 { changed } else { i };
+                    format!("fn f{}() {{\n    let x = {};\n}}\n\n", i, value)
+                })
+                .collect()
+        };
+        fs::write(root.join("big.rs"), source(7)).unwrap();
+        fs::write(root.join("other.rs"), "fn g() {\n    let y = 1;\n}\n").unwrap();
+
+        let store: Arc<dyn VectorStore> =
+            Arc::new(GobStore::new(root.join(".agentlens/index.json")));
+        let embedder = Arc::new(FixedEmbedder::new(vec![1.0, 0.0, 0.0]));
+        let indexer = Indexer::new(
+            Arc::clone(&store),
+            Arc::clone(&embedder) as Arc<dyn Embedder>,
+            &ChunkingConfig::default(),
+        );
+        let first = indexer.index_all(root, false, false).await.unwrap();
+        assert_eq!(first.chunks_created, 31);
+        assert_eq!(first.chunks_reused, 0);
+        let before = store.get_chunks_by_file("big.rs", None).await.unwrap();
+        embedder.texts.store(0, Ordering::SeqCst);
+
+        // One function body changes, and a new first line shifts the rest
+        fs::write(root.join("big.rs"), format!("// header\n{}", source(99))).unwrap();
+        let second = indexer.index_all(root, false, false).await.unwrap();
+
+        assert_eq!(embedder.texts(), 1);
+        assert_eq!(second.chunks_created, 1);
+        assert_eq!(second.chunks_reused, 29);
+
+        let after = store.get_chunks_by_file("big.rs", None).await.unwrap();
+        assert_eq!(after.len(), 30);
+        let old_f7 = before
+            .iter()
+            .find(|c| c.symbol_name.as_deref() == Some("f7"))
+            .unwrap();
+        assert!(after.iter().all(|c| c.id != old_f7.id));
+        let f0 = after
+            .iter()
+            .find(|c| c.symbol_name.as_deref() == Some("f0"))
+            .unwrap();
+        assert_eq!(f0.start_line, 2);
+        let doc = store.get_document("big.rs", None).await.unwrap().unwrap();
+        assert_eq!(doc.chunk_ids.len(), 30);
+        assert_eq!(
+            store
+                .get_chunks_by_file("other.rs", None)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_index_files_embeds_changed_files_in_one_pass() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        for name in ["a.rs", "b.rs", "c.rs"] {
+            fs::write(root.join(name), "fn f() {\n    let x = 1;\n}\n").unwrap();
+        }
+
+        let store: Arc<dyn VectorStore> =
+            Arc::new(GobStore::new(root.join(".agentlens/index.json")));
+        let embedder = Arc::new(FixedEmbedder::new(vec![1.0, 0.0, 0.0]));
+        let indexer = Indexer::new(
+            Arc::clone(&store),
+            Arc::clone(&embedder) as Arc<dyn Embedder>,
+            &ChunkingConfig::default(),
+        );
+        indexer.index_all(root, false, false).await.unwrap();
+        embedder.batches.store(0, Ordering::SeqCst);
+
+        // Two saves and one untouched file
+        fs::write(root.join("a.rs"), "fn a() {\n    let x = 2;\n}\n").unwrap();
+        fs::write(
+            root.join("b.rs"),
+            "fn b() {\n    let x = 3;\n}\n\nfn c() {}\n",
+        )
+        .unwrap();
+        let files = scan_directory(root, 500, false, None).unwrap();
+
+        let reindexed = indexer.index_files(&files, false).await.unwrap();
+
+        assert_eq!(reindexed, 2);
+        assert_eq!(embedder.batches(), 1);
+        let b_doc = store.get_document("b.rs", None).await.unwrap().unwrap();
+        assert_eq!(b_doc.chunk_ids.len(), 2);
+        let chunks = store.get_all_chunks(None).await.unwrap();
+        assert!(chunks
+            .iter()
+            .any(|c| c.file_path == "a.rs" && c.content.contains("fn a()")));
+        assert_eq!(chunks.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_identical_chunks_are_embedded_once() {
+        // Copies are read back from the unsaved batch, or from the store once
+        // every file is saved on its own
+        for save_batch_size in [DEFAULT_SAVE_BATCH_SIZE, 1] {
+            let temp = TempDir::new().unwrap();
+            let root = temp.path();
+            let boilerplate = "fn setup() {\n    let x = 1;\n}\n";
+            for name in ["a.rs", "b.rs", "c.rs"] {
+                fs::write(root.join(name), boilerplate).unwrap();
+            }
+            fs::write(root.join("d.rs"), "fn other() {\n    let y = 2;\n}\n").unwrap();
+
+            let store: Arc<dyn VectorStore> =
+                Arc::new(GobStore::new(root.join(".agentlens/index.json")));
+            let embedder = Arc::new(FixedEmbedder::new(vec![1.0, 0.0, 0.0]));
+            let indexer = Indexer::new(
+                Arc::clone(&store),
+                Arc::clone(&embedder) as Arc<dyn Embedder>,
+                &ChunkingConfig::default(),
+            )
+            .with_save_batch_size(save_batch_size);
+
+            let result = indexer.index_all(root, false, false).await.unwrap();
+
+            assert_eq!(result.chunks_created, 4);
+            assert_eq!(result.chunks_deduplicated, 2);
+            assert_eq!(embedder.texts(), 2);
+            let chunks = store.get_all_chunks(None).await.unwrap();
+            assert_eq!(chunks.len(), 4);
+            assert!(chunks.iter().all(|c| c.vector.len() == 3));
+        }
+    }
+
+    #[test]
+    fn test_dedup_key_ignores_only_the_location() {
+        let chunk = |file: &str, symbol: &str, lines: &str| {
+            format!(
+                "File: {}\nSymbol: {} (function)\nLines: {}\n\nfn setup() {{}}\n",
+                file, symbol, lines
+            )
+        };
+        let key = dedup_key(&chunk("a.rs", "setup", "1-3"));
+
+        assert_eq!(key, dedup_key(&chunk("lib/b.rs", "setup", "40-42")));
+        assert_ne!(key, dedup_key(&chunk("a.rs", "init", "1-3")));
+        assert_ne!(key, dedup_key("fn setup() {}\n"));
+    }
+
+    #[tokio::test]
+    async fn test_module_summaries_are_indexed_and_searchable() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("src/search")).unwrap();
+        fs::create_dir_all(root.join("src/analyze")).unwrap();
+        fs::write(
+            root.join("src/search/mod.rs"),
+            "//! Semantic search over embedded chunks.\npub mod rank;\n",
+        )
+        .unwrap();
+        fs::write(root.join("src/search/rank.rs"), "pub fn rank() {}\n").unwrap();
+        fs::write(root.join("src/analyze/mod.rs"), "pub mod parse;\n").unwrap();
+        fs::write(root.join("src/analyze/parse.rs"), "pub fn parse() {}\n").unwrap();
+
+        let store: Arc<dyn VectorStore> =
+            Arc::new(GobStore::new(root.join(".agentlens/index.json")));
+        let embedder: Arc<dyn Embedder> = Arc::new(KeywordEmbedder(&["search", "analy"]));
+        let indexer = Indexer::new(
+            Arc::clone(&store),
+            Arc::clone(&embedder),
+            &ChunkingConfig::default(),
+        )
+        .with_module_summaries();
+        indexer.index_all(root, false, false).await.unwrap();
+
+        let files = scan_directory(root, 500, false, None).unwrap();
+        let mut module_paths: Vec<String> = store
+            .get_all_chunks(None)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|c| c.chunk_type == ChunkType::Module)
+            .map(|c| c.file_path)
+            .collect();
+        module_paths.sort();
+        let mut expected: Vec<String> = crate::analyze::detect_modules(&files)
+            .iter()
+            .map(module_document_path)
+            .collect();
+        expected.sort();
+        assert_eq!(module_paths, expected);
+        assert!(module_paths.contains(&"src/search".to_string()));
+
+        // Unchanged summaries are not re-embedded, and pruning keeps them
+        let result = indexer.index_all(root, false, false).await.unwrap();
+        assert_eq!(result.chunks_created, 0);
+        assert_eq!(indexer.prune_deleted(root, false).await.unwrap(), 0);
+
+        let searcher = crate::search::Searcher::new(Arc::clone(&store), embedder, false, 60.0);
+        let filter = crate::search::SearchFilter {
+            chunk_types: Some(vec![ChunkType::Module]),
+            ..Default::default()
+        };
+        let results = searcher
+            .search_filtered("the search subsystem", 5, filter)
+            .await
+            .unwrap();
+        assert_eq!(results[0].chunk.file_path, "src/search");
+        assert!(results[0]
+            .chunk
+            .content
+            .contains("Semantic search over embedded chunks."));
+    }
+
+    #[tokio::test]
+    async fn test_dimension_mismatch_requires_reindex() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(root.join("a.rs"), "fn a() {\n    let x = 1;\n}\n").unwrap();
+
+        // Index left behind by a 768-dimension model
+        let store: Arc<dyn VectorStore> =
+            Arc::new(GobStore::new(root.join(".agentlens/index.json")));
+        store
+            .save_meta(IndexMeta {
+                model: Some("nomic-embed-text".to_string()),
+                dimensions: Some(768),
+            })
+            .await
+            .unwrap();
+        store.persist().await.unwrap();
+
+        let indexer = Indexer::new(
+            Arc::clone(&store),
+            Arc::new(FixedEmbedder::new(vec![1.0, 0.0, 0.0])),
+            &ChunkingConfig::default(),
+        );
+        let err = indexer.index_all(root, false, false).await.err().unwrap();
+        assert!(
+            err.to_string()
+                .contains("768-dimension vectors from 'nomic-embed-text'"),
+            "{}",
+            err
+        );
+
+        // --force re-embeds everything, so it may proceed
+        let result = indexer.index_all(root, false, true).await.unwrap();
+        assert_eq!(result.files_processed, 1);
+    }
+
+    #[tokio::test]
+    async fn test_dimensions_come_from_vectors_not_config() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(root.join("a.rs"), "fn a() {\n    let x = 1;\n}\n").unwrap();
+        let store: Arc<dyn VectorStore> =
+            Arc::new(GobStore::new(root.join(".agentlens/index.json")));
+        store
+            .save_meta(IndexMeta {
+                model: Some("mock".to_string()),
+                dimensions: Some(3),
+            })
+            .await
+            .unwrap();
+        store.persist().await.unwrap();
+
+        // Configured for 3 dimensions, but the model returns 2
+        let indexer = Indexer::new(
+            Arc::clone(&store),
+            Arc::new(FixedEmbedder::new(vec![1.0, 0.0]).claiming_dimensions(3)),
+            &ChunkingConfig::default(),
+        );
+        assert_eq!(indexer.vector_dimensions().await.unwrap(), 2);
+        let err = indexer.index_all(root, false, false).await.err().unwrap();
+        assert!(err.to_string().contains("produces 2-dimension"), "{}", err);
+
+        // A rebuild records what the vectors are, not what was configured
+        assert!(indexer
+            .reindex_if_mismatched(root, "mock", true)
+            .await
+            .unwrap());
+        assert_eq!(store.get_meta().await.unwrap().dimensions, Some(2));
+        assert!(indexer.index_all(root, false, false).await.is_ok());
+
+        // Configured for 768 but returning 2 fits the index
+        let indexer = Indexer::new(
+            Arc::clone(&store),
+            Arc::new(FixedEmbedder::new(vec![1.0, 0.0]).claiming_dimensions(768)),
+            &ChunkingConfig::default(),
+        );
+        assert!(indexer.index_all(root, false, false).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_checkpoints_persist_progress_mid_run() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        for i in 0..5 {
+            let body = format!("fn f{}() {{\n    let x = {};\n}}\n", i, i);
+            fs::write(root.join(format!("f{}.rs", i)), body).unwrap();
+        }
+
+        let index_path = root.join(".agentlens/index.json");
+        let store: Arc<dyn VectorStore> = Arc::new(GobStore::new(index_path.clone()));
+        let indexer = Indexer::new(
+            Arc::clone(&store),
+            Arc::new(FixedEmbedder::new(vec![1.0, 0.0, 0.0])),
+            &ChunkingConfig::default(),
+        )
+        .with_checkpoint_interval(2);
+
+        // (files so far, documents already on disk) at each checkpoint
+        let checkpoints = Mutex::new(Vec::new());
+        let result = indexer
+            .index_all_with_checkpoints(root, false, false, &|progress| {
+                let on_disk: serde_json::Value =
+                    serde_json::from_slice(&fs::read(&index_path).unwrap()).unwrap();
+                let documents = on_disk["documents"].as_object().unwrap().len();
+                checkpoints
+                    .lock()
+                    .unwrap()
+                    .push((progress.files_processed, documents));
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(checkpoints.into_inner().unwrap(), vec![(2, 2), (4, 4)]);
+        assert_eq!(result.files_processed, 5);
+        assert_eq!(result.chunks_created, 5);
+        assert_eq!(store.list_documents(None).await.unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_saves_are_batched_across_files() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        for i in 0..7 {
+            let body = format!("fn f{}() {{\n    let x = {};\n}}\n", i, i);
+            fs::write(root.join(format!("f{}.rs", i)), body).unwrap();
+        }
+
+        let store = Arc::new(CountingStore {
+            inner: GobStore::new(root.join(".agentlens/index.json")),
+            saves: Mutex::new(0),
+        });
+        let indexer = Indexer::new(
+            Arc::clone(&store) as Arc<dyn VectorStore>,
+            Arc::new(FixedEmbedder::new(vec![1.0, 0.0, 0.0])),
+            &ChunkingConfig::default(),
+        )
+        .with_save_batch_size(3);
+
+        let result = indexer.index_all(root, false, false).await.unwrap();
+
+        // 7 files in batches of 3: 3 + 3 + 1
+        assert_eq!(*store.saves.lock().unwrap(), 3);
+        assert_eq!(result.chunks_created, 7);
+        assert_eq!(store.list_documents(None).await.unwrap().len(), 7);
+        let doc = store.get_document("f6.rs", None).await.unwrap().unwrap();
+        assert_eq!(doc.chunk_ids.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_index_diff_touches_only_changed_files() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        git(root, &["init", "-q"]);
+        for name in ["kept.rs", "edited.rs", "removed.rs"] {
+            fs::write(root.join(name), "fn f() {\n    let x = 1;\n}\n").unwrap();
+        }
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", "initial"]);
+
+        let store: Arc<dyn VectorStore> =
+            Arc::new(GobStore::new(root.join(".agentlens/index.json")));
+        let embedder = Arc::new(FixedEmbedder::new(vec![1.0, 0.0, 0.0]));
+        let indexer = Indexer::new(
+            Arc::clone(&store),
+            Arc::clone(&embedder) as Arc<dyn Embedder>,
+            &ChunkingConfig::default(),
+        );
+        indexer.index_all(root, true, false).await.unwrap();
+        let kept = store.get_document("kept.rs", None).await.unwrap().unwrap();
+
+        fs::write(root.join("edited.rs"), "fn f() {\n    let x = 2;\n}\n").unwrap();
+        fs::write(root.join("added.rs"), "fn g() {\n    let y = 1;\n}\n").unwrap();
+        fs::remove_file(root.join("removed.rs")).unwrap();
+        git(root, &["add", "-A"]);
+        git(root, &["commit", "-q", "-m", "change"]);
+        embedder.batches.store(0, Ordering::SeqCst);
+
+        let result = indexer.index_diff(root, "HEAD~1").await.unwrap();
+
+        assert_eq!(result.files_processed, 2);
+        assert_eq!(result.chunks_created, 2);
+        assert_eq!(embedder.batches(), 2);
+        let mut docs = store.list_documents(None).await.unwrap();
+        docs.sort();
+        assert_eq!(docs, vec!["added.rs", "edited.rs", "kept.rs"]);
+        let still_kept = store.get_document("kept.rs", None).await.unwrap().unwrap();
+        assert_eq!(still_kept.mod_time, kept.mod_time);
+    }
+
+    #[tokio::test]
+    async fn test_hotness_from_commit_frequency() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        git(root, &["init", "-q"]);
+
+        fs::write(root.join("cold.rs"), "fn cold() {\n    let x = 1;\n}\n").unwrap();
+        fs::write(root.join("hot.rs"), "fn hot() {\n    let x = 0;\n}\n").unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", "initial"]);
+
+        for i in 1..4 {
+            let body = format!("fn hot() {{\n    let x = {};\n}}\n", i);
+            fs::write(root.join("hot.rs"), body).unwrap();
+            git(root, &["commit", "-q", "-am", "touch hot"]);
+        }
+
+        let counts = crate::scan::get_commit_counts(root, 100).unwrap();
+        let store: Arc<dyn VectorStore> =
+            Arc::new(GobStore::new(root.join(".agentlens/index.json")));
+        let indexer = Indexer::new(
+            Arc::clone(&store),
+            Arc::new(FixedEmbedder::new(vec![1.0, 0.0, 0.0])),
+            &ChunkingConfig::default(),
+        )
+        .with_hotness(counts);
+        indexer.index_all(root, true, false).await.unwrap();
+
+        let chunks = store.get_all_chunks(None).await.unwrap();
+        let hotness_of = |file: &str| {
+            chunks
+                .iter()
+                .find(|c| c.file_path == file)
+                .and_then(|c| c.hotness)
+                .unwrap()
+        };
+
+        assert!(hotness_of("hot.rs") > hotness_of("cold.rs"));
+        assert!((hotness_of("hot.rs") - 1.0).abs() < 0.001);
+    }
+
+    #[tokio::test]
+    async fn test_hotness_on_unchanged_index_updates_without_embedding() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(root.join("cold.rs"), "fn cold() {\n    let x = 1;\n}\n").unwrap();
+        fs::write(root.join("hot.rs"), "fn hot() {\n    let x = 0;\n}\n").unwrap();
+
+        // Indexed without --hotness
+        let store: Arc<dyn VectorStore> =
+            Arc::new(GobStore::new(root.join(".agentlens/index.json")));
+        let embedder = Arc::new(FixedEmbedder::new(vec![1.0, 0.0, 0.0]));
+        Indexer::new(
+            Arc::clone(&store),
+            embedder.clone(),
+            &ChunkingConfig::default(),
+        )
+        .index_all(root, true, false)
+        .await
+        .unwrap();
+        let embedded = embedder.texts();
+
+        let counts = HashMap::from([("hot.rs".to_string(), 4), ("cold.rs".to_string(), 1)]);
+        let result = Indexer::new(
+            Arc::clone(&store),
+            embedder.clone(),
+            &ChunkingConfig::default(),
+        )
+        .with_hotness(counts)
+        .index_all(root, true, false)
+        .await
+        .unwrap();
+
+        assert_eq!(result.files_skipped, 2);
+        assert_eq!(embedder.texts(), embedded);
+        for (file, hotness) in [("hot.rs", 1.0), ("cold.rs", 0.25)] {
+            let chunks = store.get_chunks_by_file(file, None).await.unwrap();
+            assert!(!chunks.is_empty());
+            assert!(
+                chunks.iter().all(|c| c.hotness == Some(hotness)),
+                "{}",
+                file
+            );
+        }
+
+        // The refreshed hotness is persisted
+        let reloaded = GobStore::new(root.join(".agentlens/index.json"));
+        reloaded.load().await.unwrap();
+        let chunks = reloaded.get_chunks_by_file("cold.rs", None).await.unwrap();
+        assert_eq!(chunks[0].hotness, Some(0.25));
+    }
 
     #[test]
     fn test_hash_content() {
@@ -202,4 +1772,93 @@ mod tests {
         assert_ne!(h1, h3);
         assert_eq!(h1.len(), 16);
     }
+
+    #[tokio::test]
+    async fn test_prune_progress_called_per_document() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        for name in ["a.rs", "b.rs", "c.rs"] {
+            fs::write(root.join(name), "fn f() {\n    let x = 1;\n}\n").unwrap();
+        }
+
+        let store: Arc<dyn VectorStore> =
+            Arc::new(GobStore::new(root.join(".agentlens/index.json")));
+        let indexer = Indexer::new(
+            Arc::clone(&store),
+            Arc::new(FixedEmbedder::new(vec![1.0, 0.0, 0.0])),
+            &ChunkingConfig::default(),
+        );
+        indexer.index_all(root, true, false).await.unwrap();
+        fs::remove_file(root.join("b.rs")).unwrap();
+
+        let calls = Mutex::new(Vec::new());
+        let pruned = indexer
+            .prune_deleted_with_progress(root, true, &|checked, total, pruned| {
+                calls.lock().unwrap().push((checked, total, pruned));
+            })
+            .await
+            .unwrap();
+
+        let calls = calls.into_inner().unwrap();
+        assert_eq!(pruned, 1);
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls.iter().map(|c| c.0).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(calls.last(), Some(&(3, 3, 1)));
+    }
+
+    #[tokio::test]
+    async fn test_reindex_on_model_mismatch_then_search() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(root.join("a.rs"), "fn f() {\n    let x = 1;\n}\n").unwrap();
+
+        // An index built by another model, with vectors of another dimension
+        let store: Arc<dyn VectorStore> =
+            Arc::new(GobStore::new(root.join(".agentlens/index.json")));
+        store
+            .save_chunks(vec![Chunk {
+                end_line: 3,
+                content: "fn f() {}".to_string(),
+                hash: "old".to_string(),
+                ..make_chunk("a.rs:block:1", "a.rs", vec![0.5; 5])
+            }])
+            .await
+            .unwrap();
+        store
+            .save_meta(IndexMeta {
+                model: Some("old-model".to_string()),
+                dimensions: Some(3),
+            })
+            .await
+            .unwrap();
+        store.persist().await.unwrap();
+
+        let embedder: Arc<dyn Embedder> = Arc::new(FixedEmbedder::new(vec![1.0, 0.0, 0.0]));
+        let indexer = Indexer::new(
+            Arc::clone(&store),
+            Arc::clone(&embedder),
+            &ChunkingConfig::default(),
+        );
+
+        assert!(indexer
+            .reindex_if_mismatched(root, "mock", true)
+            .await
+            .unwrap());
+        assert_eq!(
+            store.get_meta().await.unwrap().model.as_deref(),
+            Some("mock")
+        );
+        // Already matching: nothing to do
+        assert!(!indexer
+            .reindex_if_mismatched(root, "mock", true)
+            .await
+            .unwrap());
+
+        assert_eq!(store.get_meta().await.unwrap().dimensions, Some(3));
+
+        let searcher = crate::search::Searcher::new(Arc::clone(&store), embedder, false, 60.0);
+        let results = searcher.search("f", 5).await.unwrap();
+        assert!(!results.is_empty());
+        assert!(results.iter().all(|r| r.chunk.vector.len() == 3));
+    }
 }
@@ -0,0 +1,114 @@
+//! An index that is searched while a watcher keeps it current.
+//!
+//! Re-indexing and searching share one store. Searches reload the persisted
+//! index, so a search landing mid-update could discard chunks the indexer has
+//! not persisted yet; a read/write gate keeps the two from overlapping.
+
+use anyhow::Result;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::config::{ChunkingConfig, SearchOptionsConfig};
+use crate::scan::{scan_directory, DEFAULT_LARGE_FILE_LINES};
+use crate::types::FileEntry;
+
+use super::embedder::Embedder;
+use super::indexer::{IndexResult, Indexer};
+use super::searcher::Searcher;
+use super::store::{SearchResult, VectorStore};
+
+pub struct LiveIndex {
+    root: PathBuf,
+    store: Arc<dyn VectorStore>,
+    indexer: Indexer,
+    searcher: Searcher,
+    gate: RwLock<()>,
+}
+
+impl LiveIndex {
+    pub fn new(
+        root: &Path,
+        store: Arc<dyn VectorStore>,
+        embedder: Arc<dyn Embedder>,
+        chunking: &ChunkingConfig,
+        search: &SearchOptionsConfig,
+    ) -> Result<Self> {
+        let root = root.canonicalize()?;
+        let indexer = Indexer::new(Arc::clone(&store), Arc::clone(&embedder), chunking);
+        let searcher = Searcher::new(
+            Arc::clone(&store),
+            embedder,
+            search.hybrid_enabled,
+            search.hybrid_k,
+        )
+        .with_hybrid_weights(search.hybrid_vector_weight, search.hybrid_text_weight);
+
+        Ok(Self {
+            root,
+            store,
+            indexer,
+            searcher,
+            gate: RwLock::new(()),
+        })
+    }
+
+    /// Bring the index up to date with the whole tree (unchanged files are skipped)
+    pub async fn build(&self) -> Result<IndexResult> {
+        let _write = self.gate.write().await;
+        let result = self.indexer.index_all(&self.root, true, false).await?;
+        self.indexer.prune_deleted(&self.root, true).await?;
+        Ok(result)
+    }
+
+    /// Re-index the given changed paths with one embedding pass; paths no
+    /// longer indexable (deleted, ignored) are dropped from the index along
+    /// with anything indexed under them, e.g. a removed directory. Returns how
+    /// many files were re-embedded.
+    pub async fn apply_changes(&self, paths: &[PathBuf]) -> Result<usize> {
+        let changed: HashSet<String> = paths
+            .iter()
+            .filter_map(|p| p.strip_prefix(&self.root).ok())
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .collect();
+        if changed.is_empty() {
+            return Ok(0);
+        }
+
+        let _write = self.gate.write().await;
+        self.store.load().await?;
+
+        let files: Vec<FileEntry> =
+            scan_directory(&self.root, DEFAULT_LARGE_FILE_LINES, true, None)?
+                .into_iter()
+                .filter(|f| changed.contains(&f.relative_path))
+                .collect();
+        let reindexed = self.indexer.index_files(&files, false).await?;
+
+        let still_indexable: HashSet<&str> =
+            files.iter().map(|f| f.relative_path.as_str()).collect();
+        let removed: Vec<&String> = changed
+            .iter()
+            .filter(|p| !still_indexable.contains(p.as_str()))
+            .collect();
+        if !removed.is_empty() {
+            for path in self.store.list_documents(None).await? {
+                let under_removed = removed
+                    .iter()
+                    .any(|r| path == **r || path.starts_with(&format!("{}/", r)));
+                if under_removed {
+                    self.store.delete_by_file(&path, None).await?;
+                }
+            }
+        }
+
+        self.store.persist().await?;
+        Ok(reindexed)
+    }
+
+    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
+        let _read = self.gate.read().await;
+        self.searcher.smart_search(query, limit).await
+    }
+}
@@ -1,13 +1,51 @@
+pub mod analytics;
+pub mod cache;
 pub mod chunker;
 pub mod embedder;
+pub mod explain;
+pub mod facets;
+pub mod grep;
+pub mod highlight;
+pub(crate) mod http;
 pub mod hybrid;
 pub mod indexer;
+pub mod live;
+pub mod module_summary;
+pub mod read_limit;
+pub mod reranker;
+pub mod review;
 pub mod searcher;
+pub mod similar;
 pub mod store;
 
-pub use chunker::{ChunkInfo, Chunker};
-pub use embedder::{create_embedder, Embedder, EmbedderConfig};
-pub use hybrid::{reciprocal_rank_fusion, text_search};
-pub use indexer::{IndexResult, Indexer};
-pub use searcher::Searcher;
-pub use store::{Chunk, ChunkType, Document, GobStore, IndexStats, SearchResult, VectorStore};
+pub use analytics::{SearchAnalytics, SearchRecord};
+pub use cache::ResultCache;
+pub use chunker::{ChunkInfo, ChunkOrigin, Chunker};
+pub use embedder::{
+    create_embedder, probe_dimensions, DimensionAdapter, Embedder, EmbedderConfig, EmbedderProfile,
+    EmbedderUnreachable,
+};
+pub use explain::{explain_chunking, ChunkExplanation};
+pub use facets::{compute_facets, FacetCount, SearchFacets};
+pub use grep::grep_search;
+pub use highlight::{matched_terms, preview_lines, term_spans};
+pub use hybrid::{
+    reciprocal_rank_fusion, reciprocal_rank_fusion_ranked, reciprocal_rank_fusion_weighted,
+    text_search, text_search_with_params, Bm25Params, ScoreBreakdown,
+};
+pub use indexer::{IndexResult, Indexer, StaleDocuments, DEFAULT_SAVE_BATCH_SIZE};
+pub use live::LiveIndex;
+pub use read_limit::{ReadLimiter, DEFAULT_IO_CONCURRENCY};
+pub use reranker::{create_reranker, NoopReranker, Reranker, RerankerConfig};
+pub use review::{review_hunks, ReviewMatch};
+pub use searcher::{
+    apply_path_affinity, SearchOutcome, Searcher, DEFAULT_HYBRID_TEXT_WEIGHT,
+    DEFAULT_HYBRID_VECTOR_WEIGHT, KEYWORD_MODE, PATH_AFFINITY_CANDIDATES_FACTOR,
+};
+pub use similar::{similar_files, SimilarFile};
+pub use store::{
+    create_store, create_store_for_writing, detect_index_format, index_path, open_index_file,
+    serve_store_http, Chunk, ChunkType, Document, GobStore, IndexFormat, IndexMeta, IndexStats,
+    RemoteStore, SearchFilter, SearchResult, SqliteStore, StoreRequest, TopK, VectorStore,
+    STORE_TOKEN_ENV,
+};
@@ -0,0 +1,123 @@
+//! Module-level chunks: one embedded summary per detected module, so queries
+//! about a subsystem as a whole match the module rather than one of its files.
+
+use crate::analyze::ModuleInfo;
+
+/// Symbol names listed in one module summary
+const MAX_SUMMARY_SYMBOLS: usize = 200;
+
+/// Path a module summary is stored under; the root module is "."
+pub fn module_document_path(module: &ModuleInfo) -> String {
+    if module.path.is_empty() {
+        ".".to_string()
+    } else {
+        module.path.clone()
+    }
+}
+
+/// Text embedded for `module`: its path, module doc, files and symbol names
+pub fn summarize_module(module: &ModuleInfo, doc: Option<&str>, symbol_names: &[String]) -> String {
+    let mut text = format!("Module {}\n", module_document_path(module));
+    if let Some(doc) = doc {
+        text.push_str(doc.trim());
+        text.push('\n');
+    }
+
+    let files: Vec<&str> = module
+        .files
+        .iter()
+        .map(|f| f.rsplit('/').next().unwrap_or(f))
+        .collect();
+    text.push_str(&format!("Files: {}\n", files.join(", ")));
+
+    let mut names: Vec<&str> = Vec::new();
+    for name in symbol_names {
+        if !names.contains(&name.as_str()) {
+            names.push(name);
+        }
+    }
+    names.truncate(MAX_SUMMARY_SYMBOLS);
+    if !names.is_empty() {
+        text.push_str(&format!("Symbols: {}\n", names.join(", ")));
+    }
+
+    text
+}
+
+/// Module doc at the top of an entry file: Rust `//!` lines, a Python module
+/// docstring, or the Go comment preceding `package`
+pub fn module_doc(content: &str) -> Option<String> {
+    let trimmed = content.trim_start();
+
+    for quote in ["\"\"\"", "'''"] {
+        if let Some(rest) = trimmed.strip_prefix(quote) {
+            let end = rest.find(quote)?;
+            return non_empty(rest[..end].trim().to_string());
+        }
+    }
+
+    let rust: Vec<&str> = content
+        .lines()
+        .map(str::trim)
+        .skip_while(|l| l.is_empty())
+        .take_while(|l| l.starts_with("//!"))
+        .map(|l| l.trim_start_matches("//!").trim())
+        .collect();
+    if !rust.is_empty() {
+        return non_empty(rust.join("\n").trim().to_string());
+    }
+
+    let mut go = Vec::new();
+    for line in content.lines().map(str::trim) {
+        if let Some(comment) = line.strip_prefix("//") {
+            go.push(comment.trim());
+        } else if line.starts_with("package ") {
+            return non_empty(go.join("\n").trim().to_string());
+        } else {
+            go.clear();
+        }
+    }
+    None
+}
+
+fn non_empty(text: String) -> Option<String> {
+    (!text.is_empty()).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze::BoundaryType;
+
+    #[test]
+    fn test_module_doc_styles() {
+        assert_eq!(
+            module_doc("//! Semantic search.\n//! Second line.\n\npub mod a;\n").as_deref(),
+            Some("Semantic search.\nSecond line.")
+        );
+        assert_eq!(
+            module_doc("\"\"\"Billing helpers.\"\"\"\nimport os\n").as_deref(),
+            Some("Billing helpers.")
+        );
+        assert_eq!(
+            module_doc("// Package store persists vectors.\npackage store\n").as_deref(),
+            Some("Package store persists vectors.")
+        );
+        assert_eq!(module_doc("use std::fs;\n"), None);
+    }
+
+    #[test]
+    fn test_summarize_module_dedupes_symbols() {
+        let mut module = ModuleInfo::new("src/search", BoundaryType::RustModule, None);
+        module.add_file("src/search/mod.rs".to_string());
+        module.add_file("src/search/indexer.rs".to_string());
+        let names = vec!["Indexer".to_string(), "new".to_string(), "new".to_string()];
+
+        let text = summarize_module(&module, Some("Semantic search."), &names);
+
+        assert_eq!(
+            text,
+            "Module src/search\nSemantic search.\nFiles: mod.rs, indexer.rs\nSymbols: Indexer, new\n"
+        );
+    }
+}
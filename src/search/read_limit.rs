@@ -0,0 +1,102 @@
+//! Bound on how many files are read at once while indexing.
+//!
+//! Separate from CPU parallelism: on spinning disks and network filesystems
+//! a handful of concurrent reads is faster than hundreds competing for IO.
+
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+use crate::scan::read_source;
+
+/// Default number of files read concurrently during indexing
+pub const DEFAULT_IO_CONCURRENCY: usize = 8;
+
+#[derive(Clone)]
+pub struct ReadLimiter {
+    permits: Arc<Semaphore>,
+    max_concurrent: usize,
+}
+
+impl ReadLimiter {
+    /// Allow up to `max_concurrent` reads at a time (at least one)
+    pub fn new(max_concurrent: usize) -> Self {
+        let max_concurrent = max_concurrent.max(1);
+        Self {
+            permits: Arc::new(Semaphore::new(max_concurrent)),
+            max_concurrent,
+        }
+    }
+
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+    }
+
+    /// Read `path` as text with `read_source`: lossy for invalid UTF-8, an
+    /// error for binary content
+    pub async fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        let path = path.to_path_buf();
+        self.run(move || read_source(&path)).await
+    }
+
+    /// Run a blocking read on the blocking pool once a permit is free
+    pub async fn run<T, F>(&self, read: F) -> T
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .expect("read semaphore is never closed");
+        tokio::task::spawn_blocking(read)
+            .await
+            .expect("file read task panicked")
+    }
+}
+
+impl Default for ReadLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_IO_CONCURRENCY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_never_exceeds_max_concurrent_reads() {
+        let limiter = ReadLimiter::new(3);
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let mut handles = Vec::new();
+        for _ in 0..12 {
+            let limiter = limiter.clone();
+            let in_flight = Arc::clone(&in_flight);
+            let peak = Arc::clone(&peak);
+            handles.push(tokio::spawn(async move {
+                limiter
+                    .run(move || {
+                        let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        std::thread::sleep(Duration::from_millis(20));
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+                    })
+                    .await
+            }));
+        }
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        let peak = peak.load(Ordering::SeqCst);
+        assert!(peak <= 3, "{} reads ran at once", peak);
+        assert!(peak > 1, "reads never overlapped");
+    }
+}
@@ -0,0 +1,68 @@
+mod ollama;
+
+pub use ollama::OllamaReranker;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::time::Duration;
+
+use super::store::SearchResult;
+
+/// Retrieved candidates per requested result handed to the reranker
+pub const RERANK_CANDIDATES_FACTOR: usize = 4;
+
+/// Re-scores retrieved results with a model too slow to run over the whole
+/// index, returning them best first
+#[async_trait]
+pub trait Reranker: Send + Sync {
+    async fn rerank(&self, query: &str, results: Vec<SearchResult>) -> Result<Vec<SearchResult>>;
+}
+
+/// Keeps retrieval order and scores
+pub struct NoopReranker;
+
+#[async_trait]
+impl Reranker for NoopReranker {
+    async fn rerank(&self, _query: &str, results: Vec<SearchResult>) -> Result<Vec<SearchResult>> {
+        Ok(results)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RerankerConfig {
+    pub provider: String,
+    pub model: String,
+    pub endpoint: Option<String>,
+    /// Seconds before scoring one candidate is abandoned (default 60)
+    pub timeout_secs: Option<u64>,
+}
+
+impl Default for RerankerConfig {
+    fn default() -> Self {
+        Self {
+            provider: "ollama".to_string(),
+            model: "llama3.2".to_string(),
+            endpoint: None,
+            timeout_secs: None,
+        }
+    }
+}
+
+/// Build the reranker for `config.provider` ("ollama" or "none").
+/// Unknown providers fall back to Ollama.
+pub fn create_reranker(config: &RerankerConfig) -> Box<dyn Reranker> {
+    match config.provider.as_str() {
+        "none" => Box::new(NoopReranker),
+        _ => {
+            let endpoint = config
+                .endpoint
+                .clone()
+                .unwrap_or_else(|| "http://localhost:11434".to_string());
+            let reranker = OllamaReranker::new(&endpoint, &config.model);
+            Box::new(match config.timeout_secs {
+                Some(secs) => reranker.with_timeout(Duration::from_secs(secs)),
+                None => reranker,
+            })
+        }
+    }
+}
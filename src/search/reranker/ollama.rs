@@ -0,0 +1,224 @@
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::Reranker;
+use crate::search::store::SearchResult;
+
+/// Time allowed for scoring one candidate
+pub const DEFAULT_RERANK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Scores each candidate by prompting an Ollama chat model for a 0-10
+/// relevance rating, one `/api/generate` request per candidate
+pub struct OllamaReranker {
+    endpoint: String,
+    model: String,
+    client: Client,
+    request_timeout: Duration,
+}
+
+#[derive(Serialize)]
+struct GenerateRequest<'a> {
+    model: &'a str,
+    prompt: String,
+    stream: bool,
+    options: GenerateOptions,
+}
+
+#[derive(Serialize)]
+struct GenerateOptions {
+    temperature: f32,
+}
+
+#[derive(Deserialize)]
+struct GenerateResponse {
+    response: String,
+}
+
+impl OllamaReranker {
+    pub fn new(endpoint: &str, model: &str) -> Self {
+        let client = Client::builder()
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            client,
+            request_timeout: DEFAULT_RERANK_TIMEOUT,
+        }
+    }
+
+    /// Give up on scoring a candidate after `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Relevance of `result` to `query` from 0.0 to 1.0
+    async fn score(&self, query: &str, result: &SearchResult) -> Result<f32> {
+        let request = GenerateRequest {
+            model: &self.model,
+            prompt: prompt(query, result),
+            stream: false,
+            options: GenerateOptions { temperature: 0.0 },
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/api/generate", self.endpoint))
+            .timeout(self.request_timeout)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_connect() {
+                    anyhow!(
+                        "Cannot connect to Ollama at {} for reranking. Is Ollama running?",
+                        self.endpoint
+                    )
+                } else {
+                    anyhow!("Ollama rerank request failed: {}", e)
+                }
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            if status.as_u16() == 404 || body.contains("not found") {
+                return Err(anyhow!(
+                    "Rerank model '{}' not found. Pull it with:\n  ollama pull {}",
+                    self.model,
+                    self.model
+                ));
+            }
+            return Err(anyhow!("Ollama error ({}): {}", status, body));
+        }
+
+        let generated: GenerateResponse = response
+            .json()
+            .await
+            .context("Invalid Ollama rerank response")?;
+        // A reply without a rating ranks below every rated candidate
+        Ok(parse_rating(&generated.response).map_or(0.0, |rating| rating / 10.0))
+    }
+}
+
+fn prompt(query: &str, result: &SearchResult) -> String {
+    format!(
+        "Rate how relevant this code is to the search query, from 0 (unrelated) \
+         to 10 (exactly what was asked for). Reply with only the number.\n\n\
+         Query: {}\n\nCode from {}:\n{}\n\nRelevance:",
+        query, result.chunk.file_path, result.chunk.content
+    )
+}
+
+/// First number in `reply`, clamped to 0-10, e.g. "8", "Relevance: 7/10"
+fn parse_rating(reply: &str) -> Option<f32> {
+    let start = reply.find(|c: char| c.is_ascii_digit())?;
+    let number: String = reply[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    number
+        .trim_end_matches('.')
+        .parse::<f32>()
+        .ok()
+        .map(|n| n.clamp(0.0, 10.0))
+}
+
+#[async_trait]
+impl Reranker for OllamaReranker {
+    async fn rerank(&self, query: &str, results: Vec<SearchResult>) -> Result<Vec<SearchResult>> {
+        let mut scored = Vec::with_capacity(results.len());
+        for mut result in results {
+            result.score = self.score(query, &result).await?;
+            scored.push(result);
+        }
+        // Stable, so equally rated candidates keep their retrieval order
+        scored.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        Ok(scored)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::store::{Chunk, ChunkType};
+    use crate::test_support::{make_chunk, read_body, write_json_response};
+    use tokio::net::TcpListener;
+
+    /// Fake `/api/generate` rating prompts that mention "retry" 9, others 2
+    async fn spawn_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let body = read_body(&mut stream).await;
+                let prompt = serde_json::from_str::<serde_json::Value>(&body).unwrap()["prompt"]
+                    .as_str()
+                    .unwrap()
+                    .to_string();
+                let rating = if prompt.contains("fn retry") {
+                    "9"
+                } else {
+                    "Relevance: 2/10"
+                };
+                let reply = serde_json::json!({ "response": rating }).to_string();
+                write_json_response(&mut stream, "200 OK", &reply).await;
+            }
+        });
+
+        endpoint
+    }
+
+    fn result(id: &str, content: &str, score: f32) -> SearchResult {
+        SearchResult::new(
+            Chunk {
+                end_line: 3,
+                content: content.to_string(),
+                hash: id.to_string(),
+                chunk_type: ChunkType::Function,
+                ..make_chunk(id, "net.rs", vec![1.0])
+            },
+            score,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_rerank_orders_by_model_rating() {
+        let endpoint = spawn_server().await;
+        let reranker = OllamaReranker::new(&endpoint, "test-model");
+
+        let reranked = reranker
+            .rerank(
+                "retry failed requests",
+                vec![
+                    result("connect", "fn connect() {}", 0.9),
+                    result("retry", "fn retry() {}", 0.5),
+                ],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(reranked[0].chunk.id, "retry");
+        assert!((reranked[0].score - 0.9).abs() < 1e-6);
+        assert!((reranked[1].score - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_parse_rating() {
+        assert_eq!(parse_rating("8"), Some(8.0));
+        assert_eq!(parse_rating(" Relevance: 7.5/10."), Some(7.5));
+        assert_eq!(parse_rating("42"), Some(10.0));
+        assert_eq!(parse_rating("not relevant"), None);
+    }
+}
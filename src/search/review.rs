@@ -0,0 +1,160 @@
+//! Review mode: embed the hunks of a diff and look up related code in the index.
+//!
+//! Diff chunks are tagged `ChunkType::Diff` and only live in memory, so a
+//! review never touches the persistent index.
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+
+use super::searcher::Searcher;
+use super::store::{Chunk, ChunkType, SearchResult};
+use crate::scan::DiffHunk;
+use crate::types::Language;
+
+/// A diff hunk and the indexed chunks most similar to it
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewMatch {
+    pub diff: Chunk,
+    pub related: Vec<SearchResult>,
+}
+
+/// Embed each hunk and find up to `limit` related chunks for it, skipping
+/// chunks that are the hunk's own (possibly outdated) lines.
+pub async fn review_hunks(
+    searcher: &Searcher,
+    hunks: &[DiffHunk],
+    limit: usize,
+) -> Result<Vec<ReviewMatch>> {
+    let hunks: Vec<&DiffHunk> = hunks
+        .iter()
+        .filter(|h| !h.content.trim().is_empty())
+        .collect();
+    if hunks.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let texts: Vec<String> = hunks.iter().map(|h| h.content.clone()).collect();
+    let vectors = searcher.embed_batch(&texts).await?;
+
+    let mut matches = Vec::with_capacity(hunks.len());
+    for (hunk, vector) in hunks.into_iter().zip(vectors) {
+        let diff = diff_chunk(hunk, vector);
+
+        // Over-fetch so filtering out the hunk's own lines still leaves `limit`
+        let mut related = searcher.search_vector(&diff.vector, limit * 2).await?;
+        related.retain(|r| !overlaps(&r.chunk, &diff));
+        related.truncate(limit);
+
+        matches.push(ReviewMatch { diff, related });
+    }
+
+    Ok(matches)
+}
+
+fn diff_chunk(hunk: &DiffHunk, vector: Vec<f32>) -> Chunk {
+    Chunk {
+        id: format!("diff:{}:{}-{}", hunk.path, hunk.start_line, hunk.end_line),
+        file_path: hunk.path.clone(),
+        start_line: hunk.start_line,
+        end_line: hunk.end_line,
+        content: hunk.content.clone(),
+        vector,
+        hash: String::new(),
+        updated_at: Utc::now(),
+        chunk_type: ChunkType::Diff,
+        hotness: None,
+        language: Some(Language::from_path(&hunk.path)),
+        symbol_name: None,
+        symbol_kind: None,
+        collection: None,
+        norm: None,
+    }
+}
+
+fn overlaps(chunk: &Chunk, diff: &Chunk) -> bool {
+    chunk.file_path == diff.file_path
+        && chunk.start_line <= diff.end_line
+        && diff.start_line <= chunk.end_line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ChunkingConfig;
+    use crate::search::{Embedder, GobStore, Indexer, VectorStore};
+    use crate::test_support::KeywordEmbedder;
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[tokio::test]
+    async fn test_review_finds_related_code_for_diff() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        git(root, &["init", "-q"]);
+
+        fs::write(
+            root.join("net.rs"),
+            "fn fetch_with_retry() {\n    // retry three times\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("config.rs"),
+            "fn parse_config() {\n    // parse toml\n}\n",
+        )
+        .unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", "initial"]);
+
+        let store_path = root.join(".agentlens/index.json");
+        let store: Arc<dyn VectorStore> = Arc::new(GobStore::new(store_path.clone()));
+        let embedder: Arc<dyn Embedder> = Arc::new(KeywordEmbedder(&["retry", "parse"]));
+        Indexer::new(
+            Arc::clone(&store),
+            Arc::clone(&embedder),
+            &ChunkingConfig::default(),
+        )
+        .index_all(root, true, false)
+        .await
+        .unwrap();
+        let indexed_chunks = store.stats().await.unwrap().total_chunks;
+
+        fs::write(
+            root.join("upload.rs"),
+            "fn upload() {\n    // retry on timeout\n}\n",
+        )
+        .unwrap();
+        git(root, &["add", "upload.rs"]);
+        git(root, &["commit", "-q", "-m", "add upload"]);
+
+        let hunks = crate::scan::get_diff_hunks(root, "HEAD~1").unwrap();
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].path, "upload.rs");
+
+        let searcher = Searcher::new(Arc::clone(&store), embedder, false, 60.0);
+        let matches = review_hunks(&searcher, &hunks, 1).await.unwrap();
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].diff.chunk_type, ChunkType::Diff);
+        assert_eq!(matches[0].related[0].chunk.file_path, "net.rs");
+
+        // Diff chunks stay out of the persistent index
+        let reloaded = GobStore::new(store_path);
+        reloaded.load().await.unwrap();
+        assert_eq!(reloaded.stats().await.unwrap().total_chunks, indexed_chunks);
+    }
+}
@@ -1,15 +1,69 @@
 use anyhow::Result;
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use super::embedder::Embedder;
-use super::hybrid::{reciprocal_rank_fusion, text_search};
-use super::store::{SearchResult, VectorStore};
+use super::cache::{CacheKey, ResultCache};
+use super::embedder::{is_unreachable, Embedder};
+use super::hybrid::{reciprocal_rank_fusion_ranked, text_search, ScoreBreakdown};
+use super::reranker::{Reranker, RERANK_CANDIDATES_FACTOR};
+use super::store::{SearchFilter, SearchResult, VectorStore};
+
+/// Relative score boost applied to chunks from the hottest files (hotness = 1.0)
+const HOTNESS_WEIGHT: f32 = 0.1;
+
+/// Relative score boost for chunks inside the focus path (all components shared)
+const PATH_AFFINITY_WEIGHT: f32 = 0.1;
+
+/// With a focus path, this many times the requested results are ranked
+/// before truncating, so the boost can lift results from just past the limit
+pub const PATH_AFFINITY_CANDIDATES_FACTOR: usize = 3;
+
+/// Relative boost for chunks defining a symbol named exactly like a query term
+const EXACT_NAME_WEIGHT: f32 = 1.0;
+
+/// Default RRF weights for the vector and text lists in hybrid search
+pub const DEFAULT_HYBRID_VECTOR_WEIGHT: f32 = 0.7;
+pub const DEFAULT_HYBRID_TEXT_WEIGHT: f32 = 0.3;
+
+/// `SearchOutcome::mode` of results matched by keywords alone
+pub const KEYWORD_MODE: &str = "keyword";
+
+/// Results of `Searcher::search_or_keywords` and how they were found
+#[derive(Debug)]
+pub struct SearchOutcome {
+    pub results: Vec<SearchResult>,
+    /// Why each result ranked where it did, when explained
+    pub breakdowns: Option<Vec<ScoreBreakdown>>,
+    /// "hybrid", "vector", or `KEYWORD_MODE` when the embedder was unreachable
+    pub mode: &'static str,
+}
+
+impl SearchOutcome {
+    fn new(results: Vec<SearchResult>, mode: &'static str) -> Self {
+        Self {
+            results,
+            breakdowns: None,
+            mode,
+        }
+    }
+
+    /// The embedder could not be reached, so the results are keyword-only
+    pub fn degraded(&self) -> bool {
+        self.mode == KEYWORD_MODE
+    }
+}
 
 pub struct Searcher {
     store: Arc<dyn VectorStore>,
     embedder: Arc<dyn Embedder>,
     hybrid_enabled: bool,
     hybrid_k: f32,
+    vector_weight: f32,
+    text_weight: f32,
+    collection: Option<String>,
+    cache: Option<Arc<ResultCache>>,
+    focus: Option<String>,
+    reranker: Option<Arc<dyn Reranker>>,
 }
 
 impl Searcher {
@@ -24,52 +78,416 @@ impl Searcher {
             embedder,
             hybrid_enabled,
             hybrid_k,
+            vector_weight: DEFAULT_HYBRID_VECTOR_WEIGHT,
+            text_weight: DEFAULT_HYBRID_TEXT_WEIGHT,
+            collection: None,
+            cache: None,
+            focus: None,
+            reranker: None,
         }
     }
 
+    /// Reuse results for identical queries while the index file is unchanged.
+    /// The cache can be shared by several searchers over the same index.
+    pub fn with_cache(mut self, cache: Arc<ResultCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Weight the vector and text lists when fusing hybrid results
+    pub fn with_hybrid_weights(mut self, vector_weight: f32, text_weight: f32) -> Self {
+        self.vector_weight = vector_weight;
+        self.text_weight = text_weight;
+        self
+    }
+
+    /// Restrict searches to a named collection instead of the global one
+    pub fn with_collection(mut self, collection: impl Into<String>) -> Self {
+        self.collection = Some(collection.into());
+        self
+    }
+
+    /// Favor results near `focus` (a path relative to the project root),
+    /// e.g. the service directory a developer is working in
+    pub fn with_focus(mut self, focus: impl Into<String>) -> Self {
+        self.focus = Some(focus.into());
+        self
+    }
+
+    /// Re-score `smart_search` candidates with `reranker`, fetching
+    /// `RERANK_CANDIDATES_FACTOR` times the requested results for it to choose from
+    pub fn with_reranker(mut self, reranker: Arc<dyn Reranker>) -> Self {
+        self.reranker = Some(reranker);
+        self
+    }
+
     /// Search with vector similarity only
     pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
-        // Load index if needed
-        self.store.load().await?;
+        let key = self.cache_key(query, limit, false).await?;
+        if let Some(results) = self.cached(&key) {
+            return Ok(results);
+        }
 
+        let results = self.search_uncached(query, limit).await?;
+        self.remember(key, &results);
+        Ok(results)
+    }
+
+    async fn search_uncached(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
         // Embed the query
         let query_vector = self.embedder.embed(query).await?;
 
-        // Vector search
-        self.store.search(&query_vector, limit).await
+        let results = self
+            .vector_results(&query_vector, self.candidates(limit))
+            .await?;
+        let mut results = self.rerank(results, Some(query));
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// Vector search with an already-computed embedding
+    pub async fn search_vector(&self, vector: &[f32], limit: usize) -> Result<Vec<SearchResult>> {
+        let results = self.vector_results(vector, self.candidates(limit)).await?;
+        let mut results = self.rerank(results, None);
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    async fn vector_results(&self, vector: &[f32], limit: usize) -> Result<Vec<SearchResult>> {
+        // Load index if needed
+        self.store.load().await?;
+
+        self.store
+            .search(vector, limit, self.collection.as_deref())
+            .await
+    }
+
+    /// Embed texts with the same embedder used for queries
+    pub async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.embedder.embed_batch(texts).await
     }
 
     /// Hybrid search: combines vector search with text search using RRF
     pub async fn search_hybrid(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
+        let key = self.cache_key(query, limit, true).await?;
+        if let Some(results) = self.cached(&key) {
+            return Ok(results);
+        }
+
+        let results = self.search_hybrid_uncached(query, limit).await?;
+        self.remember(key, &results);
+        Ok(results)
+    }
+
+    async fn search_hybrid_uncached(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
+        self.search_with_filter(
+            query,
+            limit,
+            SearchFilter::for_collection(self.collection.as_deref()),
+        )
+        .await
+    }
+
+    /// Search only chunks accepted by `filter` (hybrid when enabled). The
+    /// searcher's collection applies unless the filter names one. Not cached.
+    pub async fn search_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        mut filter: SearchFilter,
+    ) -> Result<Vec<SearchResult>> {
+        if filter.collection.is_none() {
+            filter.collection = self.collection.clone();
+        }
+        self.search_with_filter(query, limit, filter).await
+    }
+
+    async fn search_with_filter(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: SearchFilter,
+    ) -> Result<Vec<SearchResult>> {
+        if self.hybrid_enabled {
+            let results = self.search_fused(query, limit, filter).await?;
+            return Ok(results.into_iter().map(|(result, _)| result).collect());
+        }
+
         // Load index if needed
         self.store.load().await?;
 
         // Embed the query
         let query_vector = self.embedder.embed(query).await?;
 
-        // Vector search (get more results for fusion)
-        let vector_results = self.store.search(&query_vector, limit * 2).await?;
+        let results = self
+            .store
+            .search_filtered(&query_vector, self.candidates(limit) * 2, filter)
+            .await?;
+        let mut results = self.rerank(results, Some(query));
+        results.truncate(limit);
+        Ok(results)
+    }
 
-        if !self.hybrid_enabled {
-            // Just return vector results, truncated
-            let mut results = vector_results;
-            results.truncate(limit);
-            return Ok(results);
+    /// Hybrid search (even when hybrid is off) that pairs each result with the
+    /// ranks and fused score behind it, to show why it ranked where it did.
+    /// The searcher's collection applies unless the filter names one. Not cached.
+    pub async fn search_hybrid_explained(
+        &self,
+        query: &str,
+        limit: usize,
+        mut filter: SearchFilter,
+    ) -> Result<Vec<(SearchResult, ScoreBreakdown)>> {
+        if filter.collection.is_none() {
+            filter.collection = self.collection.clone();
         }
+        self.search_fused(query, limit, filter).await
+    }
+
+    async fn search_fused(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: SearchFilter,
+    ) -> Result<Vec<(SearchResult, ScoreBreakdown)>> {
+        // Load index if needed
+        self.store.load().await?;
+
+        // Embed the query
+        let query_vector = self.embedder.embed(query).await?;
+
+        // Vector search (get more results for fusion)
+        let candidates = self.candidates(limit);
+        let vector_results = self
+            .store
+            .search_filtered(&query_vector, candidates * 2, filter.clone())
+            .await?;
 
         // Text search
-        let all_chunks = self.store.get_all_chunks().await?;
-        let text_results = text_search(&all_chunks, query, limit * 2);
+        let mut all_chunks = self
+            .store
+            .get_all_chunks(filter.collection.as_deref())
+            .await?;
+        all_chunks.retain(|chunk| filter.matches(chunk));
+        let text_results = text_search(&all_chunks, query, candidates * 2);
+
+        // Combine with weighted RRF
+        let fused = reciprocal_rank_fusion_ranked(
+            self.hybrid_k,
+            candidates,
+            vec![
+                (self.vector_weight, vector_results),
+                (self.text_weight, text_results),
+            ],
+        );
+        let mut breakdowns: HashMap<String, ScoreBreakdown> = HashMap::new();
+        let mut combined = Vec::with_capacity(fused.len());
+        for (result, ranks) in fused {
+            let breakdown = ScoreBreakdown {
+                vector_rank: ranks[0],
+                text_rank: ranks[1],
+                rrf_score: result.score,
+            };
+            breakdowns.insert(result.chunk.id.clone(), breakdown);
+            combined.push(result);
+        }
+
+        // Definitions named in the query compete even when neither list found them
+        let terms = query_terms(query);
+        for chunk in all_chunks {
+            let named = chunk
+                .symbol_name
+                .as_deref()
+                .is_some_and(|n| terms.contains(&n));
+            if named && !combined.iter().any(|r| r.chunk.id == chunk.id) {
+                combined.push(SearchResult::new(chunk, 0.0));
+            }
+        }
 
-        // Combine with RRF
-        let combined =
-            reciprocal_rank_fusion(self.hybrid_k, limit, vec![vector_results, text_results]);
+        let mut results = self.rerank(combined, Some(query));
+        results.truncate(limit);
+        Ok(results
+            .into_iter()
+            .map(|result| {
+                let breakdown =
+                    breakdowns
+                        .get(&result.chunk.id)
+                        .copied()
+                        .unwrap_or(ScoreBreakdown {
+                            vector_rank: None,
+                            text_rank: None,
+                            rrf_score: 0.0,
+                        });
+                (result, breakdown)
+            })
+            .collect())
+    }
+
+    /// Cache key for a query, or `None` when caching is off or the index has no mtime
+    async fn cache_key(&self, query: &str, limit: usize, hybrid: bool) -> Result<Option<CacheKey>> {
+        if self.cache.is_none() {
+            return Ok(None);
+        }
 
-        Ok(combined)
+        Ok(self
+            .store
+            .last_modified()
+            .await?
+            .map(|index_modified| CacheKey {
+                query: query.to_string(),
+                limit,
+                hybrid,
+                collection: self.collection.clone(),
+                focus: self.focus.clone(),
+                index_modified,
+            }))
+    }
+
+    fn cached(&self, key: &Option<CacheKey>) -> Option<Vec<SearchResult>> {
+        self.cache.as_ref()?.get(key.as_ref()?)
+    }
+
+    fn remember(&self, key: Option<CacheKey>, results: &[SearchResult]) {
+        if let (Some(cache), Some(key)) = (&self.cache, key) {
+            cache.insert(key, results.to_vec());
+        }
+    }
+
+    /// Results to rank for `limit`: more with a focus path, see
+    /// `PATH_AFFINITY_CANDIDATES_FACTOR`
+    fn candidates(&self, limit: usize) -> usize {
+        if self.focus.is_some() {
+            limit * PATH_AFFINITY_CANDIDATES_FACTOR
+        } else {
+            limit
+        }
+    }
+
+    /// Post-fusion score adjustments: hotness, then path affinity, then
+    /// exact symbol-name matches against `query`
+    fn rerank(&self, results: Vec<SearchResult>, query: Option<&str>) -> Vec<SearchResult> {
+        let results = apply_hotness_boost(results);
+        let results = match &self.focus {
+            Some(focus) => apply_path_affinity(results, focus),
+            None => results,
+        };
+        match query {
+            Some(query) => apply_exact_name_boost(results, query),
+            None => results,
+        }
+    }
+
+    /// Load the index and issue a trivial embed so the first real query
+    /// doesn't pay for disk reads and model startup
+    pub async fn warmup(&self) -> Result<()> {
+        self.store.load().await?;
+        self.embedder.embed("warmup").await?;
+        Ok(())
     }
 
     /// Smart search: uses hybrid if enabled, otherwise vector-only
+    /// "hybrid" or "vector", as `smart_search` would run
+    pub fn mode(&self) -> &'static str {
+        if self.hybrid_enabled {
+            "hybrid"
+        } else {
+            "vector"
+        }
+    }
+
     pub async fn smart_search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
+        let Some(reranker) = &self.reranker else {
+            return self.retrieve(query, limit).await;
+        };
+
+        let candidates = self
+            .retrieve(query, limit * RERANK_CANDIDATES_FACTOR)
+            .await?;
+        let mut results = reranker.rerank(query, candidates).await?;
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// Search as `search_hybrid_explained` does with `explain`, as
+    /// `smart_search` does without a filter and as `search_filtered` does
+    /// otherwise. If the embedding server can't be reached, the same chunks
+    /// are matched by keywords only instead of failing (the reranker is
+    /// skipped too: it usually shares the server); other embedder errors,
+    /// such as an unknown model, are still returned.
+    pub async fn search_or_keywords(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: SearchFilter,
+        explain: bool,
+    ) -> Result<SearchOutcome> {
+        let attempt = if explain {
+            self.search_hybrid_explained(query, limit, filter.clone())
+                .await
+                .map(|explained| {
+                    let (results, breakdowns) = explained.into_iter().unzip();
+                    SearchOutcome {
+                        results,
+                        breakdowns: Some(breakdowns),
+                        mode: "hybrid",
+                    }
+                })
+        } else if filter == SearchFilter::default() {
+            self.smart_search(query, limit)
+                .await
+                .map(|results| SearchOutcome::new(results, self.mode()))
+        } else {
+            self.search_filtered(query, limit, filter.clone())
+                .await
+                .map(|results| SearchOutcome::new(results, self.mode()))
+        };
+
+        match attempt {
+            Err(e) if is_unreachable(&e) => {
+                let results = self.keyword_search(query, limit, filter).await?;
+                let breakdowns = explain.then(|| {
+                    results
+                        .iter()
+                        .enumerate()
+                        .map(|(rank, result)| ScoreBreakdown {
+                            vector_rank: None,
+                            text_rank: Some(rank + 1),
+                            rrf_score: result.score,
+                        })
+                        .collect()
+                });
+                Ok(SearchOutcome {
+                    results,
+                    breakdowns,
+                    mode: KEYWORD_MODE,
+                })
+            }
+            attempt => attempt,
+        }
+    }
+
+    /// BM25 over the chunks accepted by `filter`, needing no query embedding
+    async fn keyword_search(
+        &self,
+        query: &str,
+        limit: usize,
+        mut filter: SearchFilter,
+    ) -> Result<Vec<SearchResult>> {
+        if filter.collection.is_none() {
+            filter.collection = self.collection.clone();
+        }
+        self.store.load().await?;
+        let mut chunks = self
+            .store
+            .get_all_chunks(filter.collection.as_deref())
+            .await?;
+        chunks.retain(|chunk| filter.matches(chunk));
+        let results = text_search(&chunks, query, self.candidates(limit));
+        let mut results = self.rerank(results, Some(query));
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    async fn retrieve(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
         if self.hybrid_enabled {
             self.search_hybrid(query, limit).await
         } else {
@@ -78,8 +496,491 @@ impl Searcher {
     }
 }
 
+/// Scale scores by index-time hotness so actively developed files rank slightly higher
+fn apply_hotness_boost(mut results: Vec<SearchResult>) -> Vec<SearchResult> {
+    if results.iter().all(|r| r.chunk.hotness.is_none()) {
+        return results;
+    }
+
+    for result in &mut results {
+        if let Some(hotness) = result.chunk.hotness {
+            result.score *= 1.0 + HOTNESS_WEIGHT * hotness;
+        }
+    }
+
+    results.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    results
+}
+
+/// Boost results by how many leading path components they share with `focus`,
+/// so of two equally similar chunks the one closer to the focus ranks first.
+/// The boost is a share of the score's magnitude added to it, so it lifts
+/// negative (dissimilar) scores too rather than pushing them further down.
+pub fn apply_path_affinity(mut results: Vec<SearchResult>, focus: &str) -> Vec<SearchResult> {
+    let focus = path_components(focus);
+    if focus.is_empty() {
+        return results;
+    }
+
+    for result in &mut results {
+        let shared = focus
+            .iter()
+            .zip(path_components(&result.chunk.file_path))
+            .take_while(|(a, b)| **a == *b)
+            .count();
+        result.score +=
+            result.score.abs() * PATH_AFFINITY_WEIGHT * shared as f32 / focus.len() as f32;
+    }
+
+    results.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    results
+}
+
+/// Rank chunks whose symbol is named exactly like a query term first, so
+/// searching `smart_search` jumps to its definition. Their scores are lifted
+/// above the best other result.
+fn apply_exact_name_boost(mut results: Vec<SearchResult>, query: &str) -> Vec<SearchResult> {
+    let terms = query_terms(query);
+    let is_named = |r: &SearchResult| {
+        r.chunk
+            .symbol_name
+            .as_deref()
+            .is_some_and(|name| terms.contains(&name))
+    };
+    if !results.iter().any(is_named) {
+        return results;
+    }
+
+    let best_other = results
+        .iter()
+        .filter(|r| !is_named(r))
+        .map(|r| r.score)
+        .fold(0.0_f32, f32::max);
+    for result in &mut results {
+        if is_named(result) {
+            result.score = result.score.max(best_other) * (1.0 + EXACT_NAME_WEIGHT);
+        }
+    }
+
+    results.sort_by(|a, b| {
+        is_named(b).cmp(&is_named(a)).then(
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal),
+        )
+    });
+    results
+}
+
+/// Identifier-like words of a query; `Searcher::smart_search` and
+/// `smart_search()` both yield `smart_search`
+fn query_terms(query: &str) -> Vec<&str> {
+    query
+        .split_whitespace()
+        .map(|word| {
+            let word = word.trim_matches(|c: char| !c.is_alphanumeric() && c != '_');
+            word.rsplit(['.', ':']).next().unwrap_or(word)
+        })
+        .map(|word| word.trim_end_matches(|c: char| !c.is_alphanumeric() && c != '_'))
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+fn path_components(path: &str) -> Vec<&str> {
+    path.split(['/', '\\'])
+        .filter(|c| !c.is_empty() && *c != ".")
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
-    // Integration tests would require mock store and embedder
-    // Unit tests for Searcher logic are minimal since it orchestrates other components
+    use super::*;
+    use crate::search::embedder::EmbedderUnreachable;
+    use crate::search::store::{Chunk, GobStore};
+    use crate::test_support::{make_chunk, FixedEmbedder};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    fn lib_chunk() -> Chunk {
+        Chunk {
+            end_line: 3,
+            content: "fn lib() {}".to_string(),
+            hash: "abc123".to_string(),
+            ..make_chunk("lib.rs:block:1", "lib.rs", vec![1.0, 0.0])
+        }
+    }
+
+    #[test]
+    fn test_path_affinity_promotes_focused_subtree() {
+        let result = |file_path: &str| {
+            let mut chunk = lib_chunk();
+            chunk.file_path = file_path.to_string();
+            SearchResult::new(chunk, 0.8)
+        };
+        let results = vec![
+            result("services/search/handler.rs"),
+            result("web/checkout.ts"),
+            result("services/payments/charge.rs"),
+        ];
+
+        let ranked = apply_path_affinity(results, "services/payments");
+
+        let order: Vec<&str> = ranked.iter().map(|r| r.chunk.file_path.as_str()).collect();
+        assert_eq!(
+            order,
+            vec![
+                "services/payments/charge.rs",
+                "services/search/handler.rs",
+                "web/checkout.ts"
+            ]
+        );
+        assert!((ranked[0].score - 0.88).abs() < 1e-6);
+        assert_eq!(ranked[2].score, 0.8);
+
+        // Focusing on the project root is a no-op
+        let unchanged = apply_path_affinity(vec![result("web/checkout.ts")], ".");
+        assert_eq!(unchanged[0].score, 0.8);
+
+        // Negative similarities are lifted too, not pushed further down
+        let mut near = result("services/payments/refund.rs");
+        near.score = -0.2;
+        let mut far = result("web/cart.ts");
+        far.score = -0.2;
+        let ranked = apply_path_affinity(vec![far, near], "services/payments");
+        assert_eq!(ranked[0].chunk.file_path, "services/payments/refund.rs");
+        assert!((ranked[0].score + 0.18).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_focus_boost_applies_before_truncating_to_limit() {
+        let temp = TempDir::new().unwrap();
+        let store = Arc::new(GobStore::new(temp.path().join("index.json")));
+        let chunk =
+            |file_path: &str, vector| make_chunk(&format!("{}:1", file_path), file_path, vector);
+        store
+            .save_chunks(vec![
+                chunk("web/a.ts", vec![1.0, 0.0]),
+                chunk("web/b.ts", vec![1.0, 0.0]),
+                // Cosine 0.95: ranks last on similarity, first once boosted
+                chunk("services/payments/charge.rs", vec![0.95, 0.312_25]),
+            ])
+            .await
+            .unwrap();
+
+        let searcher = Searcher::new(
+            store,
+            Arc::new(FixedEmbedder::new(vec![1.0, 0.0])),
+            false,
+            60.0,
+        )
+        .with_focus("services/payments");
+        let results = searcher.search("charge", 1).await.unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].chunk.file_path, "services/payments/charge.rs");
+    }
+
+    #[tokio::test]
+    async fn test_warmup_loads_store_and_primes_embedder() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("index.json");
+
+        let writer = GobStore::new(path.clone());
+        writer.save_chunks(vec![lib_chunk()]).await.unwrap();
+        writer.persist().await.unwrap();
+
+        let store: Arc<dyn VectorStore> = Arc::new(GobStore::new(path));
+        let embedder = Arc::new(FixedEmbedder::new(vec![1.0, 0.0]));
+        let searcher = Searcher::new(Arc::clone(&store), embedder.clone(), false, 60.0);
+
+        assert_eq!(store.stats().await.unwrap().total_chunks, 0);
+        searcher.warmup().await.unwrap();
+
+        assert_eq!(store.stats().await.unwrap().total_chunks, 1);
+        assert_eq!(embedder.texts(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_repeated_query_hits_cache_until_index_changes() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("index.json");
+        let writer = GobStore::new(path.clone());
+        writer.save_chunks(vec![lib_chunk()]).await.unwrap();
+        writer.persist().await.unwrap();
+
+        let store: Arc<dyn VectorStore> = Arc::new(GobStore::new(path.clone()));
+        let embedder = Arc::new(FixedEmbedder::new(vec![1.0, 0.0]));
+        let searcher = Searcher::new(store, embedder.clone(), false, 60.0)
+            .with_cache(Arc::new(ResultCache::new(8)));
+
+        let first = searcher.search("lib", 5).await.unwrap();
+        let second = searcher.search("lib", 5).await.unwrap();
+        assert_eq!(embedder.texts(), 1);
+        assert_eq!(first.len(), second.len());
+
+        // A different limit is a different query
+        searcher.search("lib", 1).await.unwrap();
+        assert_eq!(embedder.texts(), 2);
+
+        // Rewriting the index invalidates cached results
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(60))
+            .unwrap();
+        searcher.search("lib", 5).await.unwrap();
+        assert_eq!(embedder.texts(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_exact_symbol_name_ranks_definition_first() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("index.json");
+        let chunk = |id: &str, symbol: Option<&str>, vector: Vec<f32>| Chunk {
+            id: id.to_string(),
+            symbol_name: symbol.map(str::to_string),
+            content: format!("chunk {}", id),
+            vector,
+            ..lib_chunk()
+        };
+
+        // The query embeds to [1, 0]; the definition is the least similar chunk
+        let writer = GobStore::new(path.clone());
+        writer
+            .save_chunks(vec![
+                chunk("caller", Some("run"), vec![1.0, 0.0]),
+                chunk("doc", None, vec![0.9, 0.1]),
+                chunk("definition", Some("smart_search"), vec![0.2, 1.0]),
+            ])
+            .await
+            .unwrap();
+        writer.persist().await.unwrap();
+
+        for hybrid in [false, true] {
+            let store: Arc<dyn VectorStore> = Arc::new(GobStore::new(path.clone()));
+            let searcher = Searcher::new(
+                store,
+                Arc::new(FixedEmbedder::new(vec![1.0, 0.0])),
+                hybrid,
+                60.0,
+            );
+
+            let results = searcher
+                .smart_search("Searcher::smart_search()", 3)
+                .await
+                .unwrap();
+
+            assert_eq!(results[0].chunk.id, "definition", "hybrid: {}", hybrid);
+            assert!(results[0].score > results[1].score);
+        }
+
+        assert_eq!(
+            query_terms("where is `smart_search`?"),
+            vec!["where", "is", "smart_search"]
+        );
+    }
+
+    /// Fails every query, as an unreachable server or with an unknown model
+    struct FailingEmbedder {
+        unreachable: bool,
+    }
+
+    #[async_trait]
+    impl Embedder for FailingEmbedder {
+        async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+            if self.unreachable {
+                Err(EmbedderUnreachable("Cannot connect to Ollama".to_string()).into())
+            } else {
+                Err(anyhow::anyhow!("Model 'm' not found"))
+            }
+        }
+
+        async fn embed_batch(&self, _texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            unreachable!()
+        }
+
+        fn dimensions(&self) -> usize {
+            2
+        }
+
+        async fn health_check(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_embedder_falls_back_to_keywords() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("index.json");
+        let writer = GobStore::new(path.clone());
+        writer
+            .save_chunks(vec![
+                lib_chunk(),
+                Chunk {
+                    id: "retry".to_string(),
+                    content: "fn retry_request() {}".to_string(),
+                    ..lib_chunk()
+                },
+                Chunk {
+                    id: "vendored".to_string(),
+                    file_path: "vendor/retry.rs".to_string(),
+                    content: "fn retry_request() {}".to_string(),
+                    ..lib_chunk()
+                },
+            ])
+            .await
+            .unwrap();
+        writer.persist().await.unwrap();
+        let filter = SearchFilter {
+            exclude_paths: vec!["vendor".to_string()],
+            ..SearchFilter::default()
+        };
+
+        for (hybrid, explain) in [(false, false), (true, false), (false, true)] {
+            let store: Arc<dyn VectorStore> = Arc::new(GobStore::new(path.clone()));
+            let searcher = Searcher::new(
+                Arc::clone(&store),
+                Arc::new(FailingEmbedder { unreachable: true }),
+                hybrid,
+                60.0,
+            );
+            let outcome = searcher
+                .search_or_keywords("retry_request", 5, SearchFilter::default(), explain)
+                .await
+                .unwrap();
+            assert!(outcome.degraded());
+            assert_eq!(outcome.results.len(), 2, "hybrid: {}", hybrid);
+            assert_eq!(outcome.breakdowns.is_some(), explain);
+
+            let outcome = searcher
+                .search_or_keywords("retry_request", 5, filter.clone(), explain)
+                .await
+                .unwrap();
+            assert_eq!(outcome.results.len(), 1);
+            assert_eq!(outcome.results[0].chunk.id, "retry");
+
+            let searcher = Searcher::new(
+                store,
+                Arc::new(FailingEmbedder { unreachable: false }),
+                hybrid,
+                60.0,
+            );
+            let error = searcher
+                .search_or_keywords("retry_request", 5, filter.clone(), explain)
+                .await
+                .unwrap_err();
+            assert!(error.to_string().contains("not found"));
+        }
+    }
+
+    /// Reverses the candidates and records how many it was given
+    #[derive(Default)]
+    struct ReversingReranker {
+        candidates: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Reranker for ReversingReranker {
+        async fn rerank(
+            &self,
+            _query: &str,
+            mut results: Vec<SearchResult>,
+        ) -> Result<Vec<SearchResult>> {
+            self.candidates.store(results.len(), Ordering::SeqCst);
+            results.reverse();
+            Ok(results)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reranker_sees_extra_candidates_and_sets_order() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("index.json");
+        let writer = GobStore::new(path.clone());
+        let chunks: Vec<Chunk> = (0..10)
+            .map(|i| Chunk {
+                id: format!("c{}", i),
+                content: format!("chunk {}", i),
+                vector: vec![1.0, i as f32 / 10.0],
+                ..lib_chunk()
+            })
+            .collect();
+        writer.save_chunks(chunks).await.unwrap();
+        writer.persist().await.unwrap();
+
+        let store: Arc<dyn VectorStore> = Arc::new(GobStore::new(path));
+        let reranker = Arc::new(ReversingReranker::default());
+        let searcher = Searcher::new(
+            store,
+            Arc::new(FixedEmbedder::new(vec![1.0, 0.0])),
+            false,
+            60.0,
+        )
+        .with_reranker(Arc::clone(&reranker) as Arc<dyn Reranker>);
+
+        let results = searcher.smart_search("chunk", 2).await.unwrap();
+
+        assert_eq!(
+            reranker.candidates.load(Ordering::SeqCst),
+            2 * RERANK_CANDIDATES_FACTOR
+        );
+        assert_eq!(results.len(), 2);
+        // The least similar of the 8 candidates, now first
+        assert_eq!(results[0].chunk.id, "c7");
+    }
+
+    #[tokio::test]
+    async fn test_explained_search_breaks_down_fused_score() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("index.json");
+        let chunk = |id: &str, content: &str, vector: Vec<f32>| Chunk {
+            id: id.to_string(),
+            content: content.to_string(),
+            vector,
+            ..lib_chunk()
+        };
+
+        let writer = GobStore::new(path.clone());
+        writer
+            .save_chunks(vec![
+                chunk("config", "parse config", vec![1.0, 0.0]),
+                chunk("retry", "retry request", vec![0.2, 1.0]),
+            ])
+            .await
+            .unwrap();
+        writer.persist().await.unwrap();
+
+        // Explaining fuses even though this searcher is vector-only
+        let store: Arc<dyn VectorStore> = Arc::new(GobStore::new(path));
+        let searcher = Searcher::new(
+            store,
+            Arc::new(FixedEmbedder::new(vec![1.0, 0.0])),
+            false,
+            60.0,
+        );
+        let results = searcher
+            .search_hybrid_explained("retry", 5, SearchFilter::default())
+            .await
+            .unwrap();
+
+        assert_eq!(results[0].0.chunk.id, "retry");
+        assert_eq!(results[0].1.vector_rank, Some(2));
+        assert_eq!(results[0].1.text_rank, Some(1));
+        let expected = DEFAULT_HYBRID_VECTOR_WEIGHT / 62.0 + DEFAULT_HYBRID_TEXT_WEIGHT / 61.0;
+        assert!((results[0].1.rrf_score - expected).abs() < 1e-6);
+
+        assert_eq!(results[1].0.chunk.id, "config");
+        assert_eq!(results[1].1.vector_rank, Some(1));
+        assert_eq!(results[1].1.text_rank, None);
+    }
 }
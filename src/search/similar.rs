@@ -0,0 +1,139 @@
+//! File-level similarity: average each file's chunk vectors into a centroid
+//! and rank other files by cosine similarity to the target's centroid.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use super::store::{cosine_similarity, Chunk, VectorStore};
+
+/// An indexed file and how closely its centroid matches the target file's
+#[derive(Debug, Clone, Serialize)]
+pub struct SimilarFile {
+    pub file_path: String,
+    pub score: f32,
+    /// Number of chunks averaged into the file's centroid
+    pub chunks: usize,
+}
+
+/// Up to `limit` files most similar to `target`, best first. Errors if
+/// `target` has no chunks in the index.
+pub async fn similar_files(
+    store: &dyn VectorStore,
+    target: &str,
+    collection: Option<&str>,
+    limit: usize,
+) -> Result<Vec<SimilarFile>> {
+    let chunks = store.get_all_chunks(collection).await?;
+    let centroids = file_centroids(&chunks);
+
+    let Some((target_vector, _)) = centroids.get(target) else {
+        anyhow::bail!(
+            "'{}' is not in the search index; run `agentlens index` or check the path",
+            target
+        );
+    };
+
+    let mut ranked: Vec<SimilarFile> = centroids
+        .iter()
+        .filter(|(path, _)| **path != target)
+        .map(|(path, (vector, count))| SimilarFile {
+            file_path: path.to_string(),
+            score: cosine_similarity(target_vector, vector),
+            chunks: *count,
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.file_path.cmp(&b.file_path))
+    });
+    ranked.truncate(limit);
+
+    Ok(ranked)
+}
+
+/// Mean chunk vector and chunk count per file
+fn file_centroids(chunks: &[Chunk]) -> BTreeMap<&str, (Vec<f32>, usize)> {
+    let mut centroids: BTreeMap<&str, (Vec<f32>, usize)> = BTreeMap::new();
+
+    for chunk in chunks.iter().filter(|c| !c.vector.is_empty()) {
+        let (sum, count) = centroids
+            .entry(chunk.file_path.as_str())
+            .or_insert_with(|| (vec![0.0; chunk.vector.len()], 0));
+        // Chunks from a different model would corrupt the mean; skip them
+        if sum.len() != chunk.vector.len() {
+            continue;
+        }
+        for (s, v) in sum.iter_mut().zip(&chunk.vector) {
+            *s += v;
+        }
+        *count += 1;
+    }
+
+    for (sum, count) in centroids.values_mut() {
+        for s in sum.iter_mut() {
+            *s /= *count as f32;
+        }
+    }
+
+    centroids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::store::GobStore;
+    use crate::test_support::make_chunk;
+    use tempfile::TempDir;
+
+    /// The `n`th 10-line block of `file_path`
+    fn block(file_path: &str, n: usize, vector: Vec<f32>) -> Chunk {
+        Chunk {
+            start_line: n * 10 + 1,
+            end_line: n * 10 + 10,
+            ..make_chunk(&format!("{}:block:{}", file_path, n), file_path, vector)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_near_duplicate_file_ranks_first() {
+        let temp = TempDir::new().unwrap();
+        let store = GobStore::new(temp.path().join("index.json"));
+        store
+            .save_chunks(vec![
+                block("src/retry.rs", 0, vec![1.0, 0.2, 0.0]),
+                block("src/retry.rs", 1, vec![0.8, 0.0, 0.1]),
+                // Copy of retry.rs with one chunk slightly edited
+                block("src/retry_copy.rs", 0, vec![1.0, 0.2, 0.0]),
+                block("src/retry_copy.rs", 1, vec![0.7, 0.1, 0.1]),
+                block("src/parse.rs", 0, vec![0.1, 1.0, 0.0]),
+                block("src/render.rs", 0, vec![0.0, 0.1, 1.0]),
+                block("src/render.rs", 1, vec![0.5, 0.0, 0.9]),
+            ])
+            .await
+            .unwrap();
+
+        let similar = similar_files(&store, "src/retry.rs", None, 10)
+            .await
+            .unwrap();
+
+        let paths: Vec<&str> = similar.iter().map(|s| s.file_path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["src/retry_copy.rs", "src/render.rs", "src/parse.rs"]
+        );
+        assert!(similar[0].score > 0.99);
+        assert_eq!(similar[0].chunks, 2);
+
+        let limited = similar_files(&store, "src/retry.rs", None, 1)
+            .await
+            .unwrap();
+        assert_eq!(limited.len(), 1);
+
+        assert!(similar_files(&store, "src/missing.rs", None, 10)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,188 @@
+//! Detect an index file's format from its first bytes rather than its name.
+//! Gzip-compressed JSON indexes are recognized by their gzip header.
+
+use anyhow::Result;
+use flate2::read::GzDecoder;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, ErrorKind, Read};
+use std::path::Path;
+
+const SQLITE_MAGIC: &[u8] = b"SQLite format 3\0";
+const GZIP_MAGIC: &[u8] = b"\x1f\x8b";
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexFormat {
+    /// `GobStore` JSON document
+    Json,
+    /// `SqliteStore` database
+    Sqlite,
+}
+
+impl IndexFormat {
+    /// `store_backend` value that reads this format
+    pub fn backend(&self) -> &'static str {
+        match self {
+            IndexFormat::Json => "gob",
+            IndexFormat::Sqlite => "sqlite",
+        }
+    }
+
+    /// Format of an index from its leading bytes, if recognized
+    pub fn sniff(head: &[u8]) -> Option<Self> {
+        if head.starts_with(SQLITE_MAGIC) {
+            return Some(IndexFormat::Sqlite);
+        }
+        let head = head.strip_prefix(UTF8_BOM).unwrap_or(head);
+        match head.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'{') => Some(IndexFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for IndexFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndexFormat::Json => write!(f, "JSON"),
+            IndexFormat::Sqlite => write!(f, "SQLite"),
+        }
+    }
+}
+
+/// Format of the index file at `path`; `None` if it is missing, empty or unrecognized
+pub fn detect_index_format(path: &Path) -> Result<Option<IndexFormat>> {
+    Ok(read_head(path)?.and_then(|head| IndexFormat::sniff(&head)))
+}
+
+/// Fail with a message naming the detected and expected formats unless the
+/// file at `path` is `expected` (or is missing or empty, i.e. not written yet)
+pub(super) fn ensure_format(path: &Path, expected: IndexFormat) -> Result<()> {
+    let Some(head) = read_head(path)? else {
+        return Ok(());
+    };
+    match IndexFormat::sniff(&head) {
+        Some(found) if found == expected => Ok(()),
+        Some(found) => anyhow::bail!(
+            "{} is a {} index, expected {}; set `store_backend = \"{}\"` under [search] or point at the right file",
+            path.display(),
+            found,
+            expected,
+            found.backend()
+        ),
+        None => anyhow::bail!(
+            "{} is not a recognized index format (expected {}); remove it and run `agentlens index`",
+            path.display(),
+            expected
+        ),
+    }
+}
+
+/// First bytes of the file at `path`, decompressed if it is gzipped, or
+/// `None` if it is missing or empty
+fn read_head(path: &Path) -> Result<Option<Vec<u8>>> {
+    let reader = match open_index_reader(path) {
+        Ok((reader, _)) => reader,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut head = Vec::with_capacity(SQLITE_MAGIC.len());
+    reader
+        .take(SQLITE_MAGIC.len() as u64)
+        .read_to_end(&mut head)?;
+    Ok((!head.is_empty()).then_some(head))
+}
+
+/// Buffered reader over the file at `path` that decompresses it when it is
+/// gzipped, and whether it was
+pub(super) fn open_index_reader(path: &Path) -> std::io::Result<(Box<dyn Read>, bool)> {
+    let mut file = BufReader::new(File::open(path)?);
+    if file.fill_buf()?.starts_with(GZIP_MAGIC) {
+        Ok((Box::new(BufReader::new(GzDecoder::new(file))), true))
+    } else {
+        Ok((Box::new(file), false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::store::{open_index_file, Chunk, GobStore, SqliteStore, VectorStore};
+    use crate::test_support::make_chunk;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn lib_chunk() -> Chunk {
+        Chunk {
+            content: "fn lib() {}".to_string(),
+            ..make_chunk("src/lib.rs:block:1", "src/lib.rs", vec![1.0, 0.0])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_loads_by_content_regardless_of_extension() {
+        let temp = TempDir::new().unwrap();
+
+        // A JSON index under a binary-looking name
+        let json_path = temp.path().join("index.bin");
+        let store = GobStore::new(json_path.clone());
+        store.save_chunks(vec![lib_chunk()]).await.unwrap();
+        store.persist().await.unwrap();
+
+        // A SQLite index under a JSON-looking name
+        let sqlite_path = temp.path().join("index.json");
+        let store = SqliteStore::open(sqlite_path.clone()).unwrap();
+        store.save_chunks(vec![lib_chunk()]).await.unwrap();
+        drop(store);
+
+        assert_eq!(
+            detect_index_format(&json_path).unwrap(),
+            Some(IndexFormat::Json)
+        );
+        assert_eq!(
+            detect_index_format(&sqlite_path).unwrap(),
+            Some(IndexFormat::Sqlite)
+        );
+
+        for path in [&json_path, &sqlite_path] {
+            let store = open_index_file(path).unwrap();
+            store.load().await.unwrap();
+            assert_eq!(store.get_all_chunks(None).await.unwrap().len(), 1);
+        }
+
+        let gob = GobStore::new(json_path);
+        gob.load().await.unwrap();
+        assert_eq!(gob.get_all_chunks(None).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mismatched_format_names_detected_and_expected() {
+        let temp = TempDir::new().unwrap();
+        let sqlite_path = temp.path().join("index.json");
+        SqliteStore::open(sqlite_path.clone()).unwrap();
+
+        let err = GobStore::new(sqlite_path).load().await.unwrap_err();
+        assert!(
+            err.to_string().contains("is a SQLite index, expected JSON"),
+            "{}",
+            err
+        );
+
+        let json_path = temp.path().join("index.db");
+        fs::write(&json_path, "{\"chunks\": {}, \"documents\": {}}").unwrap();
+        let err = SqliteStore::open(json_path).err().unwrap();
+        assert!(
+            err.to_string().contains("is a JSON index, expected SQLite"),
+            "{}",
+            err
+        );
+
+        let garbage = temp.path().join("garbage.json");
+        fs::write(&garbage, [0u8, 159, 146, 150]).unwrap();
+        let err = GobStore::new(garbage.clone()).load().await.unwrap_err();
+        assert!(err.to_string().contains("not a recognized index format"));
+        assert!(open_index_file(&garbage).is_err());
+    }
+}
@@ -1,22 +1,80 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use serde::{Deserialize, Serialize};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rayon::prelude::*;
+use serde::de::{DeserializeSeed, IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
-use std::fs;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Write};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::RwLock;
+use std::time::SystemTime;
 
-use super::{cosine_similarity, Chunk, Document, IndexStats, SearchResult, VectorStore};
+use super::format::{ensure_format, open_index_reader, IndexFormat};
+use super::hnsw::HnswGraph;
+use super::quantize::{QuantizedChunk, QuantizedChunks};
+use super::{
+    cosine_similarity_batch, cosine_similarity_normed, storage_key, vector_norm, Chunk, Document,
+    IndexMeta, IndexStats, SearchFilter, SearchResult, TopK, VectorStore,
+};
+
+/// Below this many chunks scoring every chunk is fast enough that no HNSW
+/// graph is built
+const HNSW_MIN_CHUNKS: usize = 1000;
+/// Graph candidates gathered per requested result, so filtered-out chunks
+/// can be skipped without falling back to a full scan
+const HNSW_OVERSAMPLE: usize = 4;
+/// Minimum candidate list size when searching the graph
+const HNSW_EF_SEARCH: usize = 64;
+/// Chunks scored per `cosine_similarity_batch` call in a full scan
+const SCORE_BATCH_SIZE: usize = 1024;
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 struct IndexData {
+    #[serde(default)]
+    meta: IndexMeta,
+    #[serde(default)]
     chunks: HashMap<String, Chunk>,
+    /// Chunks of a quantized index as read from disk; moved into `chunks`
+    /// with reconstructed vectors on load
+    #[serde(default, skip_serializing)]
+    quantized_chunks: HashMap<String, QuantizedChunk>,
     documents: HashMap<String, Document>,
 }
 
+/// On-disk layout of a quantized index: `IndexData` with `quantized_chunks`
+/// in place of `chunks`
+#[derive(Serialize)]
+struct QuantizedIndexData<'a> {
+    meta: &'a IndexMeta,
+    quantized_chunks: QuantizedChunks<'a>,
+    documents: &'a HashMap<String, Document>,
+}
+
 pub struct GobStore {
     path: PathBuf,
     data: RwLock<IndexData>,
+    streaming: bool,
+    /// Write vectors as int8 (see `with_quantization`)
+    quantize: bool,
+    /// The loaded file was quantized, so writes keep it quantized
+    loaded_quantized: AtomicBool,
+    /// Gzip the file on write (see `with_compression`)
+    compress: bool,
+    /// The loaded file was gzipped, so writes keep it gzipped
+    loaded_compressed: AtomicBool,
+    /// Modification time and size of the file `data` was last loaded from or
+    /// written to, so repeated `load` calls skip an unchanged index
+    synced: RwLock<Option<(SystemTime, u64)>>,
+    /// Search through an HNSW graph (see `with_hnsw`)
+    hnsw: bool,
+    /// Graph over the chunks in `data`; dropped whenever chunks change and
+    /// rebuilt by the next search
+    graph: RwLock<Option<HnswGraph>>,
 }
 
 impl GobStore {
@@ -24,7 +82,168 @@ impl GobStore {
         Self {
             path,
             data: RwLock::new(IndexData::default()),
+            streaming: false,
+            quantize: false,
+            loaded_quantized: AtomicBool::new(false),
+            compress: false,
+            loaded_compressed: AtomicBool::new(false),
+            synced: RwLock::new(None),
+            hnsw: false,
+            graph: RwLock::new(None),
+        }
+    }
+
+    /// Answer searches from an in-memory HNSW graph instead of scoring every
+    /// chunk. Approximate: results can miss a few true neighbors. The graph is
+    /// built on the first search after a load or write, costs a normalized
+    /// copy of every vector, and is skipped for indexes under 1000 chunks.
+    pub fn with_hnsw(mut self, hnsw: bool) -> Self {
+        self.hnsw = hnsw;
+        self
+    }
+
+    /// Forget the graph; call while holding the `data` write lock
+    fn invalidate_graph(&self) -> Result<()> {
+        if self.hnsw {
+            *self.graph.write().map_err(|e| anyhow::anyhow!("{}", e))? = None;
+        }
+        Ok(())
+    }
+
+    /// Top `limit` chunks matching `filter` from the HNSW graph, building it
+    /// first if needed. `None` when the index is too small for a graph or the
+    /// filter rejects too many candidates, so the caller scores every chunk.
+    fn search_graph(
+        &self,
+        query_vector: &[f32],
+        limit: usize,
+        filter: &SearchFilter,
+    ) -> Result<Option<Vec<SearchResult>>> {
+        let data = self.data.read().map_err(|e| anyhow::anyhow!("{}", e))?;
+        if data.chunks.len() < HNSW_MIN_CHUNKS {
+            return Ok(None);
+        }
+
+        let ef = (limit * HNSW_OVERSAMPLE).max(HNSW_EF_SEARCH);
+        let search = |graph: &HnswGraph| {
+            let results: Vec<SearchResult> = graph
+                .search(query_vector, ef, ef)
+                .into_iter()
+                .filter_map(|(key, score)| {
+                    let chunk = data.chunks.get(key)?;
+                    filter
+                        .matches(chunk)
+                        .then(|| SearchResult::new(chunk.clone(), score))
+                })
+                .take(limit)
+                .collect();
+            (results.len() >= limit).then_some(results)
+        };
+
+        // Holding the `data` read lock keeps writers from invalidating the graph meanwhile
+        if let Some(graph) = self
+            .graph
+            .read()
+            .map_err(|e| anyhow::anyhow!("{}", e))?
+            .as_ref()
+        {
+            return Ok(search(graph));
         }
+        // Key order, so the same index always builds the same graph
+        let mut items: Vec<(&str, &[f32])> = data
+            .chunks
+            .iter()
+            .map(|(key, chunk)| (key.as_str(), chunk.vector.as_slice()))
+            .collect();
+        items.sort_unstable_by_key(|(key, _)| *key);
+        let built = HnswGraph::build(items);
+        let mut graph = self.graph.write().map_err(|e| anyhow::anyhow!("{}", e))?;
+        Ok(search(graph.get_or_insert(built)))
+    }
+
+    /// Persist vectors with int8 scalar quantization, about a quarter of the
+    /// size on disk. An index that is already quantized stays quantized until
+    /// it is cleared, whatever this is set to.
+    pub fn with_quantization(mut self, quantize: bool) -> Self {
+        self.quantize = quantize;
+        self
+    }
+
+    fn quantizes(&self) -> bool {
+        self.quantize || self.loaded_quantized.load(Ordering::Relaxed)
+    }
+
+    /// Gzip the index file on write; it is recognized by content when read.
+    /// A file named `*.gz` is always gzipped. Like quantization, a compressed
+    /// index stays compressed until it is cleared.
+    pub fn with_compression(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    fn compresses(&self) -> bool {
+        self.compress
+            || self.loaded_compressed.load(Ordering::Relaxed)
+            || self.path.extension().is_some_and(|ext| ext == "gz")
+    }
+
+    /// Read-only store that never holds the index in memory.
+    ///
+    /// `load` is a no-op and `search` streams chunks from disk one at a time,
+    /// so peak memory is O(limit) rather than O(chunks). Intended for indexes
+    /// larger than RAM; writes are not supported in this mode.
+    pub fn streaming(path: PathBuf) -> Self {
+        Self {
+            streaming: true,
+            ..Self::new(path)
+        }
+    }
+
+    /// Walk the on-disk index without materializing the whole file, returning its metadata.
+    /// Chunks are handed to `visit` one at a time, or skipped when `visit` is `None`.
+    fn stream_from_disk(&self, visit: Option<&mut dyn FnMut(Chunk)>) -> Result<IndexMeta> {
+        let mut meta = IndexMeta::default();
+        if !self.path.exists() {
+            return Ok(meta);
+        }
+        ensure_format(&self.path, IndexFormat::Json)?;
+
+        let (reader, _) = open_index_reader(&self.path)?;
+        let mut deserializer = serde_json::Deserializer::from_reader(reader);
+        IndexSeed {
+            visit,
+            meta: &mut meta,
+        }
+        .deserialize(&mut deserializer)?;
+        Ok(meta)
+    }
+
+    fn for_each_chunk_on_disk(&self, visit: &mut dyn FnMut(Chunk)) -> Result<()> {
+        self.stream_from_disk(Some(visit)).map(|_| ())
+    }
+
+    /// Bounded top-k search over chunks streamed from disk
+    pub fn search_streaming(
+        &self,
+        query_vector: &[f32],
+        limit: usize,
+        filter: &SearchFilter,
+    ) -> Result<Vec<SearchResult>> {
+        let query_norm = vector_norm(query_vector);
+        let mut top = TopK::new(limit);
+        self.for_each_chunk_on_disk(&mut |chunk| {
+            if !filter.matches(&chunk) {
+                return;
+            }
+            let score = cosine_similarity_normed(
+                query_vector,
+                query_norm,
+                &chunk.vector,
+                chunk.vector_norm(),
+            );
+            top.offer(score, || chunk);
+        })?;
+        Ok(top.into_sorted_vec())
     }
 
     fn atomic_write(&self, data: &IndexData) -> Result<()> {
@@ -33,10 +252,44 @@ impl GobStore {
         }
 
         let temp_path = self.path.with_extension("tmp");
-        let json = serde_json::to_vec(data)?;
-        fs::write(&temp_path, json)?;
+        let mut file = BufWriter::new(File::create(&temp_path)?);
+        if self.compresses() {
+            let mut encoder = GzEncoder::new(file, Compression::default());
+            self.write_json(&mut encoder, data)?;
+            encoder.finish()?.flush()?;
+        } else {
+            self.write_json(&mut file, data)?;
+            file.flush()?;
+        }
         fs::rename(temp_path, &self.path)?;
 
+        self.mark_synced()
+    }
+
+    fn write_json(&self, writer: &mut impl Write, data: &IndexData) -> Result<()> {
+        if self.quantizes() {
+            serde_json::to_writer(
+                writer,
+                &QuantizedIndexData {
+                    meta: &data.meta,
+                    quantized_chunks: QuantizedChunks(&data.chunks),
+                    documents: &data.documents,
+                },
+            )?;
+        } else {
+            serde_json::to_writer(writer, data)?;
+        }
+        Ok(())
+    }
+
+    fn file_stamp(&self) -> Result<(SystemTime, u64)> {
+        let meta = fs::metadata(&self.path)?;
+        Ok((meta.modified()?, meta.len()))
+    }
+
+    fn mark_synced(&self) -> Result<()> {
+        let stamp = self.file_stamp().ok();
+        *self.synced.write().map_err(|e| anyhow::anyhow!("{}", e))? = stamp;
         Ok(())
     }
 }
@@ -46,71 +299,175 @@ impl VectorStore for GobStore {
     async fn save_chunks(&self, chunks: Vec<Chunk>) -> Result<()> {
         let mut data = self.data.write().map_err(|e| anyhow::anyhow!("{}", e))?;
         for chunk in chunks {
-            data.chunks.insert(chunk.id.clone(), chunk);
+            let key = storage_key(chunk.collection.as_deref(), &chunk.id);
+            data.chunks.insert(key, chunk);
         }
-        Ok(())
+        self.invalidate_graph()
     }
 
-    async fn delete_by_file(&self, file_path: &str) -> Result<()> {
+    async fn delete_by_file(&self, file_path: &str, collection: Option<&str>) -> Result<()> {
         let mut data = self.data.write().map_err(|e| anyhow::anyhow!("{}", e))?;
 
-        let chunk_ids_to_remove: Vec<String> = data
-            .chunks
-            .iter()
-            .filter(|(_, c)| c.file_path == file_path)
-            .map(|(id, _)| id.clone())
-            .collect();
+        data.chunks
+            .retain(|_, c| c.file_path != file_path || c.collection.as_deref() != collection);
+        data.documents.remove(&storage_key(collection, file_path));
+
+        self.invalidate_graph()
+    }
 
-        for id in chunk_ids_to_remove {
-            data.chunks.remove(&id);
+    async fn delete_chunks(&self, ids: &[String], collection: Option<&str>) -> Result<()> {
+        let mut data = self.data.write().map_err(|e| anyhow::anyhow!("{}", e))?;
+        for id in ids {
+            data.chunks.remove(&storage_key(collection, id));
         }
+        self.invalidate_graph()
+    }
 
-        data.documents.remove(file_path);
+    async fn get_chunks_by_file(
+        &self,
+        file_path: &str,
+        collection: Option<&str>,
+    ) -> Result<Vec<Chunk>> {
+        let in_file = |c: &Chunk| c.file_path == file_path && c.collection.as_deref() == collection;
+        if self.streaming {
+            let mut chunks = Vec::new();
+            self.for_each_chunk_on_disk(&mut |chunk| {
+                if in_file(&chunk) {
+                    chunks.push(chunk);
+                }
+            })?;
+            return Ok(chunks);
+        }
 
-        Ok(())
+        let data = self.data.read().map_err(|e| anyhow::anyhow!("{}", e))?;
+        Ok(data
+            .chunks
+            .values()
+            .filter(|c| in_file(c))
+            .cloned()
+            .collect())
     }
 
-    async fn search(&self, query_vector: &[f32], limit: usize) -> Result<Vec<SearchResult>> {
+    async fn search(
+        &self,
+        query_vector: &[f32],
+        limit: usize,
+        collection: Option<&str>,
+    ) -> Result<Vec<SearchResult>> {
+        self.search_filtered(
+            query_vector,
+            limit,
+            SearchFilter::for_collection(collection),
+        )
+        .await
+    }
+
+    async fn search_filtered(
+        &self,
+        query_vector: &[f32],
+        limit: usize,
+        filter: SearchFilter,
+    ) -> Result<Vec<SearchResult>> {
+        if self.streaming {
+            return self.search_streaming(query_vector, limit, &filter);
+        }
+        if self.hnsw {
+            if let Some(results) = self.search_graph(query_vector, limit, &filter)? {
+                return Ok(results);
+            }
+        }
+
         let data = self.data.read().map_err(|e| anyhow::anyhow!("{}", e))?;
 
-        let mut results: Vec<SearchResult> = data
+        // Score batches in parallel against stored chunk norms, then keep the
+        // best `limit` in a bounded heap; only those chunks are cloned
+        let candidates: Vec<&Chunk> = data
             .chunks
-            .values()
-            .map(|chunk| {
-                let score = cosine_similarity(query_vector, &chunk.vector);
-                SearchResult::new(chunk.clone(), score)
+            .par_iter()
+            .filter(|(_, chunk)| filter.matches(chunk))
+            .map(|(_, chunk)| chunk)
+            .collect();
+        let scores: Vec<Vec<f32>> = candidates
+            .par_chunks(SCORE_BATCH_SIZE)
+            .map(|batch| {
+                let vectors: Vec<(&[f32], f32)> = batch
+                    .iter()
+                    .map(|c| (c.vector.as_slice(), c.vector_norm()))
+                    .collect();
+                cosine_similarity_batch(query_vector, &vectors)
             })
             .collect();
 
-        results.sort_by(|a, b| {
-            b.score
-                .partial_cmp(&a.score)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-        results.truncate(limit);
+        let mut top = TopK::new(limit);
+        for (chunk, score) in candidates.into_iter().zip(scores.into_iter().flatten()) {
+            top.offer(score, || chunk.clone());
+        }
 
-        Ok(results)
+        Ok(top.into_sorted_vec())
     }
 
-    async fn get_document(&self, file_path: &str) -> Result<Option<Document>> {
+    async fn get_document(
+        &self,
+        file_path: &str,
+        collection: Option<&str>,
+    ) -> Result<Option<Document>> {
         let data = self.data.read().map_err(|e| anyhow::anyhow!("{}", e))?;
-        Ok(data.documents.get(file_path).cloned())
+        Ok(data
+            .documents
+            .get(&storage_key(collection, file_path))
+            .cloned())
     }
 
     async fn save_document(&self, doc: Document) -> Result<()> {
         let mut data = self.data.write().map_err(|e| anyhow::anyhow!("{}", e))?;
-        data.documents.insert(doc.path.clone(), doc);
+        let key = storage_key(doc.collection.as_deref(), &doc.path);
+        data.documents.insert(key, doc);
         Ok(())
     }
 
-    async fn list_documents(&self) -> Result<Vec<String>> {
+    async fn list_documents(&self, collection: Option<&str>) -> Result<Vec<String>> {
         let data = self.data.read().map_err(|e| anyhow::anyhow!("{}", e))?;
-        Ok(data.documents.keys().cloned().collect())
+        Ok(data
+            .documents
+            .values()
+            .filter(|d| d.collection.as_deref() == collection)
+            .map(|d| d.path.clone())
+            .collect())
     }
 
-    async fn get_all_chunks(&self) -> Result<Vec<Chunk>> {
+    async fn get_meta(&self) -> Result<IndexMeta> {
+        if self.streaming {
+            return self.stream_from_disk(None);
+        }
+
         let data = self.data.read().map_err(|e| anyhow::anyhow!("{}", e))?;
-        Ok(data.chunks.values().cloned().collect())
+        Ok(data.meta.clone())
+    }
+
+    async fn save_meta(&self, meta: IndexMeta) -> Result<()> {
+        let mut data = self.data.write().map_err(|e| anyhow::anyhow!("{}", e))?;
+        data.meta = meta;
+        Ok(())
+    }
+
+    async fn get_all_chunks(&self, collection: Option<&str>) -> Result<Vec<Chunk>> {
+        if self.streaming {
+            let mut chunks = Vec::new();
+            self.for_each_chunk_on_disk(&mut |chunk| {
+                if chunk.collection.as_deref() == collection {
+                    chunks.push(chunk);
+                }
+            })?;
+            return Ok(chunks);
+        }
+
+        let data = self.data.read().map_err(|e| anyhow::anyhow!("{}", e))?;
+        Ok(data
+            .chunks
+            .values()
+            .filter(|c| c.collection.as_deref() == collection)
+            .cloned()
+            .collect())
     }
 
     async fn persist(&self) -> Result<()> {
@@ -119,15 +476,47 @@ impl VectorStore for GobStore {
     }
 
     async fn load(&self) -> Result<()> {
-        if !self.path.exists() {
+        if self.streaming || !self.path.exists() {
             return Ok(());
         }
 
-        let content = fs::read(&self.path)?;
-        let loaded: IndexData = serde_json::from_slice(&content)?;
+        let stamp = self.file_stamp().ok();
+        if stamp.is_some() && *self.synced.read().map_err(|e| anyhow::anyhow!("{}", e))? == stamp {
+            return Ok(());
+        }
+
+        ensure_format(&self.path, IndexFormat::Json)?;
+        let (mut reader, compressed) = open_index_reader(&self.path)?;
+        let mut content = Vec::new();
+        reader.read_to_end(&mut content)?;
+        if compressed {
+            self.loaded_compressed.store(true, Ordering::Relaxed);
+        }
+        let mut loaded: IndexData = serde_json::from_slice(&content)?;
+        if !loaded.quantized_chunks.is_empty() {
+            self.loaded_quantized.store(true, Ordering::Relaxed);
+            let quantized = std::mem::take(&mut loaded.quantized_chunks);
+            loaded
+                .chunks
+                .extend(quantized.into_iter().map(|(key, q)| (key, q.into_chunk())));
+        }
+        // Indexes written before chunk norms were stored
+        for chunk in loaded.chunks.values_mut() {
+            chunk.fill_norm();
+        }
+        // Indexes written before the header recorded dimensions
+        if loaded.meta.dimensions.is_none() {
+            loaded.meta.dimensions = loaded
+                .chunks
+                .values()
+                .map(|c| c.vector.len())
+                .find(|&len| len > 0);
+        }
 
         let mut data = self.data.write().map_err(|e| anyhow::anyhow!("{}", e))?;
         *data = loaded;
+        self.invalidate_graph()?;
+        *self.synced.write().map_err(|e| anyhow::anyhow!("{}", e))? = stamp;
 
         Ok(())
     }
@@ -148,18 +537,608 @@ impl VectorStore for GobStore {
             total_chunks: data.chunks.len(),
             index_size_bytes: index_size,
             last_updated,
-        })
+            ..Default::default()
+        }
+        .with_chunk_breakdown(data.chunks.values().map(|c| {
+            (
+                (c.collection.as_deref(), c.file_path.as_str()),
+                c.chunk_type,
+                c.symbol_kind.is_some(),
+            )
+        })))
     }
 
     async fn clear(&self) -> Result<()> {
         let mut data = self.data.write().map_err(|e| anyhow::anyhow!("{}", e))?;
         data.chunks.clear();
         data.documents.clear();
+        data.meta = IndexMeta::default();
+        self.invalidate_graph()?;
+        self.loaded_quantized.store(false, Ordering::Relaxed);
+        self.loaded_compressed.store(false, Ordering::Relaxed);
 
         if self.path.exists() {
             fs::remove_file(&self.path)?;
         }
+        *self.synced.write().map_err(|e| anyhow::anyhow!("{}", e))? = None;
 
         Ok(())
     }
+
+    async fn last_modified(&self) -> Result<Option<SystemTime>> {
+        match fs::metadata(&self.path) {
+            Ok(meta) => Ok(meta.modified().ok()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Deserializes `IndexData` top-level keys, streaming `chunks` (or
+/// `quantized_chunks`) and skipping documents
+struct IndexSeed<'a, 'v> {
+    visit: Option<&'v mut dyn FnMut(Chunk)>,
+    meta: &'a mut IndexMeta,
+}
+
+impl<'de> DeserializeSeed<'de> for IndexSeed<'_, '_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for IndexSeed<'_, '_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an agentlens index object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            match (key.as_str(), self.visit.as_deref_mut()) {
+                ("meta", _) => *self.meta = map.next_value()?,
+                ("chunks", Some(visit)) => map.next_value_seed(ChunksSeed { visit })?,
+                ("quantized_chunks", Some(visit)) => {
+                    map.next_value_seed(QuantizedChunksSeed { visit })?
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Visits the `chunks` map one entry at a time
+struct ChunksSeed<'a> {
+    visit: &'a mut dyn FnMut(Chunk),
+}
+
+impl<'de> DeserializeSeed<'de> for ChunksSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ChunksSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map of chunk id to chunk")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some((_, chunk)) = map.next_entry::<IgnoredAny, Chunk>()? {
+            (self.visit)(chunk);
+        }
+        Ok(())
+    }
+}
+
+/// Visits the `quantized_chunks` map one entry at a time, reconstructing vectors
+struct QuantizedChunksSeed<'a> {
+    visit: &'a mut dyn FnMut(Chunk),
+}
+
+impl<'de> DeserializeSeed<'de> for QuantizedChunksSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for QuantizedChunksSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map of chunk id to quantized chunk")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some((_, chunk)) = map.next_entry::<IgnoredAny, QuantizedChunk>()? {
+            (self.visit)(chunk.into_chunk());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::store::{cosine_similarity, detect_index_format, ChunkType};
+    use crate::test_support::make_chunk;
+    use crate::types::SymbolKind;
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    fn numbered_chunk(id: usize, vector: Vec<f32>) -> Chunk {
+        Chunk {
+            content: format!("content {}", id),
+            hash: "abc123".to_string(),
+            ..make_chunk(&format!("chunk{}", id), &format!("file{}.rs", id), vector)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chunks_without_stored_norm_get_one_on_load() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("index.json");
+        let writer = GobStore::new(path.clone());
+        writer
+            .save_chunks(vec![numbered_chunk(0, vec![3.0, 4.0])])
+            .await
+            .unwrap();
+        writer.persist().await.unwrap();
+        assert!(!fs::read_to_string(&path).unwrap().contains("\"norm\""));
+
+        let store = GobStore::new(path);
+        store.load().await.unwrap();
+
+        let chunks = store.get_all_chunks(None).await.unwrap();
+        assert_eq!(chunks[0].norm, Some(5.0));
+        let results = store.search(&[3.0, 4.0], 1, None).await.unwrap();
+        assert!((results[0].score - 1.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_search_matches_in_memory_top_k() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("index.json");
+
+        let store = GobStore::new(path.clone());
+        let chunks: Vec<Chunk> = (0..200)
+            .map(|i| {
+                let angle = i as f32 / 200.0 * std::f32::consts::PI;
+                numbered_chunk(i, vec![angle.cos(), angle.sin(), 0.0])
+            })
+            .collect();
+        store.save_chunks(chunks).await.unwrap();
+        store.persist().await.unwrap();
+
+        let query = vec![1.0, 0.0, 0.0];
+        let expected = store.search(&query, 5, None).await.unwrap();
+
+        let streaming = GobStore::streaming(path);
+        streaming.load().await.unwrap();
+        assert!(streaming.data.read().unwrap().chunks.is_empty());
+
+        let results = streaming.search(&query, 5, None).await.unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.chunk.id.as_str()).collect();
+        let expected_ids: Vec<&str> = expected.iter().map(|r| r.chunk.id.as_str()).collect();
+
+        assert_eq!(ids, expected_ids);
+        assert_eq!(ids[0], "chunk0");
+        assert!(results.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+
+    /// Deterministic pseudo-random vectors in [-1, 1)
+    fn synthetic_vectors(count: usize, dimensions: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut state = seed;
+        (0..count)
+            .map(|_| {
+                (0..dimensions)
+                    .map(|_| {
+                        state = state
+                            .wrapping_mul(6364136223846793005)
+                            .wrapping_add(1442695040888963407);
+                        (state >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_hnsw_recall_matches_brute_force() {
+        let temp = TempDir::new().unwrap();
+        let chunks: Vec<Chunk> = synthetic_vectors(2000, 32, 11)
+            .into_iter()
+            .enumerate()
+            .map(|(i, vector)| numbered_chunk(i, vector))
+            .collect();
+        let exact = GobStore::new(temp.path().join("exact.json"));
+        let hnsw = GobStore::new(temp.path().join("hnsw.json")).with_hnsw(true);
+        exact.save_chunks(chunks.clone()).await.unwrap();
+        hnsw.save_chunks(chunks).await.unwrap();
+
+        let queries = synthetic_vectors(20, 32, 99);
+        let mut overlap = 0;
+        for query in &queries {
+            let expected = exact.search(query, 10, None).await.unwrap();
+            let found = hnsw.search(query, 10, None).await.unwrap();
+            assert_eq!(found.len(), 10);
+            overlap += found
+                .iter()
+                .filter(|r| expected.iter().any(|e| e.chunk.id == r.chunk.id))
+                .count();
+        }
+        let recall = overlap as f32 / (queries.len() * 10) as f32;
+        assert!(recall > 0.9, "HNSW top-10 recall {}", recall);
+        assert!(hnsw.graph.read().unwrap().is_some());
+
+        // Writes drop the graph; the next search sees the new chunk
+        hnsw.save_chunks(vec![numbered_chunk(5000, queries[0].clone())])
+            .await
+            .unwrap();
+        assert!(hnsw.graph.read().unwrap().is_none());
+        let found = hnsw.search(&queries[0], 10, None).await.unwrap();
+        assert_eq!(found[0].chunk.id, "chunk5000");
+    }
+
+    #[tokio::test]
+    async fn test_quantized_index_keeps_recall_and_shrinks() {
+        let temp = TempDir::new().unwrap();
+        let full_path = temp.path().join("full.json");
+        let quantized_path = temp.path().join("quantized.json");
+
+        let chunks: Vec<Chunk> = synthetic_vectors(300, 256, 7)
+            .into_iter()
+            .enumerate()
+            .map(|(i, vector)| numbered_chunk(i, vector))
+            .collect();
+        let full = GobStore::new(full_path.clone());
+        full.save_chunks(chunks.clone()).await.unwrap();
+        full.persist().await.unwrap();
+        let writer = GobStore::new(quantized_path.clone()).with_quantization(true);
+        writer.save_chunks(chunks).await.unwrap();
+        writer.persist().await.unwrap();
+
+        let full_size = fs::metadata(&full_path).unwrap().len();
+        let quantized_size = fs::metadata(&quantized_path).unwrap().len();
+        assert!(
+            quantized_size * 2 < full_size,
+            "{} vs {}",
+            quantized_size,
+            full_size
+        );
+
+        let quantized = GobStore::new(quantized_path.clone());
+        quantized.load().await.unwrap();
+        let streaming = GobStore::streaming(quantized_path);
+
+        let limit = 10;
+        let (mut found, mut streamed) = (0, 0);
+        let queries = synthetic_vectors(20, 256, 99);
+        for query in &queries {
+            let ids = |results: Vec<SearchResult>| -> Vec<String> {
+                results.into_iter().map(|r| r.chunk.id).collect()
+            };
+            let expected = ids(full.search(query, limit, None).await.unwrap());
+            let actual = ids(quantized.search(query, limit, None).await.unwrap());
+            found += actual.iter().filter(|id| expected.contains(id)).count();
+            streamed += ids(streaming.search(query, limit, None).await.unwrap())
+                .iter()
+                .filter(|id| actual.contains(id))
+                .count();
+        }
+
+        let recall = found as f32 / (queries.len() * limit) as f32;
+        assert!(recall >= 0.95, "recall {}", recall);
+        assert_eq!(streamed, queries.len() * limit);
+    }
+
+    #[tokio::test]
+    async fn test_quantized_index_stays_quantized_until_cleared() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("index.json");
+
+        let writer = GobStore::new(path.clone()).with_quantization(true);
+        writer
+            .save_chunks(vec![numbered_chunk(0, vec![0.5, -1.0, 0.25])])
+            .await
+            .unwrap();
+        writer.persist().await.unwrap();
+
+        // A store opened without quantization rewrites it quantized
+        let store = GobStore::new(path.clone());
+        store.load().await.unwrap();
+        let chunk = &store.get_all_chunks(None).await.unwrap()[0];
+        assert!((chunk.vector[1] + 1.0).abs() < 1e-6);
+        assert!((chunk.vector[2] - 0.25).abs() < 0.01);
+        store
+            .save_chunks(vec![numbered_chunk(1, vec![1.0, 0.0, 0.0])])
+            .await
+            .unwrap();
+        store.persist().await.unwrap();
+        let on_disk = fs::read_to_string(&path).unwrap();
+        assert!(on_disk.contains("\"quantized_chunks\""));
+        assert!(!on_disk.contains("\"chunks\""));
+
+        store.clear().await.unwrap();
+        store
+            .save_chunks(vec![numbered_chunk(2, vec![1.0, 0.0, 0.0])])
+            .await
+            .unwrap();
+        store.persist().await.unwrap();
+        assert!(!fs::read_to_string(&path)
+            .unwrap()
+            .contains("quantized_chunks"));
+    }
+
+    #[tokio::test]
+    async fn test_search_filtered_by_path_and_type() {
+        let store = GobStore::new(PathBuf::from("unused.json"));
+        let chunk = |id: usize, file_path: &str, chunk_type: ChunkType| Chunk {
+            file_path: file_path.to_string(),
+            chunk_type,
+            ..numbered_chunk(id, vec![1.0, 0.0])
+        };
+        store
+            .save_chunks(vec![
+                chunk(0, "src/auth/login.rs", ChunkType::Function),
+                chunk(1, "src/auth/login.rs", ChunkType::Block),
+                chunk(2, "src/auth/tests/login_test.rs", ChunkType::Function),
+                chunk(3, "src/authz.rs", ChunkType::Function),
+                chunk(4, "src/db.rs", ChunkType::Function),
+            ])
+            .await
+            .unwrap();
+
+        let filter = SearchFilter {
+            path_prefix: Some("src/auth/".to_string()),
+            chunk_types: Some(vec![ChunkType::Function]),
+            exclude_paths: vec!["src/auth/tests".to_string()],
+            collection: None,
+        };
+        let results = store
+            .search_filtered(&[1.0, 0.0], 10, filter)
+            .await
+            .unwrap();
+
+        let ids: Vec<&str> = results.iter().map(|r| r.chunk.id.as_str()).collect();
+        assert_eq!(ids, vec!["chunk0"]);
+    }
+
+    #[tokio::test]
+    async fn test_stats_break_down_chunk_types() {
+        let store = GobStore::new(PathBuf::from("unused.json"));
+        let chunk = |id: usize, file_path: &str, chunk_type: ChunkType| Chunk {
+            file_path: file_path.to_string(),
+            chunk_type,
+            symbol_kind: (chunk_type != ChunkType::Block).then_some(SymbolKind::Function),
+            ..numbered_chunk(id, vec![1.0, 0.0])
+        };
+        store
+            .save_chunks(vec![
+                chunk(0, "src/lib.rs", ChunkType::Function),
+                chunk(1, "src/lib.rs", ChunkType::Block),
+                chunk(2, "src/main.rs", ChunkType::Function),
+                chunk(3, "config.yaml", ChunkType::Block),
+                chunk(4, "config.yaml", ChunkType::Block),
+            ])
+            .await
+            .unwrap();
+
+        let stats = store.stats().await.unwrap();
+
+        assert_eq!(stats.chunk_type_counts.len(), 2);
+        assert_eq!(stats.chunk_type_counts[&ChunkType::Function], 2);
+        assert_eq!(stats.chunk_type_counts[&ChunkType::Block], 3);
+        assert_eq!(stats.files_without_symbols, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_parallel_search_matches_serial_on_large_index() {
+        let store = GobStore::new(PathBuf::from("unused.json"));
+        let chunks: Vec<Chunk> = (0..50_000)
+            .map(|i| {
+                let x = i as f32;
+                numbered_chunk(
+                    i,
+                    vec![(x * 0.37).sin(), (x * 0.11).cos(), (x * 0.05).sin(), 0.5],
+                )
+            })
+            .collect();
+        store.save_chunks(chunks.clone()).await.unwrap();
+
+        let query = [0.3, -0.8, 0.1, 0.5];
+        let mut serial = TopK::new(20);
+        for chunk in &chunks {
+            serial.offer(cosine_similarity(&query, &chunk.vector), || chunk.clone());
+        }
+        let expected: Vec<(String, f32)> = serial
+            .into_sorted_vec()
+            .into_iter()
+            .map(|r| (r.chunk.id, r.score))
+            .collect();
+
+        let results: Vec<(String, f32)> = store
+            .search(&query, 20, None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| (r.chunk.id, r.score))
+            .collect();
+
+        assert_eq!(results.len(), 20);
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_top_k_holds_at_most_limit_results() {
+        let mut top = TopK::new(3);
+        for i in 0..100 {
+            top.offer(i as f32, || numbered_chunk(i, vec![]));
+            assert!(top.len() <= 3);
+        }
+
+        let scores: Vec<f32> = top.into_sorted_vec().iter().map(|r| r.score).collect();
+        assert_eq!(scores, vec![99.0, 98.0, 97.0]);
+    }
+
+    #[test]
+    fn test_top_k_only_materializes_qualifying_chunks() {
+        let mut top = TopK::new(3);
+        let mut materialized = 0;
+
+        for i in (0..100).rev() {
+            top.offer(i as f32, || {
+                materialized += 1;
+                numbered_chunk(i, vec![])
+            });
+        }
+
+        assert_eq!(materialized, 3);
+        assert_eq!(top.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_meta_survives_persist_and_streaming_read() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("index.json");
+
+        let store = GobStore::new(path.clone());
+        store
+            .save_chunks(vec![numbered_chunk(0, vec![1.0])])
+            .await
+            .unwrap();
+        store
+            .save_meta(IndexMeta {
+                model: Some("nomic-embed-text".to_string()),
+                dimensions: None,
+            })
+            .await
+            .unwrap();
+        store.persist().await.unwrap();
+
+        let reloaded = GobStore::new(path.clone());
+        reloaded.load().await.unwrap();
+        let meta = reloaded.get_meta().await.unwrap();
+        assert_eq!(meta.model.as_deref(), Some("nomic-embed-text"));
+        // Not recorded, so taken from the stored vectors
+        assert_eq!(meta.dimensions, Some(1));
+        assert!(meta.check_dimensions(768).is_err());
+
+        let streaming = GobStore::streaming(path);
+        assert_eq!(
+            streaming.get_meta().await.unwrap().model.as_deref(),
+            Some("nomic-embed-text")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_compressed_index_round_trips() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("index.json");
+
+        let store = GobStore::new(path.clone()).with_compression(true);
+        let chunks: Vec<Chunk> = (0..20)
+            .map(|i| numbered_chunk(i, vec![i as f32, 1.0, 0.0]))
+            .collect();
+        store.save_chunks(chunks).await.unwrap();
+        store
+            .save_document(Document {
+                path: "file0.rs".to_string(),
+                hash: "abc123".to_string(),
+                mod_time: Utc::now(),
+                chunk_ids: vec!["chunk0".to_string()],
+                collection: None,
+            })
+            .await
+            .unwrap();
+        store
+            .save_meta(IndexMeta {
+                model: Some("nomic-embed-text".to_string()),
+                dimensions: Some(3),
+            })
+            .await
+            .unwrap();
+        store.persist().await.unwrap();
+
+        assert!(fs::read(&path).unwrap().starts_with(&[0x1f, 0x8b]));
+        assert_eq!(detect_index_format(&path).unwrap(), Some(IndexFormat::Json));
+
+        let reloaded = GobStore::new(path.clone());
+        reloaded.load().await.unwrap();
+        assert_eq!(reloaded.get_all_chunks(None).await.unwrap().len(), 20);
+        assert_eq!(
+            reloaded.list_documents(None).await.unwrap(),
+            vec!["file0.rs".to_string()]
+        );
+        assert_eq!(
+            reloaded.get_meta().await.unwrap().model.as_deref(),
+            Some("nomic-embed-text")
+        );
+
+        let query = vec![19.0, 1.0, 0.0];
+        let streamed = GobStore::streaming(path.clone())
+            .search(&query, 1, None)
+            .await
+            .unwrap();
+        assert_eq!(streamed[0].chunk.id, "chunk19");
+
+        // Rewritten compressed even though this store was not asked to compress
+        reloaded.persist().await.unwrap();
+        assert!(fs::read(&path).unwrap().starts_with(&[0x1f, 0x8b]));
+    }
+
+    #[tokio::test]
+    async fn test_collections_are_isolated() {
+        let temp = TempDir::new().unwrap();
+        let store = GobStore::new(temp.path().join("index.json"));
+
+        let mut team_a = numbered_chunk(0, vec![1.0, 0.0]);
+        team_a.collection = Some("team-a".to_string());
+        let mut team_b = numbered_chunk(0, vec![1.0, 0.0]);
+        team_b.collection = Some("team-b".to_string());
+        let global = numbered_chunk(1, vec![1.0, 0.0]);
+        store
+            .save_chunks(vec![team_a, team_b, global])
+            .await
+            .unwrap();
+
+        let results = store.search(&[1.0, 0.0], 10, Some("team-a")).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].chunk.collection.as_deref(), Some("team-a"));
+
+        let results = store.search(&[1.0, 0.0], 10, None).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].chunk.id, "chunk1");
+
+        // Same file path in another collection is untouched by a scoped delete
+        store
+            .delete_by_file("file0.rs", Some("team-b"))
+            .await
+            .unwrap();
+        assert!(store
+            .search(&[1.0, 0.0], 10, Some("team-b"))
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            store
+                .search(&[1.0, 0.0], 10, Some("team-a"))
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
 }
@@ -0,0 +1,237 @@
+//! In-memory HNSW (hierarchical navigable small world) graph for approximate
+//! nearest neighbor search by cosine similarity, so large JSON indexes avoid
+//! scoring every chunk per query. Vectors are copied and normalized on insert;
+//! the graph lives only in memory and is rebuilt from the index.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet};
+
+/// Links per node on upper layers
+const M: usize = 16;
+/// Links per node on the bottom layer, which holds every node
+const M0: usize = 2 * M;
+/// Candidates considered while linking a new node
+const EF_CONSTRUCTION: usize = 100;
+
+/// Similarity-ordered node id for the search heaps
+#[derive(Clone, Copy, PartialEq)]
+struct Scored(f32, usize);
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+pub struct HnswGraph {
+    keys: Vec<String>,
+    vectors: Vec<Vec<f32>>,
+    /// `links[node][layer]`: neighbors of `node` on `layer`
+    links: Vec<Vec<Vec<usize>>>,
+    entry: Option<usize>,
+    top_layer: usize,
+    /// LCG state for layer assignment, seeded so builds are reproducible
+    rng: u64,
+}
+
+impl HnswGraph {
+    pub fn new() -> Self {
+        Self {
+            keys: Vec::new(),
+            vectors: Vec::new(),
+            links: Vec::new(),
+            entry: None,
+            top_layer: 0,
+            rng: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+
+    pub fn build<'a>(items: impl IntoIterator<Item = (&'a str, &'a [f32])>) -> Self {
+        let mut graph = Self::new();
+        for (key, vector) in items {
+            graph.insert(key.to_string(), vector);
+        }
+        graph
+    }
+
+    pub fn insert(&mut self, key: String, vector: &[f32]) {
+        let node = self.keys.len();
+        let layer = self.random_layer();
+        self.keys.push(key);
+        self.vectors.push(normalize(vector));
+        self.links.push(vec![Vec::new(); layer + 1]);
+
+        let Some(mut entry) = self.entry else {
+            self.entry = Some(node);
+            self.top_layer = layer;
+            return;
+        };
+
+        let query = self.vectors[node].clone();
+        for l in (layer + 1..=self.top_layer).rev() {
+            entry = self.search_layer(&query, &[entry], 1, l)[0].1;
+        }
+
+        let mut entries = vec![entry];
+        for l in (0..=layer.min(self.top_layer)).rev() {
+            let candidates = self.search_layer(&query, &entries, EF_CONSTRUCTION, l);
+            let neighbors: Vec<usize> = candidates.iter().take(M).map(|s| s.1).collect();
+            for &neighbor in &neighbors {
+                self.links[neighbor][l].push(node);
+                self.prune(neighbor, l);
+            }
+            self.links[node][l] = neighbors;
+            entries = candidates.into_iter().map(|s| s.1).collect();
+        }
+
+        if layer > self.top_layer {
+            self.entry = Some(node);
+            self.top_layer = layer;
+        }
+    }
+
+    /// Up to `k` keys most similar to `query`, best first, with their cosine
+    /// similarity. `ef` (at least `k`) trades speed for recall.
+    pub fn search(&self, query: &[f32], k: usize, ef: usize) -> Vec<(&str, f32)> {
+        let Some(mut entry) = self.entry else {
+            return Vec::new();
+        };
+        let query = normalize(query);
+        for l in (1..=self.top_layer).rev() {
+            entry = self.search_layer(&query, &[entry], 1, l)[0].1;
+        }
+        self.search_layer(&query, &[entry], ef.max(k), 0)
+            .into_iter()
+            .take(k)
+            .map(|Scored(score, node)| (self.keys[node].as_str(), score))
+            .collect()
+    }
+
+    /// Best-first search of one layer from `entries`, keeping the `ef` most
+    /// similar nodes seen; sorted best first
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entries: &[usize],
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Scored> {
+        let mut visited: HashSet<usize> = entries.iter().copied().collect();
+        let mut candidates: BinaryHeap<Scored> = BinaryHeap::new();
+        let mut found: BinaryHeap<Reverse<Scored>> = BinaryHeap::new();
+        for &node in entries {
+            let scored = Scored(dot(query, &self.vectors[node]), node);
+            candidates.push(scored);
+            found.push(Reverse(scored));
+        }
+        while found.len() > ef {
+            found.pop();
+        }
+
+        while let Some(current) = candidates.pop() {
+            let worst = found.peek().map_or(f32::MIN, |w| w.0 .0);
+            if current.0 < worst && found.len() >= ef {
+                break;
+            }
+            for &neighbor in &self.links[current.1][layer] {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                let scored = Scored(dot(query, &self.vectors[neighbor]), neighbor);
+                let worst = found.peek().map_or(f32::MIN, |w| w.0 .0);
+                if found.len() < ef || scored.0 > worst {
+                    candidates.push(scored);
+                    found.push(Reverse(scored));
+                    if found.len() > ef {
+                        found.pop();
+                    }
+                }
+            }
+        }
+
+        let mut found: Vec<Scored> = found.into_iter().map(|r| r.0).collect();
+        found.sort_by(|a, b| b.cmp(a));
+        found
+    }
+
+    /// Keep only `node`'s most similar neighbors on `layer` once it has too many
+    fn prune(&mut self, node: usize, layer: usize) {
+        let cap = if layer == 0 { M0 } else { M };
+        if self.links[node][layer].len() <= cap {
+            return;
+        }
+        let vector = &self.vectors[node];
+        let mut scored: Vec<Scored> = self.links[node][layer]
+            .iter()
+            .map(|&n| Scored(dot(vector, &self.vectors[n]), n))
+            .collect();
+        scored.sort_by(|a, b| b.cmp(a));
+        self.links[node][layer] = scored.into_iter().take(cap).map(|s| s.1).collect();
+    }
+
+    /// Layer drawn from an exponential distribution, so each layer holds
+    /// about 1/M of the nodes of the one below
+    fn random_layer(&mut self) -> usize {
+        self.rng = self
+            .rng
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let uniform = ((self.rng >> 11) as f64 + 1.0) / (1u64 << 53) as f64;
+        (-uniform.ln() / (M as f64).ln()) as usize
+    }
+}
+
+impl Default for HnswGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn normalize(vector: &[f32]) -> Vec<f32> {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return vector.to_vec();
+    }
+    vector.iter().map(|v| v / norm).collect()
+}
+
+/// Cosine similarity of normalized vectors; 0.0 for mismatched lengths, as
+/// with `cosine_similarity`
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::store::cosine_similarity;
+
+    #[test]
+    fn test_search_finds_exact_match_first() {
+        let vectors = [vec![1.0, 0.0], vec![0.0, 1.0], vec![0.7, 0.7]];
+        let keys = ["x", "y", "xy"];
+        let graph = HnswGraph::build(keys.iter().copied().zip(vectors.iter().map(Vec::as_slice)));
+
+        let results = graph.search(&[0.0, 2.0], 2, 10);
+        assert_eq!(results[0].0, "y");
+        assert!((results[0].1 - 1.0).abs() < 1e-6);
+        assert_eq!(results[1].0, "xy");
+        assert!((results[1].1 - cosine_similarity(&[0.0, 2.0], &[0.7, 0.7])).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_empty_graph_returns_nothing() {
+        assert!(HnswGraph::new().search(&[1.0], 5, 10).is_empty());
+    }
+}
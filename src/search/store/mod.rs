@@ -1,25 +1,165 @@
+mod format;
 mod gob;
+mod hnsw;
+mod quantize;
+mod remote;
+mod sqlite;
+mod top_k;
 mod types;
 
+pub use format::{detect_index_format, IndexFormat};
 pub use gob::GobStore;
-pub use types::{Chunk, ChunkType, Document, IndexStats, SearchResult};
+pub use remote::{serve_store_http, RemoteStore, StoreRequest, STORE_TOKEN_ENV};
+pub use sqlite::SqliteStore;
+pub use top_k::TopK;
+pub use types::{Chunk, ChunkType, Document, IndexMeta, IndexStats, SearchFilter, SearchResult};
 
 use anyhow::Result;
+
+use crate::config::SearchConfig;
 use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
+/// Storage for embedded chunks and per-file documents.
+///
+/// Chunks and documents carry an optional collection id so one store can hold
+/// several logically separate indexes. Methods taking `collection` only see that
+/// collection; `None` is the default global collection.
 #[async_trait]
 pub trait VectorStore: Send + Sync {
+    /// Insert chunks, replacing stored chunks with the same id and collection
     async fn save_chunks(&self, chunks: Vec<Chunk>) -> Result<()>;
-    async fn delete_by_file(&self, file_path: &str) -> Result<()>;
-    async fn search(&self, query_vector: &[f32], limit: usize) -> Result<Vec<SearchResult>>;
-    async fn get_document(&self, file_path: &str) -> Result<Option<Document>>;
+    async fn delete_by_file(&self, file_path: &str, collection: Option<&str>) -> Result<()>;
+    /// Remove chunks by id; their files' other chunks and documents are kept
+    async fn delete_chunks(&self, ids: &[String], collection: Option<&str>) -> Result<()>;
+    /// Stored chunks of one file, vectors included
+    async fn get_chunks_by_file(
+        &self,
+        file_path: &str,
+        collection: Option<&str>,
+    ) -> Result<Vec<Chunk>>;
+    async fn search(
+        &self,
+        query_vector: &[f32],
+        limit: usize,
+        collection: Option<&str>,
+    ) -> Result<Vec<SearchResult>>;
+    /// Like `search`, but chunks rejected by `filter` are never scored
+    async fn search_filtered(
+        &self,
+        query_vector: &[f32],
+        limit: usize,
+        filter: SearchFilter,
+    ) -> Result<Vec<SearchResult>>;
+    async fn get_document(
+        &self,
+        file_path: &str,
+        collection: Option<&str>,
+    ) -> Result<Option<Document>>;
     async fn save_document(&self, doc: Document) -> Result<()>;
-    async fn list_documents(&self) -> Result<Vec<String>>;
-    async fn get_all_chunks(&self) -> Result<Vec<Chunk>>;
+    async fn list_documents(&self, collection: Option<&str>) -> Result<Vec<String>>;
+    async fn get_meta(&self) -> Result<IndexMeta>;
+    async fn save_meta(&self, meta: IndexMeta) -> Result<()>;
+    async fn get_all_chunks(&self, collection: Option<&str>) -> Result<Vec<Chunk>>;
     async fn persist(&self) -> Result<()>;
     async fn load(&self) -> Result<()>;
     async fn stats(&self) -> Result<IndexStats>;
     async fn clear(&self) -> Result<()>;
+    /// When the backing index last changed, if the store can tell
+    async fn last_modified(&self) -> Result<Option<SystemTime>>;
+}
+
+/// `GobStore` index file, and its name once gzipped (`compress_index`)
+const JSON_INDEX_FILE: &str = "index.json";
+const COMPRESSED_JSON_INDEX_FILE: &str = "index.json.gz";
+
+/// Index file for `backend` ("gob" or "sqlite") inside the output directory.
+/// A JSON index is `index.json.gz`, or `index.json` if only an uncompressed
+/// one has been written.
+pub fn index_path(output_dir: &Path, backend: &str) -> PathBuf {
+    match backend {
+        "sqlite" => output_dir.join("index.db"),
+        _ => {
+            let plain = output_dir.join(JSON_INDEX_FILE);
+            let compressed = output_dir.join(COMPRESSED_JSON_INDEX_FILE);
+            if plain.exists() && !compressed.exists() {
+                plain
+            } else {
+                compressed
+            }
+        }
+    }
+}
+
+/// File a JSON index is written to: `index.json.gz` when compressing or when
+/// the index already is compressed, else `index.json`. An uncompressed
+/// `index.json` about to be compressed is renamed first, so its chunks carry
+/// over; it loads under the new name until the next write gzips it.
+fn json_index_path_for_writing(output_dir: &Path, compress: bool) -> Result<PathBuf> {
+    let plain = output_dir.join(JSON_INDEX_FILE);
+    let compressed = output_dir.join(COMPRESSED_JSON_INDEX_FILE);
+    if compressed.exists() {
+        return Ok(compressed);
+    }
+    if !compress {
+        return Ok(plain);
+    }
+    if plain.exists() {
+        std::fs::rename(&plain, &compressed)?;
+    }
+    Ok(compressed)
+}
+
+/// Open the index in `output_dir` with `backend` ("gob" or "sqlite").
+/// Unknown backends fall back to the JSON-file `GobStore`.
+pub fn create_store(output_dir: &Path, backend: &str) -> Result<Box<dyn VectorStore>> {
+    let path = index_path(output_dir, backend);
+    Ok(match backend {
+        "sqlite" => Box::new(SqliteStore::open(path)?),
+        _ => Box::new(GobStore::new(path)),
+    })
+}
+
+/// Like `create_store`, for commands that build the index: the JSON store
+/// also takes `config`'s on-disk options (quantization and compression)
+pub fn create_store_for_writing(
+    output_dir: &Path,
+    config: &SearchConfig,
+) -> Result<Box<dyn VectorStore>> {
+    Ok(match config.store_backend.as_str() {
+        "sqlite" => Box::new(SqliteStore::open(index_path(output_dir, "sqlite"))?),
+        _ => Box::new(
+            GobStore::new(json_index_path_for_writing(
+                output_dir,
+                config.compress_index,
+            )?)
+            .with_quantization(config.quantize)
+            .with_compression(config.compress_index),
+        ),
+    })
+}
+
+/// Open an existing index file with the store matching its contents,
+/// whatever the file is named
+pub fn open_index_file(path: &Path) -> Result<Box<dyn VectorStore>> {
+    match detect_index_format(path)? {
+        Some(IndexFormat::Json) => Ok(Box::new(GobStore::new(path.to_path_buf()))),
+        Some(IndexFormat::Sqlite) => Ok(Box::new(SqliteStore::open(path.to_path_buf())?)),
+        None => anyhow::bail!(
+            "{} is not a search index (expected JSON or SQLite)",
+            path.display()
+        ),
+    }
+}
+
+/// Map key for a chunk id or document path. Global entries keep the bare key
+/// so indexes written before collections existed load unchanged.
+fn storage_key(collection: Option<&str>, key: &str) -> String {
+    match collection {
+        Some(collection) => format!("{}\u{1f}{}", collection, key),
+        None => key.to_string(),
+    }
 }
 
 pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
@@ -45,6 +185,58 @@ pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     }
 }
 
+/// Lanes of the dot product loop; fixed-size accumulators let the compiler
+/// keep them in SIMD registers
+const LANES: usize = 8;
+
+/// `cosine_similarity` of `query` against each `(vector, norm)` pair, in
+/// order, where `norm` is the vector's known L2 norm (e.g. stored at index
+/// time). The query norm is computed once per batch.
+pub fn cosine_similarity_batch<V: AsRef<[f32]>>(query: &[f32], vectors: &[(V, f32)]) -> Vec<f32> {
+    let query_norm = vector_norm(query);
+    vectors
+        .iter()
+        .map(|(vector, norm)| cosine_similarity_normed(query, query_norm, vector.as_ref(), *norm))
+        .collect()
+}
+
+/// L2 norm of `vector`
+pub fn vector_norm(vector: &[f32]) -> f32 {
+    dot(vector, vector).sqrt()
+}
+
+/// Cosine similarity when both norms are already known, e.g. the query's
+/// computed once per search and a chunk's stored at index time
+pub fn cosine_similarity_normed(a: &[f32], a_norm: f32, b: &[f32], b_norm: f32) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let denom = a_norm * b_norm;
+    if denom == 0.0 {
+        0.0
+    } else {
+        dot(a, b) / denom
+    }
+}
+
+/// `a · b` for equal-length slices, `LANES` elements at a time
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    let mut sums = [0.0f32; LANES];
+    let (a_lanes, b_lanes) = (a.chunks_exact(LANES), b.chunks_exact(LANES));
+    let (a_tail, b_tail) = (a_lanes.remainder(), b_lanes.remainder());
+    for (x, y) in a_lanes.zip(b_lanes) {
+        for i in 0..LANES {
+            sums[i] += x[i] * y[i];
+        }
+    }
+
+    let mut sum: f32 = sums.iter().sum();
+    for (x, y) in a_tail.iter().zip(b_tail) {
+        sum += x * y;
+    }
+    sum
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -70,10 +262,90 @@ mod tests {
         assert!((cosine_similarity(&a, &b) - (-1.0)).abs() < 0.001);
     }
 
+    #[test]
+    fn test_cosine_similarity_batch_matches_scalar() {
+        let query: Vec<f32> = (0..19).map(|i| (i as f32 * 0.3).sin()).collect();
+        let vectors: Vec<Vec<f32>> = vec![
+            (0..19).map(|i| (i as f32 * 0.7).cos()).collect(),
+            query.iter().map(|x| x * -2.0).collect(),
+            vec![0.0; 19],
+            vec![1.0; 3],
+        ];
+        let normed: Vec<(&[f32], f32)> = vectors
+            .iter()
+            .map(|v| (v.as_slice(), vector_norm(v)))
+            .collect();
+
+        let batch = cosine_similarity_batch(&query, &normed);
+
+        assert_eq!(batch.len(), 4);
+        for (score, vector) in batch.iter().zip(&vectors) {
+            assert!((score - cosine_similarity(&query, vector)).abs() < 1e-5);
+        }
+        assert!((batch[1] + 1.0).abs() < 1e-5);
+        assert!(cosine_similarity_batch(&[], &[(Vec::<f32>::new(), 0.0)])[0] == 0.0);
+    }
+
+    #[test]
+    fn test_stored_norm_matches_computed_similarity() {
+        let query: Vec<f32> = (0..12).map(|i| i as f32 - 4.0).collect();
+        let vector: Vec<f32> = (0..12).map(|i| (i as f32).sqrt()).collect();
+
+        let normed =
+            cosine_similarity_normed(&query, vector_norm(&query), &vector, vector_norm(&vector));
+
+        assert!((normed - cosine_similarity(&query, &vector)).abs() < 1e-6);
+        assert_eq!(
+            cosine_similarity_normed(&query, 1.0, &vector[..3], 1.0),
+            0.0
+        );
+    }
+
     #[test]
     fn test_cosine_similarity_empty() {
         let a: Vec<f32> = vec![];
         let b: Vec<f32> = vec![];
         assert_eq!(cosine_similarity(&a, &b), 0.0);
     }
+
+    #[tokio::test]
+    async fn test_compressed_index_is_written_as_index_json_gz() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dir = temp.path();
+        let legacy = GobStore::new(dir.join("index.json"));
+        legacy
+            .save_chunks(vec![crate::test_support::make_chunk(
+                "src/lib.rs:block:1",
+                "src/lib.rs",
+                vec![1.0, 0.0],
+            )])
+            .await
+            .unwrap();
+        legacy.persist().await.unwrap();
+        assert_eq!(index_path(dir, "gob"), dir.join("index.json"));
+
+        // compress_index (the default) moves the old index over, then gzips it
+        let store = create_store_for_writing(dir, &SearchConfig::default()).unwrap();
+        store.load().await.unwrap();
+        assert_eq!(store.get_all_chunks(None).await.unwrap().len(), 1);
+        store.persist().await.unwrap();
+
+        assert!(!dir.join("index.json").exists());
+        let written = std::fs::read(dir.join("index.json.gz")).unwrap();
+        assert!(written.starts_with(b"\x1f\x8b"));
+        assert_eq!(index_path(dir, "gob"), dir.join("index.json.gz"));
+
+        let uncompressed = SearchConfig {
+            compress_index: false,
+            ..SearchConfig::default()
+        };
+        let fresh = tempfile::TempDir::new().unwrap();
+        let store = create_store_for_writing(fresh.path(), &uncompressed).unwrap();
+        store.persist().await.unwrap();
+        assert!(fresh.path().join("index.json").exists());
+        assert_eq!(
+            index_path(fresh.path(), "gob"),
+            fresh.path().join("index.json")
+        );
+    }
 }
@@ -0,0 +1,101 @@
+//! Int8 scalar quantization of chunk vectors for the JSON index: each vector
+//! is stored as `i8` steps of a per-vector scale, roughly a quarter of the
+//! size of full-precision floats, and reconstructed to `f32` when read.
+
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::HashMap;
+
+use super::{vector_norm, Chunk};
+
+/// A chunk as persisted in a quantized index; `chunk.vector` is left empty
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuantizedChunk {
+    #[serde(flatten)]
+    pub chunk: Chunk,
+    /// Value of one quantization step
+    pub scale: f32,
+    pub quantized: Vec<i8>,
+}
+
+impl QuantizedChunk {
+    pub fn from_chunk(chunk: &Chunk) -> Self {
+        let (quantized, scale) = quantize(&chunk.vector);
+        Self {
+            chunk: Chunk {
+                vector: Vec::new(),
+                norm: None,
+                ..chunk.clone()
+            },
+            scale,
+            quantized,
+        }
+    }
+
+    /// The chunk with its vector reconstructed to `f32`, and that vector's norm
+    pub fn into_chunk(self) -> Chunk {
+        let vector = dequantize(&self.quantized, self.scale);
+        Chunk {
+            norm: Some(vector_norm(&vector)),
+            vector,
+            ..self.chunk
+        }
+    }
+}
+
+/// `vector` as `i8` steps of the returned scale, which maps the largest
+/// magnitude to 127
+pub fn quantize(vector: &[f32]) -> (Vec<i8>, f32) {
+    let max = vector.iter().fold(0.0f32, |max, v| max.max(v.abs()));
+    if max == 0.0 {
+        return (vec![0; vector.len()], 0.0);
+    }
+
+    let scale = max / 127.0;
+    let quantized = vector
+        .iter()
+        .map(|v| (v / scale).round().clamp(-127.0, 127.0) as i8)
+        .collect();
+    (quantized, scale)
+}
+
+pub fn dequantize(quantized: &[i8], scale: f32) -> Vec<f32> {
+    quantized.iter().map(|&q| q as f32 * scale).collect()
+}
+
+/// Serializes a chunk map as `QuantizedChunk`s, converting one entry at a time
+pub struct QuantizedChunks<'a>(pub &'a HashMap<String, Chunk>);
+
+impl Serialize for QuantizedChunks<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            self.0
+                .iter()
+                .map(|(key, chunk)| (key, QuantizedChunk::from_chunk(chunk))),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantize_round_trip_error_is_within_half_a_step() {
+        let vector = vec![0.5, -0.25, 0.0, 0.125, -0.499, 0.0031];
+
+        let (quantized, scale) = quantize(&vector);
+        assert_eq!(quantized[0], 127);
+        assert_eq!(quantized[2], 0);
+
+        let restored = dequantize(&quantized, scale);
+        for (original, restored) in vector.iter().zip(&restored) {
+            assert!((original - restored).abs() <= scale / 2.0 + f32::EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_zero_vector_stays_zero() {
+        let (quantized, scale) = quantize(&[0.0, 0.0]);
+        assert_eq!(dequantize(&quantized, scale), vec![0.0, 0.0]);
+    }
+}
@@ -0,0 +1,554 @@
+//! A `VectorStore` shared over HTTP, so a team can query one central index.
+//!
+//! Wire protocol: every operation is `POST /store` with a JSON body naming the
+//! trait method in `op` plus its arguments, e.g.
+//! `{"op": "search", "query_vector": [0.1, 0.2], "limit": 10, "collection": null}`.
+//! Success is `200` with `{"result": <return value>}`; failure is a 4xx/5xx
+//! status with `{"error": "<message>"}`. `GET /health` answers `{"status": "ok"}`.
+//! A server started with a token answers write calls without
+//! `Authorization: Bearer <token>` with `401`.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::net::TcpListener;
+
+use super::{Chunk, Document, IndexMeta, IndexStats, SearchFilter, SearchResult, VectorStore};
+use crate::search::http::{serve_http, Request, Response};
+
+/// Environment variable holding the token `serve --store` requires for write
+/// calls, which clients then send
+pub const STORE_TOKEN_ENV: &str = "AGENTLENS_STORE_TOKEN";
+
+/// Request bodies larger than this are rejected; `save_chunks` batches are the biggest
+const MAX_REQUEST_BODY: usize = 256 * 1024 * 1024;
+
+/// One `VectorStore` call on the wire
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum StoreRequest {
+    SaveChunks {
+        chunks: Vec<Chunk>,
+    },
+    DeleteByFile {
+        file_path: String,
+        collection: Option<String>,
+    },
+    DeleteChunks {
+        ids: Vec<String>,
+        collection: Option<String>,
+    },
+    GetChunksByFile {
+        file_path: String,
+        collection: Option<String>,
+    },
+    Search {
+        query_vector: Vec<f32>,
+        limit: usize,
+        collection: Option<String>,
+    },
+    SearchFiltered {
+        query_vector: Vec<f32>,
+        limit: usize,
+        filter: SearchFilter,
+    },
+    GetDocument {
+        file_path: String,
+        collection: Option<String>,
+    },
+    SaveDocument {
+        doc: Document,
+    },
+    ListDocuments {
+        collection: Option<String>,
+    },
+    GetMeta,
+    SaveMeta {
+        meta: IndexMeta,
+    },
+    GetAllChunks {
+        collection: Option<String>,
+    },
+    Persist,
+    Load,
+    Stats,
+    Clear,
+    LastModified,
+}
+
+impl StoreRequest {
+    /// Whether the call changes the store (or drops unsaved changes, like
+    /// `load`), so needs the server's token
+    pub fn is_write(&self) -> bool {
+        matches!(
+            self,
+            StoreRequest::SaveChunks { .. }
+                | StoreRequest::DeleteByFile { .. }
+                | StoreRequest::DeleteChunks { .. }
+                | StoreRequest::SaveDocument { .. }
+                | StoreRequest::SaveMeta { .. }
+                | StoreRequest::Persist
+                | StoreRequest::Load
+                | StoreRequest::Clear
+        )
+    }
+
+    /// Run the call against `store`, returning its result as JSON
+    pub async fn dispatch(self, store: &dyn VectorStore) -> Result<Value> {
+        Ok(match self {
+            StoreRequest::SaveChunks { chunks } => json!(store.save_chunks(chunks).await?),
+            StoreRequest::DeleteByFile {
+                file_path,
+                collection,
+            } => json!(
+                store
+                    .delete_by_file(&file_path, collection.as_deref())
+                    .await?
+            ),
+            StoreRequest::DeleteChunks { ids, collection } => {
+                json!(store.delete_chunks(&ids, collection.as_deref()).await?)
+            }
+            StoreRequest::GetChunksByFile {
+                file_path,
+                collection,
+            } => json!(
+                store
+                    .get_chunks_by_file(&file_path, collection.as_deref())
+                    .await?
+            ),
+            StoreRequest::Search {
+                query_vector,
+                limit,
+                collection,
+            } => json!(
+                store
+                    .search(&query_vector, limit, collection.as_deref())
+                    .await?
+            ),
+            StoreRequest::SearchFiltered {
+                query_vector,
+                limit,
+                filter,
+            } => json!(store.search_filtered(&query_vector, limit, filter).await?),
+            StoreRequest::GetDocument {
+                file_path,
+                collection,
+            } => json!(
+                store
+                    .get_document(&file_path, collection.as_deref())
+                    .await?
+            ),
+            StoreRequest::SaveDocument { doc } => json!(store.save_document(doc).await?),
+            StoreRequest::ListDocuments { collection } => {
+                json!(store.list_documents(collection.as_deref()).await?)
+            }
+            StoreRequest::GetMeta => json!(store.get_meta().await?),
+            StoreRequest::SaveMeta { meta } => json!(store.save_meta(meta).await?),
+            StoreRequest::GetAllChunks { collection } => {
+                json!(store.get_all_chunks(collection.as_deref()).await?)
+            }
+            StoreRequest::Persist => json!(store.persist().await?),
+            StoreRequest::Load => json!(store.load().await?),
+            StoreRequest::Stats => json!(store.stats().await?),
+            StoreRequest::Clear => json!(store.clear().await?),
+            StoreRequest::LastModified => json!(store.last_modified().await?),
+        })
+    }
+}
+
+/// Client for a store served by `serve_store_http`
+pub struct RemoteStore {
+    endpoint: String,
+    client: Client,
+    token: Option<String>,
+}
+
+impl RemoteStore {
+    /// `url` is the server's base URL, e.g. `http://index.internal:7879`
+    pub fn new(url: &str) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(120))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            endpoint: url.trim_end_matches('/').to_string(),
+            client,
+            token: None,
+        }
+    }
+
+    /// Send `token` with every call, for servers that require it for writes
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    async fn call<T: DeserializeOwned>(&self, request: StoreRequest) -> Result<T> {
+        let mut builder = self.client.post(format!("{}/store", self.endpoint));
+        if let Some(token) = &self.token {
+            builder = builder.bearer_auth(token);
+        }
+        let response = builder.json(&request).send().await.map_err(|e| {
+            if e.is_connect() {
+                anyhow!("Cannot connect to remote index at {}", self.endpoint)
+            } else {
+                anyhow!("Remote index request failed: {}", e)
+            }
+        })?;
+
+        let status = response.status();
+        let body: Value = response.json().await.map_err(|e| {
+            anyhow!(
+                "Remote index at {} sent an invalid response ({}): {}",
+                self.endpoint,
+                status,
+                e
+            )
+        })?;
+
+        if !status.is_success() {
+            let message = body["error"].as_str().unwrap_or("unknown error");
+            return Err(anyhow!("Remote index error ({}): {}", status, message));
+        }
+
+        Ok(serde_json::from_value(body["result"].clone())?)
+    }
+}
+
+#[async_trait]
+impl VectorStore for RemoteStore {
+    async fn save_chunks(&self, chunks: Vec<Chunk>) -> Result<()> {
+        self.call(StoreRequest::SaveChunks { chunks }).await
+    }
+
+    async fn delete_by_file(&self, file_path: &str, collection: Option<&str>) -> Result<()> {
+        self.call(StoreRequest::DeleteByFile {
+            file_path: file_path.to_string(),
+            collection: collection.map(str::to_string),
+        })
+        .await
+    }
+
+    async fn delete_chunks(&self, ids: &[String], collection: Option<&str>) -> Result<()> {
+        self.call(StoreRequest::DeleteChunks {
+            ids: ids.to_vec(),
+            collection: collection.map(str::to_string),
+        })
+        .await
+    }
+
+    async fn get_chunks_by_file(
+        &self,
+        file_path: &str,
+        collection: Option<&str>,
+    ) -> Result<Vec<Chunk>> {
+        self.call(StoreRequest::GetChunksByFile {
+            file_path: file_path.to_string(),
+            collection: collection.map(str::to_string),
+        })
+        .await
+    }
+
+    async fn search(
+        &self,
+        query_vector: &[f32],
+        limit: usize,
+        collection: Option<&str>,
+    ) -> Result<Vec<SearchResult>> {
+        self.call(StoreRequest::Search {
+            query_vector: query_vector.to_vec(),
+            limit,
+            collection: collection.map(str::to_string),
+        })
+        .await
+    }
+
+    async fn search_filtered(
+        &self,
+        query_vector: &[f32],
+        limit: usize,
+        filter: SearchFilter,
+    ) -> Result<Vec<SearchResult>> {
+        self.call(StoreRequest::SearchFiltered {
+            query_vector: query_vector.to_vec(),
+            limit,
+            filter,
+        })
+        .await
+    }
+
+    async fn get_document(
+        &self,
+        file_path: &str,
+        collection: Option<&str>,
+    ) -> Result<Option<Document>> {
+        self.call(StoreRequest::GetDocument {
+            file_path: file_path.to_string(),
+            collection: collection.map(str::to_string),
+        })
+        .await
+    }
+
+    async fn save_document(&self, doc: Document) -> Result<()> {
+        self.call(StoreRequest::SaveDocument { doc }).await
+    }
+
+    async fn list_documents(&self, collection: Option<&str>) -> Result<Vec<String>> {
+        self.call(StoreRequest::ListDocuments {
+            collection: collection.map(str::to_string),
+        })
+        .await
+    }
+
+    async fn get_meta(&self) -> Result<IndexMeta> {
+        self.call(StoreRequest::GetMeta).await
+    }
+
+    async fn save_meta(&self, meta: IndexMeta) -> Result<()> {
+        self.call(StoreRequest::SaveMeta { meta }).await
+    }
+
+    async fn get_all_chunks(&self, collection: Option<&str>) -> Result<Vec<Chunk>> {
+        self.call(StoreRequest::GetAllChunks {
+            collection: collection.map(str::to_string),
+        })
+        .await
+    }
+
+    async fn persist(&self) -> Result<()> {
+        self.call(StoreRequest::Persist).await
+    }
+
+    async fn load(&self) -> Result<()> {
+        self.call(StoreRequest::Load).await
+    }
+
+    async fn stats(&self) -> Result<IndexStats> {
+        self.call(StoreRequest::Stats).await
+    }
+
+    async fn clear(&self) -> Result<()> {
+        self.call(StoreRequest::Clear).await
+    }
+
+    async fn last_modified(&self) -> Result<Option<SystemTime>> {
+        self.call(StoreRequest::LastModified).await
+    }
+}
+
+/// Serve `store` to `RemoteStore` clients on `listener` until `shutdown`
+/// resolves. With a `token`, write calls must send it as a bearer token.
+/// In-flight requests finish before this returns.
+pub async fn serve_store_http(
+    listener: TcpListener,
+    store: Arc<dyn VectorStore>,
+    token: Option<String>,
+    shutdown: impl Future<Output = ()>,
+) -> Result<()> {
+    let server = Arc::new(StoreServer { store, token });
+    serve_http(
+        listener,
+        server,
+        MAX_REQUEST_BODY,
+        |server, request| async move { server.route(&request).await },
+        shutdown,
+    )
+    .await
+}
+
+struct StoreServer {
+    store: Arc<dyn VectorStore>,
+    token: Option<String>,
+}
+
+impl StoreServer {
+    async fn route(&self, request: &Request) -> Response {
+        match (request.method.as_str(), request.target.as_str()) {
+            ("GET", "/health") => ("200 OK", json!({ "status": "ok" })),
+            ("POST", "/store") => {
+                let call: StoreRequest = match serde_json::from_slice(&request.body) {
+                    Ok(call) => call,
+                    Err(e) => {
+                        return (
+                            "400 Bad Request",
+                            json!({ "error": format!("invalid store request: {}", e) }),
+                        )
+                    }
+                };
+                if call.is_write() && !self.authorized(request) {
+                    return (
+                        "401 Unauthorized",
+                        json!({ "error": format!("writes need the server's token ({})", STORE_TOKEN_ENV) }),
+                    );
+                }
+                match call.dispatch(self.store.as_ref()).await {
+                    Ok(result) => ("200 OK", json!({ "result": result })),
+                    Err(e) => (
+                        "500 Internal Server Error",
+                        json!({ "error": e.to_string() }),
+                    ),
+                }
+            }
+            _ => ("404 Not Found", json!({ "error": "not found" })),
+        }
+    }
+
+    fn authorized(&self, request: &Request) -> bool {
+        let Some(token) = &self.token else {
+            return true;
+        };
+        request
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            == Some(token.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::store::GobStore;
+    use crate::test_support::{make_chunk, read_request, write_json_response};
+    use tempfile::TempDir;
+
+    fn file_chunk(file_path: &str, vector: Vec<f32>) -> Chunk {
+        Chunk {
+            content: format!("// {}", file_path),
+            ..make_chunk(&format!("{}:block:1", file_path), file_path, vector)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_remote_store_round_trips_through_server() {
+        let temp = TempDir::new().unwrap();
+        let backing: Arc<dyn VectorStore> = Arc::new(GobStore::new(temp.path().join("index.json")));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve_store_http(
+            listener,
+            Arc::clone(&backing),
+            None,
+            async {
+                let _ = stopped.await;
+            },
+        ));
+
+        let remote = RemoteStore::new(&format!("http://{}/", addr));
+        remote
+            .save_chunks(vec![
+                file_chunk("src/auth.rs", vec![1.0, 0.0]),
+                file_chunk("src/db.rs", vec![0.0, 1.0]),
+            ])
+            .await
+            .unwrap();
+        remote
+            .save_meta(IndexMeta {
+                model: Some("nomic-embed-text".to_string()),
+                dimensions: None,
+            })
+            .await
+            .unwrap();
+        remote.persist().await.unwrap();
+
+        let results = remote.search(&[0.9, 0.1], 1, None).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].chunk.file_path, "src/auth.rs");
+
+        let filter = SearchFilter {
+            path_prefix: Some("src/db.rs".to_string()),
+            ..SearchFilter::default()
+        };
+        let filtered = remote
+            .search_filtered(&[0.9, 0.1], 5, filter)
+            .await
+            .unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].chunk.file_path, "src/db.rs");
+
+        remote.delete_by_file("src/auth.rs", None).await.unwrap();
+        assert_eq!(remote.get_all_chunks(None).await.unwrap().len(), 1);
+        assert_eq!(
+            remote.get_meta().await.unwrap().model.as_deref(),
+            Some("nomic-embed-text")
+        );
+        assert!(remote.last_modified().await.unwrap().is_some());
+
+        // Writes landed in the server's store
+        assert_eq!(backing.get_all_chunks(None).await.unwrap().len(), 1);
+
+        stop.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_surface_in_client() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            read_request(&mut stream).await;
+            write_json_response(
+                &mut stream,
+                "500 Internal Server Error",
+                r#"{"error":"index is locked"}"#,
+            )
+            .await;
+        });
+
+        let err = RemoteStore::new(&format!("http://{}", addr))
+            .stats()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("index is locked"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_writes_need_the_server_token() {
+        let temp = TempDir::new().unwrap();
+        let backing: Arc<dyn VectorStore> = Arc::new(GobStore::new(temp.path().join("index.json")));
+        backing
+            .save_chunks(vec![file_chunk("src/auth.rs", vec![1.0, 0.0])])
+            .await
+            .unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve_store_http(
+            listener,
+            Arc::clone(&backing),
+            Some("s3cret".to_string()),
+            async {
+                let _ = stopped.await;
+            },
+        ));
+
+        // Reads stay open; clearing without (or with the wrong) token is refused
+        let anonymous = RemoteStore::new(&url);
+        assert_eq!(anonymous.get_all_chunks(None).await.unwrap().len(), 1);
+        let err = anonymous.clear().await.unwrap_err();
+        assert!(err.to_string().contains("401"), "{}", err);
+        let wrong = RemoteStore::new(&url).with_token("guess");
+        assert!(wrong.clear().await.is_err());
+        assert_eq!(backing.get_all_chunks(None).await.unwrap().len(), 1);
+
+        RemoteStore::new(&url)
+            .with_token("s3cret")
+            .clear()
+            .await
+            .unwrap();
+        assert!(backing.get_all_chunks(None).await.unwrap().is_empty());
+
+        stop.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+}
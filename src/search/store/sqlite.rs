@@ -0,0 +1,452 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use std::time::SystemTime;
+
+use super::format::{ensure_format, IndexFormat};
+use super::{
+    cosine_similarity, storage_key, Chunk, ChunkType, Document, IndexMeta, IndexStats,
+    SearchFilter, SearchResult, TopK, VectorStore,
+};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS chunks (
+    key TEXT PRIMARY KEY,
+    file_path TEXT NOT NULL,
+    collection TEXT,
+    updated_at INTEGER NOT NULL,
+    vector BLOB NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS chunks_by_file ON chunks (file_path, collection);
+CREATE TABLE IF NOT EXISTS documents (
+    key TEXT PRIMARY KEY,
+    path TEXT NOT NULL,
+    collection TEXT,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+";
+
+/// Vector store backed by a SQLite database.
+///
+/// Every write is applied to the database as it happens, so saving or deleting
+/// one file's chunks touches only those rows instead of rewriting the whole
+/// index. `persist` and `load` are no-ops. Vectors are stored as little-endian
+/// f32 BLOBs and scored in Rust.
+pub struct SqliteStore {
+    path: PathBuf,
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// Open (or create) the database at `path`
+    pub fn open(path: PathBuf) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        ensure_format(&path, IndexFormat::Sqlite)?;
+        let conn = Connection::open(&path)?;
+        conn.execute_batch(SCHEMA)?;
+
+        Ok(Self {
+            path,
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> Result<MutexGuard<'_, Connection>> {
+        self.conn.lock().map_err(|e| anyhow::anyhow!("{}", e))
+    }
+
+    /// Chunks in `collection`, each with its vector restored from the BLOB column
+    fn for_each_chunk(&self, collection: Option<&str>, visit: &mut dyn FnMut(Chunk)) -> Result<()> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare("SELECT data, vector FROM chunks WHERE collection IS ?1")?;
+        let mut rows = stmt.query(params![collection])?;
+        while let Some(row) = rows.next()? {
+            let mut chunk: Chunk = serde_json::from_str(&row.get::<_, String>(0)?)?;
+            chunk.vector = decode_vector(&row.get::<_, Vec<u8>>(1)?);
+            visit(chunk);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl VectorStore for SqliteStore {
+    async fn save_chunks(&self, chunks: Vec<Chunk>) -> Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO chunks (key, file_path, collection, updated_at, vector, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for mut chunk in chunks {
+                let vector = encode_vector(&std::mem::take(&mut chunk.vector));
+                stmt.execute(params![
+                    storage_key(chunk.collection.as_deref(), &chunk.id),
+                    chunk.file_path,
+                    chunk.collection,
+                    chunk.updated_at.timestamp_millis(),
+                    vector,
+                    serde_json::to_string(&chunk)?,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn delete_by_file(&self, file_path: &str, collection: Option<&str>) -> Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM chunks WHERE file_path = ?1 AND collection IS ?2",
+            params![file_path, collection],
+        )?;
+        tx.execute(
+            "DELETE FROM documents WHERE key = ?1",
+            params![storage_key(collection, file_path)],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn delete_chunks(&self, ids: &[String], collection: Option<&str>) -> Result<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare("DELETE FROM chunks WHERE key = ?1")?;
+            for id in ids {
+                stmt.execute(params![storage_key(collection, id)])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn get_chunks_by_file(
+        &self,
+        file_path: &str,
+        collection: Option<&str>,
+    ) -> Result<Vec<Chunk>> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare("SELECT data, vector FROM chunks WHERE file_path = ?1 AND collection IS ?2")?;
+        let mut rows = stmt.query(params![file_path, collection])?;
+        let mut chunks = Vec::new();
+        while let Some(row) = rows.next()? {
+            let mut chunk: Chunk = serde_json::from_str(&row.get::<_, String>(0)?)?;
+            chunk.vector = decode_vector(&row.get::<_, Vec<u8>>(1)?);
+            chunks.push(chunk);
+        }
+        Ok(chunks)
+    }
+
+    async fn search(
+        &self,
+        query_vector: &[f32],
+        limit: usize,
+        collection: Option<&str>,
+    ) -> Result<Vec<SearchResult>> {
+        self.search_filtered(
+            query_vector,
+            limit,
+            SearchFilter::for_collection(collection),
+        )
+        .await
+    }
+
+    async fn search_filtered(
+        &self,
+        query_vector: &[f32],
+        limit: usize,
+        filter: SearchFilter,
+    ) -> Result<Vec<SearchResult>> {
+        let mut top = TopK::new(limit);
+        self.for_each_chunk(filter.collection.as_deref(), &mut |chunk| {
+            if !filter.matches(&chunk) {
+                return;
+            }
+            let score = cosine_similarity(query_vector, &chunk.vector);
+            top.offer(score, || chunk);
+        })?;
+        Ok(top.into_sorted_vec())
+    }
+
+    async fn get_document(
+        &self,
+        file_path: &str,
+        collection: Option<&str>,
+    ) -> Result<Option<Document>> {
+        let conn = self.conn()?;
+        let data: Option<String> = conn
+            .query_row(
+                "SELECT data FROM documents WHERE key = ?1",
+                params![storage_key(collection, file_path)],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(data.map(|d| serde_json::from_str(&d)).transpose()?)
+    }
+
+    async fn save_document(&self, doc: Document) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO documents (key, path, collection, data) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                storage_key(doc.collection.as_deref(), &doc.path),
+                doc.path,
+                doc.collection,
+                serde_json::to_string(&doc)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    async fn list_documents(&self, collection: Option<&str>) -> Result<Vec<String>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare("SELECT path FROM documents WHERE collection IS ?1")?;
+        let paths = stmt
+            .query_map(params![collection], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(paths)
+    }
+
+    async fn get_meta(&self) -> Result<IndexMeta> {
+        let conn = self.conn()?;
+        let value: Option<String> = conn
+            .query_row("SELECT value FROM meta WHERE key = 'meta'", [], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        Ok(value
+            .map(|v| serde_json::from_str(&v))
+            .transpose()?
+            .unwrap_or_default())
+    }
+
+    async fn save_meta(&self, meta: IndexMeta) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES ('meta', ?1)",
+            params![serde_json::to_string(&meta)?],
+        )?;
+        Ok(())
+    }
+
+    async fn get_all_chunks(&self, collection: Option<&str>) -> Result<Vec<Chunk>> {
+        let mut chunks = Vec::new();
+        self.for_each_chunk(collection, &mut |chunk| chunks.push(chunk))?;
+        Ok(chunks)
+    }
+
+    async fn persist(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn stats(&self) -> Result<IndexStats> {
+        let conn = self.conn()?;
+        let total_files: usize =
+            conn.query_row("SELECT COUNT(*) FROM documents", [], |row| row.get(0))?;
+        let (total_chunks, newest): (usize, Option<i64>) =
+            conn.query_row("SELECT COUNT(*), MAX(updated_at) FROM chunks", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?;
+
+        let mut stmt = conn.prepare(
+            "SELECT collection, file_path, json_extract(data, '$.chunk_type'),
+                    json_extract(data, '$.symbol_kind') IS NOT NULL
+             FROM chunks",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    (row.get::<_, Option<String>>(0)?, row.get::<_, String>(1)?),
+                    row.get::<_, String>(2)?,
+                    row.get::<_, bool>(3)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let mut breakdown = Vec::with_capacity(rows.len());
+        for (file, chunk_type, has_symbol) in rows {
+            let chunk_type: ChunkType =
+                serde_json::from_value(serde_json::Value::String(chunk_type))?;
+            breakdown.push((file, chunk_type, has_symbol));
+        }
+
+        Ok(IndexStats {
+            total_files,
+            total_chunks,
+            index_size_bytes: fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0),
+            last_updated: newest.and_then(DateTime::<Utc>::from_timestamp_millis),
+            ..Default::default()
+        }
+        .with_chunk_breakdown(breakdown))
+    }
+
+    async fn clear(&self) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute_batch("DELETE FROM chunks; DELETE FROM documents; DELETE FROM meta; VACUUM;")?;
+        Ok(())
+    }
+
+    async fn last_modified(&self) -> Result<Option<SystemTime>> {
+        match fs::metadata(&self.path) {
+            Ok(meta) => Ok(meta.modified().ok()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::make_chunk;
+    use tempfile::TempDir;
+
+    fn numbered_chunk(id: usize, file_path: &str, vector: Vec<f32>) -> Chunk {
+        Chunk {
+            content: format!("content {}", id),
+            hash: "abc123".to_string(),
+            ..make_chunk(&format!("chunk{}", id), file_path, vector)
+        }
+    }
+
+    fn make_document(path: &str, chunk_ids: &[&str]) -> Document {
+        Document {
+            path: path.to_string(),
+            hash: "abc123".to_string(),
+            mod_time: Utc::now(),
+            chunk_ids: chunk_ids.iter().map(|id| id.to_string()).collect(),
+            collection: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_writes_survive_reopen_without_persist() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("index.db");
+
+        let store = SqliteStore::open(path.clone()).unwrap();
+        store
+            .save_chunks(vec![
+                numbered_chunk(0, "a.rs", vec![1.0, 0.0]),
+                numbered_chunk(1, "b.rs", vec![0.0, 1.0]),
+            ])
+            .await
+            .unwrap();
+        store
+            .save_document(make_document("a.rs", &["chunk0"]))
+            .await
+            .unwrap();
+        store
+            .save_meta(IndexMeta {
+                model: Some("nomic-embed-text".to_string()),
+                dimensions: None,
+            })
+            .await
+            .unwrap();
+        drop(store);
+
+        let reopened = SqliteStore::open(path).unwrap();
+        let results = reopened.search(&[1.0, 0.0], 1, None).await.unwrap();
+        assert_eq!(results[0].chunk.id, "chunk0");
+        assert_eq!(results[0].chunk.vector, vec![1.0, 0.0]);
+        assert!((results[0].score - 1.0).abs() < 0.001);
+
+        let doc = reopened.get_document("a.rs", None).await.unwrap().unwrap();
+        assert_eq!(doc.chunk_ids, vec!["chunk0".to_string()]);
+        assert_eq!(
+            reopened.get_meta().await.unwrap().model.as_deref(),
+            Some("nomic-embed-text")
+        );
+
+        let stats = reopened.stats().await.unwrap();
+        assert_eq!((stats.total_files, stats.total_chunks), (1, 2));
+        assert!(stats.last_updated.is_some());
+        assert_eq!(stats.chunk_type_counts.get(&ChunkType::Block), Some(&2));
+        assert_eq!(stats.files_without_symbols, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_delete_by_file_is_scoped_to_collection() {
+        let temp = TempDir::new().unwrap();
+        let store = SqliteStore::open(temp.path().join("index.db")).unwrap();
+
+        let mut team = numbered_chunk(0, "a.rs", vec![1.0, 0.0]);
+        team.collection = Some("team".to_string());
+        store
+            .save_chunks(vec![team, numbered_chunk(0, "a.rs", vec![1.0, 0.0])])
+            .await
+            .unwrap();
+        store
+            .save_document(make_document("a.rs", &["chunk0"]))
+            .await
+            .unwrap();
+
+        store.delete_by_file("a.rs", None).await.unwrap();
+
+        assert!(store.get_all_chunks(None).await.unwrap().is_empty());
+        assert!(store.list_documents(None).await.unwrap().is_empty());
+        assert_eq!(store.get_all_chunks(Some("team")).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_chunks_by_file_and_delete_by_id() {
+        let temp = TempDir::new().unwrap();
+        let store = SqliteStore::open(temp.path().join("index.db")).unwrap();
+        store
+            .save_chunks(vec![
+                numbered_chunk(0, "a.rs", vec![1.0, 0.0]),
+                numbered_chunk(1, "a.rs", vec![0.0, 1.0]),
+                numbered_chunk(2, "b.rs", vec![1.0, 1.0]),
+            ])
+            .await
+            .unwrap();
+        store
+            .save_document(make_document("a.rs", &["chunk0", "chunk1"]))
+            .await
+            .unwrap();
+
+        store
+            .delete_chunks(&["chunk0".to_string()], None)
+            .await
+            .unwrap();
+
+        let remaining = store.get_chunks_by_file("a.rs", None).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, "chunk1");
+        assert_eq!(remaining[0].vector, vec![0.0, 1.0]);
+        assert!(store.get_document("a.rs", None).await.unwrap().is_some());
+        assert_eq!(
+            store.get_chunks_by_file("b.rs", None).await.unwrap().len(),
+            1
+        );
+    }
+}
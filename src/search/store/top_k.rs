@@ -0,0 +1,84 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use super::{Chunk, SearchResult};
+
+/// Bounded collector keeping the `limit` highest-scoring results.
+///
+/// Backed by a min-heap so memory stays O(limit) regardless of how many
+/// candidates are offered.
+pub struct TopK {
+    limit: usize,
+    heap: BinaryHeap<MinScored>,
+}
+
+struct MinScored(SearchResult);
+
+impl PartialEq for MinScored {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for MinScored {}
+
+impl PartialOrd for MinScored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MinScored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so the heap's top is the lowest score
+        other.0.score.total_cmp(&self.0.score)
+    }
+}
+
+impl TopK {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            heap: BinaryHeap::with_capacity(limit.saturating_add(1).min(1024)),
+        }
+    }
+
+    /// Offer a candidate; `chunk` is only materialized if the score makes the cut
+    pub fn offer(&mut self, score: f32, chunk: impl FnOnce() -> Chunk) {
+        if self.limit == 0 {
+            return;
+        }
+
+        if self.heap.len() < self.limit {
+            self.heap.push(MinScored(SearchResult::new(chunk(), score)));
+            return;
+        }
+
+        let beats_min = self
+            .heap
+            .peek()
+            .is_some_and(|min| score.total_cmp(&min.0.score) == Ordering::Greater);
+        if beats_min {
+            self.heap.pop();
+            self.heap.push(MinScored(SearchResult::new(chunk(), score)));
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Results ordered from highest to lowest score
+    pub fn into_sorted_vec(self) -> Vec<SearchResult> {
+        // Ascending by MinScored is descending by score
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|m| m.0)
+            .collect()
+    }
+}
@@ -1,7 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+use super::vector_norm;
+use crate::types::{Language, SymbolKind};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum ChunkType {
     Function,
     Class,
@@ -9,6 +13,28 @@ pub enum ChunkType {
     Module,
     FileHeader,
     Block,
+    /// Ephemeral chunk built from a diff hunk; never persisted
+    Diff,
+}
+
+impl std::str::FromStr for ChunkType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "function" | "fn" => Ok(ChunkType::Function),
+            "class" => Ok(ChunkType::Class),
+            "method" => Ok(ChunkType::Method),
+            "module" | "mod" => Ok(ChunkType::Module),
+            "file-header" | "header" => Ok(ChunkType::FileHeader),
+            "block" => Ok(ChunkType::Block),
+            "diff" => Ok(ChunkType::Diff),
+            other => Err(format!(
+                "unknown chunk type '{}' (expected function, class, method, module, file-header or block)",
+                other
+            )),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +48,44 @@ pub struct Chunk {
     pub hash: String,
     pub updated_at: DateTime<Utc>,
     pub chunk_type: ChunkType,
+    /// Normalized git commit frequency of the source file (0.0-1.0), if indexed with --hotness
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hotness: Option<f32>,
+    /// Collection (namespace) the chunk belongs to; `None` is the global collection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
+    /// Language of the source file; absent in indexes built before it was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<Language>,
+    /// Symbol the chunk was cut from; `None` for window-based chunks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_kind: Option<SymbolKind>,
+    /// L2 norm of `vector`, stored so searches don't recompute it per query;
+    /// filled in on load for indexes written before it was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub norm: Option<f32>,
+}
+
+impl Chunk {
+    /// Stored norm of `vector`, computed when absent
+    pub fn vector_norm(&self) -> f32 {
+        self.norm.unwrap_or_else(|| vector_norm(&self.vector))
+    }
+
+    /// Record the norm of `vector` if it isn't stored yet
+    pub fn fill_norm(&mut self) {
+        if self.norm.is_none() {
+            self.norm = Some(vector_norm(&self.vector));
+        }
+    }
+
+    /// Recorded language, falling back to the file extension for older indexes
+    pub fn language(&self) -> Language {
+        self.language
+            .unwrap_or_else(|| Language::from_path(&self.file_path))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +94,90 @@ pub struct Document {
     pub hash: String,
     pub mod_time: DateTime<Utc>,
     pub chunk_ids: Vec<String>,
+    /// Collection (namespace) the document belongs to; `None` is the global collection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
+}
+
+/// Index-wide metadata recorded at index time
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct IndexMeta {
+    /// Embedding model used to build the index
+    pub model: Option<String>,
+    /// Length of the stored vectors
+    pub dimensions: Option<usize>,
+}
+
+impl IndexMeta {
+    /// Fail when the index holds vectors of a different length than an
+    /// embedder producing `dimensions`-long vectors; similarities between
+    /// them are meaningless
+    pub fn check_dimensions(&self, dimensions: usize) -> anyhow::Result<()> {
+        match self.dimensions {
+            Some(indexed) if indexed != dimensions => anyhow::bail!(
+                "Index holds {}-dimension vectors{} but the embedder produces {}-dimension vectors; re-index with `agentlens index --force`",
+                indexed,
+                self.model
+                    .as_deref()
+                    .map(|m| format!(" from '{}'", m))
+                    .unwrap_or_default(),
+                dimensions
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Predicates applied to chunks before they are scored
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchFilter {
+    /// Only chunks from files under this directory (or this exact file)
+    pub path_prefix: Option<String>,
+    /// Only chunks of these types
+    pub chunk_types: Option<Vec<ChunkType>>,
+    /// Skip files under any of these paths
+    pub exclude_paths: Vec<String>,
+    /// Collection to search; `None` is the global collection
+    pub collection: Option<String>,
+}
+
+impl SearchFilter {
+    /// Filter that only selects a collection, as plain `search` does
+    pub fn for_collection(collection: Option<&str>) -> Self {
+        Self {
+            collection: collection.map(str::to_string),
+            ..Self::default()
+        }
+    }
+
+    pub fn matches(&self, chunk: &Chunk) -> bool {
+        chunk.collection == self.collection
+            && self
+                .path_prefix
+                .as_deref()
+                .is_none_or(|prefix| is_under(&chunk.file_path, prefix))
+            && self
+                .chunk_types
+                .as_ref()
+                .is_none_or(|types| types.contains(&chunk.chunk_type))
+            && !self
+                .exclude_paths
+                .iter()
+                .any(|path| is_under(&chunk.file_path, path))
+    }
+}
+
+/// Whether `path` is `dir` itself or inside it, matching whole components
+/// so "src/auth" does not select "src/authz.rs"
+fn is_under(path: &str, dir: &str) -> bool {
+    let dir = dir.trim_start_matches("./").trim_end_matches('/');
+    dir.is_empty()
+        || path == dir
+        || path
+            .strip_prefix(dir)
+            .is_some_and(|rest| rest.starts_with('/'))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,6 +192,29 @@ pub struct IndexStats {
     pub total_chunks: usize,
     pub index_size_bytes: u64,
     pub last_updated: Option<DateTime<Utc>>,
+    /// Chunks of each type; empty when the store does not report it
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub chunk_type_counts: HashMap<ChunkType, usize>,
+    /// Indexed files with no chunk cut from a symbol, i.e. chunked by window only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub files_without_symbols: Option<usize>,
+}
+
+impl IndexStats {
+    /// Fill the chunk type breakdown from `(file, chunk_type, has_symbol)` per
+    /// stored chunk, where `file` identifies the chunk's document
+    pub fn with_chunk_breakdown<F: std::hash::Hash + Eq>(
+        mut self,
+        chunks: impl IntoIterator<Item = (F, ChunkType, bool)>,
+    ) -> Self {
+        let mut has_symbols: HashMap<F, bool> = HashMap::new();
+        for (file, chunk_type, has_symbol) in chunks {
+            *self.chunk_type_counts.entry(chunk_type).or_default() += 1;
+            *has_symbols.entry(file).or_default() |= has_symbol;
+        }
+        self.files_without_symbols = Some(has_symbols.values().filter(|has| !**has).count());
+        self
+    }
 }
 
 impl SearchResult {
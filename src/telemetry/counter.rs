@@ -1,4 +1,16 @@
-use tiktoken_rs::o200k_base;
+use serde::{Deserialize, Serialize};
+use tiktoken_rs::{cl100k_base, o200k_base};
+
+/// BPE vocabulary used to count tokens
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    /// GPT-4o family
+    #[default]
+    O200kBase,
+    /// GPT-4 / GPT-3.5 family
+    Cl100kBase,
+}
 
 #[derive(Clone)]
 pub struct TokenCounter {
@@ -7,7 +19,14 @@ pub struct TokenCounter {
 
 impl TokenCounter {
     pub fn new() -> Self {
-        let bpe = o200k_base().expect("Failed to load o200k_base tokenizer");
+        Self::with_encoding(Encoding::O200kBase)
+    }
+
+    pub fn with_encoding(encoding: Encoding) -> Self {
+        let bpe = match encoding {
+            Encoding::O200kBase => o200k_base().expect("Failed to load o200k_base tokenizer"),
+            Encoding::Cl100kBase => cl100k_base().expect("Failed to load cl100k_base tokenizer"),
+        };
         Self { bpe }
     }
 
@@ -15,6 +34,19 @@ impl TokenCounter {
         self.bpe.encode_with_special_tokens(text).len()
     }
 
+    /// Longest prefix of `text` that is at most `max_tokens` tokens
+    pub fn truncate(&self, text: &str, max_tokens: usize) -> String {
+        let tokens = self.bpe.encode_with_special_tokens(text);
+        if tokens.len() <= max_tokens {
+            return text.to_string();
+        }
+        // A cut inside a multi-byte character does not decode; back off until it does
+        (0..=max_tokens)
+            .rev()
+            .find_map(|n| self.bpe.decode(tokens[..n].to_vec()).ok())
+            .unwrap_or_default()
+    }
+
     pub fn count_bytes_estimate(text: &str) -> usize {
         text.len() / 4
     }
@@ -38,4 +70,15 @@ mod tests {
         assert!(tokens > 0);
         assert!(tokens < text.len());
     }
+
+    #[test]
+    fn test_truncate_to_token_budget() {
+        let counter = TokenCounter::with_encoding(Encoding::Cl100kBase);
+        let text = "The quick brown fox jumps over the lazy dog. ".repeat(20);
+
+        let cut = counter.truncate(&text, 10);
+        assert!(counter.count(&cut) <= 10);
+        assert!(text.starts_with(&cut));
+        assert_eq!(counter.truncate("short", 10), "short");
+    }
 }
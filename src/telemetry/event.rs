@@ -23,10 +23,10 @@ impl TelemetryEvent {
         bytes_out: usize,
         duration_ms: u64,
     ) -> Self {
-        let params_preview = if params.len() > 100 {
-            format!("{}...", &params[..100])
-        } else {
-            params.to_string()
+        // First 100 characters; slicing at byte 100 could split a multibyte one
+        let params_preview = match params.char_indices().nth(100) {
+            Some((cut, _)) => format!("{}...", &params[..cut]),
+            None => params.to_string(),
         };
 
         Self {
@@ -48,3 +48,19 @@ pub struct ToolCall {
     pub call_count: usize,
     pub total_tokens_out: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_params_preview_cuts_on_char_boundary() {
+        // Byte 100 falls inside the second byte of a two-byte 'é'
+        let params = format!("{}{}", "a".repeat(99), "é".repeat(10));
+        let event = TelemetryEvent::new("s", "search", &params, 0, 0, 0, 0);
+        assert_eq!(event.params_preview, format!("{}é...", "a".repeat(99)));
+
+        let short = TelemetryEvent::new("s", "search", "{\"q\":\"日本語\"}", 0, 0, 0, 0);
+        assert_eq!(short.params_preview, "{\"q\":\"日本語\"}");
+    }
+}
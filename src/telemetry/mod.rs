@@ -2,7 +2,7 @@ mod counter;
 mod event;
 mod storage;
 
-pub use counter::TokenCounter;
+pub use counter::{Encoding, TokenCounter};
 pub use event::{TelemetryEvent, ToolCall};
 pub use storage::TelemetryStorage;
 
@@ -71,7 +71,7 @@ impl TelemetryStorage {
         summary.tools_used = tool_stats.into_values().collect();
         summary
             .tools_used
-            .sort_by(|a, b| b.call_count.cmp(&a.call_count));
+            .sort_by_key(|t| std::cmp::Reverse(t.call_count));
 
         summary
     }
@@ -120,7 +120,7 @@ impl TelemetryStorage {
                 summary.tools_used = tools.values().cloned().collect();
                 summary
                     .tools_used
-                    .sort_by(|a, b| b.call_count.cmp(&a.call_count));
+                    .sort_by_key(|t| std::cmp::Reverse(t.call_count));
             }
         }
 
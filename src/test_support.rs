@@ -0,0 +1,166 @@
+//! Fixtures shared by unit tests across modules: chunks and embedders that
+//! need no model server, and raw HTTP helpers for fake servers.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::search::{Chunk, ChunkType, Embedder};
+
+/// A block chunk spanning lines 1-10 with no content; override the fields a
+/// test cares about with struct update syntax
+pub fn make_chunk(id: &str, file_path: &str, vector: Vec<f32>) -> Chunk {
+    Chunk {
+        id: id.to_string(),
+        file_path: file_path.to_string(),
+        start_line: 1,
+        end_line: 10,
+        content: String::new(),
+        vector,
+        hash: String::new(),
+        updated_at: Utc::now(),
+        chunk_type: ChunkType::Block,
+        hotness: None,
+        language: None,
+        symbol_name: None,
+        symbol_kind: None,
+        collection: None,
+        norm: None,
+    }
+}
+
+/// Embeds every text as the same vector, counting what it is asked for
+#[derive(Default)]
+pub struct FixedEmbedder {
+    vector: Vec<f32>,
+    /// What `dimensions()` reports instead of the vector's length
+    claimed_dimensions: Option<usize>,
+    /// `embed_batch` calls
+    pub batches: AtomicUsize,
+    /// Texts embedded, by `embed` or `embed_batch`
+    pub texts: AtomicUsize,
+}
+
+impl FixedEmbedder {
+    pub fn new(vector: Vec<f32>) -> Self {
+        Self {
+            vector,
+            ..Self::default()
+        }
+    }
+
+    /// Report `dimensions` from `dimensions()`, like a misconfigured embedder
+    pub fn claiming_dimensions(mut self, dimensions: usize) -> Self {
+        self.claimed_dimensions = Some(dimensions);
+        self
+    }
+
+    pub fn batches(&self) -> usize {
+        self.batches.load(Ordering::SeqCst)
+    }
+
+    pub fn texts(&self) -> usize {
+        self.texts.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl Embedder for FixedEmbedder {
+    async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+        self.texts.fetch_add(1, Ordering::SeqCst);
+        Ok(self.vector.clone())
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.batches.fetch_add(1, Ordering::SeqCst);
+        self.texts.fetch_add(texts.len(), Ordering::SeqCst);
+        Ok(texts.iter().map(|_| self.vector.clone()).collect())
+    }
+
+    fn dimensions(&self) -> usize {
+        self.claimed_dimensions.unwrap_or(self.vector.len())
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Embeds text as 1.0 per keyword it mentions (0.0 otherwise), then 0.1 so
+/// no vector is zero
+pub struct KeywordEmbedder(pub &'static [&'static str]);
+
+#[async_trait]
+impl Embedder for KeywordEmbedder {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let mut vector: Vec<f32> = self
+            .0
+            .iter()
+            .map(|keyword| text.contains(keyword) as u8 as f32)
+            .collect();
+        vector.push(0.1);
+        Ok(vector)
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for text in texts {
+            vectors.push(self.embed(text).await?);
+        }
+        Ok(vectors)
+    }
+
+    fn dimensions(&self) -> usize {
+        self.0.len() + 1
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Raw text (head and body) of one HTTP request read from `stream`, for fake
+/// servers in tests
+pub async fn read_request(stream: &mut TcpStream) -> String {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let n = stream.read(&mut buf).await.unwrap();
+        request.extend_from_slice(&buf[..n]);
+        let text = String::from_utf8_lossy(&request);
+        if let Some(head_end) = text.find("\r\n\r\n") {
+            let length = text[..head_end]
+                .lines()
+                .find_map(|l| {
+                    l.to_lowercase()
+                        .strip_prefix("content-length:")
+                        .map(|v| v.trim().parse::<usize>().unwrap())
+                })
+                .unwrap_or(0);
+            if request.len() >= head_end + 4 + length {
+                return String::from_utf8(request).unwrap();
+            }
+        }
+    }
+}
+
+/// Body of one HTTP request read from `stream`
+pub async fn read_body(stream: &mut TcpStream) -> String {
+    let request = read_request(stream).await;
+    let head_end = request.find("\r\n\r\n").unwrap();
+    request[head_end + 4..].to_string()
+}
+
+/// Answer with `status` (e.g. "200 OK") and the JSON `body`, then close
+pub async fn write_json_response(stream: &mut TcpStream, status: &str, body: &str) {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await.unwrap();
+}
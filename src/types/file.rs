@@ -1,7 +1,8 @@
-use serde::Serialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum Language {
     Rust,
     Python,
@@ -16,10 +17,38 @@ pub enum Language {
     Ruby,
     Dart,
     Swift,
+    Kotlin,
+    Vue,
+    Svelte,
+    Sql,
     Unknown,
 }
 
 impl Language {
+    /// Lowercase name used by `--lang` filters and per-language config keys
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::Python => "python",
+            Language::JavaScript => "javascript",
+            Language::TypeScript => "typescript",
+            Language::Go => "go",
+            Language::Php => "php",
+            Language::Java => "java",
+            Language::CSharp => "csharp",
+            Language::C => "c",
+            Language::Cpp => "cpp",
+            Language::Ruby => "ruby",
+            Language::Dart => "dart",
+            Language::Swift => "swift",
+            Language::Kotlin => "kotlin",
+            Language::Vue => "vue",
+            Language::Svelte => "svelte",
+            Language::Sql => "sql",
+            Language::Unknown => "unknown",
+        }
+    }
+
     pub fn from_extension(ext: &str) -> Self {
         match ext.to_lowercase().as_str() {
             "rs" => Language::Rust,
@@ -36,10 +65,23 @@ impl Language {
             "rb" | "rake" => Language::Ruby,
             "dart" => Language::Dart,
             "swift" => Language::Swift,
+            "kt" | "kts" => Language::Kotlin,
+            "vue" => Language::Vue,
+            "svelte" => Language::Svelte,
+            "sql" => Language::Sql,
             _ => Language::Unknown,
         }
     }
 
+    /// Language of a file path, judged by its extension
+    pub fn from_path(path: &str) -> Self {
+        std::path::Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(Language::from_extension)
+            .unwrap_or(Language::Unknown)
+    }
+
     pub fn from_shebang(first_line: &str) -> Option<Self> {
         if !first_line.starts_with("#!") {
             return None;
@@ -60,7 +102,7 @@ impl Language {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct FileEntry {
     #[serde(skip)]
     pub path: PathBuf,
@@ -100,4 +142,14 @@ impl FileEntry {
             is_large: line_count > threshold,
         }
     }
+
+    /// Markdown document, judged by its extension
+    pub fn is_markdown(&self) -> bool {
+        self.extension.as_deref().is_some_and(is_markdown_extension)
+    }
+}
+
+/// Extensions of markdown documents, which are indexed for search but not analyzed
+pub fn is_markdown_extension(ext: &str) -> bool {
+    matches!(ext.to_lowercase().as_str(), "md" | "mdx" | "markdown")
 }
@@ -1,6 +1,7 @@
-use serde::Serialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, JsonSchema)]
 pub enum Priority {
     High,
     Medium,
@@ -17,7 +18,46 @@ impl std::fmt::Display for Priority {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+/// How urgent a marker is, ordered least to most severe
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+pub enum Severity {
+    #[serde(alias = "info")]
+    Info,
+    #[serde(alias = "warning")]
+    Warning,
+    #[serde(alias = "critical")]
+    Critical,
+}
+
+impl std::str::FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "info" => Ok(Severity::Info),
+            "warning" | "warn" => Ok(Severity::Warning),
+            "critical" => Ok(Severity::Critical),
+            other => Err(format!(
+                "unknown severity '{}' (expected info, warning or critical)",
+                other
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Info => write!(f, "Info"),
+            Severity::Warning => write!(f, "Warning"),
+            Severity::Critical => write!(f, "Critical"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
 pub enum MemoryKind {
     Warning,
     BusinessRule,
@@ -28,6 +68,8 @@ pub enum MemoryKind {
     Note,
     Hack,
     Safety,
+    /// A keyword configured in `[markers]`, kept in `MemoryEntry::keyword`
+    Custom,
 }
 
 impl MemoryKind {
@@ -36,10 +78,25 @@ impl MemoryKind {
             MemoryKind::Warning | MemoryKind::Safety | MemoryKind::Deprecated => Priority::High,
             MemoryKind::BusinessRule | MemoryKind::Invariant => Priority::High,
             MemoryKind::Todo | MemoryKind::Fixme | MemoryKind::Hack => Priority::Medium,
+            MemoryKind::Custom => Priority::Medium,
             MemoryKind::Note => Priority::Low,
         }
     }
 
+    /// SAFETY and FIXME (which includes BUG and XXX) are critical; TODO and
+    /// NOTE are informational; the rest are warnings
+    pub fn severity(&self) -> Severity {
+        match self {
+            MemoryKind::Safety | MemoryKind::Fixme => Severity::Critical,
+            MemoryKind::Warning
+            | MemoryKind::Deprecated
+            | MemoryKind::Hack
+            | MemoryKind::BusinessRule
+            | MemoryKind::Invariant => Severity::Warning,
+            MemoryKind::Todo | MemoryKind::Note | MemoryKind::Custom => Severity::Info,
+        }
+    }
+
     pub fn category(&self) -> &'static str {
         match self {
             MemoryKind::Warning | MemoryKind::Safety => "Warnings",
@@ -48,6 +105,7 @@ impl MemoryKind {
                 "Technical Debt"
             }
             MemoryKind::Note => "Notes",
+            MemoryKind::Custom => "Custom Markers",
         }
     }
 
@@ -59,6 +117,7 @@ impl MemoryKind {
                 "🔧"
             }
             MemoryKind::Note => "📝",
+            MemoryKind::Custom => "🏷️",
         }
     }
 }
@@ -75,17 +134,22 @@ impl std::fmt::Display for MemoryKind {
             MemoryKind::Note => write!(f, "NOTE"),
             MemoryKind::Hack => write!(f, "HACK"),
             MemoryKind::Safety => write!(f, "SAFETY"),
+            MemoryKind::Custom => write!(f, "CUSTOM"),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct MemoryEntry {
     pub kind: MemoryKind,
     pub content: String,
     pub source_file: String,
     pub line_number: usize,
     pub priority: Priority,
+    pub severity: Severity,
+    /// The matched keyword of a `Custom` marker, e.g. "PERF" or "JIRA-1234"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keyword: Option<String>,
 }
 
 impl MemoryEntry {
@@ -97,6 +161,8 @@ impl MemoryEntry {
             source_file,
             line_number,
             priority,
+            severity: kind.severity(),
+            keyword: None,
         }
     }
 
@@ -104,4 +170,21 @@ impl MemoryEntry {
         self.priority = priority;
         self
     }
+
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    pub fn with_keyword(mut self, keyword: String) -> Self {
+        self.keyword = Some(keyword);
+        self
+    }
+
+    /// The marker as written in the source: the custom keyword, or the kind
+    pub fn marker(&self) -> String {
+        self.keyword
+            .clone()
+            .unwrap_or_else(|| self.kind.to_string())
+    }
 }
@@ -2,6 +2,6 @@ mod file;
 mod memory;
 mod symbol;
 
-pub use file::{FileEntry, Language};
-pub use memory::{MemoryEntry, MemoryKind, Priority};
+pub use file::{is_markdown_extension, FileEntry, Language};
+pub use memory::{MemoryEntry, MemoryKind, Priority, Severity};
 pub use symbol::{LineRange, Symbol, SymbolKind, Visibility};
@@ -1,6 +1,7 @@
-use serde::Serialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum SymbolKind {
     Function,
     Method,
@@ -12,6 +13,8 @@ pub enum SymbolKind {
     Const,
     Module,
     Type,
+    /// Test function, e.g. Rust `#[test] fn`, Go `func TestX`, JS `it(...)`
+    Test,
 }
 
 impl std::fmt::Display for SymbolKind {
@@ -27,11 +30,12 @@ impl std::fmt::Display for SymbolKind {
             SymbolKind::Const => write!(f, "const"),
             SymbolKind::Module => write!(f, "mod"),
             SymbolKind::Type => write!(f, "type"),
+            SymbolKind::Test => write!(f, "test"),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
 pub enum Visibility {
     Public,
     Private,
@@ -39,6 +43,40 @@ pub enum Visibility {
     Internal,
 }
 
+impl Visibility {
+    /// How widely the symbol is visible: private < protected < internal < public
+    pub fn level(&self) -> u8 {
+        match self {
+            Visibility::Private => 0,
+            Visibility::Protected => 1,
+            Visibility::Internal => 2,
+            Visibility::Public => 3,
+        }
+    }
+
+    /// Visible at least as widely as `min`
+    pub fn is_at_least(&self, min: Visibility) -> bool {
+        self.level() >= min.level()
+    }
+}
+
+impl std::str::FromStr for Visibility {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "public" | "pub" => Ok(Visibility::Public),
+            "internal" => Ok(Visibility::Internal),
+            "protected" => Ok(Visibility::Protected),
+            "private" => Ok(Visibility::Private),
+            other => Err(format!(
+                "unknown visibility '{}' (expected public, internal, protected or private)",
+                other
+            )),
+        }
+    }
+}
+
 impl std::fmt::Display for Visibility {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -50,7 +88,7 @@ impl std::fmt::Display for Visibility {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
 pub struct LineRange {
     pub start: usize,
     pub end: usize,
@@ -69,14 +107,20 @@ impl LineRange {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct Symbol {
     pub kind: SymbolKind,
     pub name: String,
     pub signature: Option<String>,
+    /// Parameters of a function or method as written, e.g. `id: u64`
+    pub params: Vec<String>,
+    pub return_type: Option<String>,
     pub line_range: LineRange,
     pub visibility: Visibility,
     pub doc_comment: Option<String>,
+    /// Name of the innermost symbol whose line range contains this one,
+    /// e.g. the class of a method
+    pub parent: Option<String>,
 }
 
 impl Symbol {
@@ -85,9 +129,12 @@ impl Symbol {
             kind,
             name,
             signature: None,
+            params: Vec::new(),
+            return_type: None,
             line_range: LineRange::single(line),
             visibility,
             doc_comment: None,
+            parent: None,
         }
     }
 
@@ -105,4 +152,20 @@ impl Symbol {
         self.doc_comment = Some(doc);
         self
     }
+
+    /// Whether `other` lies within this symbol's lines and is not the same span
+    pub fn contains(&self, other: &Symbol) -> bool {
+        self.line_range.start <= other.line_range.start
+            && other.line_range.end <= self.line_range.end
+            && self.line_range != other.line_range
+    }
+
+    /// First non-empty line of the doc comment
+    pub fn doc_summary(&self) -> Option<&str> {
+        self.doc_comment
+            .as_deref()?
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty())
+    }
 }